@@ -18,6 +18,7 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
     let visibility = input.vis;
 
     let helper_ident = Ident::new(&format!("PoshInternal{ident}BlockHelper"), ident.span());
+    let padded_ident = Ident::new(&format!("PoshInternal{ident}BlockPadded"), ident.span());
 
     let generics_init = remove_domain_param(ident, &input.generics)?;
 
@@ -127,6 +128,56 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
             }
         }
 
+        // Helper type that holds the `std140` representation of the struct,
+        // followed by explicit padding up to a multiple of 16 bytes. This is
+        // the array stride of the struct in `std140`.
+        #[doc(hidden)]
+        #[derive(
+            Clone,
+            Copy,
+            ::posh::bytemuck::Zeroable,
+            ::posh::bytemuck::Pod,
+        )]
+        #[bytemuck(crate = "::posh::bytemuck")]
+        #[repr(C)]
+        #visibility struct #padded_ident {
+            value: <#helper_ident as ::posh::crevice::std140::AsStd140>::Output,
+            _pad: [
+                u8;
+                (16 - ::std::mem::size_of::<
+                    <#helper_ident as ::posh::crevice::std140::AsStd140>::Output
+                >() % 16) % 16
+            ],
+        }
+
+        unsafe impl ::posh::crevice::std140::Std140 for #padded_ident {
+            const ALIGNMENT: usize = {
+                let alignment = <
+                    <#helper_ident as ::posh::crevice::std140::AsStd140>::Output
+                    as ::posh::crevice::std140::Std140
+                >::ALIGNMENT;
+
+                if alignment > 16 { alignment } else { 16 }
+            };
+        }
+
+        // Implement `Std140ArrayElement` for the `Gl` view of the struct, so
+        // that it can be used in arrays.
+        impl ::posh::internal::Std140ArrayElement for #ident #ty_generics_gl {
+            type Padded = #padded_ident;
+
+            fn pad(value: Self::Output) -> Self::Padded {
+                #padded_ident {
+                    value,
+                    _pad: ::posh::bytemuck::Zeroable::zeroed(),
+                }
+            }
+
+            fn unpad(value: Self::Padded) -> Self::Output {
+                value.value
+            }
+        }
+
         // Implement `Block<Gl>` for the `Gl` view of the struct.
         unsafe impl ::posh::Block<::posh::Gl> for #ident #ty_generics_gl {
             type Sl = #ident #ty_generics_sl;
@@ -173,8 +224,10 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
                         #field_idents
                     );
 
+                    // Use the `Gl` view of the field, since it knows the
+                    // format in which the attribute is stored.
                     let attrs = <
-                        #field_types_sl as ::posh::Block<::posh::Sl>
+                        #field_types_gl as ::posh::Block<::posh::Gl>
                     >::vertex_attribute_defs(
                         &::posh::internal::join_ident_path(path, #field_strings),
                     );
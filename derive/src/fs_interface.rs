@@ -51,10 +51,10 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
             type Gl = #ident #ty_generics_gl;
             type Sl = #ident #ty_generics_sl;
 
-            fn visit<'a>(
-                &'a self,
+            fn visit(
+                &self,
                 path: &str,
-                visitor: &mut impl ::posh::internal::FragmentVisitor<'a, #generics_view_type>,
+                visitor: &mut impl ::posh::internal::FragmentVisitor<#generics_view_type>,
             ) {
                 #(
                     visitor.accept(
@@ -0,0 +1,108 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    spanned::Spanned, Error, FnArg, ItemFn, Pat, PatIdent, PatType, Result, ReturnType, Type,
+};
+
+pub fn expand(item: ItemFn) -> Result<TokenStream> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = item;
+
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "posh functions cannot be generic",
+        ));
+    }
+
+    if sig.asyncness.is_some() || sig.constness.is_some() || sig.variadic.is_some() {
+        return Err(Error::new_spanned(
+            &sig,
+            "posh functions must be plain functions",
+        ));
+    }
+
+    let output_ty = match &sig.output {
+        ReturnType::Type(_, ty) => ty,
+        ReturnType::Default => {
+            return Err(Error::new(sig.span(), "posh functions must return a value"))
+        }
+    };
+
+    let params = sig
+        .inputs
+        .iter()
+        .map(|input| match input {
+            FnArg::Typed(PatType { pat, ty, .. }) => match &**pat {
+                Pat::Ident(PatIdent {
+                    ident,
+                    by_ref: None,
+                    subpat: None,
+                    ..
+                }) => Ok((ident.clone(), &**ty)),
+                _ => Err(Error::new_spanned(
+                    pat,
+                    "posh function parameters must be identifiers",
+                )),
+            },
+            FnArg::Receiver(receiver) => Err(Error::new_spanned(
+                receiver,
+                "posh functions cannot take `self`",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let ident = &sig.ident;
+    let ident_str = ident.to_string();
+    let param_idents: Vec<_> = params.iter().map(|(ident, _)| ident).collect();
+    let param_strings: Vec<_> = params.iter().map(|(ident, _)| ident.to_string()).collect();
+    let param_types: Vec<&Type> = params.iter().map(|(_, ty)| *ty).collect();
+    let inputs = &sig.inputs;
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis fn #ident(#(#param_idents: #param_types),*) -> #output_ty {
+            // The body is traced only once, with arguments as parameters. The
+            // resulting expression is then shared by all call sites.
+            fn posh_func_body(#inputs) -> #output_ty #block
+
+            struct PoshFunc;
+
+            let def = ::posh::internal::unique_func_def::<PoshFunc>(
+                || ::posh::internal::FuncDef {
+                    name: #ident_str,
+                    params: vec![
+                        #(
+                            (
+                                ::posh::internal::mangle_ident(#param_strings).into_owned(),
+                                <#param_types as ::posh::sl::Object>::ty(),
+                            )
+                        ),*
+                    ],
+                    result: ::posh::sl::Object::expr(
+                        &posh_func_body(
+                            #(
+                                <#param_types as ::posh::sl::Object>::from_arg(
+                                    &::posh::internal::mangle_ident(#param_strings),
+                                )
+                            ),*
+                        )
+                    ),
+                }
+            );
+
+            ::posh::internal::call_func_def::<#output_ty>(
+                def,
+                vec![
+                    #(
+                        ::posh::sl::Object::expr(&#param_idents)
+                    ),*
+                ],
+            )
+        }
+    })
+}
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Result};
+use syn::{Attribute, DeriveInput, Error, Result};
 
 use crate::utils::{validate_generics, StructFields};
 
@@ -15,8 +15,35 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
     let field_idents = fields.idents();
     let field_types = fields.types();
     let field_strings = fields.strings();
+    let field_qualifiers = fields
+        .attrs()
+        .into_iter()
+        .map(parse_qualifier)
+        .collect::<Result<Vec<_>>>()?;
 
-    // TODO: Allow specifying interpolation qualifiers.
+    let field_outputs = field_idents
+        .iter()
+        .zip(&field_types)
+        .zip(&field_strings)
+        .zip(&field_qualifiers)
+        .map(|(((field_ident, field_type), field_string), qualifier)| {
+            let outputs = quote! {
+                <#field_type as ::posh::sl::Interpolant>::shader_outputs(
+                    &self.#field_ident,
+                    &::posh::internal::join_ident_path(path, #field_string)
+                )
+            };
+
+            match qualifier {
+                Some(qualifier) => quote! {
+                    ::posh::internal::qualify_outputs(
+                        #outputs,
+                        ::posh::sl::program_def::InterpolationQualifier::#qualifier,
+                    )
+                },
+                None => outputs,
+            }
+        });
 
     Ok(quote! {
         unsafe impl #impl_generics ::posh::sl::Interpolant for #ident #ty_generics #where_clause {
@@ -28,12 +55,7 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
                 let mut result = Vec::new();
 
                 #(
-                    result.extend(
-                        <#field_types as ::posh::sl::Interpolant>::shader_outputs(
-                            &self.#field_idents,
-                            &::posh::internal::join_ident_path(path, #field_strings)
-                        )
-                    );
+                    result.extend(#field_outputs);
                 )*
 
                 result
@@ -61,3 +83,40 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
         };
     })
 }
+
+// Parses `#[interp(flat)]` or `#[interp(centroid)]` on a field.
+fn parse_qualifier(attrs: &[Attribute]) -> Result<Option<TokenStream>> {
+    let mut qualifier = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("interp") {
+            continue;
+        }
+
+        if qualifier.is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "posh expects at most one `interp` attribute per field",
+            ));
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if qualifier.is_some() {
+                return Err(meta.error("posh expects exactly one interpolation qualifier"));
+            }
+
+            if meta.path.is_ident("flat") {
+                qualifier = Some(quote!(Flat));
+            } else if meta.path.is_ident("centroid") {
+                qualifier = Some(quote!(Centroid));
+            } else {
+                return Err(meta
+                    .error("unsupported interpolation qualifier, expected `flat` or `centroid`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(qualifier)
+}
@@ -1,6 +1,7 @@
 mod block;
 mod r#const;
 mod fs_interface;
+mod function;
 mod interpolant;
 mod uniform;
 mod utils;
@@ -8,7 +9,7 @@ mod value;
 mod vs_interface;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemFn};
 
 /// Derives `Block` for a struct that is generic in `BlockDom`.
 #[proc_macro_derive(Block)]
@@ -43,8 +44,37 @@ pub fn derive_fs_interface(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Defines a function in the shading language.
+///
+/// The function is traced only once and emitted as a named GLSL function, which
+/// is then called wherever the Rust function is called. Without this attribute,
+/// the body of a Rust function is inlined at every call site.
+///
+/// Parameters must implement `Object` and the result must implement `Value`.
+/// Generic functions are not supported.
+#[proc_macro_attribute]
+pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = proc_macro2::TokenStream::from(attr);
+    let item = parse_macro_input!(item as ItemFn);
+
+    if !attr.is_empty() {
+        return syn::Error::new_spanned(attr, "`function` does not take any arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    match function::expand(item) {
+        Ok(ts) => ts,
+        Err(e) => e.to_compile_error(),
+    }
+    .into()
+}
+
 /// Derives `Interpolant` for a struct.
-#[proc_macro_derive(Interpolant)]
+///
+/// Fields can be annotated with `#[interp(flat)]` or `#[interp(centroid)]` to
+/// override their interpolation qualifier.
+#[proc_macro_derive(Interpolant, attributes(interp))]
 pub fn derive_interpolant(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     match interpolant::derive(input) {
@@ -55,7 +85,11 @@ pub fn derive_interpolant(input: TokenStream) -> TokenStream {
 }
 
 /// Derives `Uniform` for a struct that is generic in `UniformDom`.
-#[proc_macro_derive(Uniform)]
+///
+/// Block fields can be annotated with `#[uniform(binding = N)]` and sampler
+/// fields with `#[uniform(unit = N)]` to fix their uniform block binding point
+/// or texture unit, respectively.
+#[proc_macro_derive(Uniform, attributes(uniform))]
 pub fn derive_uniform_interface(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     match uniform::derive(input) {
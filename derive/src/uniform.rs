@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{parse_quote, DeriveInput, Result};
+use syn::{parse_quote, Attribute, DeriveInput, Ident, LitInt, Result, Type};
 
 use crate::utils::{
     get_domain_param, remove_domain_param, validate_generics, SpecializedTypeGenerics, StructFields,
@@ -26,6 +26,24 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
     let field_idents = fields.idents();
     let field_types = fields.types();
     let field_strings = fields.strings();
+    let field_bindings = fields
+        .attrs()
+        .into_iter()
+        .zip(&field_types)
+        .zip(&field_strings)
+        .map(|((attrs, ty), field_string)| {
+            let binding = parse_binding(attrs, ty, &generics_view_type)?;
+
+            Ok(binding.map(|binding| {
+                quote! {
+                    visitor.accept_binding(
+                        &::posh::internal::join_ident_path(path, #field_string),
+                        ::posh::internal::UniformBinding::#binding,
+                    );
+                }
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
     Ok(quote! {
         // Implement `Uniform<D>` for the struct.
@@ -42,6 +60,8 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
                 visitor: &mut impl ::posh::internal::UniformVisitor<'a, #generics_view_type>,
             ) {
                 #(
+                    #field_bindings
+
                     self.#field_idents.visit(
                         &::posh::internal::join_ident_path(path, #field_strings),
                         visitor,
@@ -83,3 +103,81 @@ pub fn derive(input: DeriveInput) -> Result<TokenStream> {
         };
     })
 }
+
+// Names of the associated types of `UniformDom` that are samplers.
+const SAMPLER_TYPES: &[&str] = &[
+    "ColorSampler2d",
+    "ComparisonSampler2d",
+    "ColorSamplerCube",
+    "ComparisonSamplerCube",
+    "ColorSampler3d",
+    "ColorSampler2dArray",
+    "ComparisonSampler2dArray",
+];
+
+// Parses `#[uniform(binding = N)]` or `#[uniform(unit = N)]` on a field.
+//
+// Bindings only make sense on fields that are directly declared as a block or
+// a sampler of the domain `D`, so they are rejected on all other fields.
+fn parse_binding(attrs: &[Attribute], ty: &Type, domain: &Ident) -> Result<Option<TokenStream>> {
+    let mut binding = None;
+    let domain_type = domain_type_name(ty, domain);
+    let domain_type = domain_type.as_deref();
+
+    for attr in attrs {
+        if !attr.path().is_ident("uniform") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if binding.is_some() {
+                return Err(meta.error("posh expects at most one uniform binding per field"));
+            }
+
+            let value: LitInt = meta.value()?.parse()?;
+            let value = value.base10_parse::<usize>()?;
+
+            if meta.path.is_ident("binding") {
+                if domain_type != Some("Block") {
+                    return Err(meta.error(format!(
+                        "posh expects `binding` only on fields of type `{domain}::Block<_>`"
+                    )));
+                }
+
+                binding = Some(quote!(Block(#value)));
+            } else if meta.path.is_ident("unit") {
+                if !domain_type.is_some_and(|name| SAMPLER_TYPES.contains(&name)) {
+                    return Err(meta.error(format!(
+                        "posh expects `unit` only on fields with a sampler type such as \
+                         `{domain}::ColorSampler2d<_>`"
+                    )));
+                }
+
+                binding = Some(quote!(TextureUnit(#value)));
+            } else {
+                return Err(meta
+                    .error("unsupported uniform attribute, expected `binding = N` or `unit = N`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(binding)
+}
+
+// Returns `Name` if `ty` is of the form `D::Name` or `D::Name<...>`.
+fn domain_type_name(ty: &Type, domain: &Ident) -> Option<String> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    if path.qself.is_some() || path.path.segments.len() != 2 {
+        return None;
+    }
+
+    let segments = &path.path.segments;
+
+    (segments[0].ident == *domain && segments[0].arguments.is_empty())
+        .then(|| segments[1].ident.to_string())
+}
@@ -4,8 +4,8 @@ use syn::{
     parse_quote,
     spanned::Spanned,
     visit_mut::{visit_type_mut, VisitMut},
-    Data, Error, Field, Fields, GenericParam, Generics, Ident, Path, QSelf, Result, Token, Type,
-    TypePath,
+    Attribute, Data, Error, Field, Fields, GenericParam, Generics, Ident, Path, QSelf, Result,
+    Token, Type, TypePath,
 };
 
 #[derive(Clone)]
@@ -42,6 +42,13 @@ impl StructFields {
             .collect()
     }
 
+    pub fn attrs(&self) -> Vec<&[Attribute]> {
+        self.fields
+            .iter()
+            .map(|field| field.attrs.as_slice())
+            .collect()
+    }
+
     pub fn types(&self) -> Vec<&Type> {
         self.fields.iter().map(|field| &field.ty).collect()
     }
@@ -101,8 +108,39 @@ impl ToTokens for SpecializedTypeGenerics {
 
 pub fn associated_type_to_trait(ty: &str) -> Option<Path> {
     let block_tys = vec![
-        "F32", "I32", "U32", "Vec2", "Vec3", "Vec4", "IVec2", "IVec3", "IVec4", "UVec2", "UVec3",
-        "UVec4", "Mat2", "Mat3", "Mat4",
+        "F32",
+        "I32",
+        "U32",
+        "Bool",
+        "Vec2",
+        "Vec3",
+        "Vec4",
+        "IVec2",
+        "IVec3",
+        "IVec4",
+        "UVec2",
+        "UVec3",
+        "UVec4",
+        "Mat2",
+        "Mat3",
+        "Mat4",
+        "Mat2x3",
+        "Mat2x4",
+        "Mat3x2",
+        "Mat3x4",
+        "Mat4x2",
+        "Mat4x3",
+        "Unorm8x2",
+        "Unorm8x4",
+        "Snorm8x2",
+        "Snorm8x4",
+        "Unorm16x2",
+        "Unorm16x4",
+        "Snorm16x2",
+        "Snorm16x4",
+        "F16x2",
+        "F16x4",
+        "Array",
     ];
 
     let vertex_tys = ["Block"];
@@ -90,10 +90,6 @@ mod scene_pass {
 
     const CUBE_ELEMENTS: [u32; 6] = [0, 1, 2, 0, 2, 3];
 
-    fn zxy(v: sl::Vec3) -> sl::Vec3 {
-        sl::vec3(v.z, v.x, v.y)
-    }
-
     pub fn vertex_shader(
         globals: Globals<Sl>,
         input: sl::VsInput<()>,
@@ -108,7 +104,7 @@ mod scene_pass {
             .rotate(sl::Vec2::from_angle(globals.time))
             .extend(object_pos.z);
         let screen_pos =
-            globals.view_to_screen * globals.world_to_view * zxy(world_pos).extend(1.0);
+            globals.view_to_screen * globals.world_to_view * world_pos.zxy().extend(1.0);
 
         // TODO: Fix world normal calculation.
         let world_normal = CUBE_NORMALS.to_sl().get((vertex_id / 4) % 6);
@@ -36,6 +36,7 @@ fn vertex_shader(input: sl::VsInput<()>) -> sl::Vec4 {
     VERTICES.to_sl().get(input.vertex_id)
 }
 
+#[sl::function]
 fn palette(t: sl::F32) -> sl::Vec3 {
     let a = sl::vec3(0.5, 0.5, 0.5);
     let b = sl::vec3(0.5, 0.5, 0.5);
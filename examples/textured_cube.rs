@@ -31,10 +31,6 @@ struct Uniforms<D: UniformDom> {
 
 // Shader code
 
-fn zxy(v: sl::Vec3) -> sl::Vec3 {
-    sl::vec3(v.z, v.x, v.y)
-}
-
 fn vertex_shader(uniforms: Uniforms<Sl>, vertex: Vertex<Sl>) -> sl::VsOutput<sl::Vec2> {
     let camera = uniforms.camera;
 
@@ -43,7 +39,7 @@ fn vertex_shader(uniforms: Uniforms<Sl>, vertex: Vertex<Sl>) -> sl::VsOutput<sl:
         .xy()
         .rotate(sl::Vec2::from_angle(uniforms.time))
         .extend(vertex.pos.z);
-    let position = camera.view_to_screen * camera.world_to_view * zxy(vertex_pos).extend(1.0);
+    let position = camera.view_to_screen * camera.world_to_view * vertex_pos.zxy().extend(1.0);
 
     sl::VsOutput {
         clip_pos: position,
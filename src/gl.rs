@@ -1,10 +1,13 @@
 //! The graphics library.
 
+mod array;
 mod context;
 mod element_buffer;
 mod framebuffer;
 mod image;
 mod mat;
+mod packed;
+mod plain_uniform;
 mod program;
 mod raw;
 mod texture;
@@ -18,28 +21,49 @@ use crevice::std140::AsStd140;
 
 use crate::{sl, ToSl};
 
+pub use array::Array;
 pub use context::{CacheDrawBuilder, Context};
 pub use element_buffer::{Element, ElementBuffer, ElementBufferBinding};
-pub use framebuffer::{ColorAttachment, DepthAttachment, Framebuffer};
+pub use framebuffer::{
+    AttachmentKind, ColorAttachment, CubeFaceImage, DepthAttachment, Framebuffer, LayerImage,
+    Texture2dImage,
+};
 pub use image::{ColorImage, DepthImage};
-pub use mat::{Mat2, Mat3, Mat4};
+pub use mat::{Mat2, Mat2x3, Mat2x4, Mat3, Mat3x2, Mat3x4, Mat4, Mat4x2, Mat4x3};
+pub(crate) use packed::{
+    decode_f16, decode_snorm16, decode_unorm16, encode_f16, encode_snorm16, encode_unorm16,
+};
+pub use packed::{
+    F16x2, F16x4, Snorm16x2, Snorm16x4, Snorm8x2, Snorm8x4, Unorm16x2, Unorm16x4, Unorm8x2,
+    Unorm8x4,
+};
+pub use plain_uniform::upload_plain_uniforms;
 pub use program::{
     DrawBuilder, DrawBuilderWithFramebuffer, DrawBuilderWithUniforms,
     DrawBuilderWithUniformsAndFramebuffer, Program,
 };
 pub use raw::{
     BlendEquation, BlendFunc, Blending, BufferError, BufferUsage, Caps, ClearParams, Comparison,
-    ContextError, CreateError, CullFace, DrawError, DrawParams, ElementType, FrameTrace,
+    ContextError, CreateError, CubeFace, CullFace, DrawError, DrawParams, ElementType, FrameTrace,
     FramebufferError, ImageFormat, ImageInternalFormat, PrimitiveMode, ProgramError,
-    ProgramValidationError, Rect, Sampler2dParams, SamplerMagFilter, SamplerMinFilter, SamplerWrap,
-    StencilOp, StencilOps, StencilTest, TextureError, TracingConfig, VertexArrayError,
+    ProgramValidationError, Rect, Sampler2dParams, Sampler3dParams, SamplerMagFilter,
+    SamplerMinFilter, SamplerWrap, StencilOp, StencilOps, StencilTest, TextureError, TracingConfig,
+    VertexArrayError,
+};
+pub use texture::{
+    ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube, ColorTexture2d,
+    ColorTexture2dArray, ColorTexture3d, ColorTextureCube, ComparisonSampler2d,
+    ComparisonSampler2dArray, ComparisonSamplerCube, DepthTexture2d, DepthTexture2dArray,
+    DepthTextureCube,
 };
-pub use texture::{ColorSampler2d, ColorTexture2d, ComparisonSampler2d, DepthTexture2d};
 pub use uniform_buffer::{UniformBuffer, UniformBufferBinding};
 pub use vec::{BVec2, BVec3, BVec4, IVec2, IVec3, IVec4, UVec2, UVec3, UVec4, Vec2, Vec3, Vec4};
 pub use vertex_buffer::{VertexBuffer, VertexBufferBinding};
 pub use vertex_spec::VertexSpec;
 
+#[doc(hidden)]
+pub use array::{Std140Array, Std140ArrayElement, Std140Padded};
+
 #[derive(Clone, Copy, Zeroable, Pod, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(transparent)]
 pub struct Bool(u32);
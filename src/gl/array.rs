@@ -0,0 +1,171 @@
+use bytemuck::{Pod, Zeroable};
+use crevice::std140::{self, AsStd140, Std140};
+
+use crate::{sl, ToSl};
+
+use super::{
+    Bool, IVec2, IVec3, IVec4, Mat2, Mat2x3, Mat2x4, Mat3, Mat3x2, Mat3x4, Mat4, Mat4x2, Mat4x3,
+    UVec2, UVec3, UVec4, Vec2, Vec3, Vec4,
+};
+
+/// A fixed-size array of block data.
+///
+/// This is the physical view of arrays in blocks. It has the same memory
+/// layout as `[T; N]`, which is used for vertex data. In uniform buffers, the
+/// elements are laid out with the array stride required by `std140`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[repr(transparent)]
+pub struct Array<T, const N: usize>(pub [T; N]);
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for Array<T, N> {}
+
+unsafe impl<T: Pod, const N: usize> Pod for Array<T, N> {}
+
+impl<T: Default, const N: usize> Default for Array<T, N> {
+    fn default() -> Self {
+        Self(std::array::from_fn(|_| T::default()))
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Array<T, N> {
+    fn from(value: [T; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<T, const N: usize> From<Array<T, N>> for [T; N] {
+    fn from(value: Array<T, N>) -> Self {
+        value.0
+    }
+}
+
+impl<T, const N: usize> ToSl for Array<T, N>
+where
+    T: ToSl,
+    T::Output: sl::ValueNonArray,
+{
+    type Output = sl::Array<T::Output, N>;
+
+    fn to_sl(self) -> Self::Output {
+        self.0.to_sl()
+    }
+}
+
+impl<T: Std140ArrayElement, const N: usize> AsStd140 for Array<T, N> {
+    type Output = Std140Array<T::Padded, N>;
+
+    fn as_std140(&self) -> Self::Output {
+        Std140Array(std::array::from_fn(|i| T::pad(self.0[i].as_std140())))
+    }
+
+    fn from_std140(value: Self::Output) -> Self {
+        Self(value.0.map(|value| T::from_std140(T::unpad(value))))
+    }
+}
+
+/// Types that can be elements of arrays in `std140` layout.
+///
+/// In `std140`, the stride of an array is the size of its element type rounded
+/// up to a multiple of 16 bytes.
+#[doc(hidden)]
+pub trait Std140ArrayElement: AsStd140 {
+    /// The `std140` representation of `Self`, padded to a multiple of 16
+    /// bytes.
+    type Padded: Std140;
+
+    fn pad(value: Self::Output) -> Self::Padded;
+
+    fn unpad(value: Self::Padded) -> Self::Output;
+}
+
+/// The `std140` representation of [`Array`].
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct Std140Array<P, const N: usize>([P; N]);
+
+unsafe impl<P: Zeroable, const N: usize> Zeroable for Std140Array<P, N> {}
+
+unsafe impl<P: Pod, const N: usize> Pod for Std140Array<P, N> {}
+
+unsafe impl<P: Std140, const N: usize> Std140 for Std140Array<P, N> {
+    const ALIGNMENT: usize = if P::ALIGNMENT > 16 { P::ALIGNMENT } else { 16 };
+}
+
+/// A `std140` value followed by `P` words of padding.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Std140Padded<T, const P: usize> {
+    value: T,
+    _pad: [u32; P],
+}
+
+macro_rules! impl_padded_element {
+    ($ty:ty, $output:ty, $pad:literal) => {
+        unsafe impl Zeroable for Std140Padded<$output, $pad> {}
+
+        // Safety: The value is four-byte aligned, so there is no implicit
+        // padding between it and the explicit padding words.
+        unsafe impl Pod for Std140Padded<$output, $pad> {}
+
+        unsafe impl Std140 for Std140Padded<$output, $pad> {
+            const ALIGNMENT: usize = 16;
+        }
+
+        impl Std140ArrayElement for $ty {
+            type Padded = Std140Padded<$output, $pad>;
+
+            fn pad(value: Self::Output) -> Self::Padded {
+                Std140Padded {
+                    value,
+                    _pad: [0; $pad],
+                }
+            }
+
+            fn unpad(value: Self::Padded) -> Self::Output {
+                value.value
+            }
+        }
+    };
+}
+
+macro_rules! impl_unpadded_element {
+    ($ty:ty) => {
+        impl Std140ArrayElement for $ty {
+            type Padded = <$ty as AsStd140>::Output;
+
+            fn pad(value: Self::Output) -> Self::Padded {
+                value
+            }
+
+            fn unpad(value: Self::Padded) -> Self::Output {
+                value
+            }
+        }
+    };
+}
+
+impl_padded_element!(f32, f32, 3);
+impl_padded_element!(i32, i32, 3);
+impl_padded_element!(u32, u32, 3);
+impl_padded_element!(Bool, std140::Bool, 3);
+impl_padded_element!(Vec2, std140::Vec2, 2);
+impl_padded_element!(IVec2, std140::IVec2, 2);
+impl_padded_element!(UVec2, std140::UVec2, 2);
+impl_padded_element!(Vec3, std140::Vec3, 1);
+impl_padded_element!(IVec3, std140::IVec3, 1);
+impl_padded_element!(UVec3, std140::UVec3, 1);
+
+impl_unpadded_element!(Vec4);
+impl_unpadded_element!(IVec4);
+impl_unpadded_element!(UVec4);
+impl_unpadded_element!(Mat2);
+impl_unpadded_element!(Mat3);
+impl_unpadded_element!(Mat4);
+impl_unpadded_element!(Mat2x3);
+impl_unpadded_element!(Mat2x4);
+impl_unpadded_element!(Mat3x2);
+impl_unpadded_element!(Mat3x4);
+impl_unpadded_element!(Mat4x2);
+impl_unpadded_element!(Mat4x3);
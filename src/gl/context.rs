@@ -10,7 +10,7 @@ use fxhash::FxHashMap;
 
 use crate::{
     sl::{
-        transpile::{transpile_to_program_def, transpile_to_program_def_with_consts},
+        transpile::{transpile_to_program_def_with_options, TranspileOptions},
         ColorSample, FsFunc, FsSig, VsFunc, VsSig,
     },
     Block, FsInterface, Gl, Sl, Uniform, UniformUnion,
@@ -18,9 +18,10 @@ use crate::{
 
 use super::{
     program::{DrawBuilder, DrawBuilderWithUniforms},
-    raw, BufferError, BufferUsage, Caps, ClearParams, ColorImage, ColorTexture2d, ContextError,
-    CreateError, DepthImage, DepthTexture2d, DrawError, Element, ElementBuffer, FrameTrace,
-    Framebuffer, FramebufferError, Program, ProgramError, TextureError, TracingConfig,
+    raw, BufferError, BufferUsage, Caps, ClearParams, ColorImage, ColorTexture2d,
+    ColorTexture2dArray, ColorTexture3d, ColorTextureCube, ContextError, CreateError, DepthImage,
+    DepthTexture2d, DepthTexture2dArray, DepthTextureCube, DrawError, Element, ElementBuffer,
+    FrameTrace, Framebuffer, FramebufferError, Program, ProgramError, TextureError, TracingConfig,
     UniformBuffer, VertexBuffer,
 };
 
@@ -29,6 +30,7 @@ struct ProgramCacheKey {
     vertex_shader: TypeId,
     fragment_shader: TypeId,
     uniform_union: TypeId,
+    options: TranspileOptions,
 }
 
 #[derive(Default)]
@@ -40,6 +42,7 @@ impl ProgramCache {
         raw: &raw::Context,
         vertex_shader: VFunc,
         fragment_shader: FFunc,
+        options: &TranspileOptions,
         log_sources: bool,
     ) -> Result<Program<U, VSig::V, FSig::F>, ProgramError>
     where
@@ -53,15 +56,19 @@ impl ProgramCache {
             vertex_shader: TypeId::of::<VFunc>(),
             fragment_shader: TypeId::of::<FFunc>(),
             uniform_union: TypeId::of::<U>(),
+            options: options.clone(),
         };
 
         let raw = match self.0.entry(key) {
             hash_map::Entry::Occupied(entry) => entry.get().clone(),
             hash_map::Entry::Vacant(entry) => {
-                let program_def = transpile_to_program_def::<U, VSig, VFunc, FSig, FFunc>(
-                    vertex_shader,
-                    fragment_shader,
-                );
+                let program_def = transpile_to_program_def_with_options::<
+                    U,
+                    VSig,
+                    VFunc,
+                    FSig,
+                    FFunc,
+                >(options, &(), vertex_shader, fragment_shader);
 
                 if log_sources {
                     log::info!(
@@ -113,6 +120,7 @@ where
                 &self.gl.raw,
                 self.vertex_shader,
                 self.fragment_shader,
+                &self.gl.transpile_options.borrow(),
                 self.gl.enable_program_source_logging.get(),
             );
 
@@ -136,16 +144,22 @@ pub struct Context {
     raw: Rc<raw::Context>,
     program_cache: Rc<RefCell<ProgramCache>>,
     enable_program_source_logging: Rc<Cell<bool>>,
+    transpile_options: Rc<RefCell<TranspileOptions>>,
 }
 
 impl Context {
     pub fn new(gl: glow::Context) -> Result<Self, ContextError> {
         let raw = raw::Context::new(gl)?;
+        let transpile_options = TranspileOptions {
+            target: raw.caps().glsl_target,
+            ..Default::default()
+        };
 
         Ok(Self {
             raw: Rc::new(raw),
             program_cache: Default::default(),
             enable_program_source_logging: Default::default(),
+            transpile_options: Rc::new(RefCell::new(transpile_options)),
         })
     }
 
@@ -225,6 +239,109 @@ impl Context {
         Ok(DepthTexture2d::from_raw(raw))
     }
 
+    /// Creates a cube map from six images, one per face, in the order given
+    /// by [`CubeFace::ALL`](super::CubeFace::ALL).
+    pub fn create_color_texture_cube<S: ColorSample>(
+        &self,
+        faces: [ColorImage<'_, S>; 6],
+    ) -> Result<ColorTextureCube<S>, TextureError> {
+        let raw = self
+            .raw
+            .create_texture_cube(faces.map(|face| face.raw().clone()))?;
+
+        Ok(ColorTextureCube::from_raw(raw))
+    }
+
+    pub fn create_color_texture_cube_with_mipmap<S: ColorSample>(
+        &self,
+        faces: [ColorImage<'_, S>; 6],
+    ) -> Result<ColorTextureCube<S>, TextureError> {
+        let raw = self
+            .raw
+            .create_texture_cube_with_mipmap(faces.map(|face| face.raw().clone()))?;
+
+        Ok(ColorTextureCube::from_raw(raw))
+    }
+
+    /// Creates a depth cube map from six images, one per face, in the order
+    /// given by [`CubeFace::ALL`](super::CubeFace::ALL).
+    pub fn create_depth_texture_cube(
+        &self,
+        faces: [DepthImage<'_>; 6],
+    ) -> Result<DepthTextureCube, TextureError> {
+        let raw = self
+            .raw
+            .create_texture_cube(faces.map(|face| face.raw().clone()))?;
+
+        Ok(DepthTextureCube::from_raw(raw))
+    }
+
+    /// Creates a 3D texture from its layers, which must all have the same
+    /// size.
+    pub fn create_color_texture_3d<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture3d<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(|layer| layer.raw()).collect();
+        let raw = self
+            .raw
+            .create_texture_3d(raw::Texture3dTarget::Texture3d, &layers)?;
+
+        Ok(ColorTexture3d::from_raw(raw))
+    }
+
+    pub fn create_color_texture_3d_with_mipmap<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture3d<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(|layer| layer.raw()).collect();
+        let raw = self
+            .raw
+            .create_texture_3d_with_mipmap(raw::Texture3dTarget::Texture3d, &layers)?;
+
+        Ok(ColorTexture3d::from_raw(raw))
+    }
+
+    /// Creates a 2D texture array from its layers, which must all have the
+    /// same size.
+    pub fn create_color_texture_2d_array<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture2dArray<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(|layer| layer.raw()).collect();
+        let raw = self
+            .raw
+            .create_texture_3d(raw::Texture3dTarget::Texture2dArray, &layers)?;
+
+        Ok(ColorTexture2dArray::from_raw(raw))
+    }
+
+    pub fn create_color_texture_2d_array_with_mipmap<'a, S: ColorSample>(
+        &self,
+        layers: impl IntoIterator<Item = ColorImage<'a, S>>,
+    ) -> Result<ColorTexture2dArray<S>, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(|layer| layer.raw()).collect();
+        let raw = self
+            .raw
+            .create_texture_3d_with_mipmap(raw::Texture3dTarget::Texture2dArray, &layers)?;
+
+        Ok(ColorTexture2dArray::from_raw(raw))
+    }
+
+    /// Creates a depth texture array from its layers, which must all have the
+    /// same size.
+    pub fn create_depth_texture_2d_array<'a>(
+        &self,
+        layers: impl IntoIterator<Item = DepthImage<'a>>,
+    ) -> Result<DepthTexture2dArray, TextureError> {
+        let layers: Vec<_> = layers.into_iter().map(|layer| layer.raw()).collect();
+        let raw = self
+            .raw
+            .create_texture_3d(raw::Texture3dTarget::Texture2dArray, &layers)?;
+
+        Ok(DepthTexture2dArray::from_raw(raw))
+    }
+
     pub fn create_program<U, VSig, VFunc, FSig, FFunc>(
         &self,
         vertex_shader: VFunc,
@@ -237,8 +354,12 @@ impl Context {
         FSig: FsSig<C = (), W = VSig::W>,
         FFunc: FsFunc<FSig>,
     {
-        let program_def =
-            transpile_to_program_def::<U, VSig, VFunc, FSig, FFunc>(vertex_shader, fragment_shader);
+        let program_def = transpile_to_program_def_with_options::<U, VSig, VFunc, FSig, FFunc>(
+            &self.transpile_options.borrow(),
+            &(),
+            vertex_shader,
+            fragment_shader,
+        );
 
         if self.enable_program_source_logging.get() {
             log::info!("Vertex shader:\n{}", program_def.vertex_shader_source);
@@ -263,7 +384,8 @@ impl Context {
         FSig: FsSig<C = VSig::C, W = VSig::W>,
         FFunc: FsFunc<FSig>,
     {
-        let program_def = transpile_to_program_def_with_consts::<U, VSig, VFunc, FSig, FFunc>(
+        let program_def = transpile_to_program_def_with_options::<U, VSig, VFunc, FSig, FFunc>(
+            &self.transpile_options.borrow(),
             consts,
             vertex_shader,
             fragment_shader,
@@ -314,6 +436,26 @@ impl Context {
         self.enable_program_source_logging.set(value);
     }
 
+    /// Returns the options that are used for transpiling programs created by
+    /// this context.
+    ///
+    /// Initially, [`TranspileOptions::target`] is chosen according to the
+    /// version of the OpenGL context.
+    pub fn transpile_options(&self) -> TranspileOptions {
+        self.transpile_options.borrow().clone()
+    }
+
+    /// Sets the options that are used for transpiling programs created by this
+    /// context from now on.
+    ///
+    /// For example, [`TranspileOptions::clamp_subscripts`] can be enabled
+    /// during development in order to make out-of-bounds accesses harmless.
+    /// In order to keep the GLSL target that was chosen for this context,
+    /// start from [`Context::transpile_options`].
+    pub fn set_transpile_options(&self, options: TranspileOptions) {
+        *self.transpile_options.borrow_mut() = options;
+    }
+
     pub fn clear<F: FsInterface<Sl>>(
         &self,
         framebuffer: impl Into<Framebuffer<F>>,
@@ -1,5 +1,7 @@
 use std::marker::PhantomData;
 
+use sealed::sealed;
+
 use crate::{
     interface::FragmentVisitor,
     sl::{self, ColorSample},
@@ -11,37 +13,72 @@ use super::{
     ColorSampler2d, Sampler2dParams,
 };
 
+/// The kind of texture image that a [`ColorAttachment`] refers to.
+#[sealed]
+pub trait AttachmentKind {}
+
+/// A mipmap level of a 2D texture.
+#[derive(Debug, Copy, Clone)]
+pub struct Texture2dImage;
+
+/// A mipmap level of one face of a cube map.
+#[derive(Debug, Copy, Clone)]
+pub struct CubeFaceImage;
+
+/// A mipmap level of one layer of a 3D texture or a 2D texture array.
+#[derive(Debug, Copy, Clone)]
+pub struct LayerImage;
+
+#[sealed]
+impl AttachmentKind for Texture2dImage {}
+
+#[sealed]
+impl AttachmentKind for CubeFaceImage {}
+
+#[sealed]
+impl AttachmentKind for LayerImage {}
+
+/// A color image that can be rendered to.
+///
+/// Only attachments of 2D textures can be used in fragment shader outputs with
+/// multiple attachments. Cube map faces and texture layers can be rendered to
+/// on their own, optionally together with a depth attachment.
 #[derive(Clone)]
-pub struct ColorAttachment<S = sl::Vec4> {
+pub struct ColorAttachment<S = sl::Vec4, K: AttachmentKind = Texture2dImage> {
     raw: raw::Attachment,
-    _phantom: PhantomData<S>,
+    _phantom: PhantomData<(S, K)>,
 }
 
-impl<S> ColorAttachment<S> {
+impl<S, K: AttachmentKind> ColorAttachment<S, K> {
     pub(super) fn from_raw(raw: raw::Attachment) -> Self {
         Self {
             raw,
             _phantom: PhantomData,
         }
     }
+}
 
+impl<S> ColorAttachment<S> {
+    /// Returns a sampler for the texture that contains this attachment.
     pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2d<S> {
         ColorSampler2d::from_raw(self.raw.sampler(params, None))
     }
 }
 
-impl<S: ColorSample> ColorAttachment<S> {
+impl<S: ColorSample, K: AttachmentKind> ColorAttachment<S, K> {
     pub fn with_depth(&self, depth: DepthAttachment) -> Framebuffer<S> {
-        Framebuffer(FramebufferInternal::ColorDepth {
-            color: self.clone(),
+        Framebuffer::from_internal(FramebufferInternal::ColorDepth {
+            color: [self.raw.clone()].into_iter().collect(),
             depth,
         })
     }
 }
 
-impl<S: ColorSample> From<ColorAttachment<S>> for Framebuffer<S> {
-    fn from(value: ColorAttachment<S>) -> Self {
-        Framebuffer(FramebufferInternal::Color(value))
+impl<S: ColorSample, K: AttachmentKind> From<ColorAttachment<S, K>> for Framebuffer<S> {
+    fn from(value: ColorAttachment<S, K>) -> Self {
+        Framebuffer::from_internal(FramebufferInternal::Color(
+            [value.raw].into_iter().collect(),
+        ))
     }
 }
 
@@ -56,55 +93,67 @@ impl DepthAttachment {
     }
 
     pub fn with_color<F: FsInterface<Sl>>(&self, color: F::Gl) -> Framebuffer<F> {
-        Framebuffer(FramebufferInternal::ColorDepth {
-            color,
-            depth: self.clone(),
-        })
+        Framebuffer::new_color_depth(color, self.clone())
     }
 }
 
 impl From<DepthAttachment> for Framebuffer<()> {
     fn from(value: DepthAttachment) -> Self {
-        Framebuffer(FramebufferInternal::Depth(value))
+        Framebuffer::new_depth(value)
     }
 }
 
+// Color attachments are stored in their raw form, so that attachments of
+// different kinds can be stored in the same framebuffer type.
 #[derive(Clone)]
-enum FramebufferInternal<F: FsInterface<Sl>> {
+enum FramebufferInternal {
     Default,
     Depth(DepthAttachment),
-    Color(F::Gl),
+    Color(raw::AttachmentVec),
     ColorDepth {
-        color: F::Gl,
+        color: raw::AttachmentVec,
         depth: DepthAttachment,
     },
 }
 
 #[derive(Clone)]
-pub struct Framebuffer<F: FsInterface<Sl> = sl::Vec4>(FramebufferInternal<F>);
+pub struct Framebuffer<F: FsInterface<Sl> = sl::Vec4> {
+    internal: FramebufferInternal,
+    _phantom: PhantomData<F>,
+}
 
 impl<F: FsInterface<Sl>> Framebuffer<F> {
+    fn from_internal(internal: FramebufferInternal) -> Self {
+        Self {
+            internal,
+            _phantom: PhantomData,
+        }
+    }
+
     pub fn new_color(color: F::Gl) -> Self {
-        Framebuffer(FramebufferInternal::Color(color))
+        Self::from_internal(FramebufferInternal::Color(raw_color_attachments(&color)))
     }
 
     pub fn new_color_depth(color: F::Gl, depth: DepthAttachment) -> Self {
-        Framebuffer(FramebufferInternal::ColorDepth { color, depth })
+        Self::from_internal(FramebufferInternal::ColorDepth {
+            color: raw_color_attachments(&color),
+            depth,
+        })
     }
 
     pub fn raw(&self) -> raw::Framebuffer {
-        match &self.0 {
+        match &self.internal {
             FramebufferInternal::Default => raw::Framebuffer::Default,
             FramebufferInternal::Depth(depth) => raw::Framebuffer::Attachments {
                 color_attachments: Default::default(),
                 depth_attachment: Some(depth.raw.clone()),
             },
             FramebufferInternal::Color(color) => raw::Framebuffer::Attachments {
-                color_attachments: raw_color_attachments(color),
+                color_attachments: color.clone(),
                 depth_attachment: None,
             },
             FramebufferInternal::ColorDepth { color, depth } => raw::Framebuffer::Attachments {
-                color_attachments: raw_color_attachments(color),
+                color_attachments: color.clone(),
                 depth_attachment: Some(depth.raw.clone()),
             },
         }
@@ -113,20 +162,20 @@ impl<F: FsInterface<Sl>> Framebuffer<F> {
 
 impl Framebuffer<()> {
     pub fn new_depth(depth: DepthAttachment) -> Self {
-        Framebuffer(FramebufferInternal::Depth(depth))
+        Self::from_internal(FramebufferInternal::Depth(depth))
     }
 }
 
 impl Default for Framebuffer<sl::Vec4> {
     fn default() -> Self {
-        Framebuffer(FramebufferInternal::Default)
+        Self::from_internal(FramebufferInternal::Default)
     }
 }
 
 fn raw_color_attachments<F: FsInterface<Gl>>(attachments: &F) -> raw::AttachmentVec {
     struct Visitor(raw::AttachmentVec);
 
-    impl<'a> FragmentVisitor<'a, Gl> for Visitor {
+    impl FragmentVisitor<Gl> for Visitor {
         fn accept<S: ColorSample>(&mut self, _: &str, attachment: &ColorAttachment<S>) {
             self.0.push(attachment.raw.clone());
         }
@@ -32,10 +32,65 @@ pub struct Mat4 {
     pub w_axis: Vec4,
 }
 
+/// A floating-point matrix with two columns and three rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat2x3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+}
+
+/// A floating-point matrix with two columns and four rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat2x4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+}
+
+/// A floating-point matrix with three columns and two rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat3x2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub z_axis: Vec2,
+}
+
+/// A floating-point matrix with three columns and four rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat3x4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+    pub z_axis: Vec4,
+}
+
+/// A floating-point matrix with four columns and two rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat4x2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub z_axis: Vec2,
+    pub w_axis: Vec2,
+}
+
+/// A floating-point matrix with four columns and three rows.
+#[derive(Clone, Copy, Zeroable, Pod, Debug, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Mat4x3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+    pub z_axis: Vec3,
+    pub w_axis: Vec3,
+}
+
 macro_rules! impl_convs {
     (
         $mat:ident,
-        $size:tt,
+        $cols:tt,
+        $rows:tt,
         $mint:ty,
         ($($field:ident),+),
         ($($field_crevice:ident),+)
@@ -52,9 +107,9 @@ macro_rules! impl_convs {
             }
         }
 
-        impl From<[[f32; $size]; $size]> for $mat {
+        impl From<[[f32; $rows]; $cols]> for $mat {
             #[allow(unused)]
-            fn from(value: [[f32; $size]; $size]) -> Self {
+            fn from(value: [[f32; $rows]; $cols]) -> Self {
                 let mut i = 0;
 
                 $(
@@ -66,7 +121,7 @@ macro_rules! impl_convs {
             }
         }
 
-        impl From<$mat> for [[f32; $size]; $size] {
+        impl From<$mat> for [[f32; $rows]; $cols] {
             fn from(value: $mat) -> Self {
                 [$(value.$field.into()),+]
             }
@@ -85,7 +140,11 @@ macro_rules! impl_convs {
                 Self { $($field_crevice: value.$field.into()),+ }
             }
         }
+    };
+}
 
+macro_rules! impl_glam_convs {
+    ($mat:ident, ($($field:ident),+)) => {
         #[cfg(feature = "glam")]
         impl From<glam::$mat> for $mat {
             fn from(value: glam::$mat) -> Self {
@@ -102,10 +161,18 @@ macro_rules! impl_convs {
     };
 }
 
-impl_convs!(Mat2, 2, mint::ColumnMatrix2<f32>, (x_axis, y_axis), (x, y));
+impl_convs!(
+    Mat2,
+    2,
+    2,
+    mint::ColumnMatrix2<f32>,
+    (x_axis, y_axis),
+    (x, y)
+);
 impl_convs!(
     Mat3,
     3,
+    3,
     mint::ColumnMatrix3<f32>,
     (x_axis, y_axis, z_axis),
     (x, y, z)
@@ -113,11 +180,67 @@ impl_convs!(
 impl_convs!(
     Mat4,
     4,
+    4,
     mint::ColumnMatrix4<f32>,
     (x_axis, y_axis, z_axis, w_axis),
     (x, y, z, w)
 );
 
+// Note that `mint` names matrices by rows times columns, whereas GLSL names
+// them by columns times rows.
+impl_convs!(
+    Mat2x3,
+    2,
+    3,
+    mint::ColumnMatrix3x2<f32>,
+    (x_axis, y_axis),
+    (x, y)
+);
+impl_convs!(
+    Mat2x4,
+    2,
+    4,
+    mint::ColumnMatrix4x2<f32>,
+    (x_axis, y_axis),
+    (x, y)
+);
+impl_convs!(
+    Mat3x2,
+    3,
+    2,
+    mint::ColumnMatrix2x3<f32>,
+    (x_axis, y_axis, z_axis),
+    (x, y, z)
+);
+impl_convs!(
+    Mat3x4,
+    3,
+    4,
+    mint::ColumnMatrix4x3<f32>,
+    (x_axis, y_axis, z_axis),
+    (x, y, z)
+);
+impl_convs!(
+    Mat4x2,
+    4,
+    2,
+    mint::ColumnMatrix2x4<f32>,
+    (x_axis, y_axis, z_axis, w_axis),
+    (x, y, z, w)
+);
+impl_convs!(
+    Mat4x3,
+    4,
+    3,
+    mint::ColumnMatrix3x4<f32>,
+    (x_axis, y_axis, z_axis, w_axis),
+    (x, y, z, w)
+);
+
+impl_glam_convs!(Mat2, (x_axis, y_axis));
+impl_glam_convs!(Mat3, (x_axis, y_axis, z_axis));
+impl_glam_convs!(Mat4, (x_axis, y_axis, z_axis, w_axis));
+
 impl AsStd140 for Mat2 {
     type Output = crevice::std140::Mat2;
 
@@ -182,3 +305,95 @@ impl AsStd140 for Mat4 {
         }
     }
 }
+
+/// Layouts of non-square matrices in `std140`, which `crevice` does not
+/// provide.
+///
+/// In `std140`, a matrix is laid out like an array of its column vectors, so
+/// each column is padded to 16 bytes.
+#[doc(hidden)]
+pub mod std140 {
+    use bytemuck::{Pod, Zeroable};
+    use crevice::std140::{Std140, Vec2, Vec3, Vec4};
+
+    macro_rules! std140_mat {
+        ($name:ident, $col:ident, $pad:ty, ($($field:ident, $pad_field:ident),+)) => {
+            #[derive(Debug, Clone, Copy)]
+            #[repr(C)]
+            pub struct $name {
+                $(
+                    pub $field: $col,
+                    pub $pad_field: $pad,
+                )+
+            }
+
+            unsafe impl Zeroable for $name {}
+            unsafe impl Pod for $name {}
+
+            unsafe impl Std140 for $name {
+                const ALIGNMENT: usize = 16;
+            }
+        };
+    }
+
+    std140_mat!(Mat2x3, Vec3, f32, (x, _pad_x, y, _pad_y));
+    std140_mat!(Mat2x4, Vec4, [f32; 0], (x, _pad_x, y, _pad_y));
+    std140_mat!(Mat3x2, Vec2, [f32; 2], (x, _pad_x, y, _pad_y, z, _pad_z));
+    std140_mat!(Mat3x4, Vec4, [f32; 0], (x, _pad_x, y, _pad_y, z, _pad_z));
+    std140_mat!(
+        Mat4x2,
+        Vec2,
+        [f32; 2],
+        (x, _pad_x, y, _pad_y, z, _pad_z, w, _pad_w)
+    );
+    std140_mat!(
+        Mat4x3,
+        Vec3,
+        f32,
+        (x, _pad_x, y, _pad_y, z, _pad_z, w, _pad_w)
+    );
+}
+
+macro_rules! impl_as_std140 {
+    ($mat:ident, ($($field:ident, $field_std140:ident, $pad_field:ident),+)) => {
+        impl AsStd140 for $mat {
+            type Output = std140::$mat;
+
+            fn as_std140(&self) -> Self::Output {
+                Self::Output {
+                    $(
+                        $field_std140: self.$field.as_std140(),
+                        $pad_field: Default::default(),
+                    )+
+                }
+            }
+
+            fn from_std140(value: Self::Output) -> Self {
+                Self {
+                    $(
+                        $field: AsStd140::from_std140(value.$field_std140),
+                    )+
+                }
+            }
+        }
+    };
+}
+
+impl_as_std140!(Mat2x3, (x_axis, x, _pad_x, y_axis, y, _pad_y));
+impl_as_std140!(Mat2x4, (x_axis, x, _pad_x, y_axis, y, _pad_y));
+impl_as_std140!(
+    Mat3x2,
+    (x_axis, x, _pad_x, y_axis, y, _pad_y, z_axis, z, _pad_z)
+);
+impl_as_std140!(
+    Mat3x4,
+    (x_axis, x, _pad_x, y_axis, y, _pad_y, z_axis, z, _pad_z)
+);
+impl_as_std140!(
+    Mat4x2,
+    (x_axis, x, _pad_x, y_axis, y, _pad_y, z_axis, z, _pad_z, w_axis, w, _pad_w)
+);
+impl_as_std140!(
+    Mat4x3,
+    (x_axis, x, _pad_x, y_axis, y, _pad_y, z_axis, z, _pad_z, w_axis, w, _pad_w)
+);
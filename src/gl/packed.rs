@@ -0,0 +1,261 @@
+use bytemuck::{Pod, Zeroable};
+use crevice::std140::AsStd140;
+
+use crate::{sl, ToSl};
+
+use super::{Vec2, Vec4};
+
+macro_rules! impl_packed {
+    (
+        $ty:ident,
+        $component:ty,
+        $size:literal,
+        $vec:ident,
+        $encode:ident,
+        $decode:ident,
+        $doc:literal
+    ) => {
+        #[doc = $doc]
+        ///
+        /// In vertex blocks, the components are converted to floating-point
+        /// values when vertex attributes are fetched, so the shader sees
+        #[doc = concat!("[`sl::", stringify!($vec), "`]. In uniform blocks, the value is")]
+        /// converted on the host.
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Zeroable, Pod)]
+        #[repr(transparent)]
+        pub struct $ty(pub [$component; $size]);
+
+        impl From<[f32; $size]> for $ty {
+            fn from(value: [f32; $size]) -> Self {
+                Self(value.map($encode))
+            }
+        }
+
+        impl From<$ty> for [f32; $size] {
+            fn from(value: $ty) -> Self {
+                value.0.map($decode)
+            }
+        }
+
+        impl From<$vec> for $ty {
+            fn from(value: $vec) -> Self {
+                <[f32; $size]>::from(value).into()
+            }
+        }
+
+        impl From<$ty> for $vec {
+            fn from(value: $ty) -> Self {
+                <[f32; $size]>::from(value).into()
+            }
+        }
+
+        impl AsStd140 for $ty {
+            type Output = <$vec as AsStd140>::Output;
+
+            fn as_std140(&self) -> Self::Output {
+                $vec::from(*self).as_std140()
+            }
+
+            fn from_std140(value: Self::Output) -> Self {
+                $vec::from_std140(value).into()
+            }
+        }
+
+        impl ToSl for $ty {
+            type Output = sl::$vec;
+
+            fn to_sl(self) -> Self::Output {
+                $vec::from(self).to_sl()
+            }
+        }
+    };
+}
+
+impl_packed!(
+    Unorm8x2,
+    u8,
+    2,
+    Vec2,
+    encode_unorm8,
+    decode_unorm8,
+    "A two-dimensional vector of unsigned normalized 8-bit values."
+);
+impl_packed!(
+    Unorm8x4,
+    u8,
+    4,
+    Vec4,
+    encode_unorm8,
+    decode_unorm8,
+    "A four-dimensional vector of unsigned normalized 8-bit values."
+);
+impl_packed!(
+    Snorm8x2,
+    i8,
+    2,
+    Vec2,
+    encode_snorm8,
+    decode_snorm8,
+    "A two-dimensional vector of signed normalized 8-bit values."
+);
+impl_packed!(
+    Snorm8x4,
+    i8,
+    4,
+    Vec4,
+    encode_snorm8,
+    decode_snorm8,
+    "A four-dimensional vector of signed normalized 8-bit values."
+);
+impl_packed!(
+    Unorm16x2,
+    u16,
+    2,
+    Vec2,
+    encode_unorm16,
+    decode_unorm16,
+    "A two-dimensional vector of unsigned normalized 16-bit values."
+);
+impl_packed!(
+    Unorm16x4,
+    u16,
+    4,
+    Vec4,
+    encode_unorm16,
+    decode_unorm16,
+    "A four-dimensional vector of unsigned normalized 16-bit values."
+);
+impl_packed!(
+    Snorm16x2,
+    i16,
+    2,
+    Vec2,
+    encode_snorm16,
+    decode_snorm16,
+    "A two-dimensional vector of signed normalized 16-bit values."
+);
+impl_packed!(
+    Snorm16x4,
+    i16,
+    4,
+    Vec4,
+    encode_snorm16,
+    decode_snorm16,
+    "A four-dimensional vector of signed normalized 16-bit values."
+);
+impl_packed!(
+    F16x2,
+    u16,
+    2,
+    Vec2,
+    encode_f16,
+    decode_f16,
+    "A two-dimensional vector of 16-bit floating-point values, stored as bits."
+);
+impl_packed!(
+    F16x4,
+    u16,
+    4,
+    Vec4,
+    encode_f16,
+    decode_f16,
+    "A four-dimensional vector of 16-bit floating-point values, stored as bits."
+);
+
+fn encode_unorm8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn decode_unorm8(value: u8) -> f32 {
+    f32::from(value) / 255.0
+}
+
+fn encode_snorm8(value: f32) -> i8 {
+    (value.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
+
+fn decode_snorm8(value: i8) -> f32 {
+    (f32::from(value) / 127.0).max(-1.0)
+}
+
+pub(crate) fn encode_unorm16(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+pub(crate) fn decode_unorm16(value: u16) -> f32 {
+    f32::from(value) / 65535.0
+}
+
+pub(crate) fn encode_snorm16(value: f32) -> i16 {
+    (value.clamp(-1.0, 1.0) * 32767.0).round() as i16
+}
+
+pub(crate) fn decode_snorm16(value: i16) -> f32 {
+    (f32::from(value) / 32767.0).max(-1.0)
+}
+
+// Converts to the nearest half-precision value, with ties to even.
+pub(crate) fn encode_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exp == 0xff {
+        // Infinity or NaN.
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+
+    let exp = exp - 127 + 15;
+
+    if exp >= 0x1f {
+        // Overflow to infinity.
+        return sign | 0x7c00;
+    }
+
+    if exp <= 0 {
+        // Subnormal or zero.
+        if exp < -10 {
+            return sign;
+        }
+
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exp) as u32;
+        let halfway = 1 << (shift - 1);
+        let rem = mantissa & ((1 << shift) - 1);
+        let mut half = (mantissa >> shift) as u16;
+
+        if rem > halfway || (rem == halfway && half & 1 == 1) {
+            half += 1;
+        }
+
+        return sign | half;
+    }
+
+    let mut half = sign | ((exp as u16) << 10) | (mantissa >> 13) as u16;
+    let rem = mantissa & 0x1fff;
+
+    // A carry into the exponent correctly rounds up to the next power of two
+    // or to infinity.
+    if rem > 0x1000 || (rem == 0x1000 && half & 1 == 1) {
+        half += 1;
+    }
+
+    half
+}
+
+pub(crate) fn decode_f16(value: u16) -> f32 {
+    let sign = u32::from(value & 0x8000) << 16;
+    let exp = u32::from((value >> 10) & 0x1f);
+    let mantissa = u32::from(value & 0x3ff);
+
+    match exp {
+        0 => {
+            let magnitude = mantissa as f32 * f32::powi(2.0, -24);
+
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exp + 112) << 23) | (mantissa << 13)),
+    }
+}
@@ -0,0 +1,68 @@
+use bytemuck::Pod;
+use glow::HasContext;
+
+use crate::sl::{dag::BuiltInType, program_def::Es100ProgramDef};
+
+/// Uploads the contents of uniform blocks to the plain uniforms of a GLSL ES
+/// 1.00 program.
+///
+/// `blocks` holds the `std140` data of each uniform block in
+/// `def.uniform_block_defs`, as given by
+/// [`Std140::as_bytes`](crevice::std140::Std140::as_bytes). Each entry of
+/// `def.plain_uniform_defs` is read from its block and uploaded with the
+/// matching `glUniform*` call. Uniforms that have been removed by the linker
+/// are skipped.
+///
+/// # Panics
+///
+/// Panics if the wrong number of uniform blocks is supplied, or if a block is
+/// too short to hold its uniforms.
+///
+/// # Safety
+///
+/// `program` must be a program that has been linked from the sources in
+/// `def`, and it must be in use by `gl`.
+pub unsafe fn upload_plain_uniforms(
+    gl: &glow::Context,
+    program: glow::Program,
+    def: &Es100ProgramDef,
+    blocks: &[&[u8]],
+) {
+    use BuiltInType::*;
+
+    assert_eq!(blocks.len(), def.uniform_block_defs.len());
+
+    for uniform_def in &def.plain_uniform_defs {
+        let location = gl.get_uniform_location(program, &uniform_def.name);
+        let location = location.as_ref();
+
+        let block = &blocks[uniform_def.block];
+        let offset = uniform_def.offset;
+
+        // Booleans are stored as 32-bit integers in `std140`, and GLSL ES
+        // 1.00 expects them to be uploaded as such.
+        match uniform_def.ty {
+            F32 => gl.uniform_1_f32_slice(location, &read::<f32>(block, offset, 1, 1)),
+            Vec2 => gl.uniform_2_f32_slice(location, &read::<f32>(block, offset, 1, 2)),
+            Vec3 => gl.uniform_3_f32_slice(location, &read::<f32>(block, offset, 1, 3)),
+            Vec4 => gl.uniform_4_f32_slice(location, &read::<f32>(block, offset, 1, 4)),
+            I32 | Bool => gl.uniform_1_i32_slice(location, &read::<i32>(block, offset, 1, 1)),
+            IVec2 | BVec2 => gl.uniform_2_i32_slice(location, &read::<i32>(block, offset, 1, 2)),
+            IVec3 | BVec3 => gl.uniform_3_i32_slice(location, &read::<i32>(block, offset, 1, 3)),
+            IVec4 | BVec4 => gl.uniform_4_i32_slice(location, &read::<i32>(block, offset, 1, 4)),
+            Mat2 => gl.uniform_matrix_2_f32_slice(location, false, &read(block, offset, 2, 2)),
+            Mat3 => gl.uniform_matrix_3_f32_slice(location, false, &read(block, offset, 3, 3)),
+            Mat4 => gl.uniform_matrix_4_f32_slice(location, false, &read(block, offset, 4, 4)),
+            ty => panic!("GLSL ES 1.00 does not support plain uniforms of type {ty:?}"),
+        }
+    }
+}
+
+/// Reads `columns` columns of `rows` components each, where columns are 16
+/// bytes apart.
+fn read<T: Pod>(block: &[u8], offset: usize, columns: usize, rows: usize) -> Vec<T> {
+    (0..columns)
+        .flat_map(|column| (0..rows).map(move |row| offset + column * 16 + row * 4))
+        .map(|offset| bytemuck::pod_read_unaligned(&block[offset..offset + 4]))
+        .collect()
+}
@@ -9,8 +9,9 @@ use crate::{
 };
 
 use super::{
-    raw, ColorSampler2d, ComparisonSampler2d, DrawError, DrawParams, Framebuffer,
-    UniformBufferBinding, VertexSpec,
+    raw, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+    ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, DrawError, DrawParams,
+    Framebuffer, UniformBufferBinding, VertexSpec,
 };
 
 type RawProgram = Result<Rc<raw::Program>, DrawError>;
@@ -341,4 +342,37 @@ impl<'a> UniformVisitor<'a, Gl> for CollectUniforms<'a> {
         self.raw_samplers
             .push(raw::Sampler::Sampler2d(sampler.raw().clone()))
     }
+
+    fn accept_color_sampler_cube<S: ColorSample>(
+        &mut self,
+        _: &str,
+        sampler: &ColorSamplerCube<S>,
+    ) {
+        self.raw_samplers
+            .push(raw::Sampler::SamplerCube(sampler.raw().clone()))
+    }
+
+    fn accept_comparison_sampler_cube(&mut self, _: &str, sampler: &ComparisonSamplerCube) {
+        self.raw_samplers
+            .push(raw::Sampler::SamplerCube(sampler.raw().clone()))
+    }
+
+    fn accept_color_sampler_3d<S: ColorSample>(&mut self, _: &str, sampler: &ColorSampler3d<S>) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_color_sampler_2d_array<S: ColorSample>(
+        &mut self,
+        _: &str,
+        sampler: &ColorSampler2dArray<S>,
+    ) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
+
+    fn accept_comparison_sampler_2d_array(&mut self, _: &str, sampler: &ComparisonSampler2dArray) {
+        self.raw_samplers
+            .push(raw::Sampler::Sampler3d(sampler.raw().clone()))
+    }
 }
@@ -27,8 +27,13 @@ pub use params::{
     StencilOp, StencilOps, StencilTest,
 };
 pub use program::Program;
-pub use sampler_params::{Sampler2dParams, SamplerMagFilter, SamplerMinFilter, SamplerWrap};
-pub use texture::{Sampler, Sampler2d, Texture2d};
+pub use sampler_params::{
+    Sampler2dParams, Sampler3dParams, SamplerMagFilter, SamplerMinFilter, SamplerWrap,
+};
+pub use texture::{
+    CubeFace, Sampler, Sampler2d, Sampler3d, SamplerCube, Texture2d, Texture3d, Texture3dTarget,
+    TextureCube,
+};
 pub use tracing::{FrameTrace, TracingConfig};
 pub use vertex_spec::{
     ElementType, PrimitiveMode, VertexBufferBinding, VertexBufferBindingVec, VertexSpec,
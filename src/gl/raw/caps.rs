@@ -1,30 +1,66 @@
 use glow::HasContext;
 
+use crate::sl::transpile::GlslTarget;
+
 #[derive(Debug, Copy, Clone)]
 pub struct Caps {
     pub max_texture_size: u32,
+    pub max_cube_map_texture_size: u32,
+    pub max_3d_texture_size: u32,
+    pub max_array_texture_layers: u32,
     pub max_color_attachments: u32,
     pub max_draw_buffers: u32,
     pub disjoint_timer_query_webgl2: bool,
+    pub glsl_target: GlslTarget,
 }
 
 impl Caps {
     pub fn new(gl: &glow::Context) -> Self {
         let max_texture_size = unsafe { gl.get_parameter_i32(glow::MAX_TEXTURE_SIZE) };
+        let max_cube_map_texture_size =
+            unsafe { gl.get_parameter_i32(glow::MAX_CUBE_MAP_TEXTURE_SIZE) };
+        let max_3d_texture_size = unsafe { gl.get_parameter_i32(glow::MAX_3D_TEXTURE_SIZE) };
+        let max_array_texture_layers =
+            unsafe { gl.get_parameter_i32(glow::MAX_ARRAY_TEXTURE_LAYERS) };
         let max_color_attachments = unsafe { gl.get_parameter_i32(glow::MAX_COLOR_ATTACHMENTS) };
         let max_draw_buffers = unsafe { gl.get_parameter_i32(glow::MAX_DRAW_BUFFERS) };
 
         assert!(max_texture_size > 0);
+        assert!(max_cube_map_texture_size > 0);
+        assert!(max_3d_texture_size > 0);
+        assert!(max_array_texture_layers > 0);
         assert!(max_color_attachments > 0);
         assert!(max_draw_buffers > 0);
 
         Caps {
             max_texture_size: max_texture_size.try_into().unwrap(),
+            max_cube_map_texture_size: max_cube_map_texture_size.try_into().unwrap(),
+            max_3d_texture_size: max_3d_texture_size.try_into().unwrap(),
+            max_array_texture_layers: max_array_texture_layers.try_into().unwrap(),
             max_color_attachments: max_color_attachments.try_into().unwrap(),
             max_draw_buffers: max_draw_buffers.try_into().unwrap(),
             disjoint_timer_query_webgl2: gl
                 .supported_extensions()
                 .contains("EXT_disjoint_timer_query_webgl2"),
+            glsl_target: glsl_target(gl.version()),
+        }
+    }
+}
+
+fn glsl_target(version: &glow::Version) -> GlslTarget {
+    let version_num = (version.major, version.minor);
+
+    // Contexts that are too old for any of our targets will fail to compile
+    // shaders, so we just pick the oldest target for them.
+    if version.is_embedded {
+        if version_num >= (3, 1) {
+            GlslTarget::Es310
+        } else {
+            GlslTarget::Es300
         }
+    } else if version_num >= (4, 1) {
+        GlslTarget::Core410
+    } else {
+        GlslTarget::Core330
     }
 }
@@ -12,9 +12,9 @@ use crate::{
 };
 
 use super::{
-    params::ClearParams, tracing::Tracing, Attachment, Buffer, Caps, ContextError, DrawParams,
-    FrameTrace, Framebuffer, FramebufferError, Image, Program, Texture2d, TextureError,
-    TracingConfig,
+    framebuffer::AttachmentBinding, params::ClearParams, tracing::Tracing, Attachment, Buffer,
+    Caps, ContextError, DrawParams, FrameTrace, Framebuffer, FramebufferError, Image, Program,
+    Texture2d, Texture3d, Texture3dTarget, TextureCube, TextureError, TracingConfig,
 };
 
 pub(super) struct ContextShared {
@@ -29,10 +29,10 @@ pub(super) struct ContextShared {
     // put that whole thing into a `RefCell`.
     bound_program_id: Cell<Option<glow::Program>>,
     is_draw_fbo_bound: Cell<bool>,
-    bound_color_attachment_ids: RefCell<Vec<glow::Texture>>,
-    bound_depth_attachment_id: Cell<Option<(u32, glow::Texture)>>,
+    bound_color_attachments: RefCell<Vec<AttachmentBinding>>,
+    bound_depth_attachment: Cell<Option<(u32, AttachmentBinding)>>,
     bound_uniform_buffer_ids: RefCell<Vec<Option<glow::Buffer>>>,
-    bound_texture_2d_ids: RefCell<Vec<Option<glow::Texture>>>,
+    bound_texture_ids: RefCell<Vec<Option<(u32, glow::Texture)>>>,
 }
 
 pub struct Context {
@@ -104,65 +104,33 @@ impl ContextShared {
             });
         }
 
-        let mut bound_ids = self.bound_color_attachment_ids.borrow_mut();
+        let mut bound = self.bound_color_attachments.borrow_mut();
 
         for (idx, attachment) in attachments.iter().enumerate() {
-            if idx < bound_ids.len() && bound_ids[idx] == attachment.id() {
+            let binding = attachment.binding();
+
+            if idx < bound.len() && bound[idx] == binding {
                 continue;
             }
 
-            match attachment {
-                Attachment::Texture2d { texture, level } => {
-                    // OpenGL ES 3.0.6: 4.4.2.4 Attaching Texture Images to a
-                    // Framebuffer
-                    // > If `textarget` is `TEXTURE_2D`, `level` must be greater
-                    // > than or equal to zero and no larger than `log_2` of the
-                    // > value of `MAX_TEXTURE_SIZE`.
-
-                    assert!(texture.internal_format().is_color_renderable());
-
-                    let max_level = (self.caps.max_texture_size as f64).log2() as u32;
-
-                    if *level > max_level {
-                        return Err(FramebufferError::LevelTooLarge {
-                            requested: *level,
-                            max: max_level,
-                        });
-                    }
-
-                    let location = glow::COLOR_ATTACHMENT0 + idx as u32;
-                    let level: i32 = (*level).try_into().expect("level is out of i32 range");
-
-                    unsafe {
-                        self.gl.framebuffer_texture_2d(
-                            glow::FRAMEBUFFER,
-                            location,
-                            glow::TEXTURE_2D,
-                            Some(texture.id()),
-                            level,
-                        )
-                    };
-                }
-            };
+            assert!(attachment.internal_format().is_color_renderable());
+
+            self.validate_attachment(attachment)?;
+
+            let location = glow::COLOR_ATTACHMENT0 + idx as u32;
+
+            self.attach(location, Some(binding));
         }
 
-        if bound_ids.len() > attachments.len() {
-            for idx in attachments.len()..bound_ids.len() {
+        if bound.len() > attachments.len() {
+            for idx in attachments.len()..bound.len() {
                 let location = glow::COLOR_ATTACHMENT0 + idx as u32;
 
-                unsafe {
-                    self.gl.framebuffer_texture_2d(
-                        glow::FRAMEBUFFER,
-                        location,
-                        glow::TEXTURE_2D,
-                        None,
-                        0,
-                    )
-                };
+                self.attach(location, None);
             }
         }
 
-        if bound_ids.len() != attachments.len() {
+        if bound.len() != attachments.len() {
             let draw_buffers: SmallVec<[_; 8]> = (0..attachments.len())
                 .map(|idx| glow::COLOR_ATTACHMENT0 + idx as u32)
                 .collect();
@@ -170,8 +138,8 @@ impl ContextShared {
             unsafe { self.gl.draw_buffers(&draw_buffers) };
         }
 
-        bound_ids.clear();
-        bound_ids.extend(attachments.iter().map(|attachment| attachment.id()));
+        bound.clear();
+        bound.extend(attachments.iter().map(|attachment| attachment.binding()));
 
         // FIXME: Do we need to check for the presence of at least one
         // attachment?
@@ -179,12 +147,22 @@ impl ContextShared {
         Ok(())
     }
 
-    fn bind_depth_attachment(&self, attachment: Option<&Attachment>) {
-        let attachment_id = attachment.map(|attachment| attachment.id());
-        let bound_id = self.bound_depth_attachment_id.get().map(|(_, id)| id);
+    fn bind_depth_attachment(
+        &self,
+        attachment: Option<&Attachment>,
+    ) -> Result<(), FramebufferError> {
+        let binding = attachment.map(|attachment| attachment.binding());
+        let bound_binding = self
+            .bound_depth_attachment
+            .get()
+            .map(|(_, binding)| binding);
 
-        if attachment_id == bound_id {
-            return;
+        if binding == bound_binding {
+            return Ok(());
+        }
+
+        if let Some(attachment) = attachment {
+            self.validate_attachment(attachment)?;
         }
 
         let attachment = attachment.map(|attachment| {
@@ -199,58 +177,150 @@ impl ContextShared {
 
         let location = attachment.map(|(location, _)| location);
         let bound_location = self
-            .bound_depth_attachment_id
+            .bound_depth_attachment
             .get()
             .map(|(bound_location, _)| bound_location);
 
         if let Some(bound_location) =
             bound_location.filter(|bound_location| Some(*bound_location) != location)
         {
-            // TODO: In the future, the existing binding could be a non-2D
-            // texture, but we are currently using the 2D unbinding function in
-            // all cases. Is this correct?
+            self.attach(bound_location, None);
+        }
+
+        if let Some((location, attachment)) = attachment {
+            self.attach(location, Some(attachment.binding()));
+        }
+
+        self.bound_depth_attachment
+            .set(attachment.map(|(location, attachment)| (location, attachment.binding())));
+
+        Ok(())
+    }
+
+    fn validate_attachment(&self, attachment: &Attachment) -> Result<(), FramebufferError> {
+        let binding = attachment.binding();
+
+        // OpenGL ES 3.0.6: 4.4.2.4 Attaching Texture Images to a Framebuffer
+        // > If `textarget` is `TEXTURE_2D`, `level` must be greater than or
+        // > equal to zero and no larger than `log_2` of the value of
+        // > `MAX_TEXTURE_SIZE`.
+        // >
+        // > If `textarget` is one of `TEXTURE_CUBE_MAP_POSITIVE_X`, [...],
+        // > `level` must be greater than or equal to zero and no larger than
+        // > `log_2` of the value of `MAX_CUBE_MAP_TEXTURE_SIZE`.
+        let max_level = self.max_attachment_level(attachment);
+
+        if binding.level > max_level {
+            return Err(FramebufferError::LevelTooLarge {
+                requested: binding.level,
+                max: max_level,
+            });
+        }
+
+        // OpenGL ES 3.0.6: 4.4.4.1 Framebuffer Attachment Completeness
+        // > If [...] `FRAMEBUFFER_ATTACHMENT_OBJECT_NAME` names a
+        // > three-dimensional texture, then the value of
+        // > `FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER` must be smaller than the
+        // > depth of the texture.
+        // >
+        // > If [...] `FRAMEBUFFER_ATTACHMENT_OBJECT_NAME` names a
+        // > two-dimensional array texture, then the value of
+        // > `FRAMEBUFFER_ATTACHMENT_TEXTURE_LAYER` must be smaller than the
+        // > number of layers in the texture.
+        //
+        // The depth of a 3D texture shrinks with each mipmap level, while the
+        // number of layers of an array texture does not.
+        if let (Some(layer), Attachment::Texture3d { texture, level, .. }) =
+            (binding.layer, attachment)
+        {
+            let [_, _, depth] = texture.size();
+            let layers = match texture.target() {
+                Texture3dTarget::Texture3d => depth.checked_shr(*level).unwrap_or(0).max(1),
+                Texture3dTarget::Texture2dArray => depth,
+            };
+
+            if layer >= layers {
+                return Err(FramebufferError::LayerOutOfBounds {
+                    requested: layer,
+                    layers,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn max_attachment_level(&self, attachment: &Attachment) -> u32 {
+        let max_size = match attachment {
+            Attachment::Texture2d { .. } => self.caps.max_texture_size,
+            Attachment::TextureCube { .. } => self.caps.max_cube_map_texture_size,
+            Attachment::Texture3d { texture, .. } => match texture.target() {
+                Texture3dTarget::Texture3d => self.caps.max_3d_texture_size,
+                Texture3dTarget::Texture2dArray => self.caps.max_texture_size,
+            },
+        };
+
+        (max_size as f64).log2() as u32
+    }
+
+    fn attach(&self, location: u32, binding: Option<AttachmentBinding>) {
+        // OpenGL ES 3.0.6: 4.4.2.4 Attaching Texture Images to a Framebuffer
+        // > If `texture` is zero, any image or array of images attached to the
+        // > attachment point named by `attachment` is detached. Any additional
+        // > parameters (`level`, `textarget`, and/or `layer`) are ignored when
+        // > `texture` is zero.
+        let Some(binding) = binding else {
             unsafe {
                 self.gl.framebuffer_texture_2d(
                     glow::FRAMEBUFFER,
-                    bound_location,
+                    location,
                     glow::TEXTURE_2D,
                     None,
                     0,
                 )
             };
-        }
 
-        if let Some((location, attachment)) = attachment {
-            match attachment {
-                Attachment::Texture2d { level, texture } => {
-                    let level = (*level).try_into().expect("level is out of i32 range");
-
-                    unsafe {
-                        self.gl.framebuffer_texture_2d(
-                            glow::FRAMEBUFFER,
-                            location,
-                            glow::TEXTURE_2D,
-                            Some(texture.id()),
-                            level,
-                        )
-                    };
-                }
-            }
-        }
+            return;
+        };
+
+        let level = binding.level.try_into().expect("level is out of i32 range");
+
+        if let Some(layer) = binding.layer {
+            let layer = layer.try_into().expect("layer is out of i32 range");
 
-        self.bound_depth_attachment_id
-            .set(attachment.map(|(location, attachment)| (location, attachment.id())));
+            unsafe {
+                self.gl.framebuffer_texture_layer(
+                    glow::FRAMEBUFFER,
+                    location,
+                    Some(binding.id),
+                    level,
+                    layer,
+                )
+            };
+        } else {
+            unsafe {
+                self.gl.framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    location,
+                    binding.target,
+                    Some(binding.id),
+                    level,
+                )
+            };
+        }
     }
 
-    pub(super) fn bind_texture_2d(&self, unit: usize, id: Option<glow::Texture>) {
-        let mut bound_ids = self.bound_texture_2d_ids.borrow_mut();
+    pub(super) fn bind_texture(&self, target: u32, unit: usize, id: Option<glow::Texture>) {
+        let mut bound_ids = self.bound_texture_ids.borrow_mut();
 
         if bound_ids.len() <= unit {
             let diff = unit - bound_ids.len() + 1;
             bound_ids.extend(std::iter::repeat_n(None, diff));
         }
 
-        if bound_ids[unit] == id {
+        let binding = id.map(|id| (target, id));
+
+        if bound_ids[unit] == binding {
             return;
         }
 
@@ -258,34 +328,40 @@ impl ContextShared {
 
         unsafe {
             self.gl.active_texture(unit_gl);
-            self.gl.bind_texture(glow::TEXTURE_2D, id);
         }
 
-        bound_ids[unit] = id;
+        // Each texture unit has a separate binding point per target. We keep
+        // at most one of them bound, so that the tracked state stays accurate.
+        if let Some((bound_target, _)) = bound_ids[unit].filter(|(t, _)| *t != target) {
+            unsafe {
+                self.gl.bind_texture(bound_target, None);
+            }
+        }
+
+        unsafe {
+            self.gl.bind_texture(target, id);
+        }
+
+        bound_ids[unit] = binding;
     }
 
-    pub(super) fn unbind_texture_2d_if_bound(&self, id: glow::Texture) {
+    pub(super) fn unbind_texture_if_bound(&self, id: glow::Texture) {
         if let Some((location, _)) = self
-            .bound_depth_attachment_id
+            .bound_depth_attachment
             .get()
-            .filter(|(_, bound_id)| *bound_id == id)
+            .filter(|(_, binding)| binding.id == id)
         {
             self.bind_draw_fbo(true);
-
-            unsafe {
-                self.gl.framebuffer_texture_2d(
-                    glow::FRAMEBUFFER,
-                    location,
-                    glow::TEXTURE_2D,
-                    None,
-                    0,
-                )
-            };
-
-            self.bound_depth_attachment_id.set(None);
+            self.attach(location, None);
+            self.bound_depth_attachment.set(None);
         }
 
-        if self.bound_color_attachment_ids.borrow().contains(&id) {
+        if self
+            .bound_color_attachments
+            .borrow()
+            .iter()
+            .any(|binding| binding.id == id)
+        {
             self.bind_draw_fbo(true);
 
             // TODO: We could be more efficient in how much we unbind here, but
@@ -294,18 +370,18 @@ impl ContextShared {
                 .expect("setting empty color attachments should always succeed");
         }
 
-        let mut bound_ids = self.bound_texture_2d_ids.borrow_mut();
+        let mut bound_ids = self.bound_texture_ids.borrow_mut();
 
-        for (unit, bound_id) in bound_ids.iter_mut().enumerate() {
-            if Some(id) == *bound_id {
+        for (unit, binding) in bound_ids.iter_mut().enumerate() {
+            if let Some((target, _)) = binding.filter(|(_, bound_id)| *bound_id == id) {
                 let unit_gl = texture_unit_gl(unit);
 
                 unsafe {
                     self.gl.active_texture(unit_gl);
-                    self.gl.bind_texture(glow::TEXTURE_2D, None);
+                    self.gl.bind_texture(target, None);
                 }
 
-                *bound_id = None;
+                *binding = None;
             }
         }
     }
@@ -335,7 +411,7 @@ impl ContextShared {
                 // FIXME: Check that all attachments have the same size.
                 self.bind_draw_fbo(true);
                 self.bind_color_attachments(color_attachments)?;
-                self.bind_depth_attachment(depth_attachment.as_ref());
+                self.bind_depth_attachment(depth_attachment.as_ref())?;
 
                 #[cfg(debug_assertions)]
                 super::error::check_framebuffer_completeness(&self.gl)
@@ -421,10 +497,10 @@ impl Context {
             tracing: Default::default(),
             bound_program_id: Default::default(),
             is_draw_fbo_bound: Default::default(),
-            bound_color_attachment_ids: Default::default(),
-            bound_depth_attachment_id: Default::default(),
+            bound_color_attachments: Default::default(),
+            bound_depth_attachment: Default::default(),
             bound_uniform_buffer_ids: Default::default(),
-            bound_texture_2d_ids: Default::default(),
+            bound_texture_ids: Default::default(),
         });
 
         Ok(Self { shared })
@@ -451,6 +527,33 @@ impl Context {
         Texture2d::new_with_mipmap(self.shared.clone(), image)
     }
 
+    pub fn create_texture_cube(&self, faces: [Image; 6]) -> Result<TextureCube, TextureError> {
+        TextureCube::new(self.shared.clone(), faces)
+    }
+
+    pub fn create_texture_cube_with_mipmap(
+        &self,
+        faces: [Image; 6],
+    ) -> Result<TextureCube, TextureError> {
+        TextureCube::new_with_mipmap(self.shared.clone(), faces)
+    }
+
+    pub fn create_texture_3d(
+        &self,
+        target: Texture3dTarget,
+        layers: &[Image],
+    ) -> Result<Texture3d, TextureError> {
+        Texture3d::new(self.shared.clone(), target, layers)
+    }
+
+    pub fn create_texture_3d_with_mipmap(
+        &self,
+        target: Texture3dTarget,
+        layers: &[Image],
+    ) -> Result<Texture3d, TextureError> {
+        Texture3d::new_with_mipmap(self.shared.clone(), target, layers)
+    }
+
     pub fn create_program(&self, def: ProgramDef) -> Result<Program, ProgramError> {
         Program::new(self.shared.clone(), def)
     }
@@ -59,6 +59,15 @@ pub enum TextureError {
     #[error("invalid data size: expected {expected} bytes, but got {got}")]
     DataSizeMismatch { expected: usize, got: usize },
 
+    #[error("cube map faces must be square, but got size {size:?}")]
+    NonSquareCubeFace { size: [u32; 2] },
+
+    #[error("cube map faces must all have the same size and format")]
+    CubeFaceMismatch,
+
+    #[error("texture layers must all have the same size and format")]
+    LayerMismatch,
+
     #[error("unexpected error while creating texture: {0}")]
     Unexpected(String),
 }
@@ -148,6 +157,9 @@ pub enum FramebufferError {
     #[error("texture level is too large: requested {requested}, but the maximum level is {max}")]
     LevelTooLarge { requested: u32, max: u32 },
 
+    #[error("texture layer is out of bounds: requested {requested}, but the texture has {layers} layers")]
+    LayerOutOfBounds { requested: u32, layers: u32 },
+
     #[error("too many color attachments: requested {requested}, but the maximum number of color attachments is {max}")]
     TooManyColorAttachments { requested: u32, max: u32 },
 
@@ -3,12 +3,35 @@ use std::rc::Rc;
 use smallvec::SmallVec;
 
 use super::{
-    context::ContextShared, Comparison, ImageInternalFormat, Sampler2d, Sampler2dParams, Texture2d,
+    context::ContextShared, Comparison, CubeFace, ImageInternalFormat, Sampler2d, Sampler2dParams,
+    Texture2d, Texture3d, TextureCube,
 };
 
 #[derive(Clone)]
 pub enum Attachment {
-    Texture2d { texture: Rc<Texture2d>, level: u32 },
+    Texture2d {
+        texture: Rc<Texture2d>,
+        level: u32,
+    },
+    TextureCube {
+        texture: Rc<TextureCube>,
+        face: CubeFace,
+        level: u32,
+    },
+    Texture3d {
+        texture: Rc<Texture3d>,
+        layer: u32,
+        level: u32,
+    },
+}
+
+/// Identifies the image that is attached to a framebuffer attachment point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) struct AttachmentBinding {
+    pub id: glow::Texture,
+    pub target: u32,
+    pub level: u32,
+    pub layer: Option<u32>,
 }
 
 impl Attachment {
@@ -17,6 +40,12 @@ impl Attachment {
 
         match self {
             Texture2d { texture, .. } => texture.size(),
+            TextureCube { texture, .. } => texture.size(),
+            Texture3d { texture, .. } => {
+                let [width, height, _] = texture.size();
+
+                [width, height]
+            }
         }
     }
 
@@ -25,9 +54,17 @@ impl Attachment {
 
         match self {
             Texture2d { texture, .. } => texture.internal_format(),
+            TextureCube { texture, .. } => texture.internal_format(),
+            Texture3d { texture, .. } => texture.internal_format(),
         }
     }
 
+    /// Returns a sampler for the texture that contains the attachment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the attachment is a face of a cube map or a layer of a 3D
+    /// texture, since these cannot be sampled as a 2D texture.
     pub fn sampler(&self, params: Sampler2dParams, comparison: Option<Comparison>) -> Sampler2d {
         use Attachment::*;
 
@@ -37,6 +74,8 @@ impl Attachment {
                 params,
                 comparison,
             },
+            TextureCube { .. } => panic!("cannot sample a cube map face as a 2D texture"),
+            Texture3d { .. } => panic!("cannot sample a texture layer as a 2D texture"),
         }
     }
 
@@ -45,12 +84,50 @@ impl Attachment {
 
         match self {
             Texture2d { texture, .. } => texture.id(),
+            TextureCube { texture, .. } => texture.id(),
+            Texture3d { texture, .. } => texture.id(),
+        }
+    }
+
+    pub(super) fn binding(&self) -> AttachmentBinding {
+        use Attachment::*;
+
+        match self {
+            Texture2d { texture, level } => AttachmentBinding {
+                id: texture.id(),
+                target: glow::TEXTURE_2D,
+                level: *level,
+                layer: None,
+            },
+            TextureCube {
+                texture,
+                face,
+                level,
+            } => AttachmentBinding {
+                id: texture.id(),
+                target: face.to_gl(),
+                level: *level,
+                layer: None,
+            },
+            Texture3d {
+                texture,
+                layer,
+                level,
+            } => AttachmentBinding {
+                id: texture.id(),
+                target: texture.target().to_gl(),
+                level: *level,
+                layer: Some(*layer),
+            },
         }
     }
 }
 
 pub type AttachmentVec = SmallVec<[Attachment; 8]>;
 
+// Attachments are stored inline so that binding a framebuffer does not need to
+// allocate.
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 pub enum Framebuffer {
     Default,
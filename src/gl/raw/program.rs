@@ -66,7 +66,7 @@ impl Program {
                         );
                     }
 
-                    let attribute_info = VertexAttributeLayout::new(attribute.ty)
+                    let attribute_info = VertexAttributeLayout::new(attribute.ty, attribute.format)
                         .map_err(ProgramError::InvalidVertexAttribute)?;
 
                     // Some attributes (e.g. matrices) take up multiple
@@ -33,6 +33,15 @@ pub struct Sampler2dParams {
     pub wrap_t: SamplerWrap,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sampler3dParams {
+    pub mag_filter: SamplerMagFilter,
+    pub min_filter: SamplerMinFilter,
+    pub wrap_s: SamplerWrap,
+    pub wrap_t: SamplerWrap,
+    pub wrap_r: SamplerWrap,
+}
+
 impl SamplerMagFilter {
     pub const fn to_gl(self) -> u32 {
         use SamplerMagFilter::*;
@@ -107,12 +116,12 @@ impl Sampler2dParams {
         self
     }
 
-    pub(super) fn set_delta(&self, gl: &glow::Context, current: &Sampler2dParams) {
+    pub(super) fn set_delta(&self, gl: &glow::Context, target: u32, current: &Sampler2dParams) {
         if self.mag_filter != current.mag_filter {
             let mag_filter = self.mag_filter.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, mag_filter);
+                gl.tex_parameter_i32(target, glow::TEXTURE_MAG_FILTER, mag_filter);
             }
         }
 
@@ -120,7 +129,7 @@ impl Sampler2dParams {
             let min_filter = self.min_filter.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, min_filter);
+                gl.tex_parameter_i32(target, glow::TEXTURE_MIN_FILTER, min_filter);
             }
         }
 
@@ -128,7 +137,7 @@ impl Sampler2dParams {
             let wrap_s = self.wrap_s.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, wrap_s);
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_S, wrap_s);
             }
         }
 
@@ -136,7 +145,61 @@ impl Sampler2dParams {
             let wrap_t = self.wrap_t.to_gl() as i32;
 
             unsafe {
-                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, wrap_t);
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_T, wrap_t);
+            }
+        }
+    }
+}
+
+impl Default for Sampler3dParams {
+    fn default() -> Self {
+        Sampler2dParams::default().into()
+    }
+}
+
+impl From<Sampler2dParams> for Sampler3dParams {
+    fn from(params: Sampler2dParams) -> Self {
+        Self {
+            mag_filter: params.mag_filter,
+            min_filter: params.min_filter,
+            wrap_s: params.wrap_s,
+            wrap_t: params.wrap_t,
+            wrap_r: params.wrap_t,
+        }
+    }
+}
+
+impl Sampler3dParams {
+    pub fn linear() -> Self {
+        Sampler2dParams::linear().into()
+    }
+
+    pub fn nearest() -> Self {
+        Sampler2dParams::nearest().into()
+    }
+
+    pub fn with_wrap(mut self, wrap: SamplerWrap) -> Self {
+        self.wrap_s = wrap;
+        self.wrap_t = wrap;
+        self.wrap_r = wrap;
+        self
+    }
+
+    pub(super) fn set_delta(&self, gl: &glow::Context, target: u32, current: &Sampler3dParams) {
+        let params_2d = |params: &Sampler3dParams| Sampler2dParams {
+            mag_filter: params.mag_filter,
+            min_filter: params.min_filter,
+            wrap_s: params.wrap_s,
+            wrap_t: params.wrap_t,
+        };
+
+        params_2d(self).set_delta(gl, target, &params_2d(current));
+
+        if self.wrap_r != current.wrap_r {
+            let wrap_r = self.wrap_r.to_gl() as i32;
+
+            unsafe {
+                gl.tex_parameter_i32(target, glow::TEXTURE_WRAP_R, wrap_r);
             }
         }
     }
@@ -148,6 +211,6 @@ pub(super) fn set_comparison(gl: &glow::Context, target: u32, comparison: Option
 
     if let Some(comparison) = comparison {
         let comparison = comparison.to_gl() as i32;
-        unsafe { gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_COMPARE_FUNC, comparison) };
+        unsafe { gl.tex_parameter_i32(target, glow::TEXTURE_COMPARE_FUNC, comparison) };
     }
 }
@@ -6,7 +6,7 @@ use crate::gl::{raw::error::check_gl_error, TextureError};
 
 use super::{
     context::ContextShared, sampler_params::set_comparison, Caps, Comparison, Image,
-    ImageInternalFormat, Sampler2dParams,
+    ImageInternalFormat, Sampler2dParams, Sampler3dParams,
 };
 
 pub struct Texture2d {
@@ -19,9 +19,53 @@ pub struct Texture2d {
     comparison: Cell<Option<Comparison>>,
 }
 
+pub struct TextureCube {
+    ctx: Rc<ContextShared>,
+    id: glow::Texture,
+    size: u32,
+    internal_format: ImageInternalFormat,
+    levels: usize,
+    params: Cell<Sampler2dParams>,
+    comparison: Cell<Option<Comparison>>,
+}
+
+pub struct Texture3d {
+    ctx: Rc<ContextShared>,
+    id: glow::Texture,
+    target: Texture3dTarget,
+    size: [u32; 3],
+    internal_format: ImageInternalFormat,
+    levels: usize,
+    params: Cell<Sampler3dParams>,
+    comparison: Cell<Option<Comparison>>,
+}
+
+/// The kind of a texture with multiple layers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Texture3dTarget {
+    /// A three-dimensional texture, which is filtered across layers.
+    Texture3d,
+
+    /// An array of two-dimensional textures.
+    Texture2dArray,
+}
+
+/// A face of a cube map texture.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
 #[derive(Clone)]
 pub enum Sampler {
     Sampler2d(Sampler2d),
+    SamplerCube(SamplerCube),
+    Sampler3d(Sampler3d),
 }
 
 #[derive(Clone)]
@@ -31,6 +75,20 @@ pub struct Sampler2d {
     pub comparison: Option<Comparison>,
 }
 
+#[derive(Clone)]
+pub struct Sampler3d {
+    pub texture: Rc<Texture3d>,
+    pub params: Sampler3dParams,
+    pub comparison: Option<Comparison>,
+}
+
+#[derive(Clone)]
+pub struct SamplerCube {
+    pub texture: Rc<TextureCube>,
+    pub params: Sampler2dParams,
+    pub comparison: Option<Comparison>,
+}
+
 struct ImageData<'a> {
     image: &'a Image<'a>,
     buffer: Vec<u8>,
@@ -103,7 +161,7 @@ impl Texture2d {
 
         let id = unsafe { gl.create_texture() }.map_err(TextureError::ObjectCreation)?;
 
-        ctx.bind_texture_2d(0, Some(id));
+        ctx.bind_texture(glow::TEXTURE_2D, 0, Some(id));
 
         unsafe {
             gl.tex_storage_2d(
@@ -164,7 +222,7 @@ impl Texture2d {
 
         let texture = Self::new_with_levels(ctx.clone(), image, levels)?;
 
-        ctx.bind_texture_2d(0, Some(texture.id));
+        ctx.bind_texture(glow::TEXTURE_2D, 0, Some(texture.id));
 
         let gl = ctx.gl();
 
@@ -195,7 +253,7 @@ impl Texture2d {
         lower_left_corner: [u32; 2],
         image: Image,
     ) -> Result<(), TextureError> {
-        assert!(level <= self.levels);
+        assert!(level < self.levels);
         assert_eq!(self.internal_format, image.internal_format);
 
         let mut data = ImageData::new(&image);
@@ -209,7 +267,7 @@ impl Texture2d {
         let width = image.size[0].try_into().unwrap();
         let height = image.size[1].try_into().unwrap();
 
-        self.ctx.bind_texture_2d(0, Some(self.id));
+        self.ctx.bind_texture(glow::TEXTURE_2D, 0, Some(self.id));
 
         unsafe {
             gl.tex_sub_image_2d(
@@ -236,7 +294,7 @@ impl Texture2d {
         let gl = &self.ctx.gl();
 
         let current = self.params.get();
-        new.set_delta(gl, &current);
+        new.set_delta(gl, glow::TEXTURE_2D, &current);
         self.params.set(new);
 
         if self.comparison.get() != comparison {
@@ -253,7 +311,461 @@ impl Texture2d {
 
 impl Drop for Texture2d {
     fn drop(&mut self) {
-        self.ctx.unbind_texture_2d_if_bound(self.id);
+        self.ctx.unbind_texture_if_bound(self.id);
+
+        let gl = self.ctx.gl();
+
+        unsafe {
+            gl.delete_texture(self.id);
+        }
+    }
+}
+
+impl CubeFace {
+    /// All faces, in the order in which OpenGL enumerates them.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    pub const fn to_gl(self) -> u32 {
+        use CubeFace::*;
+
+        match self {
+            PositiveX => glow::TEXTURE_CUBE_MAP_POSITIVE_X,
+            NegativeX => glow::TEXTURE_CUBE_MAP_NEGATIVE_X,
+            PositiveY => glow::TEXTURE_CUBE_MAP_POSITIVE_Y,
+            NegativeY => glow::TEXTURE_CUBE_MAP_NEGATIVE_Y,
+            PositiveZ => glow::TEXTURE_CUBE_MAP_POSITIVE_Z,
+            NegativeZ => glow::TEXTURE_CUBE_MAP_NEGATIVE_Z,
+        }
+    }
+}
+
+impl TextureCube {
+    fn new_with_levels(
+        ctx: Rc<ContextShared>,
+        faces: [Image; 6],
+        levels: usize,
+    ) -> Result<Self, TextureError> {
+        // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+        // > If [...] `levels` is less than 1, the error `INVALID_VALUE` is
+        // > generated.
+        assert!(levels > 0);
+
+        let size = faces[0].size[0];
+        let internal_format = faces[0].internal_format;
+
+        let levels = levels.try_into().expect("levels is out of i32 range");
+        let size_gl = size
+            .try_into()
+            .expect("max_cube_map_texture_size is out of i32 range");
+
+        let gl = ctx.gl();
+
+        // See `Texture2d::new_with_levels` for why we set the alignment.
+        unsafe {
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        }
+
+        let id = unsafe { gl.create_texture() }.map_err(TextureError::ObjectCreation)?;
+
+        ctx.bind_texture(glow::TEXTURE_CUBE_MAP, 0, Some(id));
+
+        unsafe {
+            gl.tex_storage_2d(
+                glow::TEXTURE_CUBE_MAP,
+                levels,
+                internal_format.to_gl(),
+                size_gl,
+                size_gl,
+            )
+        };
+
+        let texture = TextureCube {
+            ctx: ctx.clone(),
+            id,
+            size,
+            internal_format,
+            levels: levels as usize,
+            params: Default::default(),
+            comparison: Default::default(),
+        };
+
+        for (face, image) in CubeFace::ALL.into_iter().zip(&faces) {
+            let mut data = ImageData::new(image);
+            let slice = data.as_slice()?;
+
+            unsafe {
+                gl.tex_sub_image_2d(
+                    face.to_gl(),
+                    0,
+                    0,
+                    0,
+                    size_gl,
+                    size_gl,
+                    image.internal_format.to_format().to_gl(),
+                    image.ty.to_gl(),
+                    glow::PixelUnpackData::Slice(Some(slice)),
+                )
+            };
+        }
+
+        // Check for errors *after* passing ownership of the texture to
+        // `shared` so that it will be cleaned up if there is an error.
+        check_gl_error(gl, "after new cube map texture").map_err(TextureError::Unexpected)?;
+
+        Ok(texture)
+    }
+
+    pub(super) fn new(ctx: Rc<ContextShared>, faces: [Image; 6]) -> Result<Self, TextureError> {
+        validate_cube_faces(&faces, ctx.caps())?;
+
+        Self::new_with_levels(ctx, faces, 1)
+    }
+
+    pub(super) fn new_with_mipmap(
+        ctx: Rc<ContextShared>,
+        faces: [Image; 6],
+    ) -> Result<Self, TextureError> {
+        validate_cube_faces(&faces, ctx.caps())?;
+
+        // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+        // > An INVALID_OPERATION error is generated if `levels` is greater than
+        // > `floor(log_2(max(width, height))) + 1`.
+        let levels = (faces[0].size[0] as f64).log2() as usize + 1;
+
+        let texture = Self::new_with_levels(ctx.clone(), faces, levels)?;
+
+        ctx.bind_texture(glow::TEXTURE_CUBE_MAP, 0, Some(texture.id));
+
+        let gl = ctx.gl();
+
+        unsafe {
+            gl.generate_mipmap(glow::TEXTURE_CUBE_MAP);
+        }
+
+        check_gl_error(gl, "after new cube map texture with mipmaps")
+            .map_err(TextureError::Unexpected)?;
+
+        Ok(texture)
+    }
+
+    pub(super) fn id(&self) -> glow::Texture {
+        self.id
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        [self.size, self.size]
+    }
+
+    pub fn internal_format(&self) -> ImageInternalFormat {
+        self.internal_format
+    }
+
+    pub fn set(
+        &self,
+        face: CubeFace,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        image: Image,
+    ) -> Result<(), TextureError> {
+        assert!(level < self.levels);
+        assert_eq!(self.internal_format, image.internal_format);
+
+        let mut data = ImageData::new(&image);
+        let slice = data.as_slice()?;
+
+        let gl = self.ctx.gl();
+
+        let level = level.try_into().unwrap();
+        let x = lower_left_corner[0].try_into().unwrap();
+        let y = lower_left_corner[1].try_into().unwrap();
+        let width = image.size[0].try_into().unwrap();
+        let height = image.size[1].try_into().unwrap();
+
+        self.ctx
+            .bind_texture(glow::TEXTURE_CUBE_MAP, 0, Some(self.id));
+
+        unsafe {
+            gl.tex_sub_image_2d(
+                face.to_gl(),
+                level,
+                x,
+                y,
+                width,
+                height,
+                image.internal_format.to_format().to_gl(),
+                image.ty.to_gl(),
+                glow::PixelUnpackData::Slice(Some(slice)),
+            )
+        };
+
+        // This might be triggered if `rect` is outside of the texture image
+        // bounds.
+        check_gl_error(gl, "after cube map texture set").map_err(TextureError::Unexpected)?;
+
+        Ok(())
+    }
+
+    pub(super) fn set_params(&self, new: Sampler2dParams, comparison: Option<Comparison>) {
+        let gl = &self.ctx.gl();
+
+        let current = self.params.get();
+        new.set_delta(gl, glow::TEXTURE_CUBE_MAP, &current);
+        self.params.set(new);
+
+        if self.comparison.get() != comparison {
+            set_comparison(gl, glow::TEXTURE_CUBE_MAP, comparison);
+
+            self.comparison.set(comparison);
+        }
+
+        #[cfg(debug_assertions)]
+        check_gl_error(gl, "after cube map texture params").unwrap();
+    }
+}
+
+impl Drop for TextureCube {
+    fn drop(&mut self) {
+        self.ctx.unbind_texture_if_bound(self.id);
+
+        let gl = self.ctx.gl();
+
+        unsafe {
+            gl.delete_texture(self.id);
+        }
+    }
+}
+
+impl Texture3dTarget {
+    pub const fn to_gl(self) -> u32 {
+        use Texture3dTarget::*;
+
+        match self {
+            Texture3d => glow::TEXTURE_3D,
+            Texture2dArray => glow::TEXTURE_2D_ARRAY,
+        }
+    }
+}
+
+impl Texture3d {
+    fn new_with_levels(
+        ctx: Rc<ContextShared>,
+        target: Texture3dTarget,
+        layers: &[Image],
+        levels: usize,
+    ) -> Result<Self, TextureError> {
+        // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+        // > If [...] `levels` is less than 1, the error `INVALID_VALUE` is
+        // > generated.
+        assert!(levels > 0);
+
+        let [width, height] = layers[0].size;
+        let depth = layers
+            .len()
+            .try_into()
+            .expect("number of layers is out of u32 range");
+        let internal_format = layers[0].internal_format;
+
+        let levels = levels.try_into().expect("levels is out of i32 range");
+        let width_gl = width.try_into().expect("width is out of i32 range");
+        let height_gl = height.try_into().expect("height is out of i32 range");
+        let depth_gl = layers
+            .len()
+            .try_into()
+            .expect("number of layers is out of i32 range");
+
+        let gl = ctx.gl();
+
+        // See `Texture2d::new_with_levels` for why we set the alignment.
+        unsafe {
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        }
+
+        let id = unsafe { gl.create_texture() }.map_err(TextureError::ObjectCreation)?;
+
+        ctx.bind_texture(target.to_gl(), 0, Some(id));
+
+        unsafe {
+            gl.tex_storage_3d(
+                target.to_gl(),
+                levels,
+                internal_format.to_gl(),
+                width_gl,
+                height_gl,
+                depth_gl,
+            )
+        };
+
+        let texture = Texture3d {
+            ctx: ctx.clone(),
+            id,
+            target,
+            size: [width, height, depth],
+            internal_format,
+            levels: levels as usize,
+            params: Default::default(),
+            comparison: Default::default(),
+        };
+
+        for (layer, image) in layers.iter().enumerate() {
+            texture.upload(0, layer, [0, 0], image)?;
+        }
+
+        // Check for errors *after* passing ownership of the texture to
+        // `shared` so that it will be cleaned up if there is an error.
+        check_gl_error(gl, "after new 3D texture").map_err(TextureError::Unexpected)?;
+
+        Ok(texture)
+    }
+
+    pub(super) fn new(
+        ctx: Rc<ContextShared>,
+        target: Texture3dTarget,
+        layers: &[Image],
+    ) -> Result<Self, TextureError> {
+        validate_layers(target, layers, ctx.caps())?;
+
+        Self::new_with_levels(ctx, target, layers, 1)
+    }
+
+    pub(super) fn new_with_mipmap(
+        ctx: Rc<ContextShared>,
+        target: Texture3dTarget,
+        layers: &[Image],
+    ) -> Result<Self, TextureError> {
+        validate_layers(target, layers, ctx.caps())?;
+
+        // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+        // > An `INVALID_OPERATION` error is generated if `levels` is greater
+        // > than `floor(log_2(max(width, height, depth))) + 1` for a 3D
+        // > texture or `floor(log_2(max(width, height))) + 1` for a 2D array
+        // > texture.
+        let [width, height] = layers[0].size;
+        let max_size = match target {
+            Texture3dTarget::Texture3d => width.max(height).max(layers.len() as u32),
+            Texture3dTarget::Texture2dArray => width.max(height),
+        };
+        let levels = (max_size as f64).log2() as usize + 1;
+
+        let texture = Self::new_with_levels(ctx.clone(), target, layers, levels)?;
+
+        ctx.bind_texture(target.to_gl(), 0, Some(texture.id));
+
+        let gl = ctx.gl();
+
+        unsafe {
+            gl.generate_mipmap(target.to_gl());
+        }
+
+        check_gl_error(gl, "after new 3D texture with mipmaps")
+            .map_err(TextureError::Unexpected)?;
+
+        Ok(texture)
+    }
+
+    pub(super) fn id(&self) -> glow::Texture {
+        self.id
+    }
+
+    pub fn target(&self) -> Texture3dTarget {
+        self.target
+    }
+
+    /// Returns the width, the height, and the number of layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.size
+    }
+
+    pub fn internal_format(&self) -> ImageInternalFormat {
+        self.internal_format
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: Image,
+    ) -> Result<(), TextureError> {
+        assert!(level < self.levels);
+        assert!(layer < self.size[2]);
+        assert_eq!(self.internal_format, image.internal_format);
+
+        self.ctx.bind_texture(self.target.to_gl(), 0, Some(self.id));
+
+        self.upload(level, layer as usize, lower_left_corner, &image)?;
+
+        // This might be triggered if `rect` is outside of the texture image
+        // bounds.
+        check_gl_error(self.ctx.gl(), "after 3D texture set").map_err(TextureError::Unexpected)?;
+
+        Ok(())
+    }
+
+    // Assumes that the texture is bound to texture unit 0.
+    fn upload(
+        &self,
+        level: usize,
+        layer: usize,
+        lower_left_corner: [u32; 2],
+        image: &Image,
+    ) -> Result<(), TextureError> {
+        let mut data = ImageData::new(image);
+        let slice = data.as_slice()?;
+
+        let level = level.try_into().unwrap();
+        let x = lower_left_corner[0].try_into().unwrap();
+        let y = lower_left_corner[1].try_into().unwrap();
+        let z = layer.try_into().unwrap();
+        let width = image.size[0].try_into().unwrap();
+        let height = image.size[1].try_into().unwrap();
+
+        unsafe {
+            self.ctx.gl().tex_sub_image_3d(
+                self.target.to_gl(),
+                level,
+                x,
+                y,
+                z,
+                width,
+                height,
+                1,
+                image.internal_format.to_format().to_gl(),
+                image.ty.to_gl(),
+                glow::PixelUnpackData::Slice(Some(slice)),
+            )
+        };
+
+        Ok(())
+    }
+
+    pub(super) fn set_params(&self, new: Sampler3dParams, comparison: Option<Comparison>) {
+        let gl = &self.ctx.gl();
+
+        let current = self.params.get();
+        new.set_delta(gl, self.target.to_gl(), &current);
+        self.params.set(new);
+
+        if self.comparison.get() != comparison {
+            set_comparison(gl, self.target.to_gl(), comparison);
+
+            self.comparison.set(comparison);
+        }
+
+        #[cfg(debug_assertions)]
+        check_gl_error(gl, "after 3D texture params").unwrap();
+    }
+}
+
+impl Drop for Texture3d {
+    fn drop(&mut self) {
+        self.ctx.unbind_texture_if_bound(self.id);
 
         let gl = self.ctx.gl();
 
@@ -268,7 +780,9 @@ impl Sampler {
         use Sampler::*;
 
         match self {
-            Sampler2d(texture) => &texture.texture.ctx,
+            Sampler2d(sampler) => &sampler.texture.ctx,
+            SamplerCube(sampler) => &sampler.texture.ctx,
+            Sampler3d(sampler) => &sampler.texture.ctx,
         }
     }
 
@@ -281,7 +795,31 @@ impl Sampler {
             }) => {
                 let id = texture.id;
 
-                texture.ctx.bind_texture_2d(unit, Some(id));
+                texture.ctx.bind_texture(glow::TEXTURE_2D, unit, Some(id));
+                texture.set_params(*params, *comparison);
+            }
+            Sampler::SamplerCube(SamplerCube {
+                texture,
+                params,
+                comparison,
+            }) => {
+                let id = texture.id;
+
+                texture
+                    .ctx
+                    .bind_texture(glow::TEXTURE_CUBE_MAP, unit, Some(id));
+                texture.set_params(*params, *comparison);
+            }
+            Sampler::Sampler3d(Sampler3d {
+                texture,
+                params,
+                comparison,
+            }) => {
+                let id = texture.id;
+
+                texture
+                    .ctx
+                    .bind_texture(texture.target.to_gl(), unit, Some(id));
                 texture.set_params(*params, *comparison);
             }
         }
@@ -289,6 +827,10 @@ impl Sampler {
 }
 
 fn validate_size(size: [u32; 2], caps: &Caps) -> Result<(), TextureError> {
+    validate_size_with_max(size, caps.max_texture_size)
+}
+
+fn validate_size_with_max(size: [u32; 2], max: u32) -> Result<(), TextureError> {
     // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
     // > If [...] `width`, `height` [...] is less than 1, the error
     // > `INVALID_VALUE` is generated.
@@ -296,19 +838,75 @@ fn validate_size(size: [u32; 2], caps: &Caps) -> Result<(), TextureError> {
         return Err(TextureError::Empty);
     }
 
-    if size[0] > caps.max_texture_size {
+    if size[0] > max {
         return Err(TextureError::Oversized {
             requested: size[0],
-            max: caps.max_texture_size,
+            max,
         });
     }
 
-    if size[1] > caps.max_texture_size {
+    if size[1] > max {
         return Err(TextureError::Oversized {
             requested: size[1],
-            max: caps.max_texture_size,
+            max,
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_cube_faces(faces: &[Image; 6], caps: &Caps) -> Result<(), TextureError> {
+    let size = faces[0].size;
+
+    // OpenGL ES 3.0.6: 3.8.4 Immutable-Format Texture Images
+    // > If `target` is `TEXTURE_CUBE_MAP`, `width` and `height` must be equal.
+    if size[0] != size[1] {
+        return Err(TextureError::NonSquareCubeFace { size });
+    }
+
+    for face in faces.iter().skip(1) {
+        if face.size != size || face.internal_format != faces[0].internal_format {
+            return Err(TextureError::CubeFaceMismatch);
+        }
+    }
+
+    if size[0] == 0 {
+        return Err(TextureError::Empty);
+    }
+
+    if size[0] > caps.max_cube_map_texture_size {
+        return Err(TextureError::Oversized {
+            requested: size[0],
+            max: caps.max_cube_map_texture_size,
         });
     }
 
     Ok(())
 }
+
+fn validate_layers(
+    target: Texture3dTarget,
+    layers: &[Image],
+    caps: &Caps,
+) -> Result<(), TextureError> {
+    let first = layers.first().ok_or(TextureError::Empty)?;
+
+    for layer in &layers[1..] {
+        if layer.size != first.size || layer.internal_format != first.internal_format {
+            return Err(TextureError::LayerMismatch);
+        }
+    }
+
+    let depth = layers.len().try_into().unwrap_or(u32::MAX);
+
+    match target {
+        Texture3dTarget::Texture3d => {
+            validate_size_with_max(first.size, caps.max_3d_texture_size)?;
+            validate_size_with_max([depth, 1], caps.max_3d_texture_size)
+        }
+        Texture3dTarget::Texture2dArray => {
+            validate_size(first.size, caps)?;
+            validate_size_with_max([depth, 1], caps.max_array_texture_layers)
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use std::mem::size_of;
 
-use crate::sl::dag::BuiltInType;
+use crate::sl::{dag::BuiltInType, program_def::VertexAttributeFormat};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum VertexAttributeType {
     F32,
     I32,
     U32,
+    F16,
+    I8,
+    U8,
+    I16,
+    U16,
 }
 
 impl VertexAttributeType {
@@ -17,6 +22,11 @@ impl VertexAttributeType {
             F32 => size_of::<f32>(),
             I32 => size_of::<i32>(),
             U32 => size_of::<u32>(),
+            F16 => size_of::<u16>(),
+            I8 => size_of::<i8>(),
+            U8 => size_of::<u8>(),
+            I16 => size_of::<i16>(),
+            U16 => size_of::<u16>(),
         }
     }
 
@@ -27,18 +37,56 @@ impl VertexAttributeType {
             F32 => glow::FLOAT,
             I32 => glow::INT,
             U32 => glow::UNSIGNED_INT,
+            F16 => glow::HALF_FLOAT,
+            I8 => glow::BYTE,
+            U8 => glow::UNSIGNED_BYTE,
+            I16 => glow::SHORT,
+            U16 => glow::UNSIGNED_SHORT,
         }
     }
 }
 
 pub struct VertexAttributeLayout {
     pub ty: VertexAttributeType,
+    pub normalized: bool,
     pub components: usize,
     pub locations: usize,
 }
 
 impl VertexAttributeLayout {
-    pub fn new(ty: BuiltInType) -> Result<Self, String> {
+    pub fn new(ty: BuiltInType, format: VertexAttributeFormat) -> Result<Self, String> {
+        use VertexAttributeType::*;
+
+        let (packed_ty, normalized) = match format {
+            VertexAttributeFormat::Native => return Self::new_native(ty),
+            VertexAttributeFormat::Unorm8 => (U8, true),
+            VertexAttributeFormat::Snorm8 => (I8, true),
+            VertexAttributeFormat::Unorm16 => (U16, true),
+            VertexAttributeFormat::Snorm16 => (I16, true),
+            VertexAttributeFormat::F16 => (F16, false),
+        };
+
+        let components = match ty {
+            BuiltInType::F32 => 1,
+            BuiltInType::Vec2 => 2,
+            BuiltInType::Vec3 => 3,
+            BuiltInType::Vec4 => 4,
+            _ => {
+                return Err(format!(
+                    "vertex attribute format {format:?} is not supported for type {ty}"
+                ))
+            }
+        };
+
+        Ok(VertexAttributeLayout {
+            ty: packed_ty,
+            normalized,
+            components,
+            locations: 1,
+        })
+    }
+
+    fn new_native(ty: BuiltInType) -> Result<Self, String> {
         use VertexAttributeType::*;
 
         let (ty, components, locations) = match ty {
@@ -58,6 +106,12 @@ impl VertexAttributeLayout {
             BuiltInType::Mat2 => (F32, 2, 2),
             BuiltInType::Mat3 => (F32, 3, 3),
             BuiltInType::Mat4 => (F32, 4, 4),
+            BuiltInType::Mat2x3 => (F32, 3, 2),
+            BuiltInType::Mat2x4 => (F32, 4, 2),
+            BuiltInType::Mat3x2 => (F32, 2, 3),
+            BuiltInType::Mat3x4 => (F32, 4, 3),
+            BuiltInType::Mat4x2 => (F32, 2, 4),
+            BuiltInType::Mat4x3 => (F32, 3, 4),
             BuiltInType::BVec2 | BuiltInType::BVec3 | BuiltInType::BVec4 => {
                 return Err("boolean vectors are not supported".to_string())
             }
@@ -66,6 +120,7 @@ impl VertexAttributeLayout {
 
         Ok(VertexAttributeLayout {
             ty,
+            normalized: false,
             components,
             locations,
         })
@@ -121,8 +121,8 @@ impl VertexSpec {
             }
 
             for attribute in &block_def.attributes {
-                let attribute_info =
-                    VertexAttributeLayout::new(attribute.ty).expect("invalid vertex attribute");
+                let attribute_info = VertexAttributeLayout::new(attribute.ty, attribute.format)
+                    .expect("invalid vertex attribute");
 
                 for i in 0..attribute_info.locations {
                     let offset = attribute.offset + i * attribute_info.location_size();
@@ -148,14 +148,19 @@ impl VertexSpec {
                     let offset = i32::try_from(offset).unwrap();
 
                     match attribute_info.ty {
-                        VertexAttributeType::F32 => unsafe {
-                            gl.vertex_attrib_pointer_f32(
-                                index, size, data_type, false, stride, offset,
-                            )
-                        },
                         VertexAttributeType::I32 | VertexAttributeType::U32 => unsafe {
                             gl.vertex_attrib_pointer_i32(index, size, data_type, stride, offset)
                         },
+                        _ => unsafe {
+                            gl.vertex_attrib_pointer_f32(
+                                index,
+                                size,
+                                data_type,
+                                attribute_info.normalized,
+                                stride,
+                                offset,
+                            )
+                        },
                     }
 
                     index += 1;
@@ -182,8 +187,8 @@ impl VertexSpec {
 
         for VertexBufferBinding { block_def, .. } in &self.vertex_data {
             for attribute in &block_def.attributes {
-                let attribute_info =
-                    VertexAttributeLayout::new(attribute.ty).expect("invalid vertex attribute");
+                let attribute_info = VertexAttributeLayout::new(attribute.ty, attribute.format)
+                    .expect("invalid vertex attribute");
 
                 for _ in 0..attribute_info.locations {
                     unsafe {
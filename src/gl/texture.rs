@@ -3,8 +3,9 @@ use std::{marker::PhantomData, rc::Rc};
 use crate::sl::{self, ColorSample};
 
 use super::{
-    raw::{self, Sampler2dParams},
-    ColorAttachment, ColorImage, Comparison, DepthAttachment, DepthImage, TextureError,
+    raw::{self, CubeFace, Sampler2dParams, Sampler3dParams, Texture3dTarget},
+    ColorAttachment, ColorImage, Comparison, CubeFaceImage, DepthAttachment, DepthImage,
+    LayerImage, TextureError,
 };
 
 pub struct ColorTexture2d<S = sl::Vec4> {
@@ -16,6 +17,29 @@ pub struct DepthTexture2d {
     raw: Rc<raw::Texture2d>,
 }
 
+pub struct ColorTextureCube<S = sl::Vec4> {
+    raw: Rc<raw::TextureCube>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct DepthTextureCube {
+    raw: Rc<raw::TextureCube>,
+}
+
+pub struct ColorTexture3d<S = sl::Vec4> {
+    raw: Rc<raw::Texture3d>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct ColorTexture2dArray<S = sl::Vec4> {
+    raw: Rc<raw::Texture3d>,
+    _phantom: PhantomData<S>,
+}
+
+pub struct DepthTexture2dArray {
+    raw: Rc<raw::Texture3d>,
+}
+
 #[derive(Clone)]
 pub struct ColorSampler2d<S = sl::Vec4> {
     raw: raw::Sampler2d,
@@ -27,6 +51,34 @@ pub struct ComparisonSampler2d {
     raw: raw::Sampler2d,
 }
 
+#[derive(Clone)]
+pub struct ColorSamplerCube<S = sl::Vec4> {
+    raw: raw::SamplerCube,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ComparisonSamplerCube {
+    raw: raw::SamplerCube,
+}
+
+#[derive(Clone)]
+pub struct ColorSampler3d<S = sl::Vec4> {
+    raw: raw::Sampler3d,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ColorSampler2dArray<S = sl::Vec4> {
+    raw: raw::Sampler3d,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Clone)]
+pub struct ComparisonSampler2dArray {
+    raw: raw::Sampler3d,
+}
+
 impl<S> ColorTexture2d<S> {
     pub(super) fn from_raw(raw: raw::Texture2d) -> Self {
         // FIXME: This should validate against `S`.
@@ -129,6 +181,274 @@ impl DepthTexture2d {
     }
 }
 
+impl<S> ColorTextureCube<S> {
+    pub(super) fn from_raw(raw: raw::TextureCube) -> Self {
+        // FIXME: This should validate against `S`.
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTextureCube<S> {
+    pub fn as_color_attachment(&self, face: CubeFace) -> ColorAttachment<S, CubeFaceImage> {
+        self.as_color_attachment_with_level(face, 0)
+    }
+
+    pub fn as_color_attachment_with_level(
+        &self,
+        face: CubeFace,
+        level: u32,
+    ) -> ColorAttachment<S, CubeFaceImage> {
+        ColorAttachment::from_raw(raw::Attachment::TextureCube {
+            texture: self.raw.clone(),
+            face,
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSamplerCube<S> {
+        // FIXME: Check texture completeness.
+        ColorSamplerCube::from_raw(raw::SamplerCube {
+            texture: self.raw.clone(),
+            params,
+            comparison: None,
+        })
+    }
+
+    /// Returns the size of each face of the cube map.
+    pub fn size(&self) -> [u32; 2] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        face: CubeFace,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        self.raw.set(face, level, lower_left_corner, image.raw())
+    }
+}
+
+impl DepthTextureCube {
+    pub(super) fn from_raw(raw: raw::TextureCube) -> Self {
+        // FIXME: This should validate against depth.
+
+        Self { raw: Rc::new(raw) }
+    }
+}
+
+impl DepthTextureCube {
+    pub fn as_depth_attachment(&self, face: CubeFace) -> DepthAttachment {
+        self.as_depth_attachment_with_level(face, 0)
+    }
+
+    pub fn as_depth_attachment_with_level(&self, face: CubeFace, level: u32) -> DepthAttachment {
+        DepthAttachment::from_raw(raw::Attachment::TextureCube {
+            texture: self.raw.clone(),
+            face,
+            level,
+        })
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSamplerCube<sl::F32> {
+        // FIXME: Check texture completeness.
+        ColorSamplerCube::from_raw(raw::SamplerCube {
+            texture: self.raw.clone(),
+            params,
+            comparison: None,
+        })
+    }
+
+    pub fn as_comparison_sampler(
+        &self,
+        params: Sampler2dParams,
+        comparison: Comparison,
+    ) -> ComparisonSamplerCube {
+        // FIXME: Check texture completeness.
+        ComparisonSamplerCube::from_raw(raw::SamplerCube {
+            texture: self.raw.clone(),
+            params,
+            comparison: Some(comparison),
+        })
+    }
+
+    /// Returns the size of each face of the cube map.
+    pub fn size(&self) -> [u32; 2] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        face: CubeFace,
+        level: usize,
+        lower_left_corner: [u32; 2],
+        image: DepthImage,
+    ) -> Result<(), TextureError> {
+        self.raw.set(face, level, lower_left_corner, image.raw())
+    }
+}
+
+impl<S> ColorTexture3d<S> {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against `S`.
+        assert_eq!(raw.target(), Texture3dTarget::Texture3d);
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTexture3d<S> {
+    pub fn as_color_attachment(&self, layer: u32) -> ColorAttachment<S, LayerImage> {
+        self.as_color_attachment_with_level(layer, 0)
+    }
+
+    pub fn as_color_attachment_with_level(
+        &self,
+        layer: u32,
+        level: u32,
+    ) -> ColorAttachment<S, LayerImage> {
+        ColorAttachment::from_raw(layer_attachment(&self.raw, layer, level))
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler3dParams) -> ColorSampler3d<S> {
+        // FIXME: Check texture completeness.
+        ColorSampler3d::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params,
+            comparison: None,
+        })
+    }
+
+    /// Returns the width, the height, and the depth.
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        self.raw.set(level, layer, lower_left_corner, image.raw())
+    }
+}
+
+impl<S> ColorTexture2dArray<S> {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against `S`.
+        assert_eq!(raw.target(), Texture3dTarget::Texture2dArray);
+
+        Self {
+            raw: Rc::new(raw),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorTexture2dArray<S> {
+    pub fn as_color_attachment(&self, layer: u32) -> ColorAttachment<S, LayerImage> {
+        self.as_color_attachment_with_level(layer, 0)
+    }
+
+    pub fn as_color_attachment_with_level(
+        &self,
+        layer: u32,
+        level: u32,
+    ) -> ColorAttachment<S, LayerImage> {
+        ColorAttachment::from_raw(layer_attachment(&self.raw, layer, level))
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2dArray<S> {
+        // FIXME: Check texture completeness.
+        ColorSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.into(),
+            comparison: None,
+        })
+    }
+
+    /// Returns the width, the height, and the number of layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: ColorImage<S>,
+    ) -> Result<(), TextureError> {
+        self.raw.set(level, layer, lower_left_corner, image.raw())
+    }
+}
+
+impl DepthTexture2dArray {
+    pub(super) fn from_raw(raw: raw::Texture3d) -> Self {
+        // FIXME: This should validate against depth.
+        assert_eq!(raw.target(), Texture3dTarget::Texture2dArray);
+
+        Self { raw: Rc::new(raw) }
+    }
+}
+
+impl DepthTexture2dArray {
+    pub fn as_depth_attachment(&self, layer: u32) -> DepthAttachment {
+        self.as_depth_attachment_with_level(layer, 0)
+    }
+
+    pub fn as_depth_attachment_with_level(&self, layer: u32, level: u32) -> DepthAttachment {
+        DepthAttachment::from_raw(layer_attachment(&self.raw, layer, level))
+    }
+
+    pub fn as_color_sampler(&self, params: Sampler2dParams) -> ColorSampler2dArray<sl::F32> {
+        // FIXME: Check texture completeness.
+        ColorSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.into(),
+            comparison: None,
+        })
+    }
+
+    pub fn as_comparison_sampler(
+        &self,
+        params: Sampler2dParams,
+        comparison: Comparison,
+    ) -> ComparisonSampler2dArray {
+        // FIXME: Check texture completeness.
+        ComparisonSampler2dArray::from_raw(raw::Sampler3d {
+            texture: self.raw.clone(),
+            params: params.into(),
+            comparison: Some(comparison),
+        })
+    }
+
+    /// Returns the width, the height, and the number of layers.
+    pub fn size(&self) -> [u32; 3] {
+        self.raw.size()
+    }
+
+    pub fn set(
+        &self,
+        level: usize,
+        layer: u32,
+        lower_left_corner: [u32; 2],
+        image: DepthImage,
+    ) -> Result<(), TextureError> {
+        self.raw.set(level, layer, lower_left_corner, image.raw())
+    }
+}
+
 impl<S> ColorSampler2d<S> {
     pub(super) fn from_raw(raw: raw::Sampler2d) -> Self {
         Self {
@@ -151,3 +471,70 @@ impl ComparisonSampler2d {
         &self.raw
     }
 }
+
+impl<S> ColorSamplerCube<S> {
+    fn from_raw(raw: raw::SamplerCube) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::SamplerCube {
+        &self.raw
+    }
+}
+
+impl ComparisonSamplerCube {
+    fn from_raw(raw: raw::SamplerCube) -> Self {
+        Self { raw }
+    }
+
+    pub fn raw(&self) -> &raw::SamplerCube {
+        &self.raw
+    }
+}
+
+impl<S> ColorSampler3d<S> {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl<S> ColorSampler2dArray<S> {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self {
+            raw,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+impl ComparisonSampler2dArray {
+    fn from_raw(raw: raw::Sampler3d) -> Self {
+        Self { raw }
+    }
+
+    pub fn raw(&self) -> &raw::Sampler3d {
+        &self.raw
+    }
+}
+
+fn layer_attachment(texture: &Rc<raw::Texture3d>, layer: u32, level: u32) -> raw::Attachment {
+    raw::Attachment::Texture3d {
+        texture: texture.clone(),
+        layer,
+        level,
+    }
+}
@@ -5,5 +5,7 @@ mod vs_interface;
 
 pub use block::{Block, BlockDom};
 pub use fs_interface::{FragmentVisitor, FsDom, FsInterface};
-pub use uniform::{Uniform, UniformDom, UniformNonUnit, UniformUnion, UniformVisitor};
+pub use uniform::{
+    Uniform, UniformBinding, UniformDom, UniformNonUnit, UniformUnion, UniformVisitor,
+};
 pub use vs_interface::{VertexField, VertexVisitor, VsDom, VsInterface};
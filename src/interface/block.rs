@@ -3,8 +3,13 @@ use crevice::std140::AsStd140;
 use sealed::sealed;
 
 use crate::{
-    gl,
-    sl::{self, program_def::VertexAttributeDef},
+    gl::{self, Std140ArrayElement},
+    internal::join_ident_path,
+    sl::{
+        self,
+        program_def::{VertexAttributeDef, VertexAttributeFormat},
+        ValueNonArray,
+    },
     Gl, Sl, ToSl,
 };
 
@@ -28,8 +33,14 @@ pub trait BlockDom: Copy {
     /// Has [`u32`] as its physical view and [`sl::U32`] as its logical view.
     type U32: Block<Self> + ToSl<Output = sl::U32> + Default;
 
-    // TODO: Add `Bool` to `Block`. Need to special case vertex attributes.
-    //type Bool: Block<Self> + ToSl<Output = sl::Bool>;
+    /// A boolean value.
+    ///
+    /// Has [`gl::Bool`](crate::gl::Bool) as its physical view and [`sl::Bool`]
+    /// as its logical view.
+    ///
+    /// In vertex blocks, booleans are passed as unsigned integer attributes
+    /// and compared to zero in the vertex shader.
+    type Bool: Block<Self> + ToSl<Output = sl::Bool> + Default;
 
     /// A two-crate::dimensional floating-point vector.
     ///
@@ -102,6 +113,114 @@ pub trait BlockDom: Copy {
     /// Has [`gl::Mat4`](crate::gl::Mat4) as its physical view and [`sl::Mat4`]
     /// as its logical view.
     type Mat4: Block<Self> + ToSl<Output = sl::Mat4>;
+
+    /// A floating-point matrix with two columns and three rows.
+    ///
+    /// Has [`gl::Mat2x3`](crate::gl::Mat2x3) as its physical view and
+    /// [`sl::Mat2x3`] as its logical view.
+    type Mat2x3: Block<Self> + ToSl<Output = sl::Mat2x3>;
+
+    /// A floating-point matrix with two columns and four rows.
+    ///
+    /// Has [`gl::Mat2x4`](crate::gl::Mat2x4) as its physical view and
+    /// [`sl::Mat2x4`] as its logical view.
+    type Mat2x4: Block<Self> + ToSl<Output = sl::Mat2x4>;
+
+    /// A floating-point matrix with three columns and two rows.
+    ///
+    /// Has [`gl::Mat3x2`](crate::gl::Mat3x2) as its physical view and
+    /// [`sl::Mat3x2`] as its logical view.
+    type Mat3x2: Block<Self> + ToSl<Output = sl::Mat3x2>;
+
+    /// A floating-point matrix with three columns and four rows.
+    ///
+    /// Has [`gl::Mat3x4`](crate::gl::Mat3x4) as its physical view and
+    /// [`sl::Mat3x4`] as its logical view.
+    type Mat3x4: Block<Self> + ToSl<Output = sl::Mat3x4>;
+
+    /// A floating-point matrix with four columns and two rows.
+    ///
+    /// Has [`gl::Mat4x2`](crate::gl::Mat4x2) as its physical view and
+    /// [`sl::Mat4x2`] as its logical view.
+    type Mat4x2: Block<Self> + ToSl<Output = sl::Mat4x2>;
+
+    /// A floating-point matrix with four columns and three rows.
+    ///
+    /// Has [`gl::Mat4x3`](crate::gl::Mat4x3) as its physical view and
+    /// [`sl::Mat4x3`] as its logical view.
+    type Mat4x3: Block<Self> + ToSl<Output = sl::Mat4x3>;
+
+    /// A two-dimensional vector of unsigned normalized 8-bit values.
+    ///
+    /// Has [`gl::Unorm8x2`](crate::gl::Unorm8x2) as its physical view and
+    /// [`sl::Vec2`] as its logical view.
+    type Unorm8x2: Block<Self> + ToSl<Output = sl::Vec2> + Default;
+
+    /// A four-dimensional vector of unsigned normalized 8-bit values.
+    ///
+    /// Has [`gl::Unorm8x4`](crate::gl::Unorm8x4) as its physical view and
+    /// [`sl::Vec4`] as its logical view.
+    type Unorm8x4: Block<Self> + ToSl<Output = sl::Vec4> + Default;
+
+    /// A two-dimensional vector of signed normalized 8-bit values.
+    ///
+    /// Has [`gl::Snorm8x2`](crate::gl::Snorm8x2) as its physical view and
+    /// [`sl::Vec2`] as its logical view.
+    type Snorm8x2: Block<Self> + ToSl<Output = sl::Vec2> + Default;
+
+    /// A four-dimensional vector of signed normalized 8-bit values.
+    ///
+    /// Has [`gl::Snorm8x4`](crate::gl::Snorm8x4) as its physical view and
+    /// [`sl::Vec4`] as its logical view.
+    type Snorm8x4: Block<Self> + ToSl<Output = sl::Vec4> + Default;
+
+    /// A two-dimensional vector of unsigned normalized 16-bit values.
+    ///
+    /// Has [`gl::Unorm16x2`](crate::gl::Unorm16x2) as its physical view and
+    /// [`sl::Vec2`] as its logical view.
+    type Unorm16x2: Block<Self> + ToSl<Output = sl::Vec2> + Default;
+
+    /// A four-dimensional vector of unsigned normalized 16-bit values.
+    ///
+    /// Has [`gl::Unorm16x4`](crate::gl::Unorm16x4) as its physical view and
+    /// [`sl::Vec4`] as its logical view.
+    type Unorm16x4: Block<Self> + ToSl<Output = sl::Vec4> + Default;
+
+    /// A two-dimensional vector of signed normalized 16-bit values.
+    ///
+    /// Has [`gl::Snorm16x2`](crate::gl::Snorm16x2) as its physical view and
+    /// [`sl::Vec2`] as its logical view.
+    type Snorm16x2: Block<Self> + ToSl<Output = sl::Vec2> + Default;
+
+    /// A four-dimensional vector of signed normalized 16-bit values.
+    ///
+    /// Has [`gl::Snorm16x4`](crate::gl::Snorm16x4) as its physical view and
+    /// [`sl::Vec4`] as its logical view.
+    type Snorm16x4: Block<Self> + ToSl<Output = sl::Vec4> + Default;
+
+    /// A two-dimensional vector of 16-bit floating-point values.
+    ///
+    /// Has [`gl::F16x2`](crate::gl::F16x2) as its physical view and
+    /// [`sl::Vec2`] as its logical view.
+    type F16x2: Block<Self> + ToSl<Output = sl::Vec2> + Default;
+
+    /// A four-dimensional vector of 16-bit floating-point values.
+    ///
+    /// Has [`gl::F16x4`](crate::gl::F16x4) as its physical view and
+    /// [`sl::Vec4`] as its logical view.
+    type F16x4: Block<Self> + ToSl<Output = sl::Vec4> + Default;
+
+    /// A fixed-size array of block data.
+    ///
+    /// Has [`gl::Array`](crate::gl::Array) as its physical view and
+    /// [`sl::Array`] as its logical view. Arrays of arrays are not supported.
+    ///
+    /// In vertex blocks, each element of the array becomes a separate vertex
+    /// attribute.
+    type Array<T, const N: usize>: ToSl<Output = sl::Array<T::Output, N>>
+    where
+        T: ToSl,
+        T::Output: ValueNonArray;
 }
 
 #[sealed]
@@ -109,6 +228,7 @@ impl BlockDom for Gl {
     type F32 = f32;
     type I32 = i32;
     type U32 = u32;
+    type Bool = gl::Bool;
     type Vec2 = gl::Vec2;
     type Vec3 = gl::Vec3;
     type Vec4 = gl::Vec4;
@@ -121,6 +241,27 @@ impl BlockDom for Gl {
     type Mat2 = gl::Mat2;
     type Mat3 = gl::Mat3;
     type Mat4 = gl::Mat4;
+    type Mat2x3 = gl::Mat2x3;
+    type Mat2x4 = gl::Mat2x4;
+    type Mat3x2 = gl::Mat3x2;
+    type Mat3x4 = gl::Mat3x4;
+    type Mat4x2 = gl::Mat4x2;
+    type Mat4x3 = gl::Mat4x3;
+    type Unorm8x2 = gl::Unorm8x2;
+    type Unorm8x4 = gl::Unorm8x4;
+    type Snorm8x2 = gl::Snorm8x2;
+    type Snorm8x4 = gl::Snorm8x4;
+    type Unorm16x2 = gl::Unorm16x2;
+    type Unorm16x4 = gl::Unorm16x4;
+    type Snorm16x2 = gl::Snorm16x2;
+    type Snorm16x4 = gl::Snorm16x4;
+    type F16x2 = gl::F16x2;
+    type F16x4 = gl::F16x4;
+    type Array<T, const N: usize>
+        = gl::Array<T, N>
+    where
+        T: ToSl,
+        T::Output: ValueNonArray;
 }
 
 #[sealed]
@@ -128,6 +269,7 @@ impl BlockDom for Sl {
     type F32 = sl::F32;
     type I32 = sl::I32;
     type U32 = sl::U32;
+    type Bool = sl::Bool;
     type Vec2 = sl::Vec2;
     type Vec3 = sl::Vec3;
     type Vec4 = sl::Vec4;
@@ -140,6 +282,27 @@ impl BlockDom for Sl {
     type Mat2 = sl::Mat2;
     type Mat3 = sl::Mat3;
     type Mat4 = sl::Mat4;
+    type Mat2x3 = sl::Mat2x3;
+    type Mat2x4 = sl::Mat2x4;
+    type Mat3x2 = sl::Mat3x2;
+    type Mat3x4 = sl::Mat3x4;
+    type Mat4x2 = sl::Mat4x2;
+    type Mat4x3 = sl::Mat4x3;
+    type Unorm8x2 = sl::Vec2;
+    type Unorm8x4 = sl::Vec4;
+    type Snorm8x2 = sl::Vec2;
+    type Snorm8x4 = sl::Vec4;
+    type Unorm16x2 = sl::Vec2;
+    type Unorm16x4 = sl::Vec4;
+    type Snorm16x2 = sl::Vec2;
+    type Snorm16x4 = sl::Vec4;
+    type F16x2 = sl::Vec2;
+    type F16x4 = sl::Vec4;
+    type Array<T, const N: usize>
+        = sl::Array<T::Output, N>
+    where
+        T: ToSl,
+        T::Output: ValueNonArray;
 }
 
 /// Plain-old vertex or uniform block data.
@@ -236,7 +399,6 @@ macro_rules! impl_block {
             }
 
             fn vertex_input(path: &str) -> Self {
-                // FIXME: Cast from u32 to bool!
                 <Self as sl::Object>::from_arg(path)
             }
 
@@ -244,6 +406,7 @@ macro_rules! impl_block {
                 vec![VertexAttributeDef {
                     name: path.to_string(),
                     ty: <Self as sl::Object>::ty().built_in_type().unwrap(),
+                    format: VertexAttributeFormat::Native,
                     offset: 0,
                 }]
             }
@@ -266,3 +429,112 @@ impl_block!(gl::UVec4, sl::UVec4);
 impl_block!(gl::Mat2, sl::Mat2);
 impl_block!(gl::Mat3, sl::Mat3);
 impl_block!(gl::Mat4, sl::Mat4);
+impl_block!(gl::Mat2x3, sl::Mat2x3);
+impl_block!(gl::Mat2x4, sl::Mat2x4);
+impl_block!(gl::Mat3x2, sl::Mat3x2);
+impl_block!(gl::Mat3x4, sl::Mat3x4);
+impl_block!(gl::Mat4x2, sl::Mat4x2);
+impl_block!(gl::Mat4x3, sl::Mat4x3);
+
+// Vertex attributes cannot be booleans, so they are passed as `u32`.
+unsafe impl Block<Gl> for gl::Bool {
+    type Gl = gl::Bool;
+    type Sl = sl::Bool;
+}
+
+unsafe impl Block<Sl> for sl::Bool {
+    type Gl = gl::Bool;
+    type Sl = sl::Bool;
+
+    fn uniform_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+
+    fn vertex_input(path: &str) -> Self {
+        <sl::U32 as sl::Object>::from_arg(path).ne(0u32)
+    }
+
+    fn vertex_attribute_defs(path: &str) -> Vec<VertexAttributeDef> {
+        vec![VertexAttributeDef {
+            name: path.to_string(),
+            ty: <sl::U32 as sl::Object>::ty().built_in_type().unwrap(),
+            format: VertexAttributeFormat::Native,
+            offset: 0,
+        }]
+    }
+}
+
+// Packed types have the logical view of a floating-point vector. Their format
+// only matters for vertex attributes, so the physical view provides the
+// attribute definition.
+macro_rules! impl_block_packed {
+    ($gl:ty, $sl:ty, $format:ident) => {
+        unsafe impl Block<Gl> for $gl {
+            type Gl = $gl;
+            type Sl = $sl;
+
+            fn vertex_attribute_defs(path: &str) -> Vec<VertexAttributeDef> {
+                vec![VertexAttributeDef {
+                    name: path.to_string(),
+                    ty: <$sl as sl::Object>::ty().built_in_type().unwrap(),
+                    format: VertexAttributeFormat::$format,
+                    offset: 0,
+                }]
+            }
+        }
+    };
+}
+
+impl_block_packed!(gl::Unorm8x2, sl::Vec2, Unorm8);
+impl_block_packed!(gl::Unorm8x4, sl::Vec4, Unorm8);
+impl_block_packed!(gl::Snorm8x2, sl::Vec2, Snorm8);
+impl_block_packed!(gl::Snorm8x4, sl::Vec4, Snorm8);
+impl_block_packed!(gl::Unorm16x2, sl::Vec2, Unorm16);
+impl_block_packed!(gl::Unorm16x4, sl::Vec4, Unorm16);
+impl_block_packed!(gl::Snorm16x2, sl::Vec2, Snorm16);
+impl_block_packed!(gl::Snorm16x4, sl::Vec4, Snorm16);
+impl_block_packed!(gl::F16x2, sl::Vec2, F16);
+impl_block_packed!(gl::F16x4, sl::Vec4, F16);
+
+unsafe impl<T, const N: usize> Block<Gl> for gl::Array<T, N>
+where
+    T: Block<Gl, Gl = T> + ToSl<Output = <T as Block<Gl>>::Sl> + Pod + Std140ArrayElement,
+    <T as Block<Gl>>::Sl: Block<Sl, Gl = T, Sl = <T as Block<Gl>>::Sl> + ValueNonArray,
+{
+    type Gl = Self;
+    type Sl = sl::Array<T::Sl, N>;
+}
+
+unsafe impl<V, const N: usize> Block<Sl> for sl::Array<V, N>
+where
+    V: Block<Sl, Sl = V> + sl::Interpolant + ValueNonArray,
+    V::Gl: Block<Gl, Gl = V::Gl, Sl = V> + ToSl<Output = V> + Pod + Std140ArrayElement,
+{
+    type Gl = gl::Array<V::Gl, N>;
+    type Sl = Self;
+
+    fn uniform_input(path: &str) -> Self {
+        <Self as sl::Object>::from_arg(path)
+    }
+
+    fn vertex_input(path: &str) -> Self {
+        sl::array(std::array::from_fn(|i| {
+            V::vertex_input(&join_ident_path(path, &i.to_string()))
+        }))
+    }
+
+    fn vertex_attribute_defs(path: &str) -> Vec<VertexAttributeDef> {
+        let stride = std::mem::size_of::<V::Gl>();
+
+        (0..N)
+            .flat_map(|i| {
+                V::vertex_attribute_defs(&join_ident_path(path, &i.to_string()))
+                    .into_iter()
+                    .map(move |attr| VertexAttributeDef {
+                        offset: attr.offset + i * stride,
+                        ..attr
+                    })
+            })
+            .collect()
+    }
+}
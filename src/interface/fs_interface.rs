@@ -1,6 +1,6 @@
 use sealed::sealed;
 
-use crate::{gl, sl, Gl, Sl, ToSl};
+use crate::{gl, internal::join_ident_path, sl, Gl, Sl, ToSl};
 
 /// A view of fragment data attributes.
 ///
@@ -41,21 +41,14 @@ pub unsafe trait FsInterface<D: FsDom>: Clone {
     type Sl: FsInterface<Sl> + sl::Interpolant + ToSl<Output = Self::Sl>;
 
     #[doc(hidden)]
-    fn visit<'a>(&'a self, path: &str, visitor: &mut impl FragmentVisitor<'a, D>);
-}
-
-unsafe impl<D: FsDom> FsInterface<D> for () {
-    type Sl = ();
-    type Gl = ();
-
-    fn visit<'a>(&'a self, _: &str, _: &mut impl FragmentVisitor<'a, D>) {}
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<D>);
 }
 
 unsafe impl<S: sl::ColorSample> FsInterface<Gl> for gl::ColorAttachment<S> {
     type Gl = gl::ColorAttachment<S>;
     type Sl = S;
 
-    fn visit<'a>(&'a self, path: &str, visitor: &mut impl FragmentVisitor<'a, Gl>) {
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<Gl>) {
         visitor.accept(path, self);
     }
 }
@@ -64,14 +57,83 @@ unsafe impl<S: sl::ColorSample> FsInterface<Sl> for S {
     type Gl = gl::ColorAttachment<S>;
     type Sl = S;
 
-    fn visit<'a>(&'a self, path: &str, visitor: &mut impl FragmentVisitor<'a, Sl>) {
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<Sl>) {
         visitor.accept(path, self);
     }
 }
 
-// TODO: Tuple implementations for `FsInterface`.
+// Fragment outputs are assigned locations in the order in which they are
+// visited, so tuple and array elements are written to consecutive attachments.
+macro_rules! tuple_impl {
+    ($($name: ident),*) => {
+        unsafe impl<D: FsDom, $($name: FsInterface<D>,)*> FsInterface<D> for ($($name,)*) {
+            type Gl = ($($name::Gl,)*);
+            type Sl = ($($name::Sl,)*);
+
+            #[allow(unused)]
+            fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<D>) {
+                #[allow(non_snake_case)]
+                let ($($name,)*) = self;
+
+                $(
+                    $name.visit(&join_ident_path(path, stringify!($name)), visitor);
+                )*
+            }
+        }
+    };
+}
+
+smaller_tuples_too!(tuple_impl, T0, T1, T2, T3, T4, T5, T6, T7);
+
+// Arrays are viewed as `sl::Array` in shaders, so that they can be passed
+// around like any other value.
+unsafe impl<T, const N: usize> FsInterface<Gl> for [T; N]
+where
+    T: FsInterface<Gl, Gl = T>,
+    T::Sl: FsInterface<Sl, Gl = T, Sl = T::Sl> + sl::ValueNonArray,
+{
+    type Gl = Self;
+    type Sl = sl::Array<T::Sl, N>;
+
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<Gl>) {
+        for (i, item) in self.iter().enumerate() {
+            item.visit(&join_ident_path(path, &i.to_string()), visitor);
+        }
+    }
+}
+
+unsafe impl<V, const N: usize> FsInterface<Sl> for [V; N]
+where
+    V: FsInterface<Sl, Sl = V> + sl::Interpolant + sl::ValueNonArray,
+    V::Gl: FsInterface<Gl, Gl = V::Gl, Sl = V>,
+{
+    type Gl = [V::Gl; N];
+    type Sl = sl::Array<V, N>;
+
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<Sl>) {
+        for (i, item) in self.iter().enumerate() {
+            item.visit(&join_ident_path(path, &i.to_string()), visitor);
+        }
+    }
+}
+
+unsafe impl<V, const N: usize> FsInterface<Sl> for sl::Array<V, N>
+where
+    V: FsInterface<Sl, Sl = V> + sl::Interpolant + sl::ValueNonArray,
+    V::Gl: FsInterface<Gl, Gl = V::Gl, Sl = V>,
+{
+    type Gl = [V::Gl; N];
+    type Sl = Self;
+
+    fn visit(&self, path: &str, visitor: &mut impl FragmentVisitor<Sl>) {
+        for i in 0..N {
+            self.get(i as u32)
+                .visit(&join_ident_path(path, &i.to_string()), visitor);
+        }
+    }
+}
 
 #[doc(hidden)]
-pub trait FragmentVisitor<'a, D: FsDom> {
-    fn accept<S: sl::ColorSample>(&mut self, path: &str, attachment: &'a D::ColorAttachment<S>);
+pub trait FragmentVisitor<D: FsDom> {
+    fn accept<S: sl::ColorSample>(&mut self, path: &str, attachment: &D::ColorAttachment<S>);
 }
@@ -1,5 +1,3 @@
-mod interface;
-
 /// Imagine macro parameters, but more like those Russian dolls.
 ///
 /// Calls m!(A, B, C), m!(A, B), m!(B), and m!() for i.e. (m, A, B, C)
@@ -18,8 +16,10 @@ macro_rules! smaller_tuples_too {
 }
 
 pub mod gl;
+mod interface;
 #[macro_use]
 pub mod sl;
+pub mod soft;
 
 pub use interface::{
     Block, BlockDom, FsDom, FsInterface, Uniform, UniformDom, UniformNonUnit, UniformUnion, VsDom,
@@ -53,20 +53,16 @@ pub trait ToSl: Copy {
 #[doc(hidden)]
 pub mod internal {
     pub use super::{
-        interface::{FragmentVisitor, UniformVisitor, VertexField, VertexVisitor},
+        gl::Std140ArrayElement,
+        interface::{FragmentVisitor, UniformBinding, UniformVisitor, VertexField, VertexVisitor},
         sl::{
-            dag::{Expr, StructType, Type},
-            primitives::{field, simplify_struct_literal, value_arg},
-            unique_struct_type,
+            dag::{Expr, FuncDef, StructType, Type},
+            interpolant::qualify_outputs,
+            mangle::{join_ident_path, mangle_ident},
+            primitives::{call_func_def, field, simplify_struct_literal, value_arg},
+            unique_func_def, unique_struct_type,
         },
     };
-
-    #[doc(hidden)]
-    pub fn join_ident_path(lhs: &str, rhs: &str) -> String {
-        // FIXME: There is probably a chance that this produces duplicate
-        // symbol names.
-        format!("{lhs}_{rhs}")
-    }
 }
 
 // Re-export `crate` as `posh` for `posh-derive`.
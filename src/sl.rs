@@ -7,7 +7,6 @@ mod gen_type;
 #[macro_use]
 mod scalar;
 mod array;
-mod interpolant;
 mod mat;
 mod sampler;
 mod sig;
@@ -15,24 +14,36 @@ mod tuple;
 mod vec;
 
 pub(crate) mod codegen;
+pub(crate) mod const_fold;
+pub(crate) mod interpolant;
+pub(crate) mod mangle;
 pub(crate) mod primitives;
 
 pub mod dag;
+pub mod eval;
 pub mod program_def;
 pub mod transpile;
 
 use std::{collections::BTreeMap, rc::Rc};
 
-use dag::{Expr, StructType, Type};
+use dag::{Expr, FuncDef, StructType, Type};
 
 use crate::ToSl;
 
 pub use {
-    array::{array, Array},
-    interpolant::Interpolant,
-    mat::{mat2, mat3, mat4, Mat2, Mat3, Mat4},
-    primitives::{all, and, any, branch, branches, or},
-    sampler::{ColorSample, ColorSampler2d, ComparisonSampler2d, Sample},
+    array::{array, Array, ArrayView},
+    interpolant::{Centroid, Flat, Interpolant},
+    mat::{
+        mat2, mat2x3, mat2x4, mat3, mat3x2, mat3x4, mat4, mat4x2, mat4x3, Mat2, Mat2x3, Mat2x4,
+        Mat3, Mat3x2, Mat3x4, Mat4, Mat4x2, Mat4x3,
+    },
+    primitives::{
+        all, and, any, branch, branches, fold, fold_until, or, switch, LoopIndex, SwitchValue,
+    },
+    sampler::{
+        ColorSample, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+        ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, Sample,
+    },
     scalar::{Bool, F32, I32, U32},
     sig::{
         Const, Derivatives, FromFsInput, FromVsInput, FsFunc, FsInput, FsSig, FullFsOutput,
@@ -44,7 +55,7 @@ pub use {
     },
 };
 
-pub use posh_derive::{Const, Interpolant, Value};
+pub use posh_derive::{function, Const, Interpolant, Value};
 
 use self::dag::BinaryOp;
 
@@ -123,3 +134,28 @@ pub fn unique_struct_type<T: Struct>(ty: fn() -> StructType) -> Rc<StructType> {
 
     ty
 }
+
+#[doc(hidden)]
+pub fn unique_func_def<T: 'static>(def: fn() -> FuncDef) -> Rc<FuncDef> {
+    use std::{any::TypeId, cell::RefCell};
+
+    std::thread_local! {
+        static MAP: RefCell<BTreeMap<TypeId, Rc<FuncDef>>> = const { RefCell::new(BTreeMap::new()) } ;
+    }
+
+    let key = TypeId::of::<T>();
+
+    if let Some(def) = MAP.with(|map| map.borrow().get(&key).cloned()) {
+        return def;
+    }
+
+    // We must not borrow `MAP` while calling `def`, since tracing the function
+    // body may call other functions.
+    let def = Rc::new(def());
+
+    MAP.with(|map| {
+        map.borrow_mut().insert(key, def.clone());
+    });
+
+    def
+}
@@ -1,11 +1,13 @@
-use std::{marker::PhantomData, rc::Rc};
+use std::{marker::PhantomData, ops::Add, rc::Rc};
 
 use crate::ToSl;
 
 use super::{
+    branch,
     dag::{ArrayType, Expr, Trace, Type},
+    fold,
     primitives::value_arg,
-    Object, Value, ValueNonArray, U32,
+    Bool, Object, Value, ValueNonArray, U32,
 };
 
 /// An array value in the shading language.
@@ -79,8 +81,12 @@ impl<V: ValueNonArray, const N: usize> Array<V, N> {
         N == 0
     }
 
+    /// Returns the element at `index`.
+    ///
+    /// Accessing an element out of bounds leads to undefined behavior. See
+    /// [`get_clamped`](Self::get_clamped), [`get_wrapped`](Self::get_wrapped)
+    /// and [`get_or`](Self::get_or) for accessors that are always in bounds.
     pub fn get(&self, index: impl ToSl<Output = U32>) -> V {
-        // FIXME: Prevent out-of-bounds access.
         let base = self.trace.expr();
         let index = index.to_sl().expr();
         let ty = V::ty();
@@ -89,6 +95,245 @@ impl<V: ValueNonArray, const N: usize> Array<V, N> {
 
         V::from_expr(expr)
     }
+
+    /// Returns the element at `index`, clamping `index` to the last element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is empty.
+    pub fn get_clamped(&self, index: impl ToSl<Output = U32>) -> V {
+        assert!(N > 0, "cannot index into an empty array");
+
+        self.get(index.to_sl().min(N as u32 - 1))
+    }
+
+    /// Returns the element at `index` modulo the length of the array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is empty.
+    pub fn get_wrapped(&self, index: impl ToSl<Output = U32>) -> V {
+        assert!(N > 0, "cannot index into an empty array");
+
+        self.get(index.to_sl() % N as u32)
+    }
+
+    /// Returns the element at `index`, or `default` if `index` is out of
+    /// bounds.
+    pub fn get_or(&self, index: impl ToSl<Output = U32>, default: impl ToSl<Output = V>) -> V {
+        let index = index.to_sl();
+
+        branch(index.lt(N as u32), self.get(index), default)
+    }
+
+    /// Returns a lazily evaluated view of the elements of the array.
+    ///
+    /// See [`ArrayView`] for the available combinators.
+    pub fn view(self) -> ArrayView<V, N> {
+        ArrayView::new(move |index| self.get(index))
+    }
+
+    /// See [`ArrayView::map`].
+    pub fn map<W: 'static>(self, f: impl Fn(V) -> W + 'static) -> ArrayView<W, N> {
+        self.view().map(f)
+    }
+
+    /// See [`ArrayView::zip`].
+    pub fn zip<W: 'static>(self, other: impl Into<ArrayView<W, N>>) -> ArrayView<(V, W), N> {
+        self.view().zip(other)
+    }
+
+    /// See [`ArrayView::enumerate`].
+    pub fn enumerate(self) -> ArrayView<(U32, V), N> {
+        self.view().enumerate()
+    }
+
+    /// See [`ArrayView::fold`].
+    pub fn fold<A: Value>(self, init: impl ToSl<Output = A>, f: impl FnOnce(A, V) -> A) -> A {
+        self.view().fold(init, f)
+    }
+
+    /// See [`ArrayView::sum`].
+    pub fn sum(self) -> V
+    where
+        V: Add<Output = V>,
+    {
+        self.view().sum()
+    }
+
+    /// See [`ArrayView::min_by`].
+    pub fn min_by(self, is_less: impl FnOnce(V, V) -> Bool) -> V {
+        self.view().min_by(is_less)
+    }
+
+    /// See [`ArrayView::max_by`].
+    pub fn max_by(self, is_greater: impl FnOnce(V, V) -> Bool) -> V {
+        self.view().max_by(is_greater)
+    }
+}
+
+/// A lazily evaluated view of the elements of an array.
+///
+/// Combinators such as [`map`](Self::map) and [`zip`](Self::zip) do not
+/// generate any code by themselves. Reductions such as [`fold`](Self::fold)
+/// generate a single loop over the array, in which the combinators are applied
+/// to one element at a time. This keeps the generated code compact, even for
+/// large arrays.
+///
+/// # Example
+///
+/// ```
+/// use posh::sl;
+///
+/// fn skin(
+///     bones: sl::Array<sl::Mat4, 64>,
+///     weights: sl::Array<sl::F32, 64>,
+///     pos: sl::Vec4,
+/// ) -> sl::Vec4 {
+///     bones
+///         .zip(weights)
+///         .map(move |(bone, weight)| bone * pos * weight)
+///         .sum()
+/// }
+/// ```
+pub struct ArrayView<T, const N: usize> {
+    get: Rc<dyn Fn(U32) -> T>,
+}
+
+impl<T, const N: usize> Clone for ArrayView<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            get: self.get.clone(),
+        }
+    }
+}
+
+impl<V: ValueNonArray, const N: usize> From<Array<V, N>> for ArrayView<V, N> {
+    fn from(array: Array<V, N>) -> Self {
+        array.view()
+    }
+}
+
+impl<T: 'static, const N: usize> ArrayView<T, N> {
+    fn new(get: impl Fn(U32) -> T + 'static) -> Self {
+        Self { get: Rc::new(get) }
+    }
+
+    pub fn len(&self) -> U32 {
+        (N as u32).to_sl()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the element at `index`.
+    ///
+    /// Accessing an element out of bounds leads to undefined behavior.
+    pub fn get(&self, index: impl ToSl<Output = U32>) -> T {
+        (self.get)(index.to_sl())
+    }
+
+    /// Applies `f` to each element.
+    pub fn map<W: 'static>(self, f: impl Fn(T) -> W + 'static) -> ArrayView<W, N> {
+        ArrayView::new(move |index| f((self.get)(index)))
+    }
+
+    /// Pairs up the elements of `self` and `other`.
+    pub fn zip<W: 'static>(self, other: impl Into<ArrayView<W, N>>) -> ArrayView<(T, W), N> {
+        let other = other.into();
+
+        ArrayView::new(move |index| ((self.get)(index), (other.get)(index)))
+    }
+
+    /// Pairs up each element with its index.
+    pub fn enumerate(self) -> ArrayView<(U32, T), N> {
+        ArrayView::new(move |index| (index, (self.get)(index)))
+    }
+
+    /// Folds `f` over the elements with a loop in the shading language.
+    ///
+    /// See [`fold`](super::fold) for details.
+    pub fn fold<A: Value>(self, init: impl ToSl<Output = A>, f: impl FnOnce(A, T) -> A) -> A {
+        fold(0u32..N as u32, init, |acc, index| f(acc, (self.get)(index)))
+    }
+
+    /// Returns the sum of the elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is empty.
+    pub fn sum(self) -> T
+    where
+        T: Value + Add<Output = T>,
+    {
+        self.reduce(|acc, value| acc + value)
+    }
+
+    /// Returns the minimum element with respect to `is_less`, which must
+    /// return true if its first argument is less than its second argument.
+    ///
+    /// If several elements are equally minimum, the first one is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is empty.
+    pub fn min_by(self, is_less: impl FnOnce(T, T) -> Bool) -> T
+    where
+        T: Value,
+    {
+        self.reduce(|acc, value| branch(is_less(value, acc), value, acc))
+    }
+
+    /// Returns the maximum element with respect to `is_greater`, which must
+    /// return true if its first argument is greater than its second argument.
+    ///
+    /// If several elements are equally maximum, the first one is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the array is empty.
+    pub fn max_by(self, is_greater: impl FnOnce(T, T) -> Bool) -> T
+    where
+        T: Value,
+    {
+        self.reduce(|acc, value| branch(is_greater(value, acc), value, acc))
+    }
+
+    /// Evaluates all elements into an array.
+    ///
+    /// In contrast to the reductions, this unrolls the combinators for each of
+    /// the `N` elements.
+    pub fn to_array(self) -> Array<T, N>
+    where
+        T: ValueNonArray,
+    {
+        let args = (0..N as u32)
+            .map(|index| (self.get)(index.to_sl()).expr())
+            .collect();
+        let ty = ArrayType {
+            ty: Box::new(T::ty()),
+            len: N,
+        };
+
+        let expr = Expr::ArrayLiteral { args, ty };
+
+        Array::from_expr(expr)
+    }
+
+    // Folds `f` over all elements but the first, starting with the first.
+    fn reduce(self, f: impl FnOnce(T, T) -> T) -> T
+    where
+        T: Value,
+    {
+        assert!(N > 0, "cannot reduce an empty array");
+
+        let first = (self.get)(0u32.to_sl());
+
+        fold(1u32..N as u32, first, |acc, index| {
+            f(acc, (self.get)(index))
+        })
+    }
 }
 
 pub fn array<V: ValueNonArray, const N: usize>(args: [V; N]) -> Array<V, N> {
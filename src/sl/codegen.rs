@@ -1,8 +1,12 @@
+mod func_registry;
 mod scope_form;
 mod simplified_expr;
 mod struct_registry;
 mod var_form;
 
+pub mod es100;
+pub mod wgsl;
+
 #[cfg(test)]
 mod tests;
 
@@ -14,13 +18,16 @@ use std::{
 use crate::sl::dag::ArrayType;
 
 use super::{
-    dag::{Expr, Type},
+    dag::{BuiltInType, Expr, SamplerType, Type},
+    mangle::mangle_ident,
     program_def::{UniformBlockDef, UniformSamplerDef},
+    transpile::TranspileOptions,
 };
 
 use self::{
+    func_registry::FuncRegistry,
     scope_form::{Scope, ScopeForm, VarInit},
-    simplified_expr::VarId,
+    simplified_expr::{SimplifiedExpr, VarId},
     struct_registry::StructRegistry,
     var_form::VarForm,
 };
@@ -59,14 +66,32 @@ impl Display for Indent {
 
 pub fn write_shader_stage(
     f: &mut impl Write,
+    options: &TranspileOptions,
     block_defs: &[UniformBlockDef],
     sampler_defs: &[UniformSamplerDef],
     attributes: impl Iterator<Item = (String, String, Type)>,
     outputs: &[(&str, Rc<Expr>)],
 ) -> fmt::Result {
     let roots: Vec<_> = outputs.iter().map(|(_, root)| root.clone()).collect();
-    let struct_registry = StructRegistry::new(&roots, block_defs.iter().map(|def| &def.ty));
-    let var_form = VarForm::new(&struct_registry, &roots);
+    let func_registry = FuncRegistry::new(&roots);
+
+    // Structs can also occur in the bodies and signatures of functions, which
+    // are not reachable from `roots`.
+    let struct_roots: Vec<_> = roots
+        .iter()
+        .cloned()
+        .chain(func_registry.defs().map(|(_, def)| def.result.clone()))
+        .collect();
+    let struct_registry = StructRegistry::new(
+        &struct_roots,
+        block_defs.iter().map(|def| &def.ty).chain(
+            func_registry
+                .defs()
+                .flat_map(|(_, def)| def.params.iter().map(|(_, ty)| ty)),
+        ),
+    );
+
+    let var_form = VarForm::new(&struct_registry, &func_registry, options, &roots);
     let scope_form = ScopeForm::new(&var_form);
 
     let write_context = WriteFuncContext {
@@ -75,29 +100,66 @@ pub fn write_shader_stage(
         depth: 1,
     };
 
-    // TODO: Specify appropriate GLSL version depending on the target.
-    writeln!(f, "#version 300 es")?;
-    writeln!(f)?;
+    let target = options.target;
 
-    // TODO: Make precision configurable.
-    writeln!(f, "precision highp float;")?;
-    writeln!(f, "precision highp int;")?;
-    writeln!(f, "precision highp sampler2DShadow;")?;
-    writeln!(f, "precision highp sampler2D;")?;
+    writeln!(f, "#version {}", target.version())?;
     writeln!(f)?;
 
+    // Desktop GLSL ignores precision qualifiers, so we only declare them for
+    // GLSL ES.
+    if target.is_es() {
+        // TODO: Make precision configurable.
+        writeln!(f, "precision highp float;")?;
+        writeln!(f, "precision highp int;")?;
+        writeln!(f, "precision highp sampler2DShadow;")?;
+        writeln!(f, "precision highp sampler2D;")?;
+
+        // GLSL ES predeclares `lowp` for `sampler2D` and `samplerCube` only,
+        // and the remaining sampler types have no default precision. We
+        // declare `highp` for each other type that is used, which also
+        // overrides the `lowp` default of `samplerCube`.
+        let mut sampler_types = vec![
+            SamplerType::ComparisonSampler2d,
+            SamplerType::ColorSampler2d,
+        ];
+
+        for sampler_def in sampler_defs {
+            if !sampler_types.contains(&sampler_def.ty) {
+                writeln!(f, "precision highp {};", sampler_def.ty)?;
+
+                sampler_types.push(sampler_def.ty);
+            }
+        }
+
+        writeln!(f)?;
+    }
+
     write_struct_defs(f, &struct_registry)?;
 
     writeln!(f)?;
 
+    // Without `layout(binding = N)`, bindings are assigned when the program is
+    // created.
     for sampler_def in sampler_defs {
+        if target.supports_layout_binding() {
+            write!(f, "layout(binding = {}) ", sampler_def.texture_unit)?;
+        }
+
         writeln!(f, "uniform {} {};", sampler_def.ty, sampler_def.name)?;
     }
 
     for block_def in block_defs {
         let ty_name = type_name(&struct_registry, &block_def.ty);
 
-        writeln!(f, "layout(std140) uniform {} {{", block_def.block_name)?;
+        if target.supports_layout_binding() {
+            writeln!(
+                f,
+                "layout(std140, binding = {}) uniform {} {{",
+                block_def.location, block_def.block_name
+            )?;
+        } else {
+            writeln!(f, "layout(std140) uniform {} {{", block_def.block_name)?;
+        }
         writeln!(f, "    {} {};", ty_name, block_def.arg_name)?;
         writeln!(f, "}};")?;
     }
@@ -112,6 +174,8 @@ pub fn write_shader_stage(
 
     writeln!(f)?;
 
+    write_func_defs(f, options, &struct_registry, &func_registry)?;
+
     writeln!(f, "void main() {{")?;
     write_scope(f, write_context, scope_form.root_scope())?;
     for ((name, _), simplified_expr) in outputs.iter().zip(var_form.simplified_roots()) {
@@ -122,6 +186,44 @@ pub fn write_shader_stage(
     Ok(())
 }
 
+fn write_func_defs(
+    f: &mut impl Write,
+    options: &TranspileOptions,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+) -> fmt::Result {
+    for (name, def) in func_registry.defs() {
+        let roots = [def.result.clone()];
+        let var_form = VarForm::new(struct_registry, func_registry, options, &roots);
+        let scope_form = ScopeForm::new(&var_form);
+
+        let write_context = WriteFuncContext {
+            struct_registry,
+            scope_form: &scope_form,
+            depth: 1,
+        };
+
+        let result_ty_name = type_name(struct_registry, &def.result.ty());
+        let params = def
+            .params
+            .iter()
+            .map(|(param_name, ty)| format!("{} {param_name}", type_name(struct_registry, ty)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f, "{result_ty_name} {name}({params}) {{")?;
+
+        if write_scope(f, write_context, scope_form.root_scope())? {
+            writeln!(f, "    return {};", var_form.simplified_roots()[0])?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
 fn write_var(
     f: &mut impl Write,
     ctx: WriteFuncContext,
@@ -198,6 +300,91 @@ fn write_var(
 
             Ok(true)
         }
+        Switch {
+            value,
+            case_ids,
+            default_id,
+            ty,
+        } => {
+            let ty_name = type_name(ctx.struct_registry, ty);
+            let labels = case_ids
+                .iter()
+                .map(|(label, case_id)| (format!("case {}", case_label(*label, value)), *case_id))
+                .chain([("default".to_string(), *default_id)]);
+
+            writeln!(f, "{indent}{ty_name} {var_id};")?;
+            writeln!(f, "{indent}switch ({value}) {{")?;
+
+            for (label, case_id) in labels {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+                let case_scope = ctx.scope_form.scope(case_id);
+
+                writeln!(f, "{indent}{label}: {{")?;
+
+                {
+                    let ctx = ctx.nest();
+                    let indent = ctx.indent();
+
+                    let result = case_scope.result.unwrap();
+
+                    if write_scope(f, ctx, case_scope)? {
+                        writeln!(f, "{indent}{var_id} = {result};")?;
+                        writeln!(f, "{indent}break;")?;
+                    }
+                }
+
+                writeln!(f, "{indent}}}")?;
+            }
+
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
+        Loop {
+            start,
+            end,
+            init,
+            acc_name,
+            index_name,
+            body_id,
+            stop,
+            ty,
+        } => {
+            let ty_name = type_name(ctx.struct_registry, ty);
+            let index_ty_name = type_name(ctx.struct_registry, &start.ty());
+            let body_scope = ctx.scope_form.scope(*body_id);
+
+            writeln!(f, "{indent}{ty_name} {var_id} = {init};")?;
+            writeln!(
+                f,
+                "{indent}for ({index_ty_name} {index_name} = {start}; \
+                 {index_name} < {end}; {index_name}++) {{"
+            )?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let result = body_scope.result.unwrap();
+
+                writeln!(f, "{indent}{ty_name} {acc_name} = {var_id};")?;
+
+                if write_scope(f, ctx.clone(), body_scope)? {
+                    writeln!(f, "{indent}{var_id} = {result};")?;
+
+                    if let Some(stop) = stop {
+                        writeln!(f, "{indent}if ({stop}) {{")?;
+                        writeln!(f, "{}break;", ctx.nest().indent())?;
+                        writeln!(f, "{indent}}}")?;
+                    }
+                }
+            }
+
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
         Discard => {
             writeln!(f, "{indent}discard;")?;
 
@@ -227,7 +414,7 @@ fn write_struct_defs(f: &mut impl Write, struct_reg: &StructRegistry) -> fmt::Re
         for (field_name, field_ty) in ty.fields.iter() {
             let field_ty_name = type_name(struct_reg, field_ty);
 
-            writeln!(f, "    {field_ty_name} {field_name};")?;
+            writeln!(f, "    {field_ty_name} {};", mangle_ident(field_name))?;
         }
 
         writeln!(f, "}};")?;
@@ -236,6 +423,14 @@ fn write_struct_defs(f: &mut impl Write, struct_reg: &StructRegistry) -> fmt::Re
     Ok(())
 }
 
+fn case_label(label: i64, value: &SimplifiedExpr) -> String {
+    // GLSL and WGSL both require case labels to match the selector type.
+    match value.ty() {
+        Type::BuiltIn(BuiltInType::U32) => format!("{label}u"),
+        _ => label.to_string(),
+    }
+}
+
 fn type_name(struct_reg: &StructRegistry, ty: &Type) -> String {
     use Type::*;
 
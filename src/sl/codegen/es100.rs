@@ -0,0 +1,853 @@
+//! GLSL ES 1.00 code generation.
+//!
+//! Programs are lowered to GLSL ES 1.00 by rewriting their expressions before
+//! they are handed to GLSL code generation. For example, switches become
+//! branches and texture lookups get the names of their GLSL ES 1.00
+//! counterparts. Features without a counterpart are reported as an
+//! [`Es100Error`].
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    rc::Rc,
+};
+
+use crate::sl::{
+    dag::{
+        ArrayType, BinaryOp, BuiltInType, Expr, FuncDef, SamplerType, StructType, Type, UnaryOp,
+    },
+    mangle::mangle_ident,
+    program_def::{InterpolationQualifier, PlainUniformDef, UniformBlockDef, UniformSamplerDef},
+    transpile::{Es100Error, ShaderStage, TranspileOptions},
+};
+
+use super::{
+    func_registry::FuncRegistry, scope_form::ScopeForm, simplified_expr::ExprKey,
+    struct_registry::StructRegistry, type_name, var_form::VarForm, write_func_defs, write_scope,
+    WriteFuncContext,
+};
+
+/// Built-in functions of GLSL ES 1.00 that only take floating-point arguments.
+const FLOAT_FUNCS: &[&str] = &[
+    "abs",
+    "acos",
+    "asin",
+    "atan",
+    "ceil",
+    "clamp",
+    "cos",
+    "cross",
+    "degrees",
+    "distance",
+    "dot",
+    "exp",
+    "exp2",
+    "faceforward",
+    "floor",
+    "fract",
+    "inversesqrt",
+    "length",
+    "log",
+    "log2",
+    "matrixCompMult",
+    "max",
+    "min",
+    "mix",
+    "mod",
+    "normalize",
+    "pow",
+    "radians",
+    "reflect",
+    "refract",
+    "sign",
+    "sin",
+    "smoothstep",
+    "sqrt",
+    "step",
+    "tan",
+];
+
+/// Built-in functions of GLSL ES 1.00 that also take integer or boolean
+/// arguments.
+const OTHER_FUNCS: &[&str] = &[
+    "all",
+    "any",
+    "equal",
+    "greaterThan",
+    "greaterThanEqual",
+    "lessThan",
+    "lessThanEqual",
+    "not",
+    "notEqual",
+];
+
+/// Writes the vertex shader and the fragment shader of a program.
+pub fn write_program(
+    options: &TranspileOptions,
+    block_defs: &[UniformBlockDef],
+    sampler_defs: &[UniformSamplerDef],
+    vertex_attributes: &[(String, BuiltInType)],
+    varyings: &[(String, InterpolationQualifier)],
+    vertex_outputs: &[(&str, Rc<Expr>)],
+    fragment_outputs: &[(&str, Rc<Expr>)],
+) -> Result<(String, String), Es100Error> {
+    for sampler_def in sampler_defs {
+        check_type(&Type::BuiltIn(BuiltInType::Sampler(sampler_def.ty)))?;
+    }
+
+    for block_def in block_defs {
+        check_type(&block_def.ty)?;
+    }
+
+    for (name, ty) in vertex_attributes {
+        if scalar_type(*ty) != Some(BuiltInType::F32) {
+            return Err(Es100Error::IntegerAttribute(name.clone()));
+        }
+
+        check_type(&Type::BuiltIn(*ty))?;
+    }
+
+    for (name, interp) in varyings {
+        if *interp == InterpolationQualifier::Flat {
+            return Err(Es100Error::FlatInterpolant(name.clone()));
+        }
+    }
+
+    if fragment_outputs
+        .iter()
+        .any(|(name, _)| *name == "gl_FragDepth")
+    {
+        return Err(Es100Error::FragmentDepth);
+    }
+
+    if fragment_outputs.len() > 1 {
+        return Err(Es100Error::MultipleRenderTargets(fragment_outputs.len()));
+    }
+
+    let fragment_outputs = fragment_outputs
+        .iter()
+        .map(|(name, expr)| Ok(("gl_FragColor", frag_color(name, expr)?)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let vertex_outputs = lower(options, ShaderStage::Vertex, vertex_outputs)?;
+    let fragment_outputs = lower(options, ShaderStage::Fragment, &fragment_outputs)?;
+
+    let attributes = vertex_attributes
+        .iter()
+        .map(|(name, ty)| ("attribute", name.clone(), Type::BuiltIn(*ty)));
+    let varying_types = |outputs: &[(&str, Rc<Expr>)]| {
+        varyings
+            .iter()
+            .map(|(name, _)| {
+                let (_, expr) = outputs
+                    .iter()
+                    .find(|(output_name, _)| output_name == name)
+                    .unwrap();
+
+                ("varying", name.clone(), expr.ty())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut vertex_source = String::new();
+    write_shader_stage(
+        &mut vertex_source,
+        ShaderStage::Vertex,
+        block_defs,
+        sampler_defs,
+        attributes.chain(varying_types(&vertex_outputs)),
+        &vertex_outputs,
+    )
+    .unwrap();
+
+    let mut fragment_source = String::new();
+    write_shader_stage(
+        &mut fragment_source,
+        ShaderStage::Fragment,
+        block_defs,
+        sampler_defs,
+        varying_types(&vertex_outputs).into_iter(),
+        &fragment_outputs,
+    )
+    .unwrap();
+
+    Ok((vertex_source, fragment_source))
+}
+
+/// Returns the plain uniforms that hold the contents of the uniform blocks.
+pub fn plain_uniform_defs(block_defs: &[UniformBlockDef]) -> Vec<PlainUniformDef> {
+    let mut defs = Vec::new();
+
+    for (block, block_def) in block_defs.iter().enumerate() {
+        push_plain_uniform_defs(&mut defs, block, &block_def.arg_name, &block_def.ty, 0);
+    }
+
+    defs
+}
+
+fn push_plain_uniform_defs(
+    defs: &mut Vec<PlainUniformDef>,
+    block: usize,
+    name: &str,
+    ty: &Type,
+    offset: usize,
+) {
+    match ty {
+        Type::BuiltIn(ty) => defs.push(PlainUniformDef {
+            name: name.to_string(),
+            ty: *ty,
+            block,
+            offset,
+        }),
+        Type::Struct(ty) => {
+            for ((field_name, field_ty), field_offset) in ty.fields.iter().zip(field_offsets(ty)) {
+                let name = format!("{name}.{}", mangle_ident(field_name));
+
+                push_plain_uniform_defs(defs, block, &name, field_ty, offset + field_offset);
+            }
+        }
+        Type::Array(ArrayType { ty, len }) => {
+            let (_, stride) = std140_array_layout(ty);
+
+            for i in 0..*len {
+                let name = format!("{name}[{i}]");
+
+                push_plain_uniform_defs(defs, block, &name, ty, offset + i * stride);
+            }
+        }
+    }
+}
+
+fn write_shader_stage<'a>(
+    f: &mut impl Write,
+    stage: ShaderStage,
+    block_defs: &[UniformBlockDef],
+    sampler_defs: &[UniformSamplerDef],
+    declarations: impl Iterator<Item = (&'a str, String, Type)>,
+    outputs: &[(&str, Rc<Expr>)],
+) -> fmt::Result {
+    // Subscripts have been clamped while lowering, if needed.
+    let options = TranspileOptions::default();
+
+    let roots: Vec<_> = outputs.iter().map(|(_, root)| root.clone()).collect();
+    let func_registry = FuncRegistry::new(&roots);
+
+    let struct_roots: Vec<_> = roots
+        .iter()
+        .cloned()
+        .chain(func_registry.defs().map(|(_, def)| def.result.clone()))
+        .collect();
+    let struct_registry = StructRegistry::new(
+        &struct_roots,
+        block_defs.iter().map(|def| &def.ty).chain(
+            func_registry
+                .defs()
+                .flat_map(|(_, def)| def.params.iter().map(|(_, ty)| ty)),
+        ),
+    );
+
+    let var_form = VarForm::new(&struct_registry, &func_registry, &options, &roots);
+    let scope_form = ScopeForm::new(&var_form);
+
+    let write_context = WriteFuncContext {
+        struct_registry: &struct_registry,
+        scope_form: &scope_form,
+        depth: 1,
+    };
+
+    writeln!(f, "#version 100")?;
+    writeln!(f)?;
+
+    // `highp` is optional in fragment shaders.
+    match stage {
+        ShaderStage::Vertex => {
+            writeln!(f, "precision highp float;")?;
+            writeln!(f, "precision highp int;")?;
+        }
+        ShaderStage::Fragment => {
+            writeln!(f, "#ifdef GL_FRAGMENT_PRECISION_HIGH")?;
+            writeln!(f, "precision highp float;")?;
+            writeln!(f, "precision highp int;")?;
+            writeln!(f, "#else")?;
+            writeln!(f, "precision mediump float;")?;
+            writeln!(f, "precision mediump int;")?;
+            writeln!(f, "#endif")?;
+        }
+    }
+
+    writeln!(f)?;
+
+    for (name, ty) in struct_registry.defs() {
+        writeln!(f, "struct {name} {{")?;
+
+        for (field_name, field_ty) in ty.fields.iter() {
+            let field = declarator(&struct_registry, field_ty, &mangle_ident(field_name));
+
+            writeln!(f, "    {field};")?;
+        }
+
+        writeln!(f, "}};")?;
+    }
+
+    writeln!(f)?;
+
+    for sampler_def in sampler_defs {
+        writeln!(f, "uniform {} {};", sampler_def.ty, sampler_def.name)?;
+    }
+
+    for block_def in block_defs {
+        let uniform = declarator(&struct_registry, &block_def.ty, &block_def.arg_name);
+
+        writeln!(f, "uniform {uniform};")?;
+    }
+
+    writeln!(f)?;
+
+    for (kind, name, ty) in declarations {
+        writeln!(f, "{kind} {};", declarator(&struct_registry, &ty, &name))?;
+    }
+
+    writeln!(f)?;
+
+    write_func_defs(f, &options, &struct_registry, &func_registry)?;
+
+    writeln!(f, "void main() {{")?;
+    write_scope(f, write_context, scope_form.root_scope())?;
+    for ((name, _), simplified_expr) in outputs.iter().zip(var_form.simplified_roots()) {
+        writeln!(f, "    {name} = {simplified_expr};")?;
+    }
+    writeln!(f, "}}")?;
+
+    Ok(())
+}
+
+/// Lowers the outputs of a shader stage to GLSL ES 1.00.
+fn lower<'a>(
+    options: &TranspileOptions,
+    stage: ShaderStage,
+    outputs: &[(&'a str, Rc<Expr>)],
+) -> Result<Vec<(&'a str, Rc<Expr>)>, Es100Error> {
+    let mut lower = Lower {
+        options,
+        stage,
+        exprs: BTreeMap::new(),
+        funcs: BTreeMap::new(),
+        loop_indices: BTreeMap::new(),
+    };
+
+    outputs
+        .iter()
+        .map(|(name, expr)| Ok((*name, lower.expr(expr)?)))
+        .collect()
+}
+
+// Converts a fragment output to the `vec4` that is written to `gl_FragColor`.
+fn frag_color(name: &str, expr: &Rc<Expr>) -> Result<Rc<Expr>, Es100Error> {
+    use BuiltInType::*;
+
+    let float = |value: &str| {
+        Rc::new(Expr::ScalarLiteral {
+            value: value.to_string(),
+            ty: F32,
+        })
+    };
+
+    let args = match expr.ty() {
+        Type::BuiltIn(Vec4) => return Ok(expr.clone()),
+        Type::BuiltIn(Vec3) => vec![expr.clone(), float("1.0")],
+        Type::BuiltIn(Vec2) => vec![expr.clone(), float("0.0"), float("1.0")],
+        Type::BuiltIn(F32) => vec![expr.clone(), float("0.0"), float("0.0"), float("1.0")],
+        _ => return Err(Es100Error::IntegerFragmentOutput(name.to_string())),
+    };
+
+    Ok(Rc::new(Expr::CallBuiltIn {
+        name: "vec4".to_string(),
+        args,
+        ty: Type::BuiltIn(Vec4),
+    }))
+}
+
+struct Lower<'a> {
+    options: &'a TranspileOptions,
+    stage: ShaderStage,
+    exprs: BTreeMap<ExprKey, Rc<Expr>>,
+    funcs: BTreeMap<*const FuncDef, Rc<FuncDef>>,
+
+    // Unsigned loops are lowered to signed loops. Maps the names of their
+    // indices to the exclusive end of the loop.
+    loop_indices: BTreeMap<String, usize>,
+}
+
+impl<'a> Lower<'a> {
+    fn expr(&mut self, expr: &Rc<Expr>) -> Result<Rc<Expr>, Es100Error> {
+        let key = ExprKey::from(expr);
+
+        if let Some(lowered) = self.exprs.get(&key) {
+            return Ok(lowered.clone());
+        }
+
+        let lowered = self.lower_expr(expr)?;
+
+        check_type(&lowered.ty())?;
+
+        // Values that contain arrays cannot be assigned in GLSL ES 1.00. By
+        // not sharing them, we make sure that they never become variables.
+        if !contains_array(&expr.ty()) {
+            self.exprs.insert(key, lowered.clone());
+        }
+
+        Ok(lowered)
+    }
+
+    fn lower_expr(&mut self, expr: &Rc<Expr>) -> Result<Rc<Expr>, Es100Error> {
+        use Expr::*;
+
+        let ty = expr.ty();
+
+        if contains_array(&ty) && !matches!(**expr, Arg { .. } | Field { .. } | Subscript { .. }) {
+            return Err(Es100Error::ArrayValue);
+        }
+
+        let lowered = match &**expr {
+            Arg { name, .. } => {
+                if name == "gl_VertexID" || name == "gl_InstanceID" {
+                    return Err(Es100Error::BuiltInVariable(name.clone()));
+                }
+
+                if self.loop_indices.contains_key(name) {
+                    Arg {
+                        name: name.clone(),
+                        ty: Type::BuiltIn(BuiltInType::I32),
+                    }
+                } else {
+                    return Ok(expr.clone());
+                }
+            }
+            ScalarLiteral { .. } | Discard { .. } => return Ok(expr.clone()),
+            StructLiteral { args, ty } => StructLiteral {
+                args: self.exprs(args)?,
+                ty: ty.clone(),
+            },
+            ArrayLiteral { .. } => return Err(Es100Error::ArrayValue),
+            Unary { op, arg, ty } => {
+                if *op == UnaryOp::BitNot {
+                    return Err(Es100Error::UnaryOperator(*op));
+                }
+
+                Unary {
+                    op: *op,
+                    arg: self.expr(arg)?,
+                    ty: ty.clone(),
+                }
+            }
+            Binary {
+                left,
+                op,
+                right,
+                ty,
+            } => {
+                use BinaryOp::*;
+
+                if matches!(op, Shl | Shr | BitAnd | BitOr | BitXor | Rem) {
+                    return Err(Es100Error::BinaryOperator(*op));
+                }
+
+                if contains_array(&left.ty()) {
+                    return Err(Es100Error::ArrayValue);
+                }
+
+                Binary {
+                    left: self.expr(left)?,
+                    op: *op,
+                    right: self.expr(right)?,
+                    ty: ty.clone(),
+                }
+            }
+            CallFuncDef { def, args } => CallFuncDef {
+                def: self.func_def(def)?,
+                args: self.exprs(args)?,
+            },
+            CallBuiltIn { name, args, ty } => {
+                let args = self.exprs(args)?;
+                let name = self.built_in_name(name, &args, ty)?;
+
+                CallBuiltIn {
+                    name,
+                    args,
+                    ty: ty.clone(),
+                }
+            }
+            Field { base, name, ty } => Field {
+                base: self.expr(base)?,
+                name,
+                ty: ty.clone(),
+            },
+            Subscript { base, index, ty } => {
+                let len = subscript_len(&base.ty());
+
+                // Indices are unsigned, so only literal indices and the indices
+                // of unsigned loops can be expressed as signed integers. Loop
+                // indices cannot be clamped, so they must be in bounds.
+                let index = match &**index {
+                    ScalarLiteral { value, .. } => {
+                        let mut value: usize = value.trim_end_matches('u').parse().unwrap();
+
+                        if self.options.clamp_subscripts {
+                            value = value.min(len.saturating_sub(1));
+                        }
+
+                        signed_literal(value)
+                    }
+                    Arg { name, .. }
+                        if self.loop_indices.get(name).is_some_and(|&end| end <= len) =>
+                    {
+                        self.expr(index)?
+                    }
+                    _ => return Err(Es100Error::DynamicIndex),
+                };
+
+                Subscript {
+                    base: self.expr(base)?,
+                    index,
+                    ty: ty.clone(),
+                }
+            }
+            Branch { cond, yes, no, ty } => Branch {
+                cond: self.expr(cond)?,
+                yes: self.expr(yes)?,
+                no: self.expr(no)?,
+                ty: ty.clone(),
+            },
+            Switch {
+                value,
+                cases,
+                default,
+                ty,
+            } => {
+                let value = self.expr(value)?;
+                let mut lowered = self.expr(default)?;
+
+                for (label, case) in cases.iter().rev() {
+                    let label_ty = value.ty().built_in_type().unwrap();
+                    let label = Rc::new(Expr::ScalarLiteral {
+                        value: match label_ty {
+                            BuiltInType::U32 => format!("{label}u"),
+                            _ => label.to_string(),
+                        },
+                        ty: label_ty,
+                    });
+
+                    lowered = Rc::new(Branch {
+                        cond: Rc::new(Binary {
+                            left: value.clone(),
+                            op: BinaryOp::Eq,
+                            right: label,
+                            ty: Type::BuiltIn(BuiltInType::Bool),
+                        }),
+                        yes: self.expr(case)?,
+                        no: lowered,
+                        ty: ty.clone(),
+                    });
+                }
+
+                return Ok(lowered);
+            }
+            Loop {
+                start,
+                end,
+                init,
+                acc_name,
+                index_name,
+                body,
+                stop,
+                ty,
+            } => {
+                let init = self.expr(init)?;
+
+                let (start, end, unsigned_end) = match (&**start, &**end) {
+                    (
+                        ScalarLiteral {
+                            value: start,
+                            ty: BuiltInType::U32,
+                        },
+                        ScalarLiteral {
+                            value: end,
+                            ty: BuiltInType::U32,
+                        },
+                    ) => {
+                        let start: usize = start.trim_end_matches('u').parse().unwrap();
+                        let end: usize = end.trim_end_matches('u').parse().unwrap();
+
+                        (signed_literal(start), signed_literal(end), Some(end))
+                    }
+                    _ => (self.expr(start)?, self.expr(end)?, None),
+                };
+
+                if !matches!(*start, ScalarLiteral { .. }) || !matches!(*end, ScalarLiteral { .. })
+                {
+                    return Err(Es100Error::DynamicLoopBounds);
+                }
+
+                // Sibling loops reuse index names, so the index is only
+                // registered while lowering the body.
+                if let Some(end) = unsigned_end {
+                    self.loop_indices.insert(index_name.clone(), end);
+                }
+
+                let body = self.expr(body);
+                let stop = stop.as_ref().map(|stop| self.expr(stop)).transpose();
+
+                self.loop_indices.remove(index_name);
+
+                Loop {
+                    start,
+                    end,
+                    init,
+                    acc_name: acc_name.clone(),
+                    index_name: index_name.clone(),
+                    body: body?,
+                    stop: stop?,
+                    ty: ty.clone(),
+                }
+            }
+        };
+
+        Ok(Rc::new(lowered))
+    }
+
+    fn exprs(&mut self, exprs: &[Rc<Expr>]) -> Result<Vec<Rc<Expr>>, Es100Error> {
+        exprs.iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    fn func_def(&mut self, def: &Rc<FuncDef>) -> Result<Rc<FuncDef>, Es100Error> {
+        let key = &**def as *const FuncDef;
+
+        if let Some(lowered) = self.funcs.get(&key) {
+            return Ok(lowered.clone());
+        }
+
+        for (_, ty) in &def.params {
+            check_type(ty)?;
+
+            if contains_array(ty) {
+                return Err(Es100Error::ArrayValue);
+            }
+        }
+
+        let lowered = Rc::new(FuncDef {
+            name: def.name,
+            params: def.params.clone(),
+            result: self.expr(&def.result)?,
+        });
+
+        self.funcs.insert(key, lowered.clone());
+
+        Ok(lowered)
+    }
+
+    fn built_in_name(
+        &self,
+        name: &str,
+        args: &[Rc<Expr>],
+        ty: &Type,
+    ) -> Result<String, Es100Error> {
+        use SamplerType::*;
+        use ShaderStage::*;
+
+        let unsupported = || Es100Error::BuiltInFunction {
+            name: name.to_string(),
+            stage: self.stage,
+        };
+
+        // Constructors are called like their type.
+        if *name == format!("{ty}") {
+            return Ok(name.to_string());
+        }
+
+        if FLOAT_FUNCS.contains(&name) {
+            for arg in args {
+                let scalar_ty = arg.ty().built_in_type().and_then(scalar_type);
+
+                if scalar_ty != Some(BuiltInType::F32) {
+                    return Err(Es100Error::IntegerArgument(name.to_string()));
+                }
+            }
+
+            return Ok(name.to_string());
+        }
+
+        if OTHER_FUNCS.contains(&name) {
+            return Ok(name.to_string());
+        }
+
+        if name == "texelFetch" || name == "texelFetchOffset" {
+            return Err(Es100Error::TexelFetch);
+        }
+
+        let sampler_ty = match args.first().map(|arg| arg.ty()) {
+            Some(Type::BuiltIn(BuiltInType::Sampler(sampler_ty))) => sampler_ty,
+            _ => return Err(unsupported()),
+        };
+
+        // Biased lookups and explicit levels of detail are only available in
+        // fragment shaders and vertex shaders, respectively.
+        let lowered = match (name, sampler_ty, self.stage, args.len()) {
+            ("texture", ColorSampler2d, _, 2) | ("texture", ColorSampler2d, Fragment, 3) => {
+                "texture2D"
+            }
+            ("texture", ColorSamplerCube, _, 2) | ("texture", ColorSamplerCube, Fragment, 3) => {
+                "textureCube"
+            }
+            ("textureProj", ColorSampler2d, _, 2)
+            | ("textureProj", ColorSampler2d, Fragment, 3) => "texture2DProj",
+            ("textureLod", ColorSampler2d, Vertex, 3) => "texture2DLod",
+            ("textureLod", ColorSamplerCube, Vertex, 3) => "textureCubeLod",
+            ("textureProjLod", ColorSampler2d, Vertex, 3) => "texture2DProjLod",
+            _ => return Err(unsupported()),
+        };
+
+        Ok(lowered.to_string())
+    }
+}
+
+/// Checks that a type can be used in GLSL ES 1.00.
+fn check_type(ty: &Type) -> Result<(), Es100Error> {
+    use BuiltInType::*;
+
+    match ty {
+        Type::BuiltIn(ty) => match ty {
+            U32 | UVec2 | UVec3 | UVec4 | Mat2x3 | Mat2x4 | Mat3x2 | Mat3x4 | Mat4x2 | Mat4x3 => {
+                Err(Es100Error::UnsupportedType(*ty))
+            }
+            Sampler(SamplerType::ColorSampler2d | SamplerType::ColorSamplerCube) => Ok(()),
+            Sampler(_) => Err(Es100Error::UnsupportedType(*ty)),
+            _ => Ok(()),
+        },
+        Type::Struct(ty) => ty.fields.iter().try_for_each(|(_, ty)| check_type(ty)),
+        Type::Array(ArrayType { ty, .. }) => {
+            if matches!(**ty, Type::Array(_)) {
+                return Err(Es100Error::ArrayValue);
+            }
+
+            check_type(ty)
+        }
+    }
+}
+
+fn contains_array(ty: &Type) -> bool {
+    match ty {
+        Type::BuiltIn(_) => false,
+        Type::Struct(ty) => ty.fields.iter().any(|(_, ty)| contains_array(ty)),
+        Type::Array(_) => true,
+    }
+}
+
+fn scalar_type(ty: BuiltInType) -> Option<BuiltInType> {
+    use BuiltInType::*;
+
+    match ty {
+        F32 | Vec2 | Vec3 | Vec4 | Mat2 | Mat3 | Mat4 | Mat2x3 | Mat2x4 | Mat3x2 | Mat3x4
+        | Mat4x2 | Mat4x3 => Some(F32),
+        I32 | IVec2 | IVec3 | IVec4 => Some(I32),
+        U32 | UVec2 | UVec3 | UVec4 => Some(U32),
+        Bool | BVec2 | BVec3 | BVec4 => Some(Bool),
+        Sampler(_) => None,
+    }
+}
+
+fn signed_literal(value: usize) -> Rc<Expr> {
+    Rc::new(Expr::ScalarLiteral {
+        value: value.to_string(),
+        ty: BuiltInType::I32,
+    })
+}
+
+fn subscript_len(ty: &Type) -> usize {
+    use BuiltInType::*;
+
+    match ty {
+        Type::Array(ArrayType { len, .. }) => *len,
+        Type::BuiltIn(Vec2 | IVec2 | UVec2 | BVec2 | Mat2 | Mat2x3 | Mat2x4) => 2,
+        Type::BuiltIn(Vec3 | IVec3 | UVec3 | BVec3 | Mat3 | Mat3x2 | Mat3x4) => 3,
+        Type::BuiltIn(Vec4 | IVec4 | UVec4 | BVec4 | Mat4 | Mat4x2 | Mat4x3) => 4,
+        Type::BuiltIn(_) | Type::Struct(_) => 0,
+    }
+}
+
+// GLSL ES 1.00 puts the length of arrays after the declared name.
+fn declarator(struct_reg: &StructRegistry, ty: &Type, name: &str) -> String {
+    match ty {
+        Type::Array(ArrayType { ty, len }) => {
+            format!("{} {name}[{len}]", type_name(struct_reg, ty))
+        }
+        _ => format!("{} {name}", type_name(struct_reg, ty)),
+    }
+}
+
+// Returns the alignment and the size of a type in the `std140` layout.
+fn std140_layout(ty: &Type) -> (usize, usize) {
+    use BuiltInType::*;
+
+    match ty {
+        Type::BuiltIn(ty) => match ty {
+            F32 | I32 | U32 | Bool => (4, 4),
+            Vec2 | IVec2 | UVec2 | BVec2 => (8, 8),
+            Vec3 | IVec3 | UVec3 | BVec3 => (16, 12),
+            Vec4 | IVec4 | UVec4 | BVec4 => (16, 16),
+            // Matrices are laid out like arrays of their columns.
+            Mat2 | Mat2x3 | Mat2x4 => (16, 2 * 16),
+            Mat3 | Mat3x2 | Mat3x4 => (16, 3 * 16),
+            Mat4 | Mat4x2 | Mat4x3 => (16, 4 * 16),
+            Sampler(_) => panic!("samplers cannot be stored in uniform blocks"),
+        },
+        Type::Struct(ty) => {
+            let align = ty
+                .fields
+                .iter()
+                .map(|(_, ty)| std140_layout(ty).0)
+                .fold(16, usize::max);
+            let end = ty
+                .fields
+                .iter()
+                .zip(field_offsets(ty))
+                .map(|((_, ty), offset)| offset + std140_layout(ty).1)
+                .next_back()
+                .unwrap_or(0);
+
+            (align, end.next_multiple_of(align))
+        }
+        Type::Array(ArrayType { ty, len }) => {
+            let (align, stride) = std140_array_layout(ty);
+
+            (align, len * stride)
+        }
+    }
+}
+
+// Returns the alignment and the stride of array elements in the `std140`
+// layout.
+fn std140_array_layout(ty: &Type) -> (usize, usize) {
+    let (align, size) = std140_layout(ty);
+    let align = align.next_multiple_of(16);
+
+    (align, size.next_multiple_of(align))
+}
+
+fn field_offsets(ty: &StructType) -> Vec<usize> {
+    let mut offset: usize = 0;
+
+    ty.fields
+        .iter()
+        .map(|(_, ty)| {
+            let (align, size) = std140_layout(ty);
+            let field_offset = offset.next_multiple_of(align);
+
+            offset = field_offset + size;
+
+            field_offset
+        })
+        .collect()
+}
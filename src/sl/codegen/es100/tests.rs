@@ -0,0 +1,529 @@
+use std::rc::Rc;
+
+use expect_test::{expect, Expect};
+
+use crate::{
+    sl::{
+        self,
+        dag::{ArrayType, BinaryOp, BuiltInType, StructType, Type},
+        primitives::value_arg,
+        program_def::UniformBlockDef,
+        transpile::{transpile_to_es100_program_def, Es100Error, ShaderStage, TranspileOptions},
+        Object, Value,
+    },
+    Block, BlockDom, FsDom, FsInterface, Sl, ToSl, Uniform, UniformDom,
+};
+
+use super::{
+    super::{
+        func_registry::FuncRegistry, scope_form::ScopeForm, struct_registry::StructRegistry,
+        var_form::VarForm, write_scope, WriteFuncContext,
+    },
+    lower, plain_uniform_defs,
+};
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct MyBlock<D: BlockDom> {
+    scale: D::F32,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct MyGlobals<D: BlockDom> {
+    ambient: D::Vec4,
+    world_to_clip: D::Mat4,
+    block: MyBlock<D>,
+}
+
+#[derive(Copy, Clone, Uniform)]
+struct MyUniform<D: UniformDom> {
+    sampler: D::ColorSampler2d<sl::Vec3>,
+    globals: D::Block<MyGlobals<Sl>>,
+}
+
+#[derive(Copy, Clone, sl::Value, sl::Interpolant)]
+struct MyInterpolant {
+    uv: sl::Vec2,
+    scale: sl::F32,
+    instance_id: sl::U32,
+}
+
+#[derive(Copy, Clone, FsInterface)]
+struct MyFsInterface<D: FsDom> {
+    albedo: D::ColorAttachment<sl::Vec4>,
+    normal: D::ColorAttachment<sl::Vec3>,
+    glow: D::ColorAttachment<sl::F32>,
+}
+
+#[sl::function]
+fn lambert(normal: sl::Vec3, light_dir: sl::Vec3) -> sl::F32 {
+    normal.dot(light_dir).max(0.0)
+}
+
+fn transpile_expr<V: Value>(
+    value: V,
+    stage: ShaderStage,
+    options: &TranspileOptions,
+) -> Result<String, Es100Error> {
+    let roots = [lower(options, stage, &[("", value.expr())])?[0].1.clone()];
+    let func_registry = FuncRegistry::new(&roots);
+    let struct_registry = StructRegistry::new(&roots, None.into_iter());
+    let var_form = VarForm::new(
+        &struct_registry,
+        &func_registry,
+        &TranspileOptions::default(),
+        &roots,
+    );
+    let scope_form = ScopeForm::new(&var_form);
+    let write_context = WriteFuncContext {
+        struct_registry: &struct_registry,
+        scope_form: &scope_form,
+        depth: 0,
+    };
+
+    let mut str = String::new();
+    write_scope(&mut str, write_context, scope_form.root_scope()).unwrap();
+    str += &format!("{}", var_form.simplified_roots()[0]);
+
+    Ok(str)
+}
+
+fn check_expr<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = transpile_expr(
+        value.to_sl(),
+        ShaderStage::Fragment,
+        &TranspileOptions::default(),
+    )
+    .unwrap();
+
+    expect.assert_eq(&actual);
+}
+
+fn check_vertex_expr<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = transpile_expr(
+        value.to_sl(),
+        ShaderStage::Vertex,
+        &TranspileOptions::default(),
+    )
+    .unwrap();
+
+    expect.assert_eq(&actual);
+}
+
+fn check_error<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = transpile_expr(
+        value.to_sl(),
+        ShaderStage::Fragment,
+        &TranspileOptions::default(),
+    )
+    .unwrap_err();
+
+    expect.assert_eq(&actual.to_string());
+}
+
+#[test]
+fn test_switch() {
+    let x: sl::F32 = value_arg("x");
+    let i: sl::I32 = value_arg("i");
+
+    check_expr(
+        sl::switch(i, [(0, x), (1, x * 2.0), (-1, -x)], 0.0),
+        expect![[r#"
+            float var_2;
+            if ((i == 0)) {
+                var_2 = x;
+            } else {
+                float var_1;
+                if ((i == 1)) {
+                    var_1 = (x * 2.0);
+                } else {
+                    float var_0 = ((i == -1)) ? (- x) : (0.0);
+                    var_1 = var_0;
+                }
+                var_2 = var_1;
+            }
+            var_2"#]],
+    );
+}
+
+#[test]
+fn test_subscripts() {
+    let a = sl::Array::<sl::Vec2, 4>::from_arg("a");
+    let m: sl::Mat3 = value_arg("m");
+
+    check_expr(a.get(2u32).x + m.y_axis.z, expect!["(a[2].x + m[1].z)"]);
+    check_expr(
+        {
+            let v = a.get(1u32);
+
+            v + v
+        },
+        expect![[r#"
+            vec2 var_0 = a[1];
+            (var_0 + var_0)"#]],
+    );
+
+    let actual = transpile_expr(
+        a.get(7u32),
+        ShaderStage::Fragment,
+        &TranspileOptions {
+            clamp_subscripts: true,
+            ..TranspileOptions::default()
+        },
+    )
+    .unwrap();
+
+    expect!["a[3]"].assert_eq(&actual);
+}
+
+#[test]
+fn test_sampling() {
+    let sampler = sl::ColorSampler2d::<sl::Vec3>::from_arg("s");
+    let cube = sl::ColorSamplerCube::<sl::Vec4>::from_arg("c");
+    let uv: sl::Vec2 = value_arg("uv");
+
+    check_expr(sampler.sample(uv), expect!["texture2D(s, uv).xyz"]);
+    check_expr(
+        sampler.sample_bias(uv, 1.0),
+        expect!["texture2D(s, uv, 1.0).xyz"],
+    );
+    check_expr(
+        sampler.sample_proj(uv.extend(2.0)),
+        expect!["texture2DProj(s, vec3(uv.x, uv.y, 2.0)).xyz"],
+    );
+    check_expr(
+        cube.sample(uv.extend(1.0)),
+        expect!["textureCube(c, vec3(uv.x, uv.y, 1.0))"],
+    );
+    check_vertex_expr(
+        sampler.sample_lod(uv, 2.0),
+        expect!["texture2DLod(s, uv, 2.0).xyz"],
+    );
+    check_vertex_expr(
+        cube.sample_lod(uv.extend(1.0), 2.0),
+        expect!["textureCubeLod(c, vec3(uv.x, uv.y, 1.0), 2.0)"],
+    );
+}
+
+#[test]
+fn test_errors() {
+    let sampler = sl::ColorSampler2d::<sl::Vec3>::from_arg("s");
+    let shadow = sl::ComparisonSampler2d::from_arg("t");
+    let a = sl::Array::<sl::Vec2, 4>::from_arg("a");
+    let uv: sl::Vec2 = value_arg("uv");
+    let x: sl::F32 = value_arg("x");
+    let i: sl::I32 = value_arg("i");
+    let n: sl::U32 = value_arg("n");
+    let texel: sl::IVec2 = value_arg("texel");
+
+    check_error(
+        n.as_f32(),
+        expect!["GLSL ES 1.00 does not support the type `uint`"],
+    );
+    check_error(
+        i & 3,
+        expect!["GLSL ES 1.00 does not support the operator `&`"],
+    );
+    check_error(
+        i.abs(),
+        expect!["GLSL ES 1.00 only supports floating-point arguments for `abs`"],
+    );
+    check_error(
+        a.get(n),
+        expect!["GLSL ES 1.00 only supports subscripts with constant indices"],
+    );
+    check_error(
+        [x, x].to_sl().get(0u32),
+        expect!["GLSL ES 1.00 only supports arrays that are read from uniforms"],
+    );
+    check_error(
+        sampler.fetch(texel, 0u32),
+        expect!["GLSL ES 1.00 does not support the type `uint`"],
+    );
+    check_error(
+        sampler.sample_lod(uv, 2.0),
+        expect![
+            "GLSL ES 1.00 does not support the built-in function `textureLod` in fragment shaders"
+        ],
+    );
+    check_error(
+        shadow.sample_compare(uv, 0.5.to_sl()),
+        expect!["GLSL ES 1.00 does not support the type `sampler2DShadow`"],
+    );
+    check_error(
+        sl::fold(0.to_sl()..i, x, |acc, _| acc * 2.0),
+        expect!["GLSL ES 1.00 only supports loops with constant bounds"],
+    );
+
+    assert!(matches!(
+        transpile_expr(i % 2, ShaderStage::Vertex, &TranspileOptions::default()),
+        Err(Es100Error::BinaryOperator(BinaryOp::Rem))
+    ));
+    assert!(matches!(
+        transpile_expr(
+            sl::fold(0u32..8, x, |acc, j| acc + a.get(j).x),
+            ShaderStage::Fragment,
+            &TranspileOptions::default()
+        ),
+        Err(Es100Error::DynamicIndex)
+    ));
+    assert!(matches!(
+        transpile_expr(
+            sampler.sample_bias(uv, 1.0),
+            ShaderStage::Vertex,
+            &TranspileOptions::default()
+        ),
+        Err(Es100Error::BuiltInFunction {
+            stage: ShaderStage::Vertex,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_fold() {
+    let x: sl::F32 = value_arg("x");
+
+    check_expr(
+        sl::fold(0i32..4, x, |acc, i| acc + i.as_f32()),
+        expect![[r#"
+    float var_0 = x;
+    for (int loop_index_0 = 0; loop_index_0 < 4; loop_index_0++) {
+        float loop_acc_0 = var_0;
+        var_0 = (loop_acc_0 + float(loop_index_0));
+    }
+    var_0"#]],
+    );
+
+    // Unsigned loop indices are lowered to `int`, so arrays can be folded.
+    let a = sl::Array::<sl::Vec2, 4>::from_arg("a");
+
+    check_expr(
+        a.fold(x, |acc, v| acc + v.x),
+        expect![[r#"
+        float var_0 = x;
+        for (int loop_index_0 = 0; loop_index_0 < 4; loop_index_0++) {
+            float loop_acc_0 = var_0;
+            var_0 = (loop_acc_0 + a[loop_index_0].x);
+        }
+        var_0"#]],
+    );
+}
+
+#[test]
+fn test_plain_uniform_defs() {
+    use BuiltInType::*;
+
+    let inner = Rc::new(StructType {
+        name: "Inner".to_string(),
+        fields: vec![
+            ("scale".to_string(), Type::BuiltIn(F32)),
+            ("dir".to_string(), Type::BuiltIn(Vec3)),
+        ],
+    });
+    let outer = Rc::new(StructType {
+        name: "Outer".to_string(),
+        fields: vec![
+            ("flag".to_string(), Type::BuiltIn(Bool)),
+            ("transform".to_string(), Type::BuiltIn(Mat3)),
+            (
+                "weights".to_string(),
+                Type::Array(ArrayType {
+                    ty: Box::new(Type::BuiltIn(F32)),
+                    len: 2,
+                }),
+            ),
+            ("inner".to_string(), Type::Struct(inner)),
+            ("offset".to_string(), Type::BuiltIn(Vec2)),
+        ],
+    });
+
+    let block_defs = [
+        UniformBlockDef {
+            block_name: "a_posh_block".to_string(),
+            arg_name: "a".to_string(),
+            ty: Type::BuiltIn(Vec4),
+            location: 0,
+        },
+        UniformBlockDef {
+            block_name: "b_posh_block".to_string(),
+            arg_name: "b".to_string(),
+            ty: Type::Struct(outer),
+            location: 1,
+        },
+    ];
+
+    let defs: Vec<_> = plain_uniform_defs(&block_defs)
+        .into_iter()
+        .map(|def| (def.name, def.ty, def.block, def.offset))
+        .collect();
+
+    assert_eq!(
+        defs,
+        [
+            ("a".to_string(), Vec4, 0, 0),
+            ("b.flag".to_string(), Bool, 1, 0),
+            ("b.transform".to_string(), Mat3, 1, 16),
+            ("b.weights[0]".to_string(), F32, 1, 64),
+            ("b.weights[1]".to_string(), F32, 1, 80),
+            ("b.inner.scale".to_string(), F32, 1, 96),
+            ("b.inner.dir".to_string(), Vec3, 1, 112),
+            ("b.offset".to_string(), Vec2, 1, 128),
+        ]
+    );
+}
+
+#[test]
+fn test_program() {
+    fn vertex_shader(uniform: MyUniform<Sl>, vertex: sl::Vec3) -> sl::VsOutput<sl::Vec2> {
+        sl::VsOutput {
+            clip_pos: uniform.globals.world_to_clip * vertex.extend(1.0),
+            interp: vertex.xy() * uniform.globals.block.scale,
+        }
+    }
+
+    fn fragment_shader(uniform: MyUniform<Sl>, uv: sl::Vec2) -> sl::Vec3 {
+        let color = uniform.sampler.sample(uv);
+
+        color * lambert(color, sl::Vec3::Z) + uniform.globals.ambient.xyz()
+    }
+
+    let actual =
+        transpile_to_es100_program_def::<MyUniform<Sl>, _, _, _, _>(vertex_shader, fragment_shader)
+            .unwrap();
+
+    let plain_uniforms: Vec<_> = actual
+        .plain_uniform_defs
+        .iter()
+        .map(|def| (def.name.as_str(), def.offset))
+        .collect();
+
+    assert_eq!(
+        plain_uniforms,
+        [
+            ("uniforms_globals.ambient", 0),
+            ("uniforms_globals.world_to_clip", 16),
+            ("uniforms_globals.block.scale", 80),
+        ]
+    );
+
+    expect![[r#"
+        #version 100
+
+        precision highp float;
+        precision highp int;
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+        struct MyGlobals_Posh1 {
+            vec4 ambient;
+            mat4 world_to_clip;
+            MyBlock_Posh0 block;
+        };
+
+        uniform sampler2D uniforms_sampler;
+        uniform MyGlobals_Posh1 uniforms_globals;
+
+        attribute vec3 vertex_input;
+        varying vec2 vertex_output;
+
+        void main() {
+            gl_Position = (uniforms_globals.world_to_clip * vec4(vertex_input.x, vertex_input.y, vertex_input.z, 1.0));
+            vertex_output = (vertex_input.xy * uniforms_globals.block.scale);
+        }
+    "#]].assert_eq(&actual.vertex_shader_source);
+    expect![[r#"
+        #version 100
+
+        #ifdef GL_FRAGMENT_PRECISION_HIGH
+        precision highp float;
+        precision highp int;
+        #else
+        precision mediump float;
+        precision mediump int;
+        #endif
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+        struct MyGlobals_Posh1 {
+            vec4 ambient;
+            mat4 world_to_clip;
+            MyBlock_Posh0 block;
+        };
+
+        uniform sampler2D uniforms_sampler;
+        uniform MyGlobals_Posh1 uniforms_globals;
+
+        varying vec2 vertex_output;
+
+        float lambert_posh_func0(vec3 normal, vec3 light_dir) {
+            return max(dot(normal, light_dir), 0.0);
+        }
+
+        void main() {
+            vec3 var_0 = texture2D(uniforms_sampler, vertex_output).xyz;
+            gl_FragColor = vec4(((var_0 * lambert_posh_func0(var_0, vec3(0.0, 0.0, 1.0))) + uniforms_globals.ambient.xyz), 1.0);
+        }
+    "#]].assert_eq(&actual.fragment_shader_source);
+}
+
+#[test]
+fn test_program_errors() {
+    fn vertex_shader((): (), vertex: sl::IVec2) -> sl::VsOutput<sl::Vec2> {
+        sl::VsOutput {
+            clip_pos: vertex.as_vec2().extend(0.0).extend(1.0),
+            interp: vertex.as_vec2(),
+        }
+    }
+
+    fn fragment_shader((): (), uv: sl::Vec2) -> sl::Vec4 {
+        uv.extend(0.0).extend(1.0)
+    }
+
+    let actual = transpile_to_es100_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    assert!(matches!(actual, Err(Es100Error::IntegerAttribute(_))));
+
+    fn flat_vertex_shader((): (), vertex: sl::Vec2) -> sl::VsOutput<MyInterpolant> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(0.0).extend(1.0),
+            interp: MyInterpolant {
+                uv: vertex,
+                scale: 1.0.to_sl(),
+                instance_id: 0u32.to_sl(),
+            },
+        }
+    }
+
+    fn flat_fragment_shader((): (), interp: MyInterpolant) -> sl::Vec2 {
+        interp.uv
+    }
+
+    let actual =
+        transpile_to_es100_program_def::<(), _, _, _, _>(flat_vertex_shader, flat_fragment_shader);
+
+    assert!(matches!(actual, Err(Es100Error::FlatInterpolant(_))));
+
+    fn mrt_vertex_shader((): (), vertex: sl::Vec2) -> sl::VsOutput<sl::Vec2> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(0.0).extend(1.0),
+            interp: vertex,
+        }
+    }
+
+    fn mrt_fragment_shader((): (), uv: sl::Vec2) -> MyFsInterface<Sl> {
+        MyFsInterface {
+            albedo: uv.extend(0.0).extend(1.0),
+            normal: sl::Vec3::Z,
+            glow: uv.x,
+        }
+    }
+
+    let actual =
+        transpile_to_es100_program_def::<(), _, _, _, _>(mrt_vertex_shader, mrt_fragment_shader);
+
+    assert!(matches!(actual, Err(Es100Error::MultipleRenderTargets(3))));
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use crate::sl::{
+    dag::{Expr, FuncDef},
+    mangle::mangle_prefix,
+};
+
+use super::simplified_expr::ExprKey;
+
+type FuncId = usize;
+
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+struct FuncKey(*const FuncDef);
+
+impl<'a> From<&'a Rc<FuncDef>> for FuncKey {
+    fn from(value: &'a Rc<FuncDef>) -> Self {
+        FuncKey(&**value as *const _)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FuncRegistry {
+    defs: Vec<Rc<FuncDef>>,
+    ids: BTreeMap<FuncKey, FuncId>,
+}
+
+impl FuncRegistry {
+    pub fn new(roots: &[Rc<Expr>]) -> Self {
+        let mut registry = Self::default();
+        let mut visited = BTreeSet::new();
+
+        for expr in roots {
+            registry.collect_funcs_in_expr(expr, &mut visited);
+        }
+
+        registry
+    }
+
+    pub fn name(&self, def: &Rc<FuncDef>) -> String {
+        func_name(def.name, self.ids[&def.into()])
+    }
+
+    /// Returns the function definitions, ordered such that every function
+    /// comes after all the functions it calls.
+    pub fn defs(&self) -> impl Iterator<Item = (String, &FuncDef)> + '_ {
+        self.defs
+            .iter()
+            .enumerate()
+            .map(|(id, def)| (func_name(def.name, id), &**def))
+    }
+
+    fn collect_funcs_in_expr(&mut self, expr: &Rc<Expr>, visited: &mut BTreeSet<ExprKey>) {
+        if visited.contains(&expr.into()) {
+            return;
+        }
+
+        visited.insert(expr.into());
+
+        expr.successors(|succ| self.collect_funcs_in_expr(succ, visited));
+
+        if let Expr::CallFuncDef { def, .. } = &**expr {
+            let key = FuncKey::from(def);
+
+            if self.ids.contains_key(&key) {
+                return;
+            }
+
+            // Visit the function body before registering the function itself,
+            // so that callees are defined before their callers.
+            self.collect_funcs_in_expr(&def.result, visited);
+
+            self.ids.insert(key, self.defs.len());
+            self.defs.push(def.clone());
+        }
+    }
+}
+
+fn func_name(name: &str, id: FuncId) -> String {
+    format!("{}_posh_func{id}", mangle_prefix(name))
+}
@@ -1,4 +1,7 @@
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    iter::once,
+};
 
 use crate::sl::dag::Type;
 
@@ -18,6 +21,22 @@ pub enum VarInit<'a> {
         no_id: ScopeId,
         ty: &'a Type,
     },
+    Switch {
+        value: &'a SimplifiedExpr,
+        case_ids: Vec<(i64, ScopeId)>,
+        default_id: ScopeId,
+        ty: &'a Type,
+    },
+    Loop {
+        start: &'a SimplifiedExpr,
+        end: &'a SimplifiedExpr,
+        init: &'a SimplifiedExpr,
+        acc_name: &'a str,
+        index_name: &'a str,
+        body_id: ScopeId,
+        stop: Option<&'a SimplifiedExpr>,
+        ty: &'a Type,
+    },
     Discard,
 }
 
@@ -95,6 +114,86 @@ impl<'a> ScopeForm<'a> {
                         ty,
                     }
                 }
+                Switch {
+                    value,
+                    cases,
+                    default,
+                    ty,
+                } => {
+                    scope_form.insert_deps(parent_id, value);
+
+                    let case_ids = cases
+                        .iter()
+                        .map(|(label, case)| {
+                            let case_id = scope_form.add(
+                                Scope {
+                                    parent_id: Some(parent_id),
+                                    depth: scope_form.scopes[&parent_id].depth + 1,
+                                    vars: BTreeMap::new(),
+                                    result: Some(case),
+                                },
+                                &[case],
+                            );
+
+                            (*label, case_id)
+                        })
+                        .collect();
+                    let default_id = scope_form.add(
+                        Scope {
+                            parent_id: Some(parent_id),
+                            depth: scope_form.scopes[&parent_id].depth + 1,
+                            vars: BTreeMap::new(),
+                            result: Some(default),
+                        },
+                        &[default],
+                    );
+
+                    VarInit::Switch {
+                        value,
+                        case_ids,
+                        default_id,
+                        ty,
+                    }
+                }
+                Loop {
+                    start,
+                    end,
+                    init,
+                    acc_name,
+                    index_name,
+                    body,
+                    stop,
+                    ty,
+                } => {
+                    scope_form.insert_deps(parent_id, start);
+                    scope_form.insert_deps(parent_id, end);
+                    scope_form.insert_deps(parent_id, init);
+
+                    // The stopping condition is evaluated in the same scope as
+                    // the loop body, since it may depend on the body's result.
+                    let body_exprs: Vec<_> = once(&**body).chain(stop.as_deref()).collect();
+
+                    let body_id = scope_form.add(
+                        Scope {
+                            parent_id: Some(parent_id),
+                            depth: scope_form.scopes[&parent_id].depth + 1,
+                            vars: BTreeMap::new(),
+                            result: Some(body),
+                        },
+                        &body_exprs,
+                    );
+
+                    VarInit::Loop {
+                        start,
+                        end,
+                        init,
+                        acc_name,
+                        index_name,
+                        body_id,
+                        stop: stop.as_deref(),
+                        ty,
+                    }
+                }
                 Arg { .. }
                 | ScalarLiteral { .. }
                 | Unary { .. }
@@ -178,6 +277,16 @@ fn unscoped_successors(expr: &SimplifiedExpr, f: &mut impl FnMut(VarId)) {
         Branch { cond, .. } => {
             unscoped_successors(cond, f);
         }
+        Switch { value, .. } => {
+            unscoped_successors(value, f);
+        }
+        Loop {
+            start, end, init, ..
+        } => {
+            unscoped_successors(start, f);
+            unscoped_successors(end, f);
+            unscoped_successors(init, f);
+        }
         Arg { .. } | ScalarLiteral { .. } => (),
         Binary { left, right, .. } => {
             unscoped_successors(left, f);
@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use crate::sl::dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp};
+use crate::sl::{
+    dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp},
+    mangle::mangle_ident,
+};
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ExprKey(*const Expr);
@@ -63,6 +66,22 @@ pub enum SimplifiedExpr {
         no: Box<SimplifiedExpr>,
         ty: Type,
     },
+    Switch {
+        value: Box<SimplifiedExpr>,
+        cases: Vec<(i64, SimplifiedExpr)>,
+        default: Box<SimplifiedExpr>,
+        ty: Type,
+    },
+    Loop {
+        start: Box<SimplifiedExpr>,
+        end: Box<SimplifiedExpr>,
+        init: Box<SimplifiedExpr>,
+        acc_name: String,
+        index_name: String,
+        body: Box<SimplifiedExpr>,
+        stop: Option<Box<SimplifiedExpr>>,
+        ty: Type,
+    },
     Discard {
         ty: Type,
     },
@@ -82,6 +101,8 @@ impl SimplifiedExpr {
             Branch { ty, .. } => ty.clone(),
             Subscript { ty, .. } => ty.clone(),
             Var { ty, .. } => ty.clone(),
+            Switch { ty, .. } => ty.clone(),
+            Loop { ty, .. } => ty.clone(),
             Discard { ty, .. } => ty.clone(),
         }
     }
@@ -132,11 +153,17 @@ impl Display for SimplifiedExpr {
 
                     write!(f, "{base}[{index}]")
                 }
+                Type::Struct(_) => write!(f, "{base}.{}", mangle_ident(name)),
                 _ => write!(f, "{base}.{name}"),
             },
             Branch { cond, yes, no, .. } => write!(f, "({cond} ? {yes} : {no})"),
             Subscript { base, index, .. } => write!(f, "{base}[{index}]"),
             Var { id, .. } => write!(f, "{id}"),
+            Switch { .. } | Loop { .. } => {
+                // Switches and loops are always assigned to variables, so they
+                // can only be written as statements.
+                unreachable!()
+            }
             Discard { .. } => write!(f, "discard"),
         }
     }
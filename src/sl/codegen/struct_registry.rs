@@ -3,7 +3,10 @@ use std::{
     rc::Rc,
 };
 
-use crate::sl::dag::{ArrayType, Expr, StructType, Type};
+use crate::sl::{
+    dag::{ArrayType, Expr, StructType, Type},
+    mangle::mangle_prefix,
+};
 
 use super::simplified_expr::ExprKey;
 
@@ -72,7 +75,7 @@ impl StructRegistry {
 }
 
 fn struct_name(name: &str, id: StructId) -> String {
-    format!("{name}_Posh{id}")
+    format!("{}_Posh{id}", mangle_prefix(name))
 }
 
 fn get_struct_type(ty: &Type) -> Option<&Rc<StructType>> {
@@ -3,18 +3,41 @@ use std::f32::consts::PI;
 use expect_test::{expect, Expect};
 
 use crate::{
-    sl::{self, primitives::value_arg, Derivatives, FsInput, Value},
-    Sl, ToSl, VsInterface,
+    sl::{
+        self,
+        primitives::value_arg,
+        transpile::{transpile_to_program_def_with_options, GlslTarget, TranspileOptions},
+        Derivatives, FsInput, Object, Value,
+    },
+    Block, BlockDom, Sl, ToSl, Uniform, UniformDom, VsInterface,
 };
 
 use super::{
-    scope_form::ScopeForm, struct_registry::StructRegistry, var_form::VarForm, write_scope,
-    WriteFuncContext,
+    func_registry::FuncRegistry, scope_form::ScopeForm, struct_registry::StructRegistry,
+    var_form::VarForm, write_scope, WriteFuncContext,
 };
 
-fn transpile_expr<V: Value>(value: V) -> String {
-    let struct_registry = StructRegistry::new(&[], None.into_iter());
-    let var_form = VarForm::new(&struct_registry, &[value.expr()]);
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct MyBlock<D: BlockDom> {
+    scale: D::F32,
+}
+
+#[derive(Copy, Clone, Uniform)]
+struct MyBoundUniform<D: UniformDom> {
+    scene: D::Block<MyBlock<Sl>>,
+    #[uniform(binding = 0)]
+    camera: D::Block<sl::Mat4>,
+    #[uniform(unit = 1)]
+    albedo: D::ColorSampler2d<sl::Vec4>,
+    shadow: D::ComparisonSampler2d,
+}
+
+fn transpile_expr<V: Value>(value: V, options: &TranspileOptions) -> String {
+    let roots = [value.expr()];
+    let func_registry = FuncRegistry::new(&roots);
+    let struct_registry = StructRegistry::new(&roots, None.into_iter());
+    let var_form = VarForm::new(&struct_registry, &func_registry, options, &roots);
     let scope_form = ScopeForm::new(&var_form);
     let write_context = WriteFuncContext {
         struct_registry: &struct_registry,
@@ -30,7 +53,17 @@ fn transpile_expr<V: Value>(value: V) -> String {
 }
 
 fn check_expr<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
-    let actual = transpile_expr(value.to_sl());
+    let actual = transpile_expr(value.to_sl(), &TranspileOptions::default());
+
+    expect.assert_eq(&actual);
+}
+
+fn check_expr_with_options<V: Value>(
+    value: impl ToSl<Output = V>,
+    options: &TranspileOptions,
+    expect: Expect,
+) {
+    let actual = transpile_expr(value.to_sl(), options);
 
     expect.assert_eq(&actual);
 }
@@ -449,3 +482,592 @@ fn fs_input<W: VsInterface<Sl>>() -> FsInput<W> {
         derivatives: Derivatives(()),
     }
 }
+
+#[test]
+fn test_fold() {
+    let x: sl::F32 = value_arg("x");
+    let n: sl::U32 = value_arg("n");
+
+    check_expr(
+        sl::fold(0i32..64, x, |acc, i| acc * 0.5 + i.as_f32()),
+        expect![[r#"
+            float var_0 = x;
+            for (int loop_index_0 = 0; loop_index_0 < 64; loop_index_0++) {
+                float loop_acc_0 = var_0;
+                var_0 = ((loop_acc_0 * 0.5) + float(loop_index_0));
+            }
+            var_0"#]],
+    );
+    check_expr(
+        sl::fold(1u32.to_sl()..n, sl::Vec2::ZERO, |acc, i| {
+            let y = x * i.as_f32();
+
+            acc + sl::vec2(y, y * y)
+        }),
+        expect![[r#"
+            vec2 var_1 = vec2(0.0, 0.0);
+            for (uint loop_index_0 = 1u; loop_index_0 < n; loop_index_0++) {
+                vec2 loop_acc_0 = var_1;
+                float var_0 = (x * float(loop_index_0));
+                var_1 = (loop_acc_0 + vec2(var_0, (var_0 * var_0)));
+            }
+            var_1"#]],
+    );
+}
+
+#[test]
+fn test_fold_until() {
+    let x: sl::F32 = value_arg("x");
+
+    check_expr(
+        sl::fold_until(
+            0i32..100,
+            x,
+            |acc, _| (acc * acc).sqrt() + 1.0,
+            |acc| acc.gt(10.0),
+        ),
+        expect![[r#"
+            float var_1 = x;
+            for (int loop_index_0 = 0; loop_index_0 < 100; loop_index_0++) {
+                float loop_acc_0 = var_1;
+                float var_0 = (sqrt((loop_acc_0 * loop_acc_0)) + 1.0);
+                var_1 = var_0;
+                if ((var_0 > 10.0)) {
+                    break;
+                }
+            }
+            var_1"#]],
+    );
+}
+
+#[test]
+fn test_fold_nested() {
+    let x: sl::F32 = value_arg("x");
+    let cond: sl::Bool = value_arg("cond");
+
+    check_expr(
+        sl::fold(0i32..4, (x, 0), |(acc, count), i| {
+            let inner = sl::fold(sl::I32::ZERO..i, acc, |inner, j| inner + j.as_f32());
+            let inner = cond.then(inner).otherwise(acc);
+
+            (inner, count + 1)
+        }),
+        expect![[r#"
+            tuple_Posh0 var_2 = tuple_Posh0(x, 0);
+            for (int loop_index_0 = 0; loop_index_0 < 4; loop_index_0++) {
+                tuple_Posh0 loop_acc_0 = var_2;
+                float var_1;
+                if (cond) {
+                    float var_0 = loop_acc_0.T6;
+                    for (int loop_index_1 = 0; loop_index_1 < loop_index_0; loop_index_1++) {
+                        float loop_acc_1 = var_0;
+                        var_0 = (loop_acc_1 + float(loop_index_1));
+                    }
+                    var_1 = var_0;
+                } else {
+                    var_1 = loop_acc_0.T6;
+                }
+                var_2 = tuple_Posh0(var_1, (loop_acc_0.T7 + 1));
+            }
+            var_2"#]],
+    );
+}
+
+#[test]
+fn test_fold_after_panic() {
+    let x: sl::F32 = value_arg("x");
+
+    let result = std::panic::catch_unwind(|| {
+        sl::fold(0i32..4, x, |_, _| -> sl::F32 { panic!("inside loop body") })
+    });
+    assert!(result.is_err());
+
+    check_expr(
+        sl::fold(0i32..4, x, |acc, _| acc * 2.0),
+        expect![[r#"
+            float var_0 = x;
+            for (int loop_index_0 = 0; loop_index_0 < 4; loop_index_0++) {
+                float loop_acc_0 = var_0;
+                var_0 = (loop_acc_0 * 2.0);
+            }
+            var_0"#]],
+    );
+}
+
+#[test]
+fn test_switch() {
+    let x: sl::F32 = value_arg("x");
+    let i: sl::I32 = value_arg("i");
+    let n: sl::U32 = value_arg("n");
+
+    check_expr(
+        sl::switch(i, [(0, x), (1, x * 2.0), (-1, -x)], 0.0),
+        expect![[r#"
+            float var_0;
+            switch (i) {
+                case 0: {
+                    var_0 = x;
+                    break;
+                }
+                case 1: {
+                    var_0 = (x * 2.0);
+                    break;
+                }
+                case -1: {
+                    var_0 = - x;
+                    break;
+                }
+                default: {
+                    var_0 = 0.0;
+                    break;
+                }
+            }
+            var_0"#]],
+    );
+    check_expr(
+        sl::switch(
+            n,
+            [(3, {
+                let y = x.sin();
+
+                sl::vec2(y, y)
+            })],
+            sl::Vec2::ZERO,
+        ) + sl::vec2(x.sin(), 1.0),
+        expect![[r#"
+            vec2 var_1;
+            switch (n) {
+                case 3u: {
+                    float var_0 = sin(x);
+                    var_1 = vec2(var_0, var_0);
+                    break;
+                }
+                default: {
+                    var_1 = vec2(0.0, 0.0);
+                    break;
+                }
+            }
+            (var_1 + vec2(sin(x), 1.0))"#]],
+    );
+}
+
+#[test]
+fn test_swizzle() {
+    let v: sl::Vec4 = value_arg("v");
+    let u: sl::UVec2 = value_arg("u");
+    let x: sl::F32 = value_arg("x");
+
+    check_expr(v.wzyx(), expect!["v.wzyx"]);
+    check_expr(v.bgr() + v.stp(), expect!["(v.zyx + v.xyz)"]);
+    check_expr(
+        u.yxyx().as_vec4() + v.xxyy(),
+        expect!["(vec4(u.yxyx) + v.xxyy)"],
+    );
+    check_expr(sl::vec3(x, 1.0, 2.0).zyx(), expect!["vec3(2.0, 1.0, x)"]);
+    check_expr(v.zyx().x, expect!["v.zyx.x"]);
+}
+
+#[test]
+fn test_sampling() {
+    let sampler = sl::ColorSampler2d::<sl::Vec3>::from_arg("s");
+    let shadow = sl::ComparisonSampler2d::from_arg("t");
+    let uv: sl::Vec2 = value_arg("uv");
+    let texel: sl::IVec2 = value_arg("texel");
+
+    check_expr(
+        sampler.sample_lod(uv, 2.0),
+        expect!["textureLod(s, uv, 2.0).xyz"],
+    );
+    check_expr(
+        sampler.sample_grad_offset(uv, uv * 0.5, uv * 2.0, [-1, 1]),
+        expect!["textureGradOffset(s, uv, (uv * 0.5), (uv * 2.0), ivec2(-1, 1)).xyz"],
+    );
+    check_expr(
+        sampler.sample_proj(uv.extend(2.0)),
+        expect!["textureProj(s, vec3(uv.x, uv.y, 2.0)).xyz"],
+    );
+    check_expr(
+        sampler.fetch(texel, 0u32),
+        expect!["texelFetch(s, texel, int(0u)).xyz"],
+    );
+    check_expr(
+        shadow.sample_compare_offset(uv, 0.5.to_sl(), [0, 1]),
+        expect!["textureOffset(t, vec3(uv.x, uv.y, 0.5), ivec2(0, 1))"],
+    );
+    check_expr(
+        shadow.sample_compare_proj(uv, 0.5.to_sl(), 2.0),
+        expect!["textureProj(t, vec4(uv.x, uv.y, 0.5, 2.0))"],
+    );
+}
+
+#[test]
+fn test_sampling_cube() {
+    let sampler = sl::ColorSamplerCube::<sl::Vec4>::from_arg("s");
+    let shadow = sl::ComparisonSamplerCube::from_arg("t");
+    let dir: sl::Vec3 = value_arg("dir");
+
+    check_expr(sampler.sample(dir), expect!["texture(s, dir)"]);
+    check_expr(
+        sampler.sample_lod(dir, 1.0),
+        expect!["textureLod(s, dir, 1.0)"],
+    );
+    check_expr(
+        sampler.size(0u32),
+        expect!["uvec2(textureSize(s, int(0u)))"],
+    );
+    check_expr(
+        shadow.sample_compare(dir, 0.5.to_sl()),
+        expect!["texture(t, vec4(dir.x, dir.y, dir.z, 0.5))"],
+    );
+}
+
+#[test]
+fn test_sampling_layered() {
+    let volume = sl::ColorSampler3d::<sl::F32>::from_arg("v");
+    let array = sl::ColorSampler2dArray::<sl::Vec4>::from_arg("a");
+    let shadow = sl::ComparisonSampler2dArray::from_arg("t");
+    let uv: sl::Vec2 = value_arg("uv");
+    let layer: sl::U32 = value_arg("layer");
+
+    check_expr(
+        volume.sample(uv.extend(0.5)),
+        expect!["texture(v, vec3(uv.x, uv.y, 0.5)).x"],
+    );
+    check_expr(
+        volume.sample_offset(uv.extend(0.5), [1, 0, -1]),
+        expect!["textureOffset(v, vec3(uv.x, uv.y, 0.5), ivec3(1, 0, -1)).x"],
+    );
+    check_expr(volume.size(0u32), expect!["uvec3(textureSize(v, int(0u)))"]);
+    check_expr(
+        array.sample(uv, layer),
+        expect!["texture(a, vec3(uv.x, uv.y, float(layer)))"],
+    );
+    check_expr(
+        array.fetch(sl::IVec2::ZERO, layer, 0u32),
+        expect!["texelFetch(a, ivec3(0, 0, int(layer)), int(0u))"],
+    );
+    check_expr(
+        shadow.sample_compare(uv, 2u32, 0.5.to_sl()),
+        expect!["texture(t, vec4(uv.x, uv.y, float(2u), 0.5))"],
+    );
+}
+
+#[test]
+fn test_non_square_mat() {
+    let m: sl::Mat4x3 = value_arg("m");
+    let n: sl::Mat2x4 = value_arg("n");
+    let v: sl::Vec4 = value_arg("v");
+
+    check_expr(m * v, expect!["(m * v)"]);
+    check_expr(m * n, expect!["(m * n)"]);
+    check_expr(
+        m.transpose() * sl::Vec3::X,
+        expect!["(transpose(m) * vec3(1.0, 0.0, 0.0))"],
+    );
+    check_expr(m.get(1u32), expect!["m[1u]"]);
+    check_expr(
+        sl::Mat3x2::IDENTITY,
+        expect!["mat3x2(vec2(1.0, 0.0), vec2(0.0, 1.0), vec2(0.0, 0.0))"],
+    );
+}
+
+#[test]
+fn test_bits_and_packing() {
+    let x: sl::F32 = value_arg("x");
+    let n: sl::Vec3 = value_arg("n");
+    let p: sl::U32 = value_arg("p");
+
+    check_expr(x.to_bits(), expect!["floatBitsToUint(x)"]);
+    check_expr(x.to_bits_i32(), expect!["floatBitsToInt(x)"]);
+    check_expr(sl::F32::from_bits(p), expect!["uintBitsToFloat(p)"]);
+    check_expr(sl::F32::from_bits_i32(-1), expect!["intBitsToFloat(-1)"]);
+    check_expr(n.to_bits(), expect!["floatBitsToUint(n)"]);
+    check_expr(n.xy().pack_half_2x16(), expect!["packHalf2x16(n.xy)"]);
+    check_expr(n.xy().pack_unorm_2x16(), expect!["packUnorm2x16(n.xy)"]);
+    check_expr(n.xy().pack_snorm_2x16(), expect!["packSnorm2x16(n.xy)"]);
+    check_expr(p.unpack_half_2x16(), expect!["unpackHalf2x16(p)"]);
+    check_expr(p.unpack_unorm_2x16(), expect!["unpackUnorm2x16(p)"]);
+    check_expr(p.unpack_snorm_2x16(), expect!["unpackSnorm2x16(p)"]);
+}
+
+#[test]
+fn test_integral_gen_type() {
+    let i: sl::I32 = value_arg("i");
+    let u: sl::UVec2 = value_arg("u");
+    let mask: sl::BVec2 = value_arg("mask");
+
+    check_expr(i.abs(), expect!["abs(i)"]);
+    check_expr(i.signum(), expect!["sign(i)"]);
+    check_expr(i.clamp(0, 15), expect!["clamp(i, 0, 15)"]);
+    check_expr(
+        u.min(sl::UVec2::splat(3u32)),
+        expect!["min(u, uvec2(3u, 3u))"],
+    );
+    check_expr(
+        sl::I32::select(i.gt(0), i, -i),
+        expect![[r#"
+        int var_0 = ((i > 0)) ? (i) : (- i);
+        var_0"#]],
+    );
+    check_expr(
+        sl::UVec2::select(mask, u, sl::UVec2::ZERO),
+        expect![[r#"
+        uint var_0 = (mask.x) ? (u.x) : (0u);
+        uint var_1 = (mask.y) ? (u.y) : (0u);
+        uvec2(var_0, var_1)"#]],
+    );
+}
+
+#[test]
+fn test_select() {
+    let mask: sl::BVec2 = value_arg("mask");
+    let a: sl::Vec2 = value_arg("a");
+    let b: sl::Vec2 = value_arg("b");
+    let i: sl::IVec2 = value_arg("i");
+    let j: sl::IVec2 = value_arg("j");
+
+    // Both selects take their first argument where `mask` holds.
+    check_expr(sl::Vec2::select(mask, a, b), expect!["mix(b, a, mask)"]);
+    check_expr(
+        sl::IVec2::select(mask, i, j),
+        expect![[r#"
+        int var_0 = (mask.x) ? (i.x) : (j.x);
+        int var_1 = (mask.y) ? (i.y) : (j.y);
+        ivec2(var_0, var_1)"#]],
+    );
+}
+
+#[test]
+fn test_bounds_safe_indexing() {
+    let array: sl::Array<sl::Vec2, 4> = value_arg("a");
+    let v: sl::IVec3 = value_arg("v");
+    let m: sl::Mat4x2 = value_arg("m");
+    let i: sl::U32 = value_arg("i");
+
+    check_expr(array.get_clamped(i), expect!["a[min(i, 3u)]"]);
+    check_expr(array.get_wrapped(i), expect!["a[(i % 4u)]"]);
+    check_expr(
+        array.get_or(i, sl::Vec2::ZERO),
+        expect![[r#"
+        vec2 var_0 = ((i < 4u)) ? (a[i]) : (vec2(0.0, 0.0));
+        var_0"#]],
+    );
+    check_expr(v.get_clamped(i), expect!["v[min(i, 2u)]"]);
+    check_expr(m.get_wrapped(i), expect!["m[(i % 4u)]"]);
+
+    let options = TranspileOptions {
+        clamp_subscripts: true,
+        ..Default::default()
+    };
+
+    check_expr_with_options(
+        array.get(i) + m.get(i),
+        &options,
+        expect!["(a[min(i, 3u)] + m[min(i, 3u)])"],
+    );
+}
+
+#[test]
+fn test_array_combinators() {
+    let weights: sl::Array<sl::F32, 8> = value_arg("w");
+    let values: sl::Array<sl::Vec2, 8> = value_arg("v");
+
+    check_expr(
+        weights.sum(),
+        expect![[r#"
+        float var_0 = w[0u];
+        for (uint loop_index_0 = 1u; loop_index_0 < 8u; loop_index_0++) {
+            float loop_acc_0 = var_0;
+            var_0 = (loop_acc_0 + w[loop_index_0]);
+        }
+        var_0"#]],
+    );
+    check_expr(
+        values
+            .zip(weights)
+            .map(|(value, weight)| value * weight)
+            .fold(sl::Vec2::ZERO, |acc, value| acc + value),
+        expect![[r#"
+            vec2 var_0 = vec2(0.0, 0.0);
+            for (uint loop_index_0 = 0u; loop_index_0 < 8u; loop_index_0++) {
+                vec2 loop_acc_0 = var_0;
+                var_0 = (loop_acc_0 + (v[loop_index_0] * w[loop_index_0]));
+            }
+            var_0"#]],
+    );
+    check_expr(
+        values.enumerate().min_by(|(_, a), (_, b)| a.x.lt(b.x)).0,
+        expect![[r#"
+            tuple_Posh0 var_2 = tuple_Posh0(0u, v[0u]);
+            for (uint loop_index_0 = 1u; loop_index_0 < 8u; loop_index_0++) {
+                tuple_Posh0 loop_acc_0 = var_2;
+                vec2 var_0 = v[loop_index_0];
+                tuple_Posh0 var_1;
+                if ((var_0.x < loop_acc_0.T7.x)) {
+                    var_1 = tuple_Posh0(loop_index_0, var_0);
+                } else {
+                    var_1 = loop_acc_0;
+                }
+                var_2 = var_1;
+            }
+            var_2.T6"#]],
+    );
+}
+
+#[test]
+fn test_glsl_targets() {
+    fn vertex_shader(uniform: MyBoundUniform<Sl>, pos: sl::Vec4) -> sl::Vec4 {
+        uniform.camera * pos * uniform.scene.scale
+    }
+
+    fn fragment_shader(uniform: MyBoundUniform<Sl>, _: sl::FsInput<()>) -> sl::Vec4 {
+        uniform.albedo.sample(sl::Vec2::ZERO)
+    }
+
+    let transpile = |target| {
+        let options = TranspileOptions {
+            target,
+            ..Default::default()
+        };
+
+        transpile_to_program_def_with_options::<MyBoundUniform<Sl>, _, _, _, _>(
+            &options,
+            &(),
+            vertex_shader,
+            fragment_shader,
+        )
+    };
+
+    let es310 = transpile(GlslTarget::Es310);
+
+    expect![[r#"
+        #version 310 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+
+        layout(binding = 1) uniform sampler2D uniforms_albedo;
+        layout(binding = 0) uniform sampler2DShadow uniforms_shadow;
+        layout(std140, binding = 1) uniform uniforms_scene_posh_block {
+            MyBlock_Posh0 uniforms_scene;
+        };
+        layout(std140, binding = 0) uniform uniforms_camera_posh_block {
+            mat4 uniforms_camera;
+        };
+
+        in vec4 vertex_input;
+
+        void main() {
+            gl_Position = ((uniforms_camera * vertex_input) * uniforms_scene.scale);
+        }
+    "#]]
+    .assert_eq(&es310.vertex_shader_source);
+    expect![[r#"
+        #version 310 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+
+        layout(binding = 1) uniform sampler2D uniforms_albedo;
+        layout(binding = 0) uniform sampler2DShadow uniforms_shadow;
+        layout(std140, binding = 1) uniform uniforms_scene_posh_block {
+            MyBlock_Posh0 uniforms_scene;
+        };
+        layout(std140, binding = 0) uniform uniforms_camera_posh_block {
+            mat4 uniforms_camera;
+        };
+
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = texture(uniforms_albedo, vec2(0.0, 0.0));
+        }
+    "#]]
+    .assert_eq(&es310.fragment_shader_source);
+
+    // GLSL 3.30 has neither precision qualifiers nor explicit bindings.
+    let core330 = transpile(GlslTarget::Core330);
+
+    expect![[r#"
+        #version 330 core
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+
+        uniform sampler2D uniforms_albedo;
+        uniform sampler2DShadow uniforms_shadow;
+        layout(std140) uniform uniforms_scene_posh_block {
+            MyBlock_Posh0 uniforms_scene;
+        };
+        layout(std140) uniform uniforms_camera_posh_block {
+            mat4 uniforms_camera;
+        };
+
+        in vec4 vertex_input;
+
+        void main() {
+            gl_Position = ((uniforms_camera * vertex_input) * uniforms_scene.scale);
+        }
+    "#]]
+    .assert_eq(&core330.vertex_shader_source);
+    expect![[r#"
+        #version 330 core
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+
+        uniform sampler2D uniforms_albedo;
+        uniform sampler2DShadow uniforms_shadow;
+        layout(std140) uniform uniforms_scene_posh_block {
+            MyBlock_Posh0 uniforms_scene;
+        };
+        layout(std140) uniform uniforms_camera_posh_block {
+            mat4 uniforms_camera;
+        };
+
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = texture(uniforms_albedo, vec2(0.0, 0.0));
+        }
+    "#]]
+    .assert_eq(&core330.fragment_shader_source);
+
+    expect![[r#"
+        #version 410 core
+
+        struct MyBlock_Posh0 {
+            float scale;
+        };
+
+        uniform sampler2D uniforms_albedo;
+        uniform sampler2DShadow uniforms_shadow;
+        layout(std140) uniform uniforms_scene_posh_block {
+            MyBlock_Posh0 uniforms_scene;
+        };
+        layout(std140) uniform uniforms_camera_posh_block {
+            mat4 uniforms_camera;
+        };
+
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = texture(uniforms_albedo, vec2(0.0, 0.0));
+        }
+    "#]]
+    .assert_eq(&transpile(GlslTarget::Core410).fragment_shader_source);
+}
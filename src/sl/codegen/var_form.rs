@@ -3,9 +3,13 @@ use std::{
     rc::Rc,
 };
 
-use crate::sl::dag::{Expr, Type};
+use crate::sl::{
+    dag::{ArrayType, BuiltInType, Expr, Type},
+    transpile::TranspileOptions,
+};
 
 use super::{
+    func_registry::FuncRegistry,
     simplified_expr::{ExprKey, SimplifiedExpr, VarId},
     struct_registry::StructRegistry,
 };
@@ -18,7 +22,12 @@ pub struct VarForm {
 }
 
 impl VarForm {
-    pub fn new(struct_registry: &StructRegistry, roots: &[Rc<Expr>]) -> Self {
+    pub fn new(
+        struct_registry: &StructRegistry,
+        func_registry: &FuncRegistry,
+        options: &TranspileOptions,
+        roots: &[Rc<Expr>],
+    ) -> Self {
         let mut var_form = Self {
             roots: roots.iter().map(ExprKey::from).collect(),
             ..Self::default()
@@ -31,7 +40,8 @@ impl VarForm {
             let key = ExprKey::from(expr);
             let count = usages.get(&key).copied().unwrap_or(0);
 
-            let simplified_expr = var_form.map_expr(struct_registry, (**expr).clone());
+            let simplified_expr =
+                var_form.map_expr(struct_registry, func_registry, options, (**expr).clone());
 
             if var_form.should_have_var(count, expr) && Self::can_have_var(expr) {
                 let var_id = VarId(var_form.var_exprs.len());
@@ -66,7 +76,13 @@ impl VarForm {
             .collect()
     }
 
-    fn map_expr(&self, struct_registry: &StructRegistry, expr: Expr) -> SimplifiedExpr {
+    fn map_expr(
+        &self,
+        struct_registry: &StructRegistry,
+        func_registry: &FuncRegistry,
+        options: &TranspileOptions,
+        expr: Expr,
+    ) -> SimplifiedExpr {
         let map_succ = |succ: Rc<Expr>| self.simplified_exprs[&ExprKey::from(&succ)].clone();
 
         match expr {
@@ -98,14 +114,11 @@ impl VarForm {
                 right: Box::new(map_succ(right)),
                 ty,
             },
-            Expr::CallFuncDef { def, args } => {
-                // TODO: Resolve function name through a prebuilt registry.
-                SimplifiedExpr::CallFunc {
-                    name: def.name.to_string(),
-                    args: args.into_iter().map(map_succ).collect(),
-                    ty: def.result.ty(),
-                }
-            }
+            Expr::CallFuncDef { def, args } => SimplifiedExpr::CallFunc {
+                name: func_registry.name(&def),
+                args: args.into_iter().map(map_succ).collect(),
+                ty: def.result.ty(),
+            },
             Expr::CallBuiltIn { name, args, ty } => SimplifiedExpr::CallFunc {
                 name,
                 args: args.into_iter().map(map_succ).collect(),
@@ -116,17 +129,71 @@ impl VarForm {
                 name,
                 ty,
             },
-            Expr::Subscript { base, index, ty } => SimplifiedExpr::Subscript {
-                base: Box::new(map_succ(base)),
-                index: Box::new(map_succ(index)),
-                ty,
-            },
+            Expr::Subscript { base, index, ty } => {
+                let len = subscript_len(&base.ty());
+                let index = map_succ(index);
+
+                let index = if options.clamp_subscripts && len > 0 {
+                    SimplifiedExpr::CallFunc {
+                        name: "min".to_string(),
+                        args: vec![
+                            index,
+                            SimplifiedExpr::ScalarLiteral {
+                                value: format!("{}u", len - 1),
+                                ty: BuiltInType::U32,
+                            },
+                        ],
+                        ty: Type::BuiltIn(BuiltInType::U32),
+                    }
+                } else {
+                    index
+                };
+
+                SimplifiedExpr::Subscript {
+                    base: Box::new(map_succ(base)),
+                    index: Box::new(index),
+                    ty,
+                }
+            }
             Expr::Branch { cond, yes, no, ty } => SimplifiedExpr::Branch {
                 cond: Box::new(map_succ(cond)),
                 yes: Box::new(map_succ(yes)),
                 no: Box::new(map_succ(no)),
                 ty,
             },
+            Expr::Switch {
+                value,
+                cases,
+                default,
+                ty,
+            } => SimplifiedExpr::Switch {
+                value: Box::new(map_succ(value)),
+                cases: cases
+                    .into_iter()
+                    .map(|(label, case)| (label, map_succ(case)))
+                    .collect(),
+                default: Box::new(map_succ(default)),
+                ty,
+            },
+            Expr::Loop {
+                start,
+                end,
+                init,
+                acc_name,
+                index_name,
+                body,
+                stop,
+                ty,
+            } => SimplifiedExpr::Loop {
+                start: Box::new(map_succ(start)),
+                end: Box::new(map_succ(end)),
+                init: Box::new(map_succ(init)),
+                acc_name,
+                index_name,
+                body: Box::new(map_succ(body)),
+                stop: stop.map(|stop| Box::new(map_succ(stop))),
+                ty,
+            },
             Expr::Discard { ty } => SimplifiedExpr::Discard { ty },
         }
     }
@@ -136,6 +203,8 @@ impl VarForm {
 
         match expr {
             Branch { .. } => true,
+            Switch { .. } => true,
+            Loop { .. } => true,
             Discard { .. } => true,
             ArrayLiteral { .. } => true,
             Arg { .. } | ScalarLiteral { .. } => false,
@@ -209,3 +278,17 @@ fn count_usages(exprs: &[Rc<Expr>]) -> BTreeMap<ExprKey, usize> {
 
     usages
 }
+
+// Returns the number of elements that can be accessed by subscripting a value
+// of type `ty`.
+fn subscript_len(ty: &Type) -> usize {
+    use BuiltInType::*;
+
+    match ty {
+        Type::Array(ArrayType { len, .. }) => *len,
+        Type::BuiltIn(Vec2 | IVec2 | UVec2 | BVec2 | Mat2 | Mat2x3 | Mat2x4) => 2,
+        Type::BuiltIn(Vec3 | IVec3 | UVec3 | BVec3 | Mat3 | Mat3x2 | Mat3x4) => 3,
+        Type::BuiltIn(Vec4 | IVec4 | UVec4 | BVec4 | Mat4 | Mat4x2 | Mat4x3) => 4,
+        Type::BuiltIn(_) | Type::Struct(_) => 0,
+    }
+}
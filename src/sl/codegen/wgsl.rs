@@ -0,0 +1,1689 @@
+//! WGSL code generation.
+//!
+//! This shares the intermediate forms with GLSL code generation, so built-in
+//! functions arrive with their GLSL names and are translated here. Built-ins
+//! that have no WGSL counterpart are implemented by helper functions, which
+//! are appended to the module.
+//!
+//! Samplers are split into a texture and a sampler, since WGSL keeps the two
+//! separate. The sampler of a texture `t` is called `t_posh_sampler`.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+    rc::Rc,
+};
+
+use crate::sl::{
+    dag::{ArrayType, BinaryOp, BuiltInType, Expr, SamplerType, Type},
+    mangle::mangle_ident,
+    program_def::{
+        InterpolationQualifier, UniformBlockDef, UniformSamplerDef, WgslBindingDef, WgslBindingKind,
+    },
+    transpile::{TranspileOptions, WgslError},
+};
+
+use super::{
+    case_label,
+    func_registry::FuncRegistry,
+    scope_form::{Scope, ScopeForm, VarInit},
+    simplified_expr::{SimplifiedExpr, VarId},
+    struct_registry::StructRegistry,
+    var_form::VarForm,
+    Indent,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Stage {
+    Vertex,
+    Fragment,
+}
+
+// Helper function definitions by name.
+type Helpers = RefCell<BTreeMap<String, String>>;
+
+#[derive(Debug, Clone)]
+struct WriteFuncContext<'a> {
+    struct_registry: &'a StructRegistry,
+    scope_form: &'a ScopeForm<'a>,
+    helpers: &'a Helpers,
+    stage: Stage,
+    depth: usize,
+}
+
+impl<'a> WriteFuncContext<'a> {
+    fn nest(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self.clone()
+        }
+    }
+
+    fn indent(&self) -> Indent {
+        Indent(self.depth)
+    }
+
+    fn type_name(&self, ty: &Type) -> String {
+        type_name(self.struct_registry, ty)
+    }
+}
+
+/// Writes a module with the entry points of both stages.
+///
+/// Returns the source of the module and the bindings of its uniforms, or an
+/// error if the program uses a feature that has no equivalent in WGSL.
+pub fn write_program(
+    options: &TranspileOptions,
+    block_defs: &[UniformBlockDef],
+    sampler_defs: &[UniformSamplerDef],
+    vertex_attributes: &[(String, BuiltInType)],
+    varyings: &[(String, InterpolationQualifier)],
+    vertex_outputs: &[(&str, Rc<Expr>)],
+    fragment_outputs: &[(&str, Rc<Expr>)],
+) -> Result<(String, Vec<WgslBindingDef>), WgslError> {
+    check_supported(vertex_outputs, fragment_outputs)?;
+
+    let mut source = String::new();
+    let bindings = write_module(
+        &mut source,
+        options,
+        block_defs,
+        sampler_defs,
+        vertex_attributes,
+        varyings,
+        vertex_outputs,
+        fragment_outputs,
+    )
+    .unwrap();
+
+    Ok((source, bindings))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_module(
+    f: &mut impl Write,
+    options: &TranspileOptions,
+    block_defs: &[UniformBlockDef],
+    sampler_defs: &[UniformSamplerDef],
+    vertex_attributes: &[(String, BuiltInType)],
+    varyings: &[(String, InterpolationQualifier)],
+    vertex_outputs: &[(&str, Rc<Expr>)],
+    fragment_outputs: &[(&str, Rc<Expr>)],
+) -> Result<Vec<WgslBindingDef>, fmt::Error> {
+    // Both stages live in the same module, so they share their structs and
+    // functions.
+    let roots: Vec<_> = vertex_outputs
+        .iter()
+        .chain(fragment_outputs)
+        .map(|(_, root)| root.clone())
+        .collect();
+    let func_registry = FuncRegistry::new(&roots);
+
+    let struct_roots: Vec<_> = roots
+        .iter()
+        .cloned()
+        .chain(func_registry.defs().map(|(_, def)| def.result.clone()))
+        .collect();
+    let struct_registry = StructRegistry::new(
+        &struct_roots,
+        block_defs.iter().map(|def| &def.ty).chain(
+            func_registry
+                .defs()
+                .flat_map(|(_, def)| def.params.iter().map(|(_, ty)| ty)),
+        ),
+    );
+
+    let helpers = Helpers::default();
+
+    write_struct_defs(f, &struct_registry)?;
+
+    let bindings = write_bindings(f, &struct_registry, block_defs, sampler_defs)?;
+
+    let stage = StageDef {
+        options,
+        struct_registry: &struct_registry,
+        func_registry: &func_registry,
+        helpers: &helpers,
+    };
+
+    stage.write_vertex(f, vertex_attributes, varyings, vertex_outputs)?;
+    writeln!(f)?;
+    stage.write_fragment(f, varyings, fragment_outputs)?;
+
+    write_func_defs(f, options, &struct_registry, &func_registry, &helpers)?;
+
+    for def in helpers.borrow().values() {
+        writeln!(f)?;
+        f.write_str(def)?;
+    }
+
+    Ok(bindings)
+}
+
+fn write_struct_defs(f: &mut impl Write, struct_registry: &StructRegistry) -> fmt::Result {
+    for (name, ty) in struct_registry.defs() {
+        writeln!(f, "struct {name} {{")?;
+
+        for (field_name, field_ty) in ty.fields.iter() {
+            let field_ty_name = type_name(struct_registry, field_ty);
+
+            writeln!(f, "    {}: {field_ty_name},", mangle_ident(field_name))?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+    }
+
+    Ok(())
+}
+
+fn write_bindings(
+    f: &mut impl Write,
+    struct_registry: &StructRegistry,
+    block_defs: &[UniformBlockDef],
+    sampler_defs: &[UniformSamplerDef],
+) -> Result<Vec<WgslBindingDef>, fmt::Error> {
+    let mut bindings = Vec::new();
+
+    for block_def in block_defs {
+        let binding = WgslBindingDef {
+            name: block_def.arg_name.clone(),
+            group: 0,
+            binding: block_def.location as u32,
+            kind: WgslBindingKind::UniformBuffer(block_def.ty.clone()),
+        };
+
+        writeln!(
+            f,
+            "@group({}) @binding({}) var<uniform> {}: {};",
+            binding.group,
+            binding.binding,
+            binding.name,
+            type_name(struct_registry, &block_def.ty),
+        )?;
+
+        bindings.push(binding);
+    }
+
+    for sampler_def in sampler_defs {
+        let texture = WgslBindingDef {
+            name: sampler_def.name.clone(),
+            group: 1,
+            binding: 2 * sampler_def.texture_unit as u32,
+            kind: WgslBindingKind::Texture(sampler_def.ty),
+        };
+        let sampler = WgslBindingDef {
+            name: sampler_name(&sampler_def.name),
+            group: 1,
+            binding: texture.binding + 1,
+            kind: WgslBindingKind::Sampler(sampler_def.ty),
+        };
+
+        writeln!(
+            f,
+            "@group({}) @binding({}) var {}: {};",
+            texture.group,
+            texture.binding,
+            texture.name,
+            texture_type_name(sampler_def.ty),
+        )?;
+        writeln!(
+            f,
+            "@group({}) @binding({}) var {}: {};",
+            sampler.group,
+            sampler.binding,
+            sampler.name,
+            sampler_type_name(sampler_def.ty),
+        )?;
+
+        bindings.push(texture);
+        bindings.push(sampler);
+    }
+
+    if !bindings.is_empty() {
+        writeln!(f)?;
+    }
+
+    Ok(bindings)
+}
+
+// Shared state for writing the entry points of the module.
+struct StageDef<'a> {
+    options: &'a TranspileOptions,
+    struct_registry: &'a StructRegistry,
+    func_registry: &'a FuncRegistry,
+    helpers: &'a Helpers,
+}
+
+impl<'a> StageDef<'a> {
+    fn write_vertex(
+        &self,
+        f: &mut impl Write,
+        attributes: &[(String, BuiltInType)],
+        varyings: &[(String, InterpolationQualifier)],
+        outputs: &[(&str, Rc<Expr>)],
+    ) -> fmt::Result {
+        let args = used_args(outputs);
+
+        writeln!(f, "struct PoshVertexOutput {{")?;
+        writeln!(f, "    @builtin(position) gl_Position: vec4<f32>,")?;
+
+        for (location, ((name, interp), (_, expr))) in
+            varyings.iter().zip(&outputs[1..]).enumerate()
+        {
+            let ty_name = type_name(self.struct_registry, &expr.ty());
+
+            writeln!(
+                f,
+                "    @location({location}) {} {name}: {ty_name},",
+                interp.to_wgsl()
+            )?;
+        }
+
+        writeln!(f, "}}")?;
+        writeln!(f)?;
+
+        let mut params = Vec::new();
+        let mut prelude = Vec::new();
+
+        if args.contains_key("gl_VertexID") {
+            params.push("@builtin(vertex_index) posh_vertex_index: u32".to_string());
+            prelude.push("let gl_VertexID: i32 = i32(posh_vertex_index);".to_string());
+        }
+
+        if args.contains_key("gl_InstanceID") {
+            params.push("@builtin(instance_index) posh_instance_index: u32".to_string());
+            prelude.push("let gl_InstanceID: i32 = i32(posh_instance_index);".to_string());
+        }
+
+        // Matrix attributes take up one location per column, so they are
+        // passed as columns and reassembled.
+        let mut location = 0;
+
+        for (name, ty) in attributes {
+            let ty_name = built_in_type_name(*ty);
+
+            if let Some((cols, rows)) = mat_dims(*ty) {
+                let columns: Vec<_> = (0..cols)
+                    .map(|col| format!("{name}_posh_col{col}"))
+                    .collect();
+
+                for column in &columns {
+                    params.push(format!("@location({location}) {column}: vec{rows}<f32>"));
+                    location += 1;
+                }
+
+                prelude.push(format!(
+                    "let {name}: {ty_name} = {ty_name}({});",
+                    columns.join(", ")
+                ));
+            } else {
+                params.push(format!("@location({location}) {name}: {ty_name}"));
+                location += 1;
+            }
+        }
+
+        writeln!(f, "@vertex")?;
+        write_entry_point_header(f, "vs_main", &params, Some("PoshVertexOutput"))?;
+        self.write_body(f, Stage::Vertex, &prelude, "PoshVertexOutput", outputs)?;
+        writeln!(f, "}}")
+    }
+
+    fn write_fragment(
+        &self,
+        f: &mut impl Write,
+        varyings: &[(String, InterpolationQualifier)],
+        outputs: &[(&str, Rc<Expr>)],
+    ) -> fmt::Result {
+        let args = used_args(outputs);
+
+        let result_name = (!outputs.is_empty()).then_some("PoshFragmentOutput");
+
+        if result_name.is_some() {
+            writeln!(f, "struct PoshFragmentOutput {{")?;
+
+            for (location, (name, expr)) in outputs.iter().enumerate() {
+                let ty_name = type_name(self.struct_registry, &expr.ty());
+
+                if *name == "gl_FragDepth" {
+                    writeln!(f, "    @builtin(frag_depth) {name}: {ty_name},")?;
+                } else {
+                    writeln!(f, "    @location({location}) {name}: {ty_name},")?;
+                }
+            }
+
+            writeln!(f, "}}")?;
+            writeln!(f)?;
+        }
+
+        let mut params = Vec::new();
+
+        if args.contains_key("gl_FragCoord") {
+            params.push("@builtin(position) gl_FragCoord: vec4<f32>".to_string());
+        }
+
+        if args.contains_key("gl_FrontFacing") {
+            params.push("@builtin(front_facing) gl_FrontFacing: bool".to_string());
+        }
+
+        for (location, (name, interp)) in varyings.iter().enumerate() {
+            let ty = args
+                .get(name.as_str())
+                .map(|ty| type_name(self.struct_registry, ty));
+
+            // The fragment stage may read a subset of the vertex outputs.
+            if let Some(ty) = ty {
+                params.push(format!(
+                    "@location({location}) {} {name}: {ty}",
+                    interp.to_wgsl()
+                ));
+            }
+        }
+
+        writeln!(f, "@fragment")?;
+        write_entry_point_header(f, "fs_main", &params, result_name)?;
+        self.write_body(
+            f,
+            Stage::Fragment,
+            &[],
+            result_name.unwrap_or_default(),
+            outputs,
+        )?;
+        writeln!(f, "}}")
+    }
+
+    fn write_body(
+        &self,
+        f: &mut impl Write,
+        stage: Stage,
+        prelude: &[String],
+        result_name: &str,
+        outputs: &[(&str, Rc<Expr>)],
+    ) -> fmt::Result {
+        let roots: Vec<_> = outputs.iter().map(|(_, root)| root.clone()).collect();
+        let var_form = VarForm::new(
+            self.struct_registry,
+            self.func_registry,
+            self.options,
+            &roots,
+        );
+        let scope_form = ScopeForm::new(&var_form);
+
+        let ctx = WriteFuncContext {
+            struct_registry: self.struct_registry,
+            scope_form: &scope_form,
+            helpers: self.helpers,
+            stage,
+            depth: 1,
+        };
+
+        for statement in prelude {
+            writeln!(f, "    {statement}")?;
+        }
+
+        let continues = write_scope(f, ctx.clone(), scope_form.root_scope())?;
+
+        if outputs.is_empty() {
+            return Ok(());
+        }
+
+        let results = if continues {
+            var_form
+                .simplified_roots()
+                .into_iter()
+                .map(|root| ctx.expr(root))
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            String::new()
+        };
+
+        writeln!(f, "    return {result_name}({results});")
+    }
+}
+
+fn write_entry_point_header(
+    f: &mut impl Write,
+    name: &str,
+    params: &[String],
+    result_name: Option<&str>,
+) -> fmt::Result {
+    let result = result_name
+        .map(|result_name| format!(" -> {result_name}"))
+        .unwrap_or_default();
+
+    if params.is_empty() {
+        return writeln!(f, "fn {name}(){result} {{");
+    }
+
+    writeln!(f, "fn {name}(")?;
+
+    for param in params {
+        writeln!(f, "    {param},")?;
+    }
+
+    writeln!(f, "){result} {{")
+}
+
+// Functions may be called from both stages. They are written for the fragment
+// stage, where all texture functions are available.
+fn write_func_defs(
+    f: &mut impl Write,
+    options: &TranspileOptions,
+    struct_registry: &StructRegistry,
+    func_registry: &FuncRegistry,
+    helpers: &Helpers,
+) -> fmt::Result {
+    for (name, def) in func_registry.defs() {
+        let roots = [def.result.clone()];
+        let var_form = VarForm::new(struct_registry, func_registry, options, &roots);
+        let scope_form = ScopeForm::new(&var_form);
+
+        let ctx = WriteFuncContext {
+            struct_registry,
+            scope_form: &scope_form,
+            helpers,
+            stage: Stage::Fragment,
+            depth: 1,
+        };
+
+        let result_ty_name = type_name(struct_registry, &def.result.ty());
+        let params = def
+            .params
+            .iter()
+            .map(|(param_name, ty)| match ty {
+                Type::BuiltIn(BuiltInType::Sampler(ty)) => format!(
+                    "{param_name}: {}, {}: {}",
+                    texture_type_name(*ty),
+                    sampler_name(param_name),
+                    sampler_type_name(*ty),
+                ),
+                ty => format!("{param_name}: {}", type_name(struct_registry, ty)),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f)?;
+        writeln!(f, "fn {name}({params}) -> {result_ty_name} {{")?;
+
+        if write_scope(f, ctx.clone(), scope_form.root_scope())? {
+            writeln!(
+                f,
+                "    return {};",
+                ctx.expr(var_form.simplified_roots()[0])
+            )?;
+        } else {
+            writeln!(f, "    return {result_ty_name}();")?;
+        }
+
+        writeln!(f, "}}")?;
+    }
+
+    Ok(())
+}
+
+fn write_var(
+    f: &mut impl Write,
+    ctx: WriteFuncContext,
+    var_id: VarId,
+    var_init: &VarInit,
+) -> Result<bool, fmt::Error> {
+    use VarInit::*;
+
+    let indent = ctx.indent();
+
+    match var_init {
+        Expr(expr) => {
+            let ty = expr.ty();
+            let ty_name = ctx.type_name(&ty);
+
+            // Arrays can only be indexed dynamically through a variable.
+            let keyword = if contains_array(&ty) { "var" } else { "let" };
+
+            writeln!(
+                f,
+                "{indent}{keyword} {var_id}: {ty_name} = {};",
+                ctx.expr(expr)
+            )?;
+
+            Ok(true)
+        }
+        Branch {
+            cond,
+            yes_id,
+            no_id,
+            ty,
+        } => {
+            let ty_name = ctx.type_name(ty);
+            let cond = ctx.expr(cond);
+            let yes_scope = ctx.scope_form.scope(*yes_id);
+            let no_scope = ctx.scope_form.scope(*no_id);
+
+            if yes_scope.vars.is_empty() && no_scope.vars.is_empty() && is_selectable(ty) {
+                // If neither branch has variables, we can use `select`
+                // instead of an if/else statement. `select` only works for
+                // scalars and vectors.
+                let yes_result = ctx.expr(yes_scope.result.unwrap());
+                let no_result = ctx.expr(no_scope.result.unwrap());
+
+                writeln!(
+                    f,
+                    "{indent}let {var_id}: {ty_name} = select({no_result}, {yes_result}, {cond});"
+                )?;
+
+                return Ok(true);
+            }
+
+            writeln!(f, "{indent}var {var_id}: {ty_name};")?;
+            writeln!(f, "{indent}if {cond} {{")?;
+            write_assigning_scope(f, ctx.nest(), var_id, yes_scope)?;
+            writeln!(f, "{indent}}} else {{")?;
+            write_assigning_scope(f, ctx.nest(), var_id, no_scope)?;
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
+        Switch {
+            value,
+            case_ids,
+            default_id,
+            ty,
+        } => {
+            let ty_name = ctx.type_name(ty);
+            let labels = case_ids
+                .iter()
+                .map(|(label, case_id)| (format!("case {}", case_label(*label, value)), *case_id))
+                .chain([("default".to_string(), *default_id)]);
+
+            writeln!(f, "{indent}var {var_id}: {ty_name};")?;
+            writeln!(f, "{indent}switch {} {{", ctx.expr(value))?;
+
+            for (label, case_id) in labels {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                // WGSL cases do not fall through, so they need no `break`.
+                writeln!(f, "{indent}{label}: {{")?;
+                write_assigning_scope(f, ctx.nest(), var_id, ctx.scope_form.scope(case_id))?;
+                writeln!(f, "{indent}}}")?;
+            }
+
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
+        Loop {
+            start,
+            end,
+            init,
+            acc_name,
+            index_name,
+            body_id,
+            stop,
+            ty,
+        } => {
+            let ty_name = ctx.type_name(ty);
+            let index_ty_name = ctx.type_name(&start.ty());
+            let body_scope = ctx.scope_form.scope(*body_id);
+
+            writeln!(f, "{indent}var {var_id}: {ty_name} = {};", ctx.expr(init))?;
+            writeln!(
+                f,
+                "{indent}for (var {index_name}: {index_ty_name} = {}; \
+                 {index_name} < {}; {index_name}++) {{",
+                ctx.expr(start),
+                ctx.expr(end),
+            )?;
+
+            {
+                let ctx = ctx.nest();
+                let indent = ctx.indent();
+
+                let keyword = if contains_array(ty) { "var" } else { "let" };
+
+                writeln!(f, "{indent}{keyword} {acc_name}: {ty_name} = {var_id};")?;
+
+                if write_scope(f, ctx.clone(), body_scope)? {
+                    let result = ctx.expr(body_scope.result.unwrap());
+
+                    writeln!(f, "{indent}{var_id} = {result};")?;
+
+                    if let Some(stop) = stop {
+                        writeln!(f, "{indent}if {} {{", ctx.expr(stop))?;
+                        writeln!(f, "{}break;", ctx.nest().indent())?;
+                        writeln!(f, "{indent}}}")?;
+                    }
+                }
+            }
+
+            writeln!(f, "{indent}}}")?;
+
+            Ok(true)
+        }
+        Discard => {
+            writeln!(f, "{indent}discard;")?;
+
+            Ok(false)
+        }
+    }
+}
+
+fn write_assigning_scope(
+    f: &mut impl Write,
+    ctx: WriteFuncContext,
+    var_id: VarId,
+    scope: &Scope,
+) -> fmt::Result {
+    let indent = ctx.indent();
+
+    if write_scope(f, ctx.clone(), scope)? {
+        writeln!(f, "{indent}{var_id} = {};", ctx.expr(scope.result.unwrap()))?;
+    }
+
+    Ok(())
+}
+
+fn write_scope(
+    f: &mut impl Write,
+    ctx: WriteFuncContext,
+    scope: &Scope,
+) -> Result<bool, fmt::Error> {
+    for (var_id, var_init) in &scope.vars {
+        if !write_var(f, ctx.clone(), *var_id, var_init)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// A scalar component of a vector expression.
+struct Component<'b> {
+    text: String,
+    expr: Option<&'b SimplifiedExpr>,
+    // The vector, its length and the index of the component.
+    swizzle: Option<(String, usize, usize)>,
+}
+
+const SWIZZLE: [char; 4] = ['x', 'y', 'z', 'w'];
+
+impl<'a> WriteFuncContext<'a> {
+    fn expr(&self, expr: &SimplifiedExpr) -> String {
+        use SimplifiedExpr::*;
+
+        match expr {
+            Arg { name, .. } => name.clone(),
+            ScalarLiteral { value, .. } => value.clone(),
+            Unary { op, arg, .. } => format!("{op} {}", self.expr(arg)),
+            Binary {
+                left, op, right, ..
+            } => self.binary(left, *op, right),
+            CallFunc { name, args, ty } => self.call(name, args, ty),
+            Field { base, name, .. } => {
+                let base_text = self.expr(base);
+
+                match base.ty() {
+                    Type::BuiltIn(ty) if ty.is_mat() => {
+                        let index = match *name {
+                            "x_axis" => 0,
+                            "y_axis" => 1,
+                            "z_axis" => 2,
+                            "w_axis" => 3,
+                            _ => unreachable!(),
+                        };
+
+                        format!("{base_text}[{index}]")
+                    }
+                    Type::Struct(_) => format!("{base_text}.{}", mangle_ident(name)),
+                    _ => format!("{base_text}.{name}"),
+                }
+            }
+            Branch { cond, yes, no, .. } => format!(
+                "select({}, {}, {})",
+                self.expr(no),
+                self.expr(yes),
+                self.expr(cond)
+            ),
+            Subscript { base, index, .. } => {
+                format!("{}[{}]", self.expr(base), self.expr(index))
+            }
+            Var { id, .. } => id.to_string(),
+            Switch { .. } | Loop { .. } | Discard { .. } => {
+                // These are always assigned to variables, so they can only be
+                // written as statements.
+                unreachable!()
+            }
+        }
+    }
+
+    fn binary(&self, left: &SimplifiedExpr, op: BinaryOp, right: &SimplifiedExpr) -> String {
+        use BinaryOp::*;
+
+        let left_ty = left.ty();
+        let right_ty = right.ty();
+
+        match op {
+            Eq => self.eq(&self.expr(left), &self.expr(right), &left_ty),
+            Ne => match left_ty.built_in_type() {
+                Some(ty) if vec_len(ty).is_some() => {
+                    format!("any({} != {})", self.expr(left), self.expr(right))
+                }
+                Some(ty) if !ty.is_mat() => {
+                    format!("({} != {})", self.expr(left), self.expr(right))
+                }
+                _ => format!(
+                    "!{}",
+                    self.eq(&self.expr(left), &self.expr(right), &left_ty)
+                ),
+            },
+            Shl | Shr => {
+                // The shift amount must be unsigned and have the same shape
+                // as the shifted value.
+                let shift_ty = Type::BuiltIn(with_scalar(
+                    left_ty.built_in_type().unwrap(),
+                    BuiltInType::U32,
+                ));
+                let right_text = if right_ty == shift_ty {
+                    self.expr(right)
+                } else {
+                    format!("{}({})", self.type_name(&shift_ty), self.expr(right))
+                };
+
+                format!("({} {op} {right_text})", self.expr(left))
+            }
+            BitAnd | BitOr | BitXor => {
+                // Bitwise operators do not mix scalars and vectors.
+                let left_text = self.splat(left, &right_ty);
+                let right_text = self.splat(right, &left_ty);
+
+                format!("({left_text} {op} {right_text})")
+            }
+            _ => format!("({} {op} {})", self.expr(left), self.expr(right)),
+        }
+    }
+
+    // WGSL only compares scalars and vectors, so other values are compared
+    // component-wise.
+    fn eq(&self, left: &str, right: &str, ty: &Type) -> String {
+        let parts: Vec<_> = match ty {
+            Type::BuiltIn(built_in) => {
+                if let Some((cols, rows)) = mat_dims(*built_in) {
+                    let col_ty = Type::BuiltIn(vec_type(BuiltInType::F32, rows));
+
+                    (0..cols)
+                        .map(|col| {
+                            self.eq(
+                                &format!("{left}[{col}]"),
+                                &format!("{right}[{col}]"),
+                                &col_ty,
+                            )
+                        })
+                        .collect()
+                } else if vec_len(*built_in).is_some() {
+                    return format!("all({left} == {right})");
+                } else {
+                    return format!("({left} == {right})");
+                }
+            }
+            Type::Struct(struct_ty) => struct_ty
+                .fields
+                .iter()
+                .map(|(name, field_ty)| {
+                    let name = mangle_ident(name);
+
+                    self.eq(
+                        &format!("{left}.{name}"),
+                        &format!("{right}.{name}"),
+                        field_ty,
+                    )
+                })
+                .collect(),
+            Type::Array(ArrayType { ty, len }) => (0..*len)
+                .map(|i| self.eq(&format!("{left}[{i}]"), &format!("{right}[{i}]"), ty))
+                .collect(),
+        };
+
+        if parts.is_empty() {
+            "true".to_string()
+        } else {
+            format!("({})", parts.join(" && "))
+        }
+    }
+
+    // Converts a scalar to a vector of type `ty` if `ty` is a vector.
+    fn splat(&self, expr: &SimplifiedExpr, ty: &Type) -> String {
+        let text = self.expr(expr);
+
+        match (expr.ty(), ty) {
+            (Type::BuiltIn(expr_ty), Type::BuiltIn(ty))
+                if vec_len(expr_ty).is_none() && vec_len(*ty).is_some() =>
+            {
+                let ty = with_scalar(*ty, expr_ty);
+
+                format!("{}({text})", built_in_type_name(ty))
+            }
+            _ => text,
+        }
+    }
+
+    fn call(&self, name: &str, args: &[SimplifiedExpr], ty: &Type) -> String {
+        match ty {
+            Type::Array(_) if name.ends_with(']') => {
+                return call(&self.type_name(ty), self.exprs(args));
+            }
+            Type::BuiltIn(built_in) if name == built_in.to_string() => {
+                return self.construct(*built_in, args);
+            }
+            _ => (),
+        }
+
+        match name {
+            "texture" | "textureLod" | "textureGrad" | "textureOffset" | "textureLodOffset"
+            | "textureGradOffset" | "textureProj" | "textureProjLod" | "texelFetch"
+            | "texelFetchOffset" | "textureSize" => self.texture(name, args, ty),
+            "atan" if args.len() == 2 => call("atan2", self.exprs(args)),
+            "dFdx" => call("dpdx", self.exprs(args)),
+            "dFdy" => call("dpdy", self.exprs(args)),
+            "floatBitsToInt" | "floatBitsToUint" | "intBitsToFloat" | "uintBitsToFloat" => call(
+                &format!("bitcast<{}>", self.type_name(ty)),
+                self.exprs(args),
+            ),
+            "equal" | "notEqual" | "lessThan" | "lessThanEqual" | "greaterThan"
+            | "greaterThanEqual" => {
+                let op = match name {
+                    "equal" => "==",
+                    "notEqual" => "!=",
+                    "lessThan" => "<",
+                    "lessThanEqual" => "<=",
+                    "greaterThan" => ">",
+                    _ => ">=",
+                };
+
+                format!("({} {op} {})", self.expr(&args[0]), self.expr(&args[1]))
+            }
+            "not" => format!("!{}", self.expr(&args[0])),
+            "packHalf2x16" => call("pack2x16float", self.exprs(args)),
+            "packUnorm2x16" => call("pack2x16unorm", self.exprs(args)),
+            "packSnorm2x16" => call("pack2x16snorm", self.exprs(args)),
+            "unpackHalf2x16" => call("unpack2x16float", self.exprs(args)),
+            "unpackUnorm2x16" => call("unpack2x16unorm", self.exprs(args)),
+            "unpackSnorm2x16" => call("unpack2x16snorm", self.exprs(args)),
+            "mix" if is_bool(&args[2].ty()) => {
+                // GLSL's `mix` with a boolean mask picks from `y` where the
+                // mask is true, just like `select`.
+                call("select", self.exprs(args))
+            }
+            "step" | "smoothstep" | "clamp" | "min" | "max" => {
+                // In contrast to GLSL, WGSL does not allow mixing scalars and
+                // vectors here.
+                call(name, args.iter().map(|arg| self.splat(arg, ty)).collect())
+            }
+            "mod" => self.mod_helper(args, ty),
+            "outerProduct" => self.outer_product_helper(args, ty),
+            "matrixCompMult" => self.comp_mult_helper(args, ty),
+            "inverse" => self.inverse_helper(args, ty),
+            _ => {
+                // User-defined functions and built-ins with the same name in
+                // WGSL. Sampler arguments are passed as texture and sampler.
+                let args = args
+                    .iter()
+                    .map(|arg| {
+                        let text = self.expr(arg);
+
+                        if is_sampler(&arg.ty()) {
+                            format!("{text}, {}", sampler_name(&text))
+                        } else {
+                            text
+                        }
+                    })
+                    .collect();
+
+                call(name, args)
+            }
+        }
+    }
+
+    fn construct(&self, ty: BuiltInType, args: &[SimplifiedExpr]) -> String {
+        let ty_name = built_in_type_name(ty);
+
+        match (mat_dims(ty), args) {
+            (Some((cols, rows)), [arg]) if arg.ty() == Type::BuiltIn(BuiltInType::F32) => {
+                // WGSL has no diagonal matrix constructor.
+                let name = format!("posh_diagonal_{}", short_type_name(ty));
+                let columns: Vec<_> = (0..cols)
+                    .map(|col| {
+                        let values: Vec<_> = (0..rows)
+                            .map(|row| if row == col { "x" } else { "0.0" })
+                            .collect();
+
+                        format!("vec{rows}<f32>({})", values.join(", "))
+                    })
+                    .collect();
+
+                self.helper(
+                    &name,
+                    &[("x", "f32".to_string())],
+                    ty_name,
+                    &format!("{ty_name}({})", columns.join(", ")),
+                );
+
+                call(&name, self.exprs(args))
+            }
+            _ => call(ty_name, self.exprs(args)),
+        }
+    }
+
+    fn mod_helper(&self, args: &[SimplifiedExpr], ty: &Type) -> String {
+        let x_ty = args[0].ty().built_in_type().unwrap();
+        let y_ty = args[1].ty().built_in_type().unwrap();
+        let name = format!(
+            "posh_mod_{}_{}",
+            short_type_name(x_ty),
+            short_type_name(y_ty)
+        );
+
+        self.helper(
+            &name,
+            &[
+                ("x", built_in_type_name(x_ty).to_string()),
+                ("y", built_in_type_name(y_ty).to_string()),
+            ],
+            &self.type_name(ty),
+            "x - y * floor(x / y)",
+        );
+
+        call(&name, self.exprs(args))
+    }
+
+    fn outer_product_helper(&self, args: &[SimplifiedExpr], ty: &Type) -> String {
+        let mat_ty = ty.built_in_type().unwrap();
+        let (cols, rows) = mat_dims(mat_ty).unwrap();
+        let name = format!("posh_outer_product_{}", short_type_name(mat_ty));
+        let ty_name = built_in_type_name(mat_ty);
+        let columns: Vec<_> = SWIZZLE[..cols]
+            .iter()
+            .map(|c| format!("c * r.{c}"))
+            .collect();
+
+        self.helper(
+            &name,
+            &[
+                ("c", format!("vec{rows}<f32>")),
+                ("r", format!("vec{cols}<f32>")),
+            ],
+            ty_name,
+            &format!("{ty_name}({})", columns.join(", ")),
+        );
+
+        call(&name, self.exprs(args))
+    }
+
+    fn comp_mult_helper(&self, args: &[SimplifiedExpr], ty: &Type) -> String {
+        let mat_ty = ty.built_in_type().unwrap();
+        let (cols, _) = mat_dims(mat_ty).unwrap();
+        let name = format!("posh_comp_mult_{}", short_type_name(mat_ty));
+        let ty_name = built_in_type_name(mat_ty);
+        let columns: Vec<_> = (0..cols).map(|i| format!("a[{i}] * b[{i}]")).collect();
+
+        self.helper(
+            &name,
+            &[("a", ty_name.to_string()), ("b", ty_name.to_string())],
+            ty_name,
+            &format!("{ty_name}({})", columns.join(", ")),
+        );
+
+        call(&name, self.exprs(args))
+    }
+
+    fn inverse_helper(&self, args: &[SimplifiedExpr], ty: &Type) -> String {
+        let mat_ty = ty.built_in_type().unwrap();
+        let (name, def) = match mat_ty {
+            BuiltInType::Mat2 => ("posh_inverse_mat2x2f32", INVERSE_MAT2),
+            BuiltInType::Mat3 => ("posh_inverse_mat3x3f32", INVERSE_MAT3),
+            BuiltInType::Mat4 => ("posh_inverse_mat4x4f32", INVERSE_MAT4),
+            _ => unreachable!(),
+        };
+
+        self.helpers
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_insert_with(|| def.to_string());
+
+        call(name, self.exprs(args))
+    }
+
+    fn helper(&self, name: &str, params: &[(&str, String)], result: &str, body: &str) {
+        self.helpers
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let params = params
+                    .iter()
+                    .map(|(name, ty)| format!("{name}: {ty}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                format!("fn {name}({params}) -> {result} {{\n    return {body};\n}}\n")
+            });
+    }
+
+    fn texture(&self, name: &str, args: &[SimplifiedExpr], ty: &Type) -> String {
+        let Type::BuiltIn(BuiltInType::Sampler(sampler_ty)) = args[0].ty() else {
+            // Sampling a constant sampler results in its constant value.
+            let value = self.expr(&args[0]);
+
+            return if args[0].ty() == *ty {
+                value
+            } else {
+                format!("{}({value})", self.type_name(ty))
+            };
+        };
+
+        let texture = self.expr(&args[0]);
+        let sampler = sampler_name(&texture);
+        let is_comparison = is_comparison(sampler_ty);
+        let vertex = self.stage == Stage::Vertex;
+
+        let coords = |proj| self.coords(&args[1], sampler_ty, proj);
+        let extra = |range: std::ops::RangeFrom<usize>| self.exprs(&args[range]);
+
+        let sample = |mut coords: Vec<String>, extra: Vec<String>| {
+            let mut all = vec![texture.clone(), sampler.clone()];
+            all.append(&mut coords);
+
+            let func = if is_comparison {
+                if vertex {
+                    "textureSampleCompareLevel"
+                } else {
+                    "textureSampleCompare"
+                }
+            } else if vertex {
+                all.push("0.0".to_string());
+                "textureSampleLevel"
+            } else {
+                "textureSample"
+            };
+
+            all.extend(extra);
+
+            call(func, all)
+        };
+
+        let sample_with = |func: &str, mut coords: Vec<String>, extra: Vec<String>| {
+            let mut all = vec![texture.clone(), sampler.clone()];
+            all.append(&mut coords);
+            all.extend(extra);
+
+            call(func, all)
+        };
+
+        match name {
+            "texture" if args.len() == 3 => {
+                sample_with("textureSampleBias", coords(false), extra(2..))
+            }
+            "texture" | "textureProj" => sample(coords(name == "textureProj"), vec![]),
+            "textureOffset" => sample(coords(false), extra(2..)),
+            // Comparisons at an explicit level of detail or with explicit
+            // derivatives are rejected by `check_supported`.
+            "textureLod" | "textureLodOffset" | "textureProjLod" => sample_with(
+                "textureSampleLevel",
+                coords(name == "textureProjLod"),
+                extra(2..),
+            ),
+            "textureGrad" | "textureGradOffset" => {
+                sample_with("textureSampleGrad", coords(false), extra(2..))
+            }
+            "texelFetch" => {
+                let mut all = vec![texture.clone()];
+                all.extend(coords(false));
+                all.extend(extra(2..));
+
+                call("textureLoad", all)
+            }
+            "texelFetchOffset" => {
+                let texel = format!("({} + {})", self.expr(&args[1]), self.expr(&args[3]));
+
+                call(
+                    "textureLoad",
+                    vec![texture.clone(), texel, self.expr(&args[2])],
+                )
+            }
+            "textureSize" => {
+                let dims = call(
+                    "textureDimensions",
+                    vec![texture.clone(), self.expr(&args[1])],
+                );
+
+                if is_array(sampler_ty) {
+                    format!("vec3<i32>(vec2<i32>({dims}), i32(textureNumLayers({texture})))")
+                } else {
+                    format!("{}({dims})", self.type_name(ty))
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // GLSL packs the array layer, the reference depth and the projective
+    // divisor into the texture coordinates, in that order. WGSL passes the
+    // layer and the reference depth separately.
+    fn coords(&self, expr: &SimplifiedExpr, sampler_ty: SamplerType, proj: bool) -> Vec<String> {
+        let dims = coord_dims(sampler_ty);
+        let is_array = is_array(sampler_ty);
+        let is_comparison = is_comparison(sampler_ty);
+
+        if !is_array && !is_comparison && !proj {
+            return vec![self.expr(expr)];
+        }
+
+        let n = dims + usize::from(is_array) + usize::from(is_comparison) + usize::from(proj);
+        let components = self.components(expr, n);
+        let (coord_components, rest) = components.split_at(dims);
+        let mut rest = rest.iter();
+
+        let mut coords = pack(coord_components, &expr.ty());
+        let layer = is_array.then(|| self.layer(rest.next().unwrap(), is_float(&expr.ty())));
+        let mut reference = is_comparison.then(|| rest.next().unwrap().text.clone());
+
+        if proj {
+            let q = &rest.next().unwrap().text;
+
+            coords = format!("({coords} / {q})");
+            reference = reference.map(|reference| format!("({reference} / {q})"));
+        }
+
+        [Some(coords), layer, reference]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn components<'b>(&self, expr: &'b SimplifiedExpr, n: usize) -> Vec<Component<'b>> {
+        // Texture coordinates are usually built by a vector constructor, whose
+        // arguments can be used directly.
+        if let SimplifiedExpr::CallFunc {
+            name,
+            args,
+            ty: Type::BuiltIn(ty),
+        } = expr
+        {
+            if *name == ty.to_string() && vec_len(*ty) == Some(n) {
+                let components: Vec<_> = args
+                    .iter()
+                    .flat_map(|arg| self.arg_components(arg))
+                    .collect();
+
+                if components.len() == n {
+                    return components;
+                }
+            }
+        }
+
+        swizzles(&self.expr(expr), n)
+    }
+
+    fn arg_components<'b>(&self, arg: &'b SimplifiedExpr) -> Vec<Component<'b>> {
+        let arg_ty = arg.ty().built_in_type().unwrap();
+
+        if let Some(n) = vec_len(arg_ty) {
+            return swizzles(&self.expr(arg), n);
+        }
+
+        let swizzle = match arg {
+            SimplifiedExpr::Field { base, name, .. } => base
+                .ty()
+                .built_in_type()
+                .and_then(vec_len)
+                .zip(SWIZZLE.iter().position(|c| c.to_string() == *name))
+                .map(|(len, index)| (self.expr(base), len, index)),
+            _ => None,
+        };
+
+        vec![Component {
+            text: self.expr(arg),
+            expr: Some(arg),
+            swizzle,
+        }]
+    }
+
+    // Array layers are passed as integers in WGSL.
+    fn layer(&self, component: &Component, float_coords: bool) -> String {
+        match component.expr {
+            Some(SimplifiedExpr::CallFunc { name, args, .. })
+                if name == "float" && args.len() == 1 && !is_float(&args[0].ty()) =>
+            {
+                self.expr(&args[0])
+            }
+            _ if float_coords => format!("i32(round({}))", component.text),
+            _ => component.text.clone(),
+        }
+    }
+
+    fn exprs(&self, args: &[SimplifiedExpr]) -> Vec<String> {
+        args.iter().map(|arg| self.expr(arg)).collect()
+    }
+}
+
+fn swizzles<'b>(base: &str, n: usize) -> Vec<Component<'b>> {
+    SWIZZLE[..n]
+        .iter()
+        .enumerate()
+        .map(|(index, c)| Component {
+            text: format!("{base}.{c}"),
+            expr: None,
+            swizzle: Some((base.to_string(), n, index)),
+        })
+        .collect()
+}
+
+// Packs components into a vector of the same scalar type as `ty`.
+fn pack(components: &[Component], ty: &Type) -> String {
+    if let [component] = components {
+        return component.text.clone();
+    }
+
+    if let Some((base, len, _)) = &components[0].swizzle {
+        let is_prefix = components.iter().enumerate().all(|(i, component)| {
+            matches!(&component.swizzle, Some((other, _, index)) if other == base && *index == i)
+        });
+
+        if is_prefix && *len == components.len() {
+            return base.clone();
+        } else if is_prefix {
+            let swizzle: String = SWIZZLE[..components.len()].iter().collect();
+
+            return format!("{base}.{swizzle}");
+        }
+    }
+
+    let ty = with_scalar(
+        vec_type(BuiltInType::F32, components.len()),
+        scalar_type(ty.built_in_type().unwrap()),
+    );
+    let texts: Vec<_> = components.iter().map(|c| c.text.clone()).collect();
+
+    call(built_in_type_name(ty), texts)
+}
+
+fn call(name: &str, args: Vec<String>) -> String {
+    format!("{name}({})", args.join(", "))
+}
+
+fn used_args(outputs: &[(&str, Rc<Expr>)]) -> BTreeMap<String, Type> {
+    let mut visited = BTreeSet::new();
+    let mut stack: Vec<_> = outputs.iter().map(|(_, root)| root.clone()).collect();
+    let mut args = BTreeMap::new();
+
+    while let Some(expr) = stack.pop() {
+        if !visited.insert(Rc::as_ptr(&expr)) {
+            continue;
+        }
+
+        if let Expr::Arg { name, ty } = &*expr {
+            args.insert(name.clone(), ty.clone());
+        }
+
+        expr.successors(|succ| stack.push(succ.clone()));
+    }
+
+    args
+}
+
+// Returns an error for features that have no equivalent in WGSL. These are
+// checked before writing the module, so the writer does not need to handle them.
+fn check_supported(
+    vertex_outputs: &[(&str, Rc<Expr>)],
+    fragment_outputs: &[(&str, Rc<Expr>)],
+) -> Result<(), WgslError> {
+    if vertex_outputs
+        .iter()
+        .any(|(name, _)| *name == "gl_PointSize")
+    {
+        return Err(WgslError::PointSize);
+    }
+
+    if used_args(fragment_outputs).contains_key("gl_PointCoord") {
+        return Err(WgslError::PointCoord);
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut stack: Vec<_> = vertex_outputs
+        .iter()
+        .chain(fragment_outputs)
+        .map(|(_, root)| root.clone())
+        .collect();
+
+    while let Some(expr) = stack.pop() {
+        if !visited.insert(Rc::as_ptr(&expr)) {
+            continue;
+        }
+
+        match &*expr {
+            Expr::CallFuncDef { def, .. } => stack.push(def.result.clone()),
+            Expr::CallBuiltIn { name, args, .. } => {
+                if let Some(Type::BuiltIn(BuiltInType::Sampler(sampler_ty))) =
+                    args.first().map(|arg| arg.ty())
+                {
+                    check_texture(name, sampler_ty)?;
+                }
+            }
+            _ => (),
+        }
+
+        expr.successors(|succ| stack.push(succ.clone()));
+    }
+
+    Ok(())
+}
+
+fn check_texture(name: &str, sampler_ty: SamplerType) -> Result<(), WgslError> {
+    // WGSL only allows filtering float textures.
+    if is_integer(sampler_ty) && !matches!(name, "texelFetch" | "texelFetchOffset" | "textureSize")
+    {
+        return Err(WgslError::IntegerSampling(name.to_string()));
+    }
+
+    if is_comparison(sampler_ty) {
+        match name {
+            "textureLod" | "textureLodOffset" | "textureProjLod" => {
+                return Err(WgslError::ComparisonLod(name.to_string()))
+            }
+            "textureGrad" | "textureGradOffset" => {
+                return Err(WgslError::ComparisonGrad(name.to_string()))
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+fn sampler_name(texture_name: &str) -> String {
+    format!("{texture_name}_posh_sampler")
+}
+
+fn type_name(struct_registry: &StructRegistry, ty: &Type) -> String {
+    use Type::*;
+
+    match ty {
+        BuiltIn(ty) => built_in_type_name(*ty).to_string(),
+        Struct(ty) => struct_registry.name(ty),
+        Array(ArrayType { ty, len }) => {
+            format!("array<{}, {len}>", type_name(struct_registry, ty))
+        }
+    }
+}
+
+fn built_in_type_name(ty: BuiltInType) -> &'static str {
+    use BuiltInType::*;
+
+    match ty {
+        F32 => "f32",
+        I32 => "i32",
+        U32 => "u32",
+        Bool => "bool",
+        Vec2 => "vec2<f32>",
+        IVec2 => "vec2<i32>",
+        UVec2 => "vec2<u32>",
+        BVec2 => "vec2<bool>",
+        Vec3 => "vec3<f32>",
+        IVec3 => "vec3<i32>",
+        UVec3 => "vec3<u32>",
+        BVec3 => "vec3<bool>",
+        Vec4 => "vec4<f32>",
+        IVec4 => "vec4<i32>",
+        UVec4 => "vec4<u32>",
+        BVec4 => "vec4<bool>",
+        Mat2 => "mat2x2<f32>",
+        Mat3 => "mat3x3<f32>",
+        Mat4 => "mat4x4<f32>",
+        Mat2x3 => "mat2x3<f32>",
+        Mat2x4 => "mat2x4<f32>",
+        Mat3x2 => "mat3x2<f32>",
+        Mat3x4 => "mat3x4<f32>",
+        Mat4x2 => "mat4x2<f32>",
+        Mat4x3 => "mat4x3<f32>",
+        Sampler(ty) => texture_type_name(ty),
+    }
+}
+
+// Used for naming helper functions.
+fn short_type_name(ty: BuiltInType) -> String {
+    built_in_type_name(ty).replace(['<', '>'], "")
+}
+
+fn texture_type_name(ty: SamplerType) -> &'static str {
+    use SamplerType::*;
+
+    match ty {
+        ColorSampler2d => "texture_2d<f32>",
+        IColorSampler2d => "texture_2d<i32>",
+        UColorSampler2d => "texture_2d<u32>",
+        ComparisonSampler2d => "texture_depth_2d",
+        ColorSamplerCube => "texture_cube<f32>",
+        IColorSamplerCube => "texture_cube<i32>",
+        UColorSamplerCube => "texture_cube<u32>",
+        ComparisonSamplerCube => "texture_depth_cube",
+        ColorSampler3d => "texture_3d<f32>",
+        IColorSampler3d => "texture_3d<i32>",
+        UColorSampler3d => "texture_3d<u32>",
+        ColorSampler2dArray => "texture_2d_array<f32>",
+        IColorSampler2dArray => "texture_2d_array<i32>",
+        UColorSampler2dArray => "texture_2d_array<u32>",
+        ComparisonSampler2dArray => "texture_depth_2d_array",
+    }
+}
+
+fn sampler_type_name(ty: SamplerType) -> &'static str {
+    if is_comparison(ty) {
+        "sampler_comparison"
+    } else {
+        "sampler"
+    }
+}
+
+fn is_comparison(ty: SamplerType) -> bool {
+    use SamplerType::*;
+
+    matches!(
+        ty,
+        ComparisonSampler2d | ComparisonSamplerCube | ComparisonSampler2dArray
+    )
+}
+
+fn is_integer(ty: SamplerType) -> bool {
+    use SamplerType::*;
+
+    matches!(
+        ty,
+        IColorSampler2d
+            | UColorSampler2d
+            | IColorSamplerCube
+            | UColorSamplerCube
+            | IColorSampler3d
+            | UColorSampler3d
+            | IColorSampler2dArray
+            | UColorSampler2dArray
+    )
+}
+
+fn is_array(ty: SamplerType) -> bool {
+    use SamplerType::*;
+
+    matches!(
+        ty,
+        ColorSampler2dArray
+            | IColorSampler2dArray
+            | UColorSampler2dArray
+            | ComparisonSampler2dArray
+    )
+}
+
+fn coord_dims(ty: SamplerType) -> usize {
+    use SamplerType::*;
+
+    match ty {
+        ColorSamplerCube
+        | IColorSamplerCube
+        | UColorSamplerCube
+        | ComparisonSamplerCube
+        | ColorSampler3d
+        | IColorSampler3d
+        | UColorSampler3d => 3,
+        _ => 2,
+    }
+}
+
+fn is_sampler(ty: &Type) -> bool {
+    matches!(ty, Type::BuiltIn(BuiltInType::Sampler(_)))
+}
+
+fn is_bool(ty: &Type) -> bool {
+    ty.built_in_type()
+        .is_some_and(|ty| scalar_type(ty) == BuiltInType::Bool)
+}
+
+fn is_float(ty: &Type) -> bool {
+    ty.built_in_type()
+        .is_some_and(|ty| scalar_type(ty) == BuiltInType::F32)
+}
+
+fn contains_array(ty: &Type) -> bool {
+    match ty {
+        Type::BuiltIn(_) => false,
+        Type::Struct(ty) => ty.fields.iter().any(|(_, ty)| contains_array(ty)),
+        Type::Array(_) => true,
+    }
+}
+
+fn is_selectable(ty: &Type) -> bool {
+    ty.built_in_type()
+        .is_some_and(|ty| ty.is_transparent() && !ty.is_mat())
+}
+
+fn vec_len(ty: BuiltInType) -> Option<usize> {
+    use BuiltInType::*;
+
+    match ty {
+        Vec2 | IVec2 | UVec2 | BVec2 => Some(2),
+        Vec3 | IVec3 | UVec3 | BVec3 => Some(3),
+        Vec4 | IVec4 | UVec4 | BVec4 => Some(4),
+        _ => None,
+    }
+}
+
+fn mat_dims(ty: BuiltInType) -> Option<(usize, usize)> {
+    use BuiltInType::*;
+
+    match ty {
+        Mat2 => Some((2, 2)),
+        Mat3 => Some((3, 3)),
+        Mat4 => Some((4, 4)),
+        Mat2x3 => Some((2, 3)),
+        Mat2x4 => Some((2, 4)),
+        Mat3x2 => Some((3, 2)),
+        Mat3x4 => Some((3, 4)),
+        Mat4x2 => Some((4, 2)),
+        Mat4x3 => Some((4, 3)),
+        _ => None,
+    }
+}
+
+fn scalar_type(ty: BuiltInType) -> BuiltInType {
+    use BuiltInType::*;
+
+    match ty {
+        IVec2 | IVec3 | IVec4 => I32,
+        UVec2 | UVec3 | UVec4 => U32,
+        BVec2 | BVec3 | BVec4 => Bool,
+        ty if vec_len(ty).is_some() || ty.is_mat() => F32,
+        ty => ty,
+    }
+}
+
+fn vec_type(scalar: BuiltInType, len: usize) -> BuiltInType {
+    use BuiltInType::*;
+
+    match (scalar, len) {
+        (F32, 2) => Vec2,
+        (F32, 3) => Vec3,
+        (F32, 4) => Vec4,
+        (I32, 2) => IVec2,
+        (I32, 3) => IVec3,
+        (I32, 4) => IVec4,
+        (U32, 2) => UVec2,
+        (U32, 3) => UVec3,
+        (U32, 4) => UVec4,
+        (Bool, 2) => BVec2,
+        (Bool, 3) => BVec3,
+        (Bool, 4) => BVec4,
+        (scalar, _) => scalar,
+    }
+}
+
+// Returns a type with the shape of `ty` and the given scalar type.
+fn with_scalar(ty: BuiltInType, scalar: BuiltInType) -> BuiltInType {
+    match vec_len(ty) {
+        Some(len) => vec_type(scalar, len),
+        None => scalar,
+    }
+}
+
+const INVERSE_MAT2: &str = "\
+fn posh_inverse_mat2x2f32(m: mat2x2<f32>) -> mat2x2<f32> {
+    let adj = mat2x2<f32>(m[1][1], -m[0][1], -m[1][0], m[0][0]);
+    return adj * (1.0 / determinant(m));
+}
+";
+
+const INVERSE_MAT3: &str = "\
+fn posh_inverse_mat3x3f32(m: mat3x3<f32>) -> mat3x3<f32> {
+    let adj = mat3x3<f32>(cross(m[1], m[2]), cross(m[2], m[0]), cross(m[0], m[1]));
+    return transpose(adj) * (1.0 / determinant(m));
+}
+";
+
+const INVERSE_MAT4: &str = "\
+fn posh_inverse_mat4x4f32(m: mat4x4<f32>) -> mat4x4<f32> {
+    let a00 = m[0][0]; let a01 = m[0][1]; let a02 = m[0][2]; let a03 = m[0][3];
+    let a10 = m[1][0]; let a11 = m[1][1]; let a12 = m[1][2]; let a13 = m[1][3];
+    let a20 = m[2][0]; let a21 = m[2][1]; let a22 = m[2][2]; let a23 = m[2][3];
+    let a30 = m[3][0]; let a31 = m[3][1]; let a32 = m[3][2]; let a33 = m[3][3];
+    let b00 = a00 * a11 - a01 * a10;
+    let b01 = a00 * a12 - a02 * a10;
+    let b02 = a00 * a13 - a03 * a10;
+    let b03 = a01 * a12 - a02 * a11;
+    let b04 = a01 * a13 - a03 * a11;
+    let b05 = a02 * a13 - a03 * a12;
+    let b06 = a20 * a31 - a21 * a30;
+    let b07 = a20 * a32 - a22 * a30;
+    let b08 = a20 * a33 - a23 * a30;
+    let b09 = a21 * a32 - a22 * a31;
+    let b10 = a21 * a33 - a23 * a31;
+    let b11 = a22 * a33 - a23 * a32;
+    let det = b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06;
+    let adj = mat4x4<f32>(
+        a11 * b11 - a12 * b10 + a13 * b09,
+        a02 * b10 - a01 * b11 - a03 * b09,
+        a31 * b05 - a32 * b04 + a33 * b03,
+        a22 * b04 - a21 * b05 - a23 * b03,
+        a12 * b08 - a10 * b11 - a13 * b07,
+        a00 * b11 - a02 * b08 + a03 * b07,
+        a32 * b02 - a30 * b05 - a33 * b01,
+        a20 * b05 - a22 * b02 + a23 * b01,
+        a10 * b10 - a11 * b08 + a13 * b06,
+        a01 * b08 - a00 * b10 - a03 * b06,
+        a30 * b04 - a31 * b02 + a33 * b00,
+        a21 * b02 - a20 * b04 - a23 * b00,
+        a11 * b07 - a10 * b09 - a12 * b06,
+        a00 * b09 - a01 * b07 + a02 * b06,
+        a31 * b01 - a30 * b03 - a32 * b00,
+        a20 * b03 - a21 * b01 + a22 * b00,
+    );
+    return adj * (1.0 / det);
+}
+";
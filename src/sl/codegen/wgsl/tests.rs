@@ -0,0 +1,505 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    sl::{
+        self,
+        primitives::value_arg,
+        program_def::WgslBindingKind,
+        transpile::{transpile_to_wgsl, TranspileOptions, WgslError},
+        Derivatives, FsInput, Object, Value,
+    },
+    Block, BlockDom, FsDom, FsInterface, Sl, ToSl, Uniform, UniformDom, VsDom, VsInterface,
+};
+
+use super::{
+    super::{
+        func_registry::FuncRegistry, scope_form::ScopeForm, struct_registry::StructRegistry,
+        var_form::VarForm,
+    },
+    write_scope, Helpers, Stage, WriteFuncContext,
+};
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct MyBlock<D: BlockDom> {
+    scale: D::F32,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct MyGlobals<D: BlockDom> {
+    ambient: D::Vec4,
+    world_to_clip: D::Mat4,
+    block: MyBlock<D>,
+}
+
+#[derive(Copy, Clone, Uniform)]
+struct MyUniform<D: UniformDom> {
+    sampler: D::ColorSampler2d<sl::Vec3>,
+    globals: D::Block<MyGlobals<Sl>>,
+}
+
+#[derive(Copy, Clone, VsInterface)]
+struct MyInstancedVsInterface<D: VsDom> {
+    vertex: D::Block<sl::Vec3>,
+    #[vertex(instance)]
+    model: D::Block<sl::Mat4>,
+}
+
+#[derive(Copy, Clone, sl::Value, sl::Interpolant)]
+struct MyInterpolant {
+    uv: sl::Vec2,
+    scale: sl::F32,
+    instance_id: sl::U32,
+}
+
+#[derive(Copy, Clone, FsInterface)]
+struct MyFsInterface<D: FsDom> {
+    albedo: D::ColorAttachment<sl::Vec4>,
+    normal: D::ColorAttachment<sl::Vec3>,
+    glow: D::ColorAttachment<sl::F32>,
+}
+
+#[sl::function]
+fn lambert(normal: sl::Vec3, light_dir: sl::Vec3) -> sl::F32 {
+    normal.dot(light_dir).max(0.0)
+}
+
+#[sl::function]
+fn shade(interp: MyInterpolant, normal: sl::Vec3) -> sl::Vec4 {
+    let diffuse = lambert(normal, sl::Vec3::Z) + lambert(normal, sl::Vec3::Y);
+
+    (interp.uv * diffuse * interp.scale).extend(0.0).extend(1.0)
+}
+
+fn transpile_expr<V: Value>(value: V, stage: Stage) -> String {
+    let roots = [value.expr()];
+    let func_registry = FuncRegistry::new(&roots);
+    let struct_registry = StructRegistry::new(&roots, None.into_iter());
+    let var_form = VarForm::new(
+        &struct_registry,
+        &func_registry,
+        &TranspileOptions::default(),
+        &roots,
+    );
+    let scope_form = ScopeForm::new(&var_form);
+    let helpers = Helpers::default();
+    let write_context = WriteFuncContext {
+        struct_registry: &struct_registry,
+        scope_form: &scope_form,
+        helpers: &helpers,
+        stage,
+        depth: 0,
+    };
+
+    let mut str = String::new();
+    write_scope(&mut str, write_context.clone(), scope_form.root_scope()).unwrap();
+    str += &write_context.expr(var_form.simplified_roots()[0]);
+
+    for def in helpers.borrow().values() {
+        str += "\n";
+        str += def;
+    }
+
+    str
+}
+
+fn check_expr<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = transpile_expr(value.to_sl(), Stage::Fragment);
+
+    expect.assert_eq(&actual);
+}
+
+fn check_vertex_expr<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = transpile_expr(value.to_sl(), Stage::Vertex);
+
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn test_simple_exprs() {
+    let x: sl::F32 = value_arg("x");
+    let v: sl::Vec3 = value_arg("v");
+    let i: sl::IVec2 = value_arg("i");
+
+    check_expr(x * 2.0 + 1.0, expect!["((x * 2.0) + 1.0)"]);
+    check_expr(sl::vec4(x, 1.0, 2.0, x).xy(), expect!["vec2<f32>(x, 1.0)"]);
+    check_expr(
+        v.eq(sl::Vec3::ZERO),
+        expect!["all(v == vec3<f32>(0.0, 0.0, 0.0))"],
+    );
+    check_expr(
+        v.ne(sl::Vec3::ZERO),
+        expect!["any(v != vec3<f32>(0.0, 0.0, 0.0))"],
+    );
+    check_expr(i << 2, expect!["(i << vec2<u32>(2))"]);
+    check_expr(i & 3, expect!["(i & vec2<i32>(3))"]);
+    check_expr(v.atan2(v), expect!["atan2(v, v)"]);
+    check_expr(x.to_bits(), expect!["bitcast<u32>(x)"]);
+}
+
+#[test]
+fn test_helpers() {
+    let x: sl::F32 = value_arg("x");
+    let v: sl::Vec3 = value_arg("v");
+    let m: sl::Mat3 = value_arg("m");
+
+    check_expr(
+        v.modulus(x),
+        expect![[r#"
+        posh_mod_vec3f32_f32(v, x)
+        fn posh_mod_vec3f32_f32(x: vec3<f32>, y: f32) -> vec3<f32> {
+            return x - y * floor(x / y);
+        }
+    "#]],
+    );
+    check_expr(
+        sl::Mat2::diagonal(x),
+        expect![[r#"
+        posh_diagonal_mat2x2f32(x)
+        fn posh_diagonal_mat2x2f32(x: f32) -> mat2x2<f32> {
+            return mat2x2<f32>(vec2<f32>(x, 0.0), vec2<f32>(0.0, x));
+        }
+    "#]],
+    );
+    check_expr(
+        m.inverse().eq(m),
+        expect![[r#"
+        (all(posh_inverse_mat3x3f32(m)[0] == m[0]) && all(posh_inverse_mat3x3f32(m)[1] == m[1]) && all(posh_inverse_mat3x3f32(m)[2] == m[2]))
+        fn posh_inverse_mat3x3f32(m: mat3x3<f32>) -> mat3x3<f32> {
+            let adj = mat3x3<f32>(cross(m[1], m[2]), cross(m[2], m[0]), cross(m[0], m[1]));
+            return transpose(adj) * (1.0 / determinant(m));
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn test_branch() {
+    let x: sl::F32 = value_arg("x");
+    let m: sl::Mat2 = value_arg("m");
+
+    check_expr(
+        sl::branch(x.lt(0.0), x, -x),
+        expect![[r#"
+        let var_0: f32 = select(- x, x, (x < 0.0));
+        var_0"#]],
+    );
+    check_expr(
+        sl::branch(x.lt(0.0), m, m * 2.0),
+        expect![[r#"
+        var var_0: mat2x2<f32>;
+        if (x < 0.0) {
+            var_0 = m;
+        } else {
+            var_0 = (m * 2.0);
+        }
+        var_0"#]],
+    );
+}
+
+#[test]
+fn test_switch() {
+    let x: sl::I32 = value_arg("x");
+
+    check_expr(
+        sl::switch(x, [(1, x + 1), (-2, x * 2)], 0),
+        expect![[r#"
+        var var_0: i32;
+        switch x {
+            case 1: {
+                var_0 = (x + 1);
+            }
+            case -2: {
+                var_0 = (x * 2);
+            }
+            default: {
+                var_0 = 0;
+            }
+        }
+        var_0"#]],
+    );
+}
+
+#[test]
+fn test_fold() {
+    let x: sl::F32 = value_arg("x");
+
+    check_expr(
+        sl::fold(0u32..4u32, x, |acc, i| acc + i.as_f32()),
+        expect![[r#"
+            var var_0: f32 = x;
+            for (var loop_index_0: u32 = 0u; loop_index_0 < 4u; loop_index_0++) {
+                let loop_acc_0: f32 = var_0;
+                var_0 = (loop_acc_0 + f32(loop_index_0));
+            }
+            var_0"#]],
+    );
+}
+
+#[test]
+fn test_discard() {
+    let input = FsInput {
+        interp: (),
+        fragment_coord: value_arg("gl_FragCoord"),
+        front_facing: value_arg("gl_FrontFacing"),
+        point_coord: value_arg("gl_PointCoord"),
+        derivatives: Derivatives(()),
+    };
+    let cond: sl::Bool = value_arg("cond");
+    let x: sl::F32 = value_arg("x");
+
+    check_expr(
+        cond.then(x).otherwise_discard(input),
+        expect![[r#"
+        var var_1: f32;
+        if cond {
+            var_1 = x;
+        } else {
+            discard;
+        }
+        var_1"#]],
+    );
+}
+
+#[test]
+fn test_sampling() {
+    let sampler = sl::ColorSampler2d::<sl::Vec3>::from_arg("s");
+    let shadow = sl::ComparisonSampler2d::from_arg("t");
+    let uv: sl::Vec2 = value_arg("uv");
+    let texel: sl::IVec2 = value_arg("texel");
+
+    check_expr(
+        sampler.sample(uv),
+        expect!["textureSample(s, s_posh_sampler, uv).xyz"],
+    );
+    check_vertex_expr(
+        sampler.sample(uv),
+        expect!["textureSampleLevel(s, s_posh_sampler, uv, 0.0).xyz"],
+    );
+    check_expr(
+        sampler.sample_bias(uv, 1.0),
+        expect!["textureSampleBias(s, s_posh_sampler, uv, 1.0).xyz"],
+    );
+    check_expr(
+        sampler.sample_lod(uv, 2.0),
+        expect!["textureSampleLevel(s, s_posh_sampler, uv, 2.0).xyz"],
+    );
+    check_expr(
+        sampler.sample_grad_offset(uv, uv * 0.5, uv * 2.0, [-1, 1]),
+        expect!["textureSampleGrad(s, s_posh_sampler, uv, (uv * 0.5), (uv * 2.0), vec2<i32>(-1, 1)).xyz"],
+    );
+    check_expr(
+        sampler.sample_proj(uv.extend(2.0)),
+        expect!["textureSample(s, s_posh_sampler, (uv / 2.0)).xyz"],
+    );
+    check_expr(
+        sampler.fetch(texel, 0u32),
+        expect!["textureLoad(s, texel, i32(0u)).xyz"],
+    );
+    check_expr(
+        sampler.size(0u32),
+        expect!["vec2<u32>(vec2<i32>(textureDimensions(s, i32(0u))))"],
+    );
+    check_expr(
+        shadow.sample_compare(uv, 0.5.to_sl()),
+        expect!["textureSampleCompare(t, t_posh_sampler, uv, 0.5)"],
+    );
+    check_vertex_expr(
+        shadow.sample_compare(uv, 0.5.to_sl()),
+        expect!["textureSampleCompareLevel(t, t_posh_sampler, uv, 0.5)"],
+    );
+    check_expr(
+        shadow.sample_compare_proj(uv, 0.5.to_sl(), 2.0),
+        expect!["textureSampleCompare(t, t_posh_sampler, (uv / 2.0), (0.5 / 2.0))"],
+    );
+}
+
+#[test]
+fn test_sampling_layered() {
+    let array = sl::ColorSampler2dArray::<sl::Vec4>::from_arg("a");
+    let shadow = sl::ComparisonSampler2dArray::from_arg("t");
+    let uv: sl::Vec2 = value_arg("uv");
+    let texel: sl::IVec2 = value_arg("texel");
+    let layer: sl::U32 = value_arg("layer");
+
+    check_expr(
+        array.sample(uv, layer),
+        expect!["textureSample(a, a_posh_sampler, uv, layer)"],
+    );
+    check_expr(
+        array.fetch(texel, layer, 0u32),
+        expect!["textureLoad(a, texel, i32(layer), i32(0u))"],
+    );
+    check_expr(array.size(0u32), expect!["vec3<u32>(vec3<i32>(vec2<i32>(textureDimensions(a, i32(0u))), i32(textureNumLayers(a))))"]);
+    check_expr(
+        shadow.sample_compare(uv, layer, 0.5.to_sl()),
+        expect!["textureSampleCompare(t, t_posh_sampler, uv, layer, 0.5)"],
+    );
+}
+
+#[test]
+fn test_program() {
+    fn vertex_shader(
+        uniform: MyUniform<Sl>,
+        input: sl::VsInput<MyInstancedVsInterface<Sl>>,
+    ) -> sl::VsOutput<MyInterpolant> {
+        let pos = input.vertex.model * input.vertex.vertex.extend(1.0);
+
+        sl::VsOutput {
+            clip_pos: uniform.globals.world_to_clip * pos,
+            interp: MyInterpolant {
+                uv: pos.xy(),
+                scale: lambert(pos.xyz(), sl::Vec3::X),
+                instance_id: input.instance_id,
+            },
+        }
+    }
+
+    fn fragment_shader(
+        uniform: MyUniform<Sl>,
+        input: sl::FsInput<MyInterpolant>,
+    ) -> MyFsInterface<Sl> {
+        let color = uniform.sampler.sample(input.interp.uv);
+        let glow = sl::branch(
+            input.interp.instance_id.eq(0u32),
+            input.fragment_coord.x,
+            uniform.globals.block.scale,
+        );
+
+        MyFsInterface {
+            albedo: shade(input.interp, color) + uniform.globals.ambient,
+            normal: color,
+            glow,
+        }
+    }
+
+    let actual =
+        transpile_to_wgsl::<MyUniform<Sl>, _, _, _, _>(vertex_shader, fragment_shader).unwrap();
+
+    let bindings: Vec<_> = actual
+        .bindings
+        .iter()
+        .map(|def| (def.name.as_str(), def.group, def.binding))
+        .collect();
+
+    assert_eq!(
+        bindings,
+        [
+            ("uniforms_globals", 0, 0),
+            ("uniforms_sampler", 1, 0),
+            ("uniforms_sampler_posh_sampler", 1, 1),
+        ]
+    );
+    assert!(matches!(
+        actual.bindings[2].kind,
+        WgslBindingKind::Sampler(_)
+    ));
+
+    expect![[r#"
+        struct MyBlock_Posh0 {
+            scale: f32,
+        }
+
+        struct MyGlobals_Posh1 {
+            ambient: vec4<f32>,
+            world_to_clip: mat4x4<f32>,
+            block: MyBlock_Posh0,
+        }
+
+        struct MyInterpolant_Posh2 {
+            uv: vec2<f32>,
+            scale: f32,
+            instance_id: u32,
+        }
+
+        @group(0) @binding(0) var<uniform> uniforms_globals: MyGlobals_Posh1;
+        @group(1) @binding(0) var uniforms_sampler: texture_2d<f32>;
+        @group(1) @binding(1) var uniforms_sampler_posh_sampler: sampler;
+
+        struct PoshVertexOutput {
+            @builtin(position) gl_Position: vec4<f32>,
+            @location(0) @interpolate(perspective) vertex_output_uv: vec2<f32>,
+            @location(1) @interpolate(perspective) vertex_output_scale: f32,
+            @location(2) @interpolate(flat) vertex_output_QinstanceQuid: u32,
+        }
+
+        @vertex
+        fn vs_main(
+            @builtin(instance_index) posh_instance_index: u32,
+            @location(0) vertex_input_vertex: vec3<f32>,
+            @location(1) vertex_input_model_posh_col0: vec4<f32>,
+            @location(2) vertex_input_model_posh_col1: vec4<f32>,
+            @location(3) vertex_input_model_posh_col2: vec4<f32>,
+            @location(4) vertex_input_model_posh_col3: vec4<f32>,
+        ) -> PoshVertexOutput {
+            let gl_InstanceID: i32 = i32(posh_instance_index);
+            let vertex_input_model: mat4x4<f32> = mat4x4<f32>(vertex_input_model_posh_col0, vertex_input_model_posh_col1, vertex_input_model_posh_col2, vertex_input_model_posh_col3);
+            let var_0: vec4<f32> = (vertex_input_model * vec4<f32>(vertex_input_vertex.x, vertex_input_vertex.y, vertex_input_vertex.z, 1.0));
+            return PoshVertexOutput((uniforms_globals.world_to_clip * var_0), var_0.xy, lambert_posh_func0(var_0.xyz, vec3<f32>(1.0, 0.0, 0.0)), u32(gl_InstanceID));
+        }
+
+        struct PoshFragmentOutput {
+            @location(0) fragment_output_albedo: vec4<f32>,
+            @location(1) fragment_output_normal: vec3<f32>,
+            @location(2) fragment_output_glow: f32,
+        }
+
+        @fragment
+        fn fs_main(
+            @builtin(position) gl_FragCoord: vec4<f32>,
+            @location(0) @interpolate(perspective) vertex_output_uv: vec2<f32>,
+            @location(1) @interpolate(perspective) vertex_output_scale: f32,
+            @location(2) @interpolate(flat) vertex_output_QinstanceQuid: u32,
+        ) -> PoshFragmentOutput {
+            let var_0: f32 = select(uniforms_globals.block.scale, gl_FragCoord.x, (vertex_output_QinstanceQuid == 0u));
+            return PoshFragmentOutput((shade_posh_func1(MyInterpolant_Posh2(vertex_output_uv, vertex_output_scale, vertex_output_QinstanceQuid), textureSample(uniforms_sampler, uniforms_sampler_posh_sampler, vertex_output_uv).xyz) + uniforms_globals.ambient), textureSample(uniforms_sampler, uniforms_sampler_posh_sampler, vertex_output_uv).xyz, var_0);
+        }
+
+        fn lambert_posh_func0(normal: vec3<f32>, light_dir: vec3<f32>) -> f32 {
+            return max(dot(normal, light_dir), 0.0);
+        }
+
+        fn shade_posh_func1(interp: MyInterpolant_Posh2, normal: vec3<f32>) -> vec4<f32> {
+            let var_0: vec2<f32> = ((interp.uv * (lambert_posh_func0(normal, vec3<f32>(0.0, 0.0, 1.0)) + lambert_posh_func0(normal, vec3<f32>(0.0, 1.0, 0.0)))) * interp.scale);
+            return vec4<f32>(var_0.x, var_0.y, 0.0, 1.0);
+        }
+    "#]].assert_eq(&actual.source);
+}
+
+#[test]
+fn test_program_errors() {
+    fn vertex_shader((): (), vertex: sl::Vec2) -> sl::FullVsOutput<sl::Vec2> {
+        sl::FullVsOutput {
+            clip_pos: vertex.extend(0.0).extend(1.0),
+            interp: vertex,
+            point_size: Some(2.0.to_sl()),
+        }
+    }
+
+    fn fragment_shader((): (), uv: sl::Vec2) -> sl::Vec4 {
+        uv.extend(0.0).extend(1.0)
+    }
+
+    let actual = transpile_to_wgsl::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    assert!(matches!(actual, Err(WgslError::PointSize)));
+
+    fn plain_vertex_shader((): (), vertex: sl::Vec2) -> sl::VsOutput<sl::Vec2> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(0.0).extend(1.0),
+            interp: vertex,
+        }
+    }
+
+    fn lod_fragment_shader(sampler: sl::ComparisonSampler2d, uv: sl::Vec2) -> sl::Vec4 {
+        sl::Vec4::ONE * sampler.sample_compare_lod(uv, 0.5.to_sl(), 0.0)
+    }
+
+    let actual = transpile_to_wgsl::<sl::ComparisonSampler2d, _, _, _, _>(
+        plain_vertex_shader,
+        lod_fragment_shader,
+    );
+
+    assert!(matches!(actual, Err(WgslError::ComparisonLod(_))));
+}
@@ -0,0 +1,630 @@
+//! Constant folding and algebraic simplification of traced expressions.
+
+use std::{collections::BTreeMap, iter::repeat, rc::Rc};
+
+use super::{
+    dag::{BinaryOp, BuiltInType, Expr, FuncDef, Type},
+    eval::{evaluate_exprs, matrix_shape, vector_shape, Bindings, EvalValue},
+};
+
+/// Simplifies expressions before they are written in a target language.
+///
+/// `sl` traces Rust code literally, so expressions often contain arithmetic on
+/// literals, especially when they are built from [`Const`](super::Const)
+/// inputs. The folder evaluates operations whose operands are all constant,
+/// removes identity and absorbing elements, selects the taken case of branches
+/// and switches with a constant condition, and turns constructors of vector
+/// components into swizzles.
+///
+/// Expressions that are shared in the input remain shared in the output, so
+/// that codegen can still assign them to a single variable.
+#[derive(Default)]
+pub(crate) struct ConstFolder {
+    // The input expressions are kept alive, so that their addresses are not
+    // reused for other expressions while they serve as keys.
+    exprs: BTreeMap<*const Expr, (Rc<Expr>, Rc<Expr>)>,
+    defs: BTreeMap<*const FuncDef, Rc<FuncDef>>,
+    may_discard: BTreeMap<*const Expr, bool>,
+}
+
+impl ConstFolder {
+    pub(crate) fn fold(&mut self, expr: &Rc<Expr>) -> Rc<Expr> {
+        let key = Rc::as_ptr(expr);
+
+        if let Some((_, folded)) = self.exprs.get(&key) {
+            return folded.clone();
+        }
+
+        let folded = self.fold_successors(expr);
+        let folded = self.simplify(expr, folded);
+
+        self.exprs.insert(key, (expr.clone(), folded.clone()));
+
+        folded
+    }
+
+    fn fold_all(&mut self, exprs: &[Rc<Expr>]) -> Vec<Rc<Expr>> {
+        exprs.iter().map(|expr| self.fold(expr)).collect()
+    }
+
+    fn fold_def(&mut self, def: &Rc<FuncDef>) -> Rc<FuncDef> {
+        let key = Rc::as_ptr(def);
+
+        if let Some(folded) = self.defs.get(&key) {
+            return folded.clone();
+        }
+
+        let result = self.fold(&def.result);
+
+        let folded = if Rc::ptr_eq(&result, &def.result) {
+            def.clone()
+        } else {
+            Rc::new(FuncDef {
+                name: def.name,
+                params: def.params.clone(),
+                result,
+            })
+        };
+
+        self.defs.insert(key, folded.clone());
+
+        folded
+    }
+
+    // Returns `expr` with its successors folded. If none of them change,
+    // `expr` itself is returned, so that it stays shared.
+    fn fold_successors(&mut self, expr: &Rc<Expr>) -> Rc<Expr> {
+        use Expr::*;
+
+        let folded = match &**expr {
+            Arg { .. } | ScalarLiteral { .. } | Discard { .. } => return expr.clone(),
+            StructLiteral { args, ty } => StructLiteral {
+                args: self.fold_all(args),
+                ty: ty.clone(),
+            },
+            ArrayLiteral { args, ty } => ArrayLiteral {
+                args: self.fold_all(args),
+                ty: ty.clone(),
+            },
+            Unary { op, arg, ty } => Unary {
+                op: *op,
+                arg: self.fold(arg),
+                ty: ty.clone(),
+            },
+            Binary {
+                left,
+                op,
+                right,
+                ty,
+            } => Binary {
+                left: self.fold(left),
+                op: *op,
+                right: self.fold(right),
+                ty: ty.clone(),
+            },
+            CallFuncDef { def, args } => CallFuncDef {
+                def: self.fold_def(def),
+                args: self.fold_all(args),
+            },
+            CallBuiltIn { name, args, ty } => CallBuiltIn {
+                name: name.clone(),
+                args: self.fold_all(args),
+                ty: ty.clone(),
+            },
+            Field { base, name, ty } => Field {
+                base: self.fold(base),
+                name,
+                ty: ty.clone(),
+            },
+            Subscript { base, index, ty } => Subscript {
+                base: self.fold(base),
+                index: self.fold(index),
+                ty: ty.clone(),
+            },
+            Branch { cond, yes, no, ty } => Branch {
+                cond: self.fold(cond),
+                yes: self.fold(yes),
+                no: self.fold(no),
+                ty: ty.clone(),
+            },
+            Switch {
+                value,
+                cases,
+                default,
+                ty,
+            } => Switch {
+                value: self.fold(value),
+                cases: cases
+                    .iter()
+                    .map(|(label, case)| (*label, self.fold(case)))
+                    .collect(),
+                default: self.fold(default),
+                ty: ty.clone(),
+            },
+            Loop {
+                start,
+                end,
+                init,
+                acc_name,
+                index_name,
+                body,
+                stop,
+                ty,
+            } => Loop {
+                start: self.fold(start),
+                end: self.fold(end),
+                init: self.fold(init),
+                acc_name: acc_name.clone(),
+                index_name: index_name.clone(),
+                body: self.fold(body),
+                stop: stop.as_ref().map(|stop| self.fold(stop)),
+                ty: ty.clone(),
+            },
+        };
+
+        let same_def = match (&**expr, &folded) {
+            (
+                CallFuncDef { def, .. },
+                CallFuncDef {
+                    def: folded_def, ..
+                },
+            ) => Rc::ptr_eq(def, folded_def),
+            _ => true,
+        };
+
+        if same_def
+            && successors(expr)
+                .iter()
+                .zip(successors(&folded))
+                .all(|(a, b)| Rc::ptr_eq(a, &b))
+        {
+            expr.clone()
+        } else {
+            Rc::new(folded)
+        }
+    }
+
+    // Simplifies `expr`, whose successors have already been folded. `original`
+    // is the expression before folding.
+    fn simplify(&mut self, original: &Rc<Expr>, expr: Rc<Expr>) -> Rc<Expr> {
+        use Expr::*;
+
+        if is_foldable(&expr) && successors(&expr).iter().all(|succ| is_constant(succ)) {
+            if let Some(folded) = evaluate(&expr).and_then(|value| value_expr(&value, &expr.ty())) {
+                return folded;
+            }
+        }
+
+        let simplified = match &*expr {
+            Unary { op, arg, .. } => match &**arg {
+                // All unary operators are involutions.
+                Unary {
+                    op: inner_op,
+                    arg: inner_arg,
+                    ..
+                } if inner_op == op => Some(inner_arg.clone()),
+                _ => None,
+            },
+            Binary {
+                left,
+                op,
+                right,
+                ty,
+            } => {
+                // Absorbing elements remove an operand, which must not change
+                // whether the fragment is discarded.
+                let pure = !self.may_discard(original);
+
+                simplify_binary(left, *op, right, ty, pure)
+            }
+            Branch { cond, yes, no, .. } => match evaluate_constant(cond) {
+                Some(EvalValue::Bool(cond)) => Some(if cond { yes.clone() } else { no.clone() }),
+                _ => None,
+            },
+            Switch {
+                value,
+                cases,
+                default,
+                ..
+            } => match evaluate_constant(value) {
+                Some(EvalValue::I32(x)) => Some(select_case(i64::from(x), cases, default)),
+                Some(EvalValue::U32(x)) => Some(select_case(i64::from(x), cases, default)),
+                _ => None,
+            },
+            Field { base, name, ty } => {
+                // Taking a field of a literal removes the other arguments, which
+                // must not change whether the fragment is discarded.
+                let pure = !self.may_discard(original);
+
+                simplify_field(base, name, ty, pure)
+            }
+            CallBuiltIn { name, args, ty } if is_constructor(name, ty) => {
+                simplify_constructor(args, ty)
+            }
+            _ => None,
+        };
+
+        simplified.unwrap_or(expr)
+    }
+
+    // Returns whether evaluating `expr` may discard the fragment.
+    fn may_discard(&mut self, expr: &Rc<Expr>) -> bool {
+        let key = Rc::as_ptr(expr);
+
+        if let Some(&may_discard) = self.may_discard.get(&key) {
+            return may_discard;
+        }
+
+        let may_discard = match &**expr {
+            Expr::Discard { .. } => true,
+            Expr::CallFuncDef { def, .. } if self.may_discard(&def.result) => true,
+            _ => successors(expr).iter().any(|succ| self.may_discard(succ)),
+        };
+
+        self.may_discard.insert(key, may_discard);
+
+        may_discard
+    }
+}
+
+fn simplify_binary(
+    left: &Rc<Expr>,
+    op: BinaryOp,
+    right: &Rc<Expr>,
+    ty: &Type,
+    pure: bool,
+) -> Option<Rc<Expr>> {
+    use BinaryOp::*;
+
+    // An operand can only replace the whole expression if it has the same
+    // type, which is not the case if it is broadcast to the other operand.
+    let keep = |operand: &Rc<Expr>| Some(operand.clone()).filter(|operand| operand.ty() == *ty);
+    let is = |operand: &Rc<Expr>, f: fn(&EvalValue) -> bool| {
+        scalar_components(operand).is_some_and(|components| components.iter().all(f))
+    };
+
+    // Returns the other operand if one operand is an identity element.
+    let identity = |f| {
+        (is(right, f).then(|| keep(left)).flatten())
+            .or_else(|| is(left, f).then(|| keep(right)).flatten())
+    };
+
+    // Returns the operand that is an absorbing element. The other operand is
+    // removed, which must not change whether the fragment is discarded.
+    let absorbing = |f| {
+        [left, right]
+            .into_iter()
+            .find(|operand| is(operand, f))
+            .and_then(keep)
+            .filter(|_| pure)
+    };
+
+    let is_integer = ty
+        .built_in_type()
+        .map(|ty| vector_shape(ty).map_or(ty, |(scalar, _)| scalar))
+        .is_some_and(|scalar| matches!(scalar, BuiltInType::I32 | BuiltInType::U32));
+
+    match op {
+        Add | BitOr | BitXor => identity(is_zero),
+        Sub | Shl | Shr => is(right, is_zero).then(|| keep(left)).flatten(),
+        // `x * 0.0` is not folded, since it is not zero for infinite or NaN
+        // `x`.
+        Mul => identity(is_one).or_else(|| absorbing(is_zero).filter(|_| is_integer)),
+        Div => is(right, is_one).then(|| keep(left)).flatten(),
+        BitAnd => absorbing(is_zero),
+        And => identity(is_one).or_else(|| absorbing(is_zero)),
+        Or => identity(is_zero).or_else(|| absorbing(is_one)),
+        Eq | Ne | Lt | Le | Ge | Gt | Rem => None,
+    }
+}
+
+fn simplify_field(base: &Rc<Expr>, name: &str, ty: &Type, pure: bool) -> Option<Rc<Expr>> {
+    match &**base {
+        Expr::StructLiteral { args, ty: base_ty } if pure => {
+            let index = base_ty
+                .fields
+                .iter()
+                .position(|(field_name, _)| field_name == name)?;
+
+            Some(args[index].clone())
+        }
+        Expr::CallBuiltIn {
+            name: base_name,
+            args,
+            ty: base_ty,
+        } if pure && is_constructor(base_name, base_ty) => {
+            // Components can only be taken from constructors that are given
+            // one argument per component.
+            let (scalar, len) = base_ty.built_in_type().and_then(vector_shape)?;
+
+            if args.len() != len || args.iter().any(|arg| arg.ty() != Type::BuiltIn(scalar)) {
+                return None;
+            }
+
+            let args: Vec<_> = swizzle_indices(name)?
+                .into_iter()
+                .map(|i| args[i].clone())
+                .collect();
+
+            if let [arg] = &args[..] {
+                Some(arg.clone())
+            } else {
+                let expr = simplify_constructor(&args, ty).unwrap_or_else(|| {
+                    Rc::new(Expr::CallBuiltIn {
+                        name: ty.to_string(),
+                        args,
+                        ty: ty.clone(),
+                    })
+                });
+
+                Some(expr)
+            }
+        }
+        Expr::Field {
+            base: inner_base,
+            name: inner_name,
+            ..
+        } => {
+            // Swizzles of swizzles are combined into one.
+            inner_base.ty().built_in_type().and_then(vector_shape)?;
+
+            let inner_indices = swizzle_indices(inner_name)?;
+            let indices = swizzle_indices(name)?
+                .into_iter()
+                .map(|i| inner_indices.get(i).copied())
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(swizzle(inner_base, &indices, ty))
+        }
+        _ => None,
+    }
+}
+
+// Turns constructors of components of a single vector into a swizzle of the
+// vector.
+fn simplify_constructor(args: &[Rc<Expr>], ty: &Type) -> Option<Rc<Expr>> {
+    let (scalar, len) = ty.built_in_type().and_then(vector_shape)?;
+
+    let base = match &**args.first()? {
+        Expr::Field { base, .. } => base,
+        _ => return None,
+    };
+
+    // Constructors convert between scalar types, swizzles do not.
+    let (base_scalar, _) = base.ty().built_in_type().and_then(vector_shape)?;
+
+    if base_scalar != scalar {
+        return None;
+    }
+
+    let mut indices = Vec::new();
+
+    for arg in args {
+        match &**arg {
+            Expr::Field {
+                base: arg_base,
+                name,
+                ..
+            } if Rc::ptr_eq(arg_base, base) => indices.extend(swizzle_indices(name)?),
+            _ => return None,
+        }
+    }
+
+    (indices.len() == len).then(|| swizzle(base, &indices, ty))
+}
+
+fn swizzle(base: &Rc<Expr>, indices: &[usize], ty: &Type) -> Rc<Expr> {
+    if base.ty() == *ty && indices.iter().copied().eq(0..indices.len()) {
+        base.clone()
+    } else {
+        Rc::new(Expr::Field {
+            base: base.clone(),
+            name: swizzle_name(indices),
+            ty: ty.clone(),
+        })
+    }
+}
+
+fn swizzle_indices(name: &str) -> Option<Vec<usize>> {
+    name.chars().map(|c| "xyzw".find(c)).collect()
+}
+
+// Every swizzle is a prefix of one of the 256 swizzles of length four, so the
+// names of all swizzles can be taken from this table.
+static SWIZZLES: [u8; 1024] = swizzle_table();
+
+const fn swizzle_table() -> [u8; 1024] {
+    let mut table = [0; 1024];
+    let mut i = 0;
+
+    while i < table.len() {
+        // The `j`-th swizzle selects the components given by the base 4 digits
+        // of `j`.
+        let j = i / 4;
+        let digit = 3 - i % 4;
+
+        table[i] = b"xyzw"[(j >> (2 * digit)) & 3];
+        i += 1;
+    }
+
+    table
+}
+
+fn swizzle_name(indices: &[usize]) -> &'static str {
+    let j = indices
+        .iter()
+        .chain(repeat(&0))
+        .take(4)
+        .fold(0, |j, i| 4 * j + i);
+
+    std::str::from_utf8(&SWIZZLES[4 * j..4 * j + indices.len()]).unwrap()
+}
+
+fn select_case(value: i64, cases: &[(i64, Rc<Expr>)], default: &Rc<Expr>) -> Rc<Expr> {
+    cases
+        .iter()
+        .find(|(label, _)| *label == value)
+        .map_or(default, |(_, case)| case)
+        .clone()
+}
+
+fn successors(expr: &Expr) -> Vec<Rc<Expr>> {
+    let mut successors = Vec::new();
+    expr.successors(|succ| successors.push(succ.clone()));
+
+    successors
+}
+
+fn is_constructor(name: &str, ty: &Type) -> bool {
+    ty.built_in_type().is_some_and(|ty| ty.to_string() == name)
+}
+
+// Returns whether `expr` is built only from literals. Vectors and matrices are
+// kept as constructors, while scalar conversions such as `float(1)` are folded
+// into literals.
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::ScalarLiteral { .. } => true,
+        Expr::StructLiteral { args, .. } | Expr::ArrayLiteral { args, .. } => {
+            args.iter().all(|arg| is_constant(arg))
+        }
+        Expr::CallBuiltIn { name, args, ty } => {
+            is_constructor(name, ty) && !is_scalar(ty) && args.iter().all(|arg| is_constant(arg))
+        }
+        _ => false,
+    }
+}
+
+// Returns whether `expr` can be replaced by its value if all of its successors
+// are constant.
+fn is_foldable(expr: &Expr) -> bool {
+    match expr {
+        Expr::Unary { .. }
+        | Expr::Binary { .. }
+        | Expr::CallFuncDef { .. }
+        | Expr::Field { .. }
+        | Expr::Subscript { .. } => true,
+        Expr::CallBuiltIn { name, ty, .. } => !is_constructor(name, ty) || is_scalar(ty),
+        _ => false,
+    }
+}
+
+fn is_scalar(ty: &Type) -> bool {
+    ty.built_in_type().is_some_and(|ty| {
+        matches!(
+            ty,
+            BuiltInType::F32 | BuiltInType::I32 | BuiltInType::U32 | BuiltInType::Bool
+        )
+    })
+}
+
+fn is_zero(value: &EvalValue) -> bool {
+    match value {
+        EvalValue::F32(x) => *x == 0.0,
+        EvalValue::I32(x) => *x == 0,
+        EvalValue::U32(x) => *x == 0,
+        EvalValue::Bool(x) => !x,
+        _ => false,
+    }
+}
+
+fn is_one(value: &EvalValue) -> bool {
+    match value {
+        EvalValue::F32(x) => *x == 1.0,
+        EvalValue::I32(x) => *x == 1,
+        EvalValue::U32(x) => *x == 1,
+        EvalValue::Bool(x) => *x,
+        _ => false,
+    }
+}
+
+// Evaluates an expression that may depend on no arguments. Evaluation can
+// fail, for example on integer division by zero, in which case the expression
+// is left to the driver.
+fn evaluate(expr: &Rc<Expr>) -> Option<EvalValue> {
+    evaluate_exprs(std::slice::from_ref(expr), &Bindings::new())
+        .ok()?
+        .pop()
+}
+
+fn evaluate_constant(expr: &Rc<Expr>) -> Option<EvalValue> {
+    is_constant(expr).then(|| evaluate(expr)).flatten()
+}
+
+// Returns the scalar components of a constant scalar or vector.
+fn scalar_components(expr: &Rc<Expr>) -> Option<Vec<EvalValue>> {
+    match evaluate_constant(expr)? {
+        EvalValue::Vec(components) => Some(components),
+        value if is_scalar(&expr.ty()) => Some(vec![value]),
+        _ => None,
+    }
+}
+
+// Returns an expression that evaluates to `value` of type `ty`, if the value
+// can be written in the shading language.
+fn value_expr(value: &EvalValue, ty: &Type) -> Option<Rc<Expr>> {
+    let expr = match (value, ty) {
+        (EvalValue::Struct(fields), Type::Struct(struct_ty)) => Expr::StructLiteral {
+            args: fields
+                .iter()
+                .zip(&struct_ty.fields)
+                .map(|(field, (_, ty))| value_expr(field, ty))
+                .collect::<Option<_>>()?,
+            ty: struct_ty.clone(),
+        },
+        (EvalValue::Array(elements), Type::Array(array_ty)) => Expr::ArrayLiteral {
+            args: elements
+                .iter()
+                .map(|element| value_expr(element, &array_ty.ty))
+                .collect::<Option<_>>()?,
+            ty: array_ty.clone(),
+        },
+        (EvalValue::Vec(components) | EvalValue::Mat(components), Type::BuiltIn(built_in_ty)) => {
+            let component_ty = if let Some((num_columns, num_rows)) = matrix_shape(*built_in_ty) {
+                debug_assert_eq!(components.len(), num_columns);
+
+                match num_rows {
+                    2 => BuiltInType::Vec2,
+                    3 => BuiltInType::Vec3,
+                    _ => BuiltInType::Vec4,
+                }
+            } else {
+                vector_shape(*built_in_ty)?.0
+            };
+
+            Expr::CallBuiltIn {
+                name: built_in_ty.to_string(),
+                args: components
+                    .iter()
+                    .map(|component| value_expr(component, &Type::BuiltIn(component_ty)))
+                    .collect::<Option<_>>()?,
+                ty: ty.clone(),
+            }
+        }
+        (_, Type::BuiltIn(built_in_ty)) => Expr::ScalarLiteral {
+            value: literal(value)?,
+            ty: *built_in_ty,
+        },
+        _ => return None,
+    };
+
+    Some(Rc::new(expr))
+}
+
+// Formats a scalar literal in the same way as `sl` does when tracing.
+fn literal(value: &EvalValue) -> Option<String> {
+    let literal = match value {
+        EvalValue::F32(x) if x.is_finite() => format!("{x:?}"),
+        // The literal `2147483648` is out of range, so `-2147483648` cannot be
+        // written as a negated literal.
+        EvalValue::I32(x) if *x != i32::MIN => format!("{x}"),
+        EvalValue::U32(x) => format!("{x}u"),
+        EvalValue::Bool(x) => format!("{x:?}"),
+        _ => return None,
+    };
+
+    Some(literal)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,279 @@
+use std::rc::Rc;
+
+use expect_test::{expect, Expect};
+
+use crate::{
+    sl::{
+        self,
+        dag::{BuiltInType, Expr, Type},
+        primitives::{field, value_arg},
+        program_def::ProgramDef,
+        transpile::{transpile_to_program_def_with_options, TranspileOptions},
+        Object, Value,
+    },
+    Block, BlockDom, Gl, ToSl,
+};
+
+use super::ConstFolder;
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct Scale<D: BlockDom> {
+    scale: D::F32,
+}
+
+fn check_fold<V: Value>(value: impl ToSl<Output = V>, expect: Expect) {
+    let actual = ConstFolder::default().fold(&value.to_sl().expr());
+
+    expect.assert_eq(&actual.to_string());
+}
+
+#[test]
+fn test_literals() {
+    let x = sl::F32::new(2.0);
+    let i = sl::I32::new(7);
+
+    check_fold(x * 3.0 + 1.0, expect!["7.0"]);
+    check_fold(sl::vec2(1.0, x) * x - 0.5, expect!["vec2(1.5, 3.5)"]);
+    check_fold(
+        sl::mat2(sl::vec2(1.0, 0.0), sl::vec2(0.0, x)) * sl::vec2(3.0, 4.0),
+        expect!["vec2(3.0, 8.0)"],
+    );
+    check_fold(i.as_f32().sqrt() * 0.0, expect!["0.0"]);
+    check_fold((i % 4).eq(3), expect!["true"]);
+    check_fold(sl::vec3(x, 0.0, -x).length().gt(2.0), expect!["true"]);
+
+    // Expressions with undefined or non-finite results are kept.
+    check_fold(i / 0, expect!["(7 / 0)"]);
+    check_fold(x / 0.0, expect!["(2.0 / 0.0)"]);
+}
+
+#[test]
+fn test_identities() {
+    let x = value_arg::<sl::F32>("x");
+    let v = value_arg::<sl::Vec3>("v");
+    let i = value_arg::<sl::I32>("i");
+    let b = value_arg::<sl::Bool>("b");
+
+    check_fold(x * 1.0 + 0.0, expect!["x"]);
+    check_fold((v - 0.0) / 1.0, expect!["v"]);
+    check_fold(sl::Vec3::ONE * v, expect!["v"]);
+    check_fold(-(-x), expect!["x"]);
+    check_fold((i << 0) * 1, expect!["i"]);
+    check_fold(sl::and(b, true), expect!["b"]);
+    check_fold(sl::or(false, b), expect!["b"]);
+
+    // Broadcast operands cannot replace the whole expression.
+    check_fold(x + sl::Vec3::ZERO, expect!["(x + vec3(0.0, 0.0, 0.0))"]);
+
+    // Absorbing elements.
+    check_fold(i * sl::I32::ZERO + 1, expect!["1"]);
+    check_fold(sl::and(b, false), expect!["false"]);
+    check_fold(sl::or(b, true), expect!["true"]);
+
+    // `x * 0.0` is NaN for infinite or NaN `x`.
+    check_fold(x * 0.0, expect!["(x * 0.0)"]);
+}
+
+#[test]
+fn test_discard() {
+    let discarded = sl::I32::from_expr(Expr::Discard {
+        ty: Type::BuiltIn(BuiltInType::I32),
+    });
+
+    check_fold(discarded * sl::I32::ZERO, expect!["(discard * 0)"]);
+    check_fold(sl::branch(false, discarded, 1), expect!["1"]);
+
+    // Fields of literals are kept if the other arguments may discard. The
+    // fields of a pair are called `T6` and `T7`.
+    let x = value_arg::<sl::F32>("x");
+    let discarded = sl::F32::from_expr(Expr::Discard {
+        ty: Type::BuiltIn(BuiltInType::F32),
+    });
+
+    check_fold(
+        field::<sl::F32>((x, discarded).expr(), "T6"),
+        expect!["tuple(x, discard).T6"],
+    );
+    check_fold(
+        field::<sl::F32>(sl::vec2(x, discarded).expr(), "x"),
+        expect!["vec2(x, discard).x"],
+    );
+    check_fold(
+        field::<sl::F32>((x, 1.0.to_sl()).expr(), "T6"),
+        expect!["x"],
+    );
+}
+
+#[test]
+fn test_branches() {
+    let x = value_arg::<sl::F32>("x");
+    let i = value_arg::<sl::I32>("i");
+    let n = sl::U32::new(2);
+
+    check_fold(sl::branch(n.eq(2u32), x, 0.0), expect!["x"]);
+    check_fold(
+        sl::branch(x.lt(0.0), 1.0 - 1.0, x),
+        expect!["((x < 0.0) ? 0.0 : x)"],
+    );
+    check_fold(sl::switch(n * 2u32, [(2, i + 1), (4, i)], 0), expect!["i"]);
+    check_fold(sl::switch(n, [(2u32, i + 0)], 0), expect!["i"]);
+}
+
+#[test]
+fn test_swizzles() {
+    let v = value_arg::<sl::Vec4>("v");
+    let iv = value_arg::<sl::IVec2>("iv");
+
+    check_fold(sl::vec3(v.z, v.y, v.x), expect!["v.zyx"]);
+    check_fold(sl::vec3(v.x, v.y, v.z), expect!["v.xyz"]);
+    check_fold(v.zyx().yx(), expect!["v.yz"]);
+    check_fold(v.wzyx().xy().y, expect!["v.z"]);
+    check_fold(sl::vec4(v.x, v.w, 1.0, 2.0).yx(), expect!["v.wx"]);
+
+    check_fold(sl::ivec2(iv.y, iv.x), expect!["iv.yx"]);
+
+    // Constructors convert between scalar types, swizzles do not.
+    check_fold(
+        sl::Vec2::from_expr(Expr::CallBuiltIn {
+            name: "vec2".to_string(),
+            args: vec![iv.y.expr(), iv.x.expr()],
+            ty: Type::BuiltIn(BuiltInType::Vec2),
+        }),
+        expect!["vec2(iv.y, iv.x)"],
+    );
+}
+
+#[test]
+fn test_sharing() {
+    let x = value_arg::<sl::F32>("x");
+    let shared = (x * 1.0).sin();
+
+    let mut folder = ConstFolder::default();
+    let a = folder.fold(&(shared + 1.0).expr());
+    let b = folder.fold(&(shared * 1.0).expr());
+
+    assert_eq!(a.to_string(), "(sin(x) + 1.0)");
+    assert_eq!(b.to_string(), "sin(x)");
+
+    match &*a {
+        Expr::Binary { left, .. } => assert!(Rc::ptr_eq(left, &b)),
+        _ => panic!("expected binary expression"),
+    }
+
+    // Expressions without constants are not copied.
+    let unchanged = (x.sin() + x).expr();
+
+    assert!(Rc::ptr_eq(&folder.fold(&unchanged), &unchanged));
+}
+
+#[test]
+fn test_program() {
+    fn vertex_shader(consts: &Scale<Gl>, (): (), vertex: sl::Vec4) -> sl::VsOutput<sl::Vec2> {
+        let scale = consts.scale.to_sl();
+
+        sl::VsOutput {
+            clip_pos: vertex * scale * 2.0 + (scale - 1.0),
+            interp: sl::vec2(vertex.y, vertex.x) * scale,
+        }
+    }
+
+    fn fragment_shader(consts: &Scale<Gl>, (): (), interp: sl::Vec2) -> sl::Vec4 {
+        let scale = consts.scale.to_sl();
+        let color = sl::branch(scale.gt(0.0), interp.extend(scale), sl::Vec3::ZERO);
+
+        color.extend(scale * 0.5)
+    }
+
+    let transpile = |options: &TranspileOptions| -> ProgramDef {
+        transpile_to_program_def_with_options::<(), _, _, _, _>(
+            options,
+            &Scale { scale: 1.0 },
+            vertex_shader,
+            fragment_shader,
+        )
+    };
+
+    let folded = transpile(&TranspileOptions::default());
+
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        in vec4 vertex_input;
+        smooth out vec2 vertex_output;
+
+        void main() {
+            gl_Position = (vertex_input * 2.0);
+            vertex_output = vertex_input.yx;
+        }
+    "#]]
+    .assert_eq(&folded.vertex_shader_source);
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        smooth in vec2 vertex_output;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = vec4(vertex_output.x, vertex_output.y, 1.0, 0.5);
+        }
+    "#]]
+    .assert_eq(&folded.fragment_shader_source);
+
+    let unfolded = transpile(&TranspileOptions {
+        disable_const_folding: true,
+        ..Default::default()
+    });
+
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        in vec4 vertex_input;
+        smooth out vec2 vertex_output;
+
+        void main() {
+            gl_Position = (((vertex_input * 1.0) * 2.0) + (1.0 - 1.0));
+            vertex_output = (vec2(vertex_input.y, vertex_input.x) * 1.0);
+        }
+    "#]]
+    .assert_eq(&unfolded.vertex_shader_source);
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        smooth in vec2 vertex_output;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            vec3 var_0 = ((1.0 > 0.0)) ? (vec3(vertex_output.x, vertex_output.y, 1.0)) : (vec3(0.0, 0.0, 0.0));
+            fragment_output = vec4(var_0.x, var_0.y, var_0.z, (1.0 * 0.5));
+        }
+    "#]].assert_eq(&unfolded.fragment_shader_source);
+}
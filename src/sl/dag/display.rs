@@ -83,6 +83,47 @@ impl Display for Expr {
             Field { base, name, .. } => write!(f, "{base}.{name}"),
             Subscript { base, index, .. } => write!(f, "{base}[{index}]"),
             Branch { cond, yes, no, .. } => write!(f, "({cond} ? {yes} : {no})"),
+            Switch {
+                value,
+                cases,
+                default,
+                ..
+            } => {
+                write!(f, "switch({value}) {{ ")?;
+
+                let suffix = if value.ty() == Type::BuiltIn(BuiltInType::U32) {
+                    "u"
+                } else {
+                    ""
+                };
+
+                for (label, case) in cases {
+                    write!(f, "{label}{suffix} => {case}, ")?;
+                }
+
+                write!(f, "_ => {default} }}")
+            }
+            Loop {
+                start,
+                end,
+                init,
+                acc_name,
+                index_name,
+                body,
+                stop,
+                ..
+            } => {
+                write!(
+                    f,
+                    "fold({start}..{end}, {init}, |{acc_name}, {index_name}| {body})"
+                )?;
+
+                if let Some(stop) = stop {
+                    write!(f, " until {stop}")?;
+                }
+
+                Ok(())
+            }
             Discard { .. } => write!(f, "discard"),
         }
     }
@@ -97,6 +138,17 @@ impl Display for SamplerType {
             IColorSampler2d => "isampler2D",
             UColorSampler2d => "usampler2D",
             ComparisonSampler2d => "sampler2DShadow",
+            ColorSamplerCube => "samplerCube",
+            IColorSamplerCube => "isamplerCube",
+            UColorSamplerCube => "usamplerCube",
+            ComparisonSamplerCube => "samplerCubeShadow",
+            ColorSampler3d => "sampler3D",
+            IColorSampler3d => "isampler3D",
+            UColorSampler3d => "usampler3D",
+            ColorSampler2dArray => "sampler2DArray",
+            IColorSampler2dArray => "isampler2DArray",
+            UColorSampler2dArray => "usampler2DArray",
+            ComparisonSampler2dArray => "sampler2DArrayShadow",
         };
 
         f.write_str(s)
@@ -127,6 +179,12 @@ impl Display for BuiltInType {
             Mat2 => "mat2",
             Mat3 => "mat3",
             Mat4 => "mat4",
+            Mat2x3 => "mat2x3",
+            Mat2x4 => "mat2x4",
+            Mat3x2 => "mat3x2",
+            Mat3x4 => "mat3x4",
+            Mat4x2 => "mat4x2",
+            Mat4x3 => "mat4x3",
             Sampler(sampler) => return write!(f, "{sampler}"),
         };
 
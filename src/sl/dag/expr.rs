@@ -34,7 +34,7 @@ pub enum UnaryOp {
 #[derive(Debug, Clone)]
 pub struct FuncDef {
     pub name: &'static str,
-    pub params: Vec<(&'static str, Type)>,
+    pub params: Vec<(String, Type)>,
     pub result: Rc<Expr>,
 }
 
@@ -68,7 +68,7 @@ pub enum Expr {
         ty: Type,
     },
     CallFuncDef {
-        def: FuncDef,
+        def: Rc<FuncDef>,
         args: Vec<Rc<Expr>>,
     },
     CallBuiltIn {
@@ -92,6 +92,22 @@ pub enum Expr {
         no: Rc<Expr>,
         ty: Type,
     },
+    Switch {
+        value: Rc<Expr>,
+        cases: Vec<(i64, Rc<Expr>)>,
+        default: Rc<Expr>,
+        ty: Type,
+    },
+    Loop {
+        start: Rc<Expr>,
+        end: Rc<Expr>,
+        init: Rc<Expr>,
+        acc_name: String,
+        index_name: String,
+        body: Rc<Expr>,
+        stop: Option<Rc<Expr>>,
+        ty: Type,
+    },
     Discard {
         ty: Type,
     },
@@ -113,6 +129,8 @@ impl Expr {
             Field { ty, .. } => ty.clone(),
             Subscript { ty, .. } => ty.clone(),
             Branch { ty, .. } => ty.clone(),
+            Switch { ty, .. } => ty.clone(),
+            Loop { ty, .. } => ty.clone(),
             Discard { ty, .. } => ty.clone(),
         }
     }
@@ -139,9 +157,9 @@ impl Expr {
                 f(left);
                 f(right);
             }
-            CallFuncDef { def, args, .. } => {
-                f(&def.result);
-
+            CallFuncDef { args, .. } => {
+                // The body of the function is not a successor, since it is
+                // generated separately from the expressions that call it.
                 for arg in args {
                     f(arg);
                 }
@@ -163,6 +181,37 @@ impl Expr {
                 f(yes);
                 f(no);
             }
+            Switch {
+                value,
+                cases,
+                default,
+                ..
+            } => {
+                f(value);
+
+                for (_, case) in cases {
+                    f(case);
+                }
+
+                f(default);
+            }
+            Loop {
+                start,
+                end,
+                init,
+                body,
+                stop,
+                ..
+            } => {
+                f(start);
+                f(end);
+                f(init);
+                f(body);
+
+                if let Some(stop) = stop {
+                    f(stop);
+                }
+            }
             Discard { .. } => {}
         }
     }
@@ -6,6 +6,17 @@ pub enum SamplerType {
     IColorSampler2d,
     UColorSampler2d,
     ComparisonSampler2d,
+    ColorSamplerCube,
+    IColorSamplerCube,
+    UColorSamplerCube,
+    ComparisonSamplerCube,
+    ColorSampler3d,
+    IColorSampler3d,
+    UColorSampler3d,
+    ColorSampler2dArray,
+    IColorSampler2dArray,
+    UColorSampler2dArray,
+    ComparisonSampler2dArray,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -29,6 +40,12 @@ pub enum BuiltInType {
     Mat2,
     Mat3,
     Mat4,
+    Mat2x3,
+    Mat2x4,
+    Mat3x2,
+    Mat3x4,
+    Mat4x2,
+    Mat4x3,
     Sampler(SamplerType),
 }
 
@@ -36,7 +53,10 @@ impl BuiltInType {
     pub fn is_mat(&self) -> bool {
         use BuiltInType::*;
 
-        matches!(self, Mat2 | Mat3 | Mat4)
+        matches!(
+            self,
+            Mat2 | Mat3 | Mat4 | Mat2x3 | Mat2x4 | Mat3x2 | Mat3x4 | Mat4x2 | Mat4x3
+        )
     }
 }
 
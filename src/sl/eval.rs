@@ -0,0 +1,1058 @@
+//! Evaluation of shading language expressions on the CPU.
+//!
+//! This makes it possible to test functions written in the shading language
+//! with ordinary `#[test]`s, without needing a GL context.
+//!
+//! Inputs such as uniforms and vertex attributes can be passed to the function
+//! under test directly as host values by converting them with
+//! [`ToSl::to_sl`]. Alternatively, they can be given as named arguments with
+//! [`arg`], which are looked up in [`Bindings`] at evaluation time.
+//!
+//! # Example
+//!
+//! ```
+//! use posh::{gl, sl};
+//!
+//! fn scaled_length(v: sl::Vec3, scale: sl::F32) -> sl::F32 {
+//!     v.length() * scale
+//! }
+//!
+//! let mut bindings = sl::eval::Bindings::new();
+//! bindings.insert("v", gl::Vec3 { x: 3.0, y: 4.0, z: 0.0 });
+//!
+//! let value = scaled_length(sl::eval::arg("v"), 2.0.into());
+//! let result = sl::eval::evaluate(value, &bindings).unwrap();
+//!
+//! assert_eq!(f32::try_from(result), Ok(10.0));
+//! ```
+
+use std::{cmp::Ordering, collections::BTreeMap, rc::Rc};
+
+use thiserror::Error;
+
+use crate::{gl, ToSl};
+
+use super::{
+    dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp},
+    Object, Value,
+};
+
+/// A value computed by [`evaluate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalValue {
+    F32(f32),
+    I32(i32),
+    U32(u32),
+    Bool(bool),
+    /// A vector, given by its scalar components.
+    Vec(Vec<EvalValue>),
+    /// A matrix, given by its column vectors.
+    Mat(Vec<EvalValue>),
+    /// A struct, given by its fields in order of declaration.
+    Struct(Vec<EvalValue>),
+    Array(Vec<EvalValue>),
+}
+
+/// An error that occurred during [`evaluate`].
+#[derive(Debug, Clone, Error)]
+pub enum EvalError {
+    #[error("no value is bound to the argument `{0}`")]
+    UnboundArg(String),
+
+    #[error("the value bound to the argument `{name}` does not have type `{expected}`")]
+    TypeMismatch { name: String, expected: Type },
+
+    #[error("the fragment was discarded")]
+    Discarded,
+
+    #[error("the built-in function `{0}` cannot be evaluated on the CPU")]
+    UnsupportedFunction(String),
+
+    #[error("index {index} is out of bounds for length {len}")]
+    IndexOutOfBounds { index: i64, len: usize },
+
+    #[error("integer division by zero")]
+    DivisionByZero,
+}
+
+/// Values for the named arguments of an expression.
+#[derive(Debug, Clone, Default)]
+pub struct Bindings {
+    values: BTreeMap<String, EvalValue>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds the argument `name` to a host value.
+    ///
+    /// Arguments of sampler type can be bound to a value of any type. Sampling
+    /// them results in that value, just like for a constant sampler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` depends on arguments.
+    pub fn insert<V: Value>(
+        &mut self,
+        name: impl Into<String>,
+        value: impl ToSl<Output = V>,
+    ) -> &mut Self {
+        let value = evaluate(value.to_sl(), &Bindings::new())
+            .unwrap_or_else(|error| panic!("bound values must be constant: {error}"));
+
+        self.values.insert(name.into(), value);
+
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EvalValue> {
+        self.values.get(name)
+    }
+
+    pub(crate) fn insert_value(&mut self, name: impl Into<String>, value: EvalValue) -> &mut Self {
+        self.values.insert(name.into(), value);
+
+        self
+    }
+}
+
+/// Returns an argument named `name` whose value is given by the [`Bindings`]
+/// passed to [`evaluate`].
+pub fn arg<V: Object>(name: &str) -> V {
+    V::from_arg(name)
+}
+
+/// Evaluates `value` on the CPU.
+///
+/// The results of built-in functions follow the GLSL ES 3.00 specification.
+/// Where the specification leaves results undefined, such as for integer
+/// overflow or out-of-range shifts, evaluation wraps around.
+pub fn evaluate<V: Value>(value: V, bindings: &Bindings) -> Result<EvalValue, EvalError> {
+    Evaluator::new(bindings.values.clone()).eval(&value.expr())
+}
+
+/// Evaluates several expressions, sharing the values of common
+/// subexpressions.
+pub(crate) fn evaluate_exprs(
+    exprs: &[Rc<Expr>],
+    bindings: &Bindings,
+) -> Result<Vec<EvalValue>, EvalError> {
+    Evaluator::new(bindings.values.clone()).eval_all(exprs)
+}
+
+struct Evaluator {
+    args: BTreeMap<String, EvalValue>,
+    cache: BTreeMap<*const Expr, EvalValue>,
+}
+
+impl Evaluator {
+    fn new(args: BTreeMap<String, EvalValue>) -> Self {
+        Self {
+            args,
+            cache: BTreeMap::new(),
+        }
+    }
+
+    fn eval(&mut self, expr: &Rc<Expr>) -> Result<EvalValue, EvalError> {
+        let key = Rc::as_ptr(expr);
+
+        if let Some(value) = self.cache.get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = self.eval_uncached(expr)?;
+
+        self.cache.insert(key, value.clone());
+
+        Ok(value)
+    }
+
+    fn eval_all(&mut self, exprs: &[Rc<Expr>]) -> Result<Vec<EvalValue>, EvalError> {
+        exprs.iter().map(|expr| self.eval(expr)).collect()
+    }
+
+    fn eval_uncached(&mut self, expr: &Expr) -> Result<EvalValue, EvalError> {
+        use Expr::*;
+
+        match expr {
+            Arg { name, ty } => {
+                let value = self
+                    .args
+                    .get(name)
+                    .ok_or_else(|| EvalError::UnboundArg(name.clone()))?;
+
+                if !has_type(value, ty) {
+                    return Err(EvalError::TypeMismatch {
+                        name: name.clone(),
+                        expected: ty.clone(),
+                    });
+                }
+
+                Ok(value.clone())
+            }
+            ScalarLiteral { value, ty } => Ok(literal(value, *ty)),
+            StructLiteral { args, .. } => Ok(EvalValue::Struct(self.eval_all(args)?)),
+            ArrayLiteral { args, .. } => Ok(EvalValue::Array(self.eval_all(args)?)),
+            Unary { op, arg, .. } => Ok(unary(*op, &self.eval(arg)?)),
+            Binary {
+                left,
+                op: op @ (BinaryOp::And | BinaryOp::Or),
+                right,
+                ..
+            } => {
+                // Logical operators short-circuit, so that the right operand
+                // can guard against a discard, for example.
+                let left = self.eval(left)?.as_bool();
+
+                if left == (*op == BinaryOp::Or) {
+                    Ok(EvalValue::Bool(left))
+                } else {
+                    self.eval(right)
+                }
+            }
+            Binary {
+                left, op, right, ..
+            } => binary(&self.eval(left)?, *op, &self.eval(right)?),
+            CallFuncDef { def, args } => {
+                let args = def
+                    .params
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .zip(self.eval_all(args)?)
+                    .collect();
+
+                Evaluator::new(args).eval(&def.result)
+            }
+            CallBuiltIn { name, args, ty } => call_built_in(name, &self.eval_all(args)?, ty),
+            Field { base, name, .. } => Ok(field(&self.eval(base)?, &base.ty(), name)),
+            Subscript { base, index, .. } => {
+                let base = self.eval(base)?;
+                let index = self.eval(index)?.as_integer();
+                let elements = base.components();
+
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| elements.get(i))
+                    .cloned()
+                    .ok_or(EvalError::IndexOutOfBounds {
+                        index,
+                        len: elements.len(),
+                    })
+            }
+            Branch { cond, yes, no, .. } => {
+                if self.eval(cond)?.as_bool() {
+                    self.eval(yes)
+                } else {
+                    self.eval(no)
+                }
+            }
+            Switch {
+                value,
+                cases,
+                default,
+                ..
+            } => {
+                let value = self.eval(value)?.as_integer();
+                let case = cases
+                    .iter()
+                    .find(|(label, _)| *label == value)
+                    .map_or(default, |(_, case)| case);
+
+                self.eval(case)
+            }
+            Loop {
+                start,
+                end,
+                init,
+                acc_name,
+                index_name,
+                body,
+                stop,
+                ..
+            } => {
+                let start = self.eval(start)?;
+                let end = self.eval(end)?.as_integer();
+                let mut acc = self.eval(init)?;
+
+                for i in start.as_integer()..end {
+                    let index = match start {
+                        EvalValue::I32(_) => EvalValue::I32(i as i32),
+                        _ => EvalValue::U32(i as u32),
+                    };
+
+                    // The body depends on the loop variables, so it needs to
+                    // be evaluated without the cached values of the previous
+                    // iteration.
+                    let mut args = self.args.clone();
+                    args.insert(acc_name.clone(), acc);
+                    args.insert(index_name.clone(), index);
+
+                    let mut body_evaluator = Evaluator::new(args);
+
+                    acc = body_evaluator.eval(body)?;
+
+                    if let Some(stop) = stop {
+                        if body_evaluator.eval(stop)?.as_bool() {
+                            break;
+                        }
+                    }
+                }
+
+                Ok(acc)
+            }
+            Discard { .. } => Err(EvalError::Discarded),
+        }
+    }
+}
+
+impl EvalValue {
+    fn as_bool(&self) -> bool {
+        match self {
+            Self::Bool(x) => *x,
+            _ => unexpected(self),
+        }
+    }
+
+    fn as_f32(&self) -> f32 {
+        match self {
+            Self::F32(x) => *x,
+            _ => unexpected(self),
+        }
+    }
+
+    fn as_integer(&self) -> i64 {
+        match self {
+            Self::I32(x) => i64::from(*x),
+            Self::U32(x) => i64::from(*x),
+            _ => unexpected(self),
+        }
+    }
+
+    fn components(&self) -> &[EvalValue] {
+        match self {
+            Self::Vec(xs) | Self::Mat(xs) | Self::Struct(xs) | Self::Array(xs) => xs,
+            _ => std::slice::from_ref(self),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            Self::F32(_) | Self::I32(_) | Self::U32(_) | Self::Bool(_)
+        )
+    }
+}
+
+fn unexpected(value: &EvalValue) -> ! {
+    panic!("unexpected value during evaluation: {value:?}")
+}
+
+fn literal(value: &str, ty: BuiltInType) -> EvalValue {
+    use BuiltInType::*;
+
+    let parsed = match ty {
+        F32 => value.parse().ok().map(EvalValue::F32),
+        I32 => value.parse().ok().map(EvalValue::I32),
+        U32 => value.trim_end_matches('u').parse().ok().map(EvalValue::U32),
+        Bool => value.parse().ok().map(EvalValue::Bool),
+        _ => None,
+    };
+
+    parsed.unwrap_or_else(|| panic!("invalid literal `{value}` of type `{ty}`"))
+}
+
+fn has_type(value: &EvalValue, ty: &Type) -> bool {
+    use EvalValue::*;
+
+    match (value, ty) {
+        (_, Type::BuiltIn(BuiltInType::Sampler(_))) => true,
+        (value, Type::BuiltIn(ty)) => has_built_in_type(value, *ty),
+        (Struct(fields), Type::Struct(ty)) => {
+            fields.len() == ty.fields.len()
+                && fields
+                    .iter()
+                    .zip(&ty.fields)
+                    .all(|(field, (_, ty))| has_type(field, ty))
+        }
+        (Array(elements), Type::Array(ty)) => {
+            elements.len() == ty.len && elements.iter().all(|element| has_type(element, &ty.ty))
+        }
+        _ => false,
+    }
+}
+
+fn has_built_in_type(value: &EvalValue, ty: BuiltInType) -> bool {
+    use EvalValue::*;
+
+    match value {
+        F32(_) => ty == BuiltInType::F32,
+        I32(_) => ty == BuiltInType::I32,
+        U32(_) => ty == BuiltInType::U32,
+        Bool(_) => ty == BuiltInType::Bool,
+        Vec(xs) => vector_shape(ty).is_some_and(|(scalar, len)| {
+            xs.len() == len && xs.iter().all(|x| has_built_in_type(x, scalar))
+        }),
+        Mat(columns) => matrix_shape(ty).is_some_and(|(num_columns, num_rows)| {
+            columns.len() == num_columns
+                && columns.iter().all(|column| {
+                    matches!(column, Vec(xs) if xs.len() == num_rows && xs.iter().all(|x| matches!(x, F32(_))))
+                })
+        }),
+        Struct(_) | Array(_) => false,
+    }
+}
+
+// Returns the scalar type and the number of components of a vector type.
+pub(crate) fn vector_shape(ty: BuiltInType) -> Option<(BuiltInType, usize)> {
+    use BuiltInType::*;
+
+    let shape = match ty {
+        Vec2 => (F32, 2),
+        IVec2 => (I32, 2),
+        UVec2 => (U32, 2),
+        BVec2 => (Bool, 2),
+        Vec3 => (F32, 3),
+        IVec3 => (I32, 3),
+        UVec3 => (U32, 3),
+        BVec3 => (Bool, 3),
+        Vec4 => (F32, 4),
+        IVec4 => (I32, 4),
+        UVec4 => (U32, 4),
+        BVec4 => (Bool, 4),
+        _ => return None,
+    };
+
+    Some(shape)
+}
+
+// Returns the number of columns and rows of a matrix type.
+pub(crate) fn matrix_shape(ty: BuiltInType) -> Option<(usize, usize)> {
+    use BuiltInType::*;
+
+    let shape = match ty {
+        Mat2 => (2, 2),
+        Mat3 => (3, 3),
+        Mat4 => (4, 4),
+        Mat2x3 => (2, 3),
+        Mat2x4 => (2, 4),
+        Mat3x2 => (3, 2),
+        Mat3x4 => (3, 4),
+        Mat4x2 => (4, 2),
+        Mat4x3 => (4, 3),
+        _ => return None,
+    };
+
+    Some(shape)
+}
+
+// Applies `f` to the corresponding scalar components of `args`. Scalar
+// arguments are broadcast to the shape of vector and matrix arguments.
+fn componentwise(
+    args: &[&EvalValue],
+    f: &mut impl FnMut(&[&EvalValue]) -> Result<EvalValue, EvalError>,
+) -> Result<EvalValue, EvalError> {
+    use EvalValue::*;
+
+    let Some(shape) = args.iter().find(|arg| !arg.is_scalar()) else {
+        return f(args);
+    };
+
+    let components = (0..shape.components().len())
+        .map(|i| {
+            let args: std::vec::Vec<_> = args
+                .iter()
+                .map(|arg| {
+                    if arg.is_scalar() {
+                        *arg
+                    } else {
+                        &arg.components()[i]
+                    }
+                })
+                .collect();
+
+            componentwise(&args, f)
+        })
+        .collect::<Result<_, _>>()?;
+
+    match shape {
+        Mat(_) => Ok(Mat(components)),
+        _ => Ok(Vec(components)),
+    }
+}
+
+fn map(args: &[&EvalValue], mut f: impl FnMut(&[&EvalValue]) -> EvalValue) -> EvalValue {
+    componentwise(args, &mut |args| Ok(f(args))).unwrap()
+}
+
+fn map_f32(args: &[&EvalValue], f: impl Fn(&[f32]) -> f32) -> EvalValue {
+    map(args, |args| {
+        let args: Vec<_> = args.iter().map(|arg| arg.as_f32()).collect();
+
+        EvalValue::F32(f(&args))
+    })
+}
+
+fn floats(value: &EvalValue) -> Vec<f32> {
+    flatten(value).iter().map(EvalValue::as_f32).collect()
+}
+
+fn float_vec(xs: impl IntoIterator<Item = f32>) -> EvalValue {
+    EvalValue::Vec(xs.into_iter().map(EvalValue::F32).collect())
+}
+
+fn flatten(value: &EvalValue) -> Vec<EvalValue> {
+    if value.is_scalar() {
+        vec![value.clone()]
+    } else {
+        value.components().iter().flat_map(flatten).collect()
+    }
+}
+
+fn compare(left: &EvalValue, right: &EvalValue) -> Option<Ordering> {
+    use EvalValue::*;
+
+    match (left, right) {
+        (F32(a), F32(b)) => a.partial_cmp(b),
+        (I32(a), I32(b)) => Some(a.cmp(b)),
+        (U32(a), U32(b)) => Some(a.cmp(b)),
+        _ => unexpected(left),
+    }
+}
+
+fn min(x: &EvalValue, y: &EvalValue) -> EvalValue {
+    if compare(y, x) == Some(Ordering::Less) {
+        y.clone()
+    } else {
+        x.clone()
+    }
+}
+
+fn max(x: &EvalValue, y: &EvalValue) -> EvalValue {
+    if compare(x, y) == Some(Ordering::Less) {
+        y.clone()
+    } else {
+        x.clone()
+    }
+}
+
+fn convert(value: &EvalValue, ty: BuiltInType) -> EvalValue {
+    use EvalValue::*;
+
+    match (ty, value) {
+        (BuiltInType::F32, F32(x)) => F32(*x),
+        (BuiltInType::F32, I32(x)) => F32(*x as f32),
+        (BuiltInType::F32, U32(x)) => F32(*x as f32),
+        (BuiltInType::F32, Bool(x)) => F32(f32::from(u8::from(*x))),
+        (BuiltInType::I32, F32(x)) => I32(*x as i32),
+        (BuiltInType::I32, I32(x)) => I32(*x),
+        (BuiltInType::I32, U32(x)) => I32(*x as i32),
+        (BuiltInType::I32, Bool(x)) => I32(i32::from(*x)),
+        (BuiltInType::U32, F32(x)) => U32(*x as u32),
+        (BuiltInType::U32, I32(x)) => U32(*x as u32),
+        (BuiltInType::U32, U32(x)) => U32(*x),
+        (BuiltInType::U32, Bool(x)) => U32(u32::from(*x)),
+        (BuiltInType::Bool, F32(x)) => Bool(*x != 0.0),
+        (BuiltInType::Bool, I32(x)) => Bool(*x != 0),
+        (BuiltInType::Bool, U32(x)) => Bool(*x != 0),
+        (BuiltInType::Bool, Bool(x)) => Bool(*x),
+        _ => unexpected(value),
+    }
+}
+
+fn scalar_type(value: &EvalValue) -> BuiltInType {
+    match value {
+        EvalValue::F32(_) => BuiltInType::F32,
+        EvalValue::I32(_) => BuiltInType::I32,
+        EvalValue::U32(_) => BuiltInType::U32,
+        EvalValue::Bool(_) => BuiltInType::Bool,
+        _ => unexpected(value),
+    }
+}
+
+// Evaluates a constructor, which is named after the type that it constructs.
+pub(crate) fn construct(args: &[EvalValue], ty: BuiltInType) -> EvalValue {
+    use EvalValue::*;
+
+    if let Some((num_columns, num_rows)) = matrix_shape(ty) {
+        let columns = match args {
+            [Mat(m)] => {
+                // Resizing keeps the overlapping part of the matrix and fills
+                // in the rest with the identity matrix.
+                let m: std::vec::Vec<_> = m.iter().map(floats).collect();
+
+                (0..num_columns)
+                    .map(|j| {
+                        float_vec((0..num_rows).map(|i| {
+                            let identity = if i == j { 1.0 } else { 0.0 };
+
+                            m.get(j).and_then(|c| c.get(i)).copied().unwrap_or(identity)
+                        }))
+                    })
+                    .collect()
+            }
+            [diagonal] if diagonal.is_scalar() => {
+                let diagonal = convert(diagonal, BuiltInType::F32).as_f32();
+
+                (0..num_columns)
+                    .map(|j| float_vec((0..num_rows).map(|i| if i == j { diagonal } else { 0.0 })))
+                    .collect()
+            }
+            _ => {
+                let xs: std::vec::Vec<_> = args.iter().flat_map(floats).collect();
+
+                xs.chunks(num_rows)
+                    .take(num_columns)
+                    .map(|column| float_vec(column.iter().copied()))
+                    .collect()
+            }
+        };
+
+        Mat(columns)
+    } else if let Some((scalar, len)) = vector_shape(ty) {
+        match args {
+            [x] if x.is_scalar() => Vec(vec![convert(x, scalar); len]),
+            _ => Vec(args
+                .iter()
+                .flat_map(flatten)
+                .take(len)
+                .map(|x| convert(&x, scalar))
+                .collect()),
+        }
+    } else {
+        convert(&flatten(&args[0])[0], ty)
+    }
+}
+
+// Sampling a constant sampler results in its constant value, padded to the
+// result type of the lookup.
+fn constant_sample(value: &EvalValue, ty: &Type) -> EvalValue {
+    let mut xs = flatten(value);
+
+    match ty.built_in_type().and_then(vector_shape) {
+        Some((_, len)) => {
+            let scalar = scalar_type(&xs[0]);
+
+            while xs.len() < len {
+                let padding = if xs.len() == 3 { 1.0 } else { 0.0 };
+
+                xs.push(convert(&EvalValue::F32(padding), scalar));
+            }
+
+            EvalValue::Vec(xs)
+        }
+        None => xs.swap_remove(0),
+    }
+}
+
+fn field(base: &EvalValue, base_ty: &Type, name: &str) -> EvalValue {
+    match (base, base_ty) {
+        (EvalValue::Struct(fields), Type::Struct(ty)) => {
+            let index = ty
+                .fields
+                .iter()
+                .position(|(field_name, _)| field_name == name)
+                .unwrap_or_else(|| panic!("struct `{}` has no field `{name}`", ty.name));
+
+            fields[index].clone()
+        }
+        // Matrix columns are named `x_axis`, `y_axis`, and so on.
+        (EvalValue::Mat(columns), _) => {
+            columns[swizzle_index(name.chars().next().unwrap())].clone()
+        }
+        (EvalValue::Vec(xs), _) if name.len() == 1 => {
+            xs[swizzle_index(name.chars().next().unwrap())].clone()
+        }
+        (EvalValue::Vec(xs), _) => {
+            EvalValue::Vec(name.chars().map(|c| xs[swizzle_index(c)].clone()).collect())
+        }
+        _ => unexpected(base),
+    }
+}
+
+fn swizzle_index(c: char) -> usize {
+    match c {
+        'x' | 'r' | 's' => 0,
+        'y' | 'g' | 't' => 1,
+        'z' | 'b' | 'p' => 2,
+        'w' | 'a' | 'q' => 3,
+        _ => panic!("invalid swizzle component `{c}`"),
+    }
+}
+
+fn unary(op: UnaryOp, arg: &EvalValue) -> EvalValue {
+    use EvalValue::*;
+
+    map(&[arg], |args| match (op, args[0]) {
+        (UnaryOp::Neg, F32(x)) => F32(-x),
+        (UnaryOp::Neg, I32(x)) => I32(x.wrapping_neg()),
+        (UnaryOp::Neg, U32(x)) => U32(x.wrapping_neg()),
+        (UnaryOp::Not, Bool(x)) => Bool(!x),
+        (UnaryOp::Not | UnaryOp::BitNot, I32(x)) => I32(!x),
+        (UnaryOp::Not | UnaryOp::BitNot, U32(x)) => U32(!x),
+        (_, x) => unexpected(x),
+    })
+}
+
+fn binary(left: &EvalValue, op: BinaryOp, right: &EvalValue) -> Result<EvalValue, EvalError> {
+    use EvalValue::*;
+
+    match (left, op, right) {
+        (_, BinaryOp::Eq, _) => Ok(Bool(left == right)),
+        (_, BinaryOp::Ne, _) => Ok(Bool(left != right)),
+        (Mat(_), BinaryOp::Mul, Mat(_) | Vec(_)) | (Vec(_), BinaryOp::Mul, Mat(_)) => {
+            Ok(mul_linear(left, right))
+        }
+        _ => componentwise(&[left, right], &mut |args| {
+            binary_scalar(args[0], op, args[1])
+        }),
+    }
+}
+
+fn binary_scalar(
+    left: &EvalValue,
+    op: BinaryOp,
+    right: &EvalValue,
+) -> Result<EvalValue, EvalError> {
+    use BinaryOp::*;
+    use EvalValue::*;
+
+    let value = match (left, op, right) {
+        (_, Lt, _) => Bool(compare(left, right) == Some(Ordering::Less)),
+        (_, Le, _) => Bool(matches!(
+            compare(left, right),
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        (_, Ge, _) => Bool(matches!(
+            compare(left, right),
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        (_, Gt, _) => Bool(compare(left, right) == Some(Ordering::Greater)),
+        (F32(a), Add, F32(b)) => F32(a + b),
+        (F32(a), Sub, F32(b)) => F32(a - b),
+        (F32(a), Mul, F32(b)) => F32(a * b),
+        (F32(a), Div, F32(b)) => F32(a / b),
+        (F32(a), Rem, F32(b)) => F32(a % b),
+        (I32(_), Div | Rem, I32(0)) | (U32(_), Div | Rem, U32(0)) => {
+            return Err(EvalError::DivisionByZero);
+        }
+        (I32(a), Add, I32(b)) => I32(a.wrapping_add(*b)),
+        (I32(a), Sub, I32(b)) => I32(a.wrapping_sub(*b)),
+        (I32(a), Mul, I32(b)) => I32(a.wrapping_mul(*b)),
+        (I32(a), Div, I32(b)) => I32(a.wrapping_div(*b)),
+        (I32(a), Rem, I32(b)) => I32(a.wrapping_rem(*b)),
+        (I32(a), BitAnd, I32(b)) => I32(a & b),
+        (I32(a), BitOr, I32(b)) => I32(a | b),
+        (I32(a), BitXor, I32(b)) => I32(a ^ b),
+        (U32(a), Add, U32(b)) => U32(a.wrapping_add(*b)),
+        (U32(a), Sub, U32(b)) => U32(a.wrapping_sub(*b)),
+        (U32(a), Mul, U32(b)) => U32(a.wrapping_mul(*b)),
+        (U32(a), Div, U32(b)) => U32(a / b),
+        (U32(a), Rem, U32(b)) => U32(a % b),
+        (U32(a), BitAnd, U32(b)) => U32(a & b),
+        (U32(a), BitOr, U32(b)) => U32(a | b),
+        (U32(a), BitXor, U32(b)) => U32(a ^ b),
+        (Bool(a), And | BitAnd, Bool(b)) => Bool(*a && *b),
+        (Bool(a), Or | BitOr, Bool(b)) => Bool(*a || *b),
+        (Bool(a), BitXor, Bool(b)) => Bool(a ^ b),
+        // The shift amount may have a different signedness than the shifted
+        // value.
+        (I32(a), Shl, b) => I32(a.wrapping_shl(b.as_integer() as u32)),
+        (I32(a), Shr, b) => I32(a.wrapping_shr(b.as_integer() as u32)),
+        (U32(a), Shl, b) => U32(a.wrapping_shl(b.as_integer() as u32)),
+        (U32(a), Shr, b) => U32(a.wrapping_shr(b.as_integer() as u32)),
+        _ => unexpected(left),
+    };
+
+    Ok(value)
+}
+
+// Computes the linear algebraic product of a matrix with a matrix or vector.
+fn mul_linear(left: &EvalValue, right: &EvalValue) -> EvalValue {
+    use EvalValue::*;
+
+    match (left, right) {
+        (Mat(columns), Vec(_)) => {
+            let v = floats(right);
+            let columns: std::vec::Vec<_> = columns.iter().map(floats).collect();
+            let num_rows = columns[0].len();
+
+            float_vec((0..num_rows).map(|i| columns.iter().zip(&v).map(|(c, x)| c[i] * x).sum()))
+        }
+        (Vec(_), Mat(columns)) => {
+            let v = floats(left);
+
+            float_vec(columns.iter().map(|c| dot(&v, &floats(c))))
+        }
+        (Mat(_), Mat(columns)) => Mat(columns.iter().map(|c| mul_linear(left, c)).collect()),
+        _ => unexpected(left),
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// Returns the columns of `m` without column `j` and row `i`.
+fn minor(m: &[Vec<f32>], j: usize, i: usize) -> Vec<Vec<f32>> {
+    m.iter()
+        .enumerate()
+        .filter(|(k, _)| *k != j)
+        .map(|(_, column)| {
+            column
+                .iter()
+                .enumerate()
+                .filter(|(k, _)| *k != i)
+                .map(|(_, x)| *x)
+                .collect()
+        })
+        .collect()
+}
+
+fn cofactor(m: &[Vec<f32>], j: usize, i: usize) -> f32 {
+    let sign = if (i + j).is_multiple_of(2) { 1.0 } else { -1.0 };
+
+    sign * determinant(&minor(m, j, i))
+}
+
+fn determinant(m: &[Vec<f32>]) -> f32 {
+    if m.len() == 1 {
+        return m[0][0];
+    }
+
+    (0..m.len()).map(|j| m[j][0] * cofactor(m, j, 0)).sum()
+}
+
+fn call_built_in(name: &str, args: &[EvalValue], ty: &Type) -> Result<EvalValue, EvalError> {
+    use EvalValue::*;
+
+    if let Some(ty) = ty.built_in_type().filter(|ty| ty.to_string() == name) {
+        return Ok(construct(args, ty));
+    }
+
+    let refs: std::vec::Vec<_> = args.iter().collect();
+
+    let value = match (name, &refs[..]) {
+        ("sin", _) => map_f32(&refs, |x| x[0].sin()),
+        ("cos", _) => map_f32(&refs, |x| x[0].cos()),
+        ("tan", _) => map_f32(&refs, |x| x[0].tan()),
+        ("asin", _) => map_f32(&refs, |x| x[0].asin()),
+        ("acos", _) => map_f32(&refs, |x| x[0].acos()),
+        ("atan", [_]) => map_f32(&refs, |x| x[0].atan()),
+        ("atan", [_, _]) => map_f32(&refs, |x| x[0].atan2(x[1])),
+        ("sinh", _) => map_f32(&refs, |x| x[0].sinh()),
+        ("cosh", _) => map_f32(&refs, |x| x[0].cosh()),
+        ("tanh", _) => map_f32(&refs, |x| x[0].tanh()),
+        ("asinh", _) => map_f32(&refs, |x| x[0].asinh()),
+        ("acosh", _) => map_f32(&refs, |x| x[0].acosh()),
+        ("atanh", _) => map_f32(&refs, |x| x[0].atanh()),
+        ("pow", _) => map_f32(&refs, |x| x[0].powf(x[1])),
+        ("exp", _) => map_f32(&refs, |x| x[0].exp()),
+        ("log", _) => map_f32(&refs, |x| x[0].ln()),
+        ("exp2", _) => map_f32(&refs, |x| x[0].exp2()),
+        ("log2", _) => map_f32(&refs, |x| x[0].log2()),
+        ("sqrt", _) => map_f32(&refs, |x| x[0].sqrt()),
+        ("floor", _) => map_f32(&refs, |x| x[0].floor()),
+        ("ceil", _) => map_f32(&refs, |x| x[0].ceil()),
+        ("fract", _) => map_f32(&refs, |x| x[0] - x[0].floor()),
+        ("round", _) => map_f32(&refs, |x| x[0].round()),
+        ("mod", _) => map_f32(&refs, |x| x[0] - x[1] * (x[0] / x[1]).floor()),
+        ("step", _) => map_f32(&refs, |x| if x[1] < x[0] { 0.0 } else { 1.0 }),
+        ("smoothstep", _) => map_f32(&refs, |x| {
+            let t = ((x[2] - x[0]) / (x[1] - x[0])).clamp(0.0, 1.0);
+
+            t * t * (3.0 - 2.0 * t)
+        }),
+        ("abs", _) => map(&refs, |x| match x[0] {
+            F32(x) => F32(x.abs()),
+            I32(x) => I32(x.wrapping_abs()),
+            x => unexpected(x),
+        }),
+        ("sign", _) => map(&refs, |x| match x[0] {
+            F32(x) if *x > 0.0 => F32(1.0),
+            F32(x) if *x < 0.0 => F32(-1.0),
+            F32(x) => F32(*x),
+            I32(x) => I32(x.signum()),
+            x => unexpected(x),
+        }),
+        ("min", _) => map(&refs, |x| min(x[0], x[1])),
+        ("max", _) => map(&refs, |x| max(x[0], x[1])),
+        ("clamp", _) => map(&refs, |x| min(&max(x[0], x[1]), x[2])),
+        ("mix", [_, _, Bool(_)]) => map(&refs, |x| x[usize::from(x[2].as_bool())].clone()),
+        ("mix", [_, _, Vec(a)]) if matches!(a[0], Bool(_)) => {
+            map(&refs, |x| x[usize::from(x[2].as_bool())].clone())
+        }
+        ("mix", _) => map_f32(&refs, |x| x[0] * (1.0 - x[2]) + x[1] * x[2]),
+        ("length", [x]) => F32(dot(&floats(x), &floats(x)).sqrt()),
+        ("distance", [x, y]) => {
+            let d: std::vec::Vec<_> = floats(x)
+                .iter()
+                .zip(floats(y))
+                .map(|(x, y)| x - y)
+                .collect();
+
+            F32(dot(&d, &d).sqrt())
+        }
+        ("dot", [x, y]) => F32(dot(&floats(x), &floats(y))),
+        ("cross", [x, y]) => {
+            let (a, b) = (floats(x), floats(y));
+
+            float_vec([
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ])
+        }
+        ("normalize", [x]) => {
+            let length = dot(&floats(x), &floats(x)).sqrt();
+
+            map_f32(&refs, |x| x[0] / length)
+        }
+        ("equal", [x, y]) => map(&[x, y], |x| Bool(x[0] == x[1])),
+        ("notEqual", [x, y]) => map(&[x, y], |x| Bool(x[0] != x[1])),
+        ("lessThan", [x, y]) => {
+            componentwise(&[x, y], &mut |x| binary_scalar(x[0], BinaryOp::Lt, x[1]))?
+        }
+        ("lessThanEqual", [x, y]) => {
+            componentwise(&[x, y], &mut |x| binary_scalar(x[0], BinaryOp::Le, x[1]))?
+        }
+        ("greaterThan", [x, y]) => {
+            componentwise(&[x, y], &mut |x| binary_scalar(x[0], BinaryOp::Gt, x[1]))?
+        }
+        ("greaterThanEqual", [x, y]) => {
+            componentwise(&[x, y], &mut |x| binary_scalar(x[0], BinaryOp::Ge, x[1]))?
+        }
+        ("any", [x]) => Bool(x.components().iter().any(EvalValue::as_bool)),
+        ("all", [x]) => Bool(x.components().iter().all(EvalValue::as_bool)),
+        ("not", _) => map(&refs, |x| Bool(!x[0].as_bool())),
+        ("matrixCompMult", _) => map_f32(&refs, |x| x[0] * x[1]),
+        ("outerProduct", [c, r]) => {
+            let c = floats(c);
+
+            Mat(floats(r)
+                .into_iter()
+                .map(|r| float_vec(c.iter().map(|c| c * r)))
+                .collect())
+        }
+        ("transpose", [Mat(m)]) => {
+            let m: std::vec::Vec<_> = m.iter().map(floats).collect();
+
+            Mat((0..m[0].len())
+                .map(|i| float_vec(m.iter().map(|column| column[i])))
+                .collect())
+        }
+        ("determinant", [Mat(m)]) => {
+            let m: std::vec::Vec<_> = m.iter().map(floats).collect();
+
+            F32(determinant(&m))
+        }
+        ("inverse", [Mat(m)]) => {
+            let m: std::vec::Vec<_> = m.iter().map(floats).collect();
+            let det = determinant(&m);
+
+            Mat((0..m.len())
+                .map(|j| float_vec((0..m.len()).map(|i| cofactor(&m, i, j) / det)))
+                .collect())
+        }
+        ("floatBitsToUint", _) => map(&refs, |x| U32(x[0].as_f32().to_bits())),
+        ("floatBitsToInt", _) => map(&refs, |x| I32(x[0].as_f32().to_bits() as i32)),
+        ("uintBitsToFloat", _) => map(&refs, |x| F32(f32::from_bits(x[0].as_integer() as u32))),
+        ("intBitsToFloat", _) => map(&refs, |x| F32(f32::from_bits(x[0].as_integer() as u32))),
+        ("packHalf2x16", [x]) => pack_2x16(x, gl::encode_f16),
+        ("packUnorm2x16", [x]) => pack_2x16(x, gl::encode_unorm16),
+        ("packSnorm2x16", [x]) => pack_2x16(x, |x| gl::encode_snorm16(x) as u16),
+        ("unpackHalf2x16", [x]) => unpack_2x16(x, gl::decode_f16),
+        ("unpackUnorm2x16", [x]) => unpack_2x16(x, gl::decode_unorm16),
+        ("unpackSnorm2x16", [x]) => unpack_2x16(x, |x| gl::decode_snorm16(x as i16)),
+        (
+            "texture" | "textureLod" | "textureGrad" | "textureOffset" | "textureLodOffset"
+            | "textureGradOffset" | "textureProj" | "textureProjLod" | "texelFetch"
+            | "texelFetchOffset",
+            [sample, ..],
+        ) => constant_sample(sample, ty),
+        _ => return Err(EvalError::UnsupportedFunction(name.to_string())),
+    };
+
+    Ok(value)
+}
+
+fn pack_2x16(value: &EvalValue, encode: impl Fn(f32) -> u16) -> EvalValue {
+    let xs = floats(value);
+
+    EvalValue::U32(u32::from(encode(xs[0])) | (u32::from(encode(xs[1])) << 16))
+}
+
+fn unpack_2x16(value: &EvalValue, decode: impl Fn(u16) -> f32) -> EvalValue {
+    let bits = value.as_integer() as u32;
+
+    float_vec([decode(bits as u16), decode((bits >> 16) as u16)])
+}
+
+macro_rules! impl_try_from_scalar {
+    ($ty:ty, $variant:ident) => {
+        impl TryFrom<EvalValue> for $ty {
+            type Error = EvalValue;
+
+            fn try_from(value: EvalValue) -> Result<Self, EvalValue> {
+                match value {
+                    EvalValue::$variant(x) => Ok(x.into()),
+                    value => Err(value),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_scalar!(f32, F32);
+impl_try_from_scalar!(i32, I32);
+impl_try_from_scalar!(u32, U32);
+impl_try_from_scalar!(bool, Bool);
+impl_try_from_scalar!(gl::Bool, Bool);
+
+// Implements conversion of vector or matrix values to the `gl` type `$ty`,
+// which has fields of type `$component`.
+macro_rules! impl_try_from_composite {
+    ($ty:ident, $variant:ident, $component:ty, $($field:ident),+) => {
+        impl TryFrom<EvalValue> for gl::$ty {
+            type Error = EvalValue;
+
+            fn try_from(value: EvalValue) -> Result<Self, EvalValue> {
+                if let EvalValue::$variant(components) = &value {
+                    if let [$($field),+] = components.as_slice() {
+                        if let ($(Ok($field),)+) = ($(<$component>::try_from($field.clone()),)+) {
+                            return Ok(Self { $($field),+ });
+                        }
+                    }
+                }
+
+                Err(value)
+            }
+        }
+    };
+}
+
+impl_try_from_composite!(Vec2, Vec, f32, x, y);
+impl_try_from_composite!(Vec3, Vec, f32, x, y, z);
+impl_try_from_composite!(Vec4, Vec, f32, x, y, z, w);
+impl_try_from_composite!(IVec2, Vec, i32, x, y);
+impl_try_from_composite!(IVec3, Vec, i32, x, y, z);
+impl_try_from_composite!(IVec4, Vec, i32, x, y, z, w);
+impl_try_from_composite!(UVec2, Vec, u32, x, y);
+impl_try_from_composite!(UVec3, Vec, u32, x, y, z);
+impl_try_from_composite!(UVec4, Vec, u32, x, y, z, w);
+impl_try_from_composite!(BVec2, Vec, gl::Bool, x, y);
+impl_try_from_composite!(BVec3, Vec, gl::Bool, x, y, z);
+impl_try_from_composite!(BVec4, Vec, gl::Bool, x, y, z, w);
+impl_try_from_composite!(Mat2, Mat, gl::Vec2, x_axis, y_axis);
+impl_try_from_composite!(Mat3, Mat, gl::Vec3, x_axis, y_axis, z_axis);
+impl_try_from_composite!(Mat4, Mat, gl::Vec4, x_axis, y_axis, z_axis, w_axis);
+impl_try_from_composite!(Mat2x3, Mat, gl::Vec3, x_axis, y_axis);
+impl_try_from_composite!(Mat2x4, Mat, gl::Vec4, x_axis, y_axis);
+impl_try_from_composite!(Mat3x2, Mat, gl::Vec2, x_axis, y_axis, z_axis);
+impl_try_from_composite!(Mat3x4, Mat, gl::Vec4, x_axis, y_axis, z_axis);
+impl_try_from_composite!(Mat4x2, Mat, gl::Vec2, x_axis, y_axis, z_axis, w_axis);
+impl_try_from_composite!(Mat4x3, Mat, gl::Vec3, x_axis, y_axis, z_axis, w_axis);
+
+#[cfg(test)]
+mod tests;
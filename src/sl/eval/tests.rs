@@ -0,0 +1,393 @@
+use crate::{
+    gl,
+    sl::{
+        self,
+        dag::{BinaryOp, BuiltInType, Expr, Type},
+        primitives::value_arg,
+        Object, Value,
+    },
+    Block, BlockDom, Gl, Sl, ToSl,
+};
+
+use super::{arg, evaluate, Bindings, EvalError, EvalValue};
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct Scale<D: BlockDom> {
+    scale: D::F32,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct Globals<D: BlockDom> {
+    ambient: D::Vec4,
+    world_to_clip: D::Mat4,
+    block: Scale<D>,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct Skin<D: BlockDom> {
+    bones: D::Array<D::Mat4, 2>,
+    weights: D::Array<D::F32, 2>,
+}
+
+#[derive(Copy, Clone, sl::Value, sl::Interpolant)]
+struct Interpolant {
+    uv: sl::Vec2,
+    scale: sl::F32,
+    instance_id: sl::U32,
+}
+
+#[sl::function]
+fn lambert(normal: sl::Vec3, light_dir: sl::Vec3) -> sl::F32 {
+    normal.dot(light_dir).max(0.0)
+}
+
+#[sl::function]
+fn shade(interp: Interpolant, normal: sl::Vec3) -> sl::Vec4 {
+    let diffuse = lambert(normal, sl::Vec3::Z) + lambert(normal, sl::Vec3::Y);
+
+    (interp.uv * diffuse * interp.scale).extend(0.0).extend(1.0)
+}
+
+fn eval<V: Value>(value: impl ToSl<Output = V>) -> EvalValue {
+    evaluate(value.to_sl(), &Bindings::new()).unwrap()
+}
+
+fn eval_f32(value: impl ToSl<Output = sl::F32>) -> f32 {
+    f32::try_from(eval(value)).unwrap()
+}
+
+fn eval_vec2(value: impl ToSl<Output = sl::Vec2>) -> gl::Vec2 {
+    gl::Vec2::try_from(eval(value)).unwrap()
+}
+
+fn eval_vec3(value: impl ToSl<Output = sl::Vec3>) -> gl::Vec3 {
+    gl::Vec3::try_from(eval(value)).unwrap()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-5,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_scalar_ops() {
+    let x = sl::F32::new(3.0);
+    let i = sl::I32::new(-7);
+    let n = sl::U32::new(7);
+
+    assert_eq!(eval_f32(x * 2.0 - 1.0 / x.powf(0.0)), 5.0);
+    assert_eq!(eval(i / 2), EvalValue::I32(-3));
+    assert_eq!(eval(i % 2), EvalValue::I32(-1));
+    assert_eq!(eval(i >> 1), EvalValue::I32(-4));
+    assert_eq!(eval(n << 2), EvalValue::U32(28));
+    assert_eq!(eval(!n), EvalValue::U32(!7));
+    assert_eq!(eval(sl::U32::new(0) - 1), EvalValue::U32(u32::MAX));
+    assert_eq!(eval(sl::I32::new(i32::MAX) + 1), EvalValue::I32(i32::MIN));
+    assert_eq!(eval(i.as_f32()), EvalValue::F32(-7.0));
+    assert_eq!(eval(x.as_u32()), EvalValue::U32(3));
+    assert_eq!(eval(x.lt(i.as_f32())), EvalValue::Bool(false));
+    assert_eq!(eval(i.abs()), EvalValue::I32(7));
+    assert_eq!(eval_f32(sl::F32::new(-0.5).signum()), -1.0);
+    assert_eq!(eval_f32(sl::F32::new(-1.25).fract()), 0.75);
+
+    assert!(matches!(
+        evaluate(i / (n.as_i32() - 7), &Bindings::new()),
+        Err(EvalError::DivisionByZero)
+    ));
+}
+
+#[test]
+fn test_vector_ops() {
+    let v = sl::vec3(1.0, -2.0, 2.0);
+
+    assert_eq!(eval_f32(v.length()), 3.0);
+    assert_eq!(eval_f32(v.dot(sl::Vec3::X)), 1.0);
+    assert_eq!(eval_vec3(v * 2.0 + 1.0).y, -3.0);
+    assert_eq!(
+        eval_vec3(v.zyx()),
+        gl::Vec3 {
+            x: 2.0,
+            y: -2.0,
+            z: 1.0
+        }
+    );
+    assert_eq!(
+        eval_vec3(sl::Vec3::X.cross(sl::Vec3::Y)),
+        gl::Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0
+        }
+    );
+    assert_eq!(
+        eval_vec2(v.xy().clamp(sl::Vec2::ZERO, sl::vec2(0.5, 0.5))).x,
+        0.5
+    );
+    assert_eq!(eval(v.get(2u32)), EvalValue::F32(2.0));
+    assert_eq!(
+        gl::BVec3::try_from(eval(v.cmplt(sl::Vec3::ZERO))).unwrap(),
+        gl::BVec3 {
+            x: false.into(),
+            y: true.into(),
+            z: false.into()
+        }
+    );
+    assert_eq!(eval(v.eq(sl::vec3(1.0, -2.0, 2.0))), EvalValue::Bool(true));
+    assert_eq!(
+        gl::IVec3::try_from(eval(v.as_ivec3())).unwrap(),
+        gl::IVec3 { x: 1, y: -2, z: 2 }
+    );
+}
+
+#[test]
+fn test_matrix_ops() {
+    let m = sl::mat2(sl::vec2(1.0, 2.0), sl::vec2(3.0, 4.0));
+
+    assert_eq!(
+        eval_vec2(m * sl::vec2(1.0, 1.0)),
+        gl::Vec2 { x: 4.0, y: 6.0 }
+    );
+
+    // There is no operator for multiplying a row vector with a matrix, but it
+    // can appear in expressions that are built directly.
+    let row = sl::Vec2::from_expr(Expr::Binary {
+        left: sl::vec2(1.0, 0.0).expr(),
+        op: BinaryOp::Mul,
+        right: m.expr(),
+        ty: Type::BuiltIn(BuiltInType::Vec2),
+    });
+
+    assert_eq!(eval_vec2(row), gl::Vec2 { x: 1.0, y: 3.0 });
+    assert_eq!(eval_f32(m.determinant()), -2.0);
+    assert_eq!(
+        gl::Mat2::try_from(eval(m * m.inverse())).unwrap(),
+        gl::Mat2 {
+            x_axis: gl::Vec2 { x: 1.0, y: 0.0 },
+            y_axis: gl::Vec2 { x: 0.0, y: 1.0 },
+        }
+    );
+    assert_eq!(
+        gl::Mat2::try_from(eval(m.transpose())).unwrap().x_axis,
+        gl::Vec2 { x: 1.0, y: 3.0 }
+    );
+
+    let rotation = sl::mat3(sl::Vec3::Y, -sl::Vec3::X, sl::Vec3::Z);
+    let scale = sl::Mat3::diagonal(2.0);
+
+    assert_eq!(
+        eval_vec3(scale * rotation * sl::vec3(1.0, 0.0, 3.0)),
+        gl::Vec3 {
+            x: 0.0,
+            y: 2.0,
+            z: 6.0
+        }
+    );
+    assert_eq!(eval_f32((scale * rotation).determinant()), 8.0);
+}
+
+#[test]
+fn test_built_ins() {
+    let x = sl::F32::new(0.25);
+
+    assert_eq!(eval_f32(x.smoothstep(0.0, 0.5)), 0.5);
+    assert_eq!(eval_f32(x.lerp(1.25, 0.5)), 0.75);
+    assert_eq!(eval_f32(x.step(0.5)), 0.0);
+    assert_eq!(eval_f32(sl::F32::new(-1.0).modulus(3.0)), 2.0);
+    assert_close(eval_f32(x.atan2(x)), std::f32::consts::FRAC_PI_4);
+    assert_close(eval_f32(sl::F32::new(8.0).log2()), 3.0);
+
+    let packed = sl::vec2(0.5, -2.0).pack_half_2x16();
+
+    assert_eq!(eval(packed), EvalValue::U32(0xc000_3800));
+    assert_eq!(
+        eval_vec2(packed.unpack_half_2x16()),
+        gl::Vec2 { x: 0.5, y: -2.0 }
+    );
+    assert_eq!(eval(x.to_bits()), EvalValue::U32(0.25f32.to_bits()));
+}
+
+#[test]
+fn test_control_flow() {
+    let i: sl::I32 = arg("i");
+    let mut bindings = Bindings::new();
+    bindings.insert("i", 2);
+
+    let discard = fs_input().discard::<sl::F32>();
+    let value = sl::branch(i.lt(0), discard, i.as_f32() * 10.0);
+
+    assert_eq!(evaluate(value, &bindings).unwrap(), EvalValue::F32(20.0));
+
+    bindings.insert("i", -1);
+
+    assert!(matches!(
+        evaluate(value, &bindings),
+        Err(EvalError::Discarded)
+    ));
+
+    let value = sl::switch(i, [(-1, 1.0), (2, 2.0)], 3.0.to_sl());
+
+    assert_eq!(evaluate(value, &bindings).unwrap(), EvalValue::F32(1.0));
+
+    let sum = sl::fold(sl::I32::ZERO..i.abs() + 3, sl::I32::ZERO, |acc, j| acc + j);
+    let first_above = sl::fold_until(0u32..10, 0u32, |_, j| j * j, |acc| acc.gt(20u32));
+
+    assert_eq!(evaluate(sum, &bindings).unwrap(), EvalValue::I32(6));
+    assert_eq!(eval(first_above), EvalValue::U32(25));
+}
+
+fn fs_input() -> sl::FsInput<()> {
+    sl::FsInput {
+        interp: (),
+        fragment_coord: value_arg("fragment_coord"),
+        front_facing: value_arg("front_facing"),
+        point_coord: value_arg("point_coord"),
+        derivatives: sl::Derivatives(()),
+    }
+}
+
+#[test]
+fn test_arrays_and_samplers() {
+    let array = [sl::vec2(1.0, 2.0), sl::vec2(3.0, 4.0)].to_sl();
+
+    assert_eq!(eval_f32(array.get(1u32).y), 4.0);
+    assert_eq!(eval_vec2(array.get_clamped(5u32)).x, 3.0);
+    assert!(matches!(
+        evaluate(array.get(2u32), &Bindings::new()),
+        Err(EvalError::IndexOutOfBounds { index: 2, len: 2 })
+    ));
+
+    let constant = sl::ColorSampler2d::constant(sl::vec3(0.5, 0.25, 1.0));
+
+    assert_eq!(
+        eval_vec3(constant.sample(sl::Vec2::ZERO)),
+        gl::Vec3 {
+            x: 0.5,
+            y: 0.25,
+            z: 1.0
+        }
+    );
+
+    let sampler: sl::ColorSampler2d<sl::F32> = arg("sampler");
+    let mut bindings = Bindings::new();
+    bindings.insert("sampler", 0.75);
+
+    assert_eq!(
+        evaluate(sampler.sample_lod(sl::Vec2::ZERO, 1.0), &bindings).unwrap(),
+        EvalValue::F32(0.75)
+    );
+}
+
+#[test]
+fn test_blocks_and_functions() {
+    fn scale_mat4(scale: f32) -> gl::Mat4 {
+        glam::Mat4::from_scale(glam::Vec3::splat(scale)).into()
+    }
+
+    fn skin_pos(globals: Globals<Sl>, skin: Skin<Sl>, pos: sl::Vec3) -> sl::Vec4 {
+        let pos = pos.extend(1.0);
+        let skinned = skin
+            .bones
+            .fold(sl::Vec4::ZERO, |acc, bone| acc + bone * pos)
+            * skin.weights.get(1u32);
+
+        globals.world_to_clip * skinned * globals.block.scale + globals.ambient
+    }
+
+    let globals = Globals::<Gl> {
+        ambient: gl::Vec4 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        },
+        world_to_clip: scale_mat4(2.0),
+        block: Scale { scale: 0.5 },
+    };
+    let skin = Skin::<Gl> {
+        bones: [scale_mat4(1.0), scale_mat4(3.0)].into(),
+        weights: [1.0, 0.25].into(),
+    };
+
+    let mut bindings = Bindings::new();
+    bindings.insert("globals", globals).insert("skin", skin);
+
+    let actual = evaluate(
+        skin_pos(arg("globals"), arg("skin"), sl::vec3(1.0, 2.0, 3.0)),
+        &bindings,
+    )
+    .unwrap();
+
+    assert_eq!(
+        gl::Vec4::try_from(actual),
+        Ok(gl::Vec4 {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+            w: 1.25,
+        })
+    );
+
+    // Host values can also be passed to functions directly.
+    let interp = Interpolant {
+        uv: sl::vec2(0.5, 1.0),
+        scale: 2.0.to_sl(),
+        instance_id: 0u32.to_sl(),
+    };
+    let normal = gl::Vec3 {
+        x: 0.0,
+        y: 0.5,
+        z: 0.5,
+    };
+
+    let actual = evaluate(shade(interp, normal.to_sl()), &Bindings::new()).unwrap();
+
+    assert_eq!(
+        gl::Vec4::try_from(actual),
+        Ok(gl::Vec4 {
+            x: 1.0,
+            y: 2.0,
+            z: 0.0,
+            w: 1.0,
+        })
+    );
+
+    fn tint(sampler: sl::ColorSampler2d<sl::Vec3>, uv: sl::Vec2) -> sl::Vec3 {
+        sampler.sample(uv) * uv.x
+    }
+
+    let sampler = sl::ColorSampler2d::constant(sl::vec3(1.0, 0.5, 0.25));
+
+    assert_eq!(
+        eval_vec3(tint(sampler, sl::vec2(0.5, 0.0))),
+        gl::Vec3 {
+            x: 0.5,
+            y: 0.25,
+            z: 0.125
+        }
+    );
+
+    let actual = evaluate(tint(arg("sampler"), sl::vec2(0.5, 0.0)), &Bindings::new());
+
+    assert!(matches!(actual, Err(EvalError::UnboundArg(name)) if name == "sampler"));
+}
+
+#[test]
+fn test_errors() {
+    let x: sl::F32 = arg("x");
+    let mut bindings = Bindings::new();
+
+    assert!(matches!(
+        evaluate(x, &bindings),
+        Err(EvalError::UnboundArg(name)) if name == "x"
+    ));
+
+    bindings.insert("x", 1);
+
+    expect_test::expect!["the value bound to the argument `x` does not have type `float`"]
+        .assert_eq(&evaluate(x, &bindings).unwrap_err().to_string());
+}
@@ -174,10 +174,12 @@ macro_rules! impl_gen_type {
                 if_true: impl crate::ToSl<Output = Self>,
                 if_false: impl crate::ToSl<Output = Self>,
             ) -> Self {
+                // `mix` with a boolean mask takes its second argument where the
+                // mask holds.
                 super::primitives::built_in_3(
                     "mix",
-                    if_true.to_sl(),
                     if_false.to_sl(),
+                    if_true.to_sl(),
                     mask.to_sl(),
                 )
             }
@@ -221,3 +223,100 @@ macro_rules! impl_gen_type {
         }
     };
 }
+
+// Implements reinterpreting the bits of the floating-point type `$ty` as the
+// signed integer type `$tyi` or the unsigned integer type `$tyu`, and back.
+macro_rules! impl_float_bits {
+    ($ty:ident, $tyi:ident, $tyu:ident) => {
+        impl $ty {
+            /// Returns the raw bits of the floating-point encoding.
+            pub fn to_bits(self) -> super::$tyu {
+                super::primitives::built_in_1("floatBitsToUint", self)
+            }
+
+            /// Returns the raw bits of the floating-point encoding as signed
+            /// integers.
+            pub fn to_bits_i32(self) -> super::$tyi {
+                super::primitives::built_in_1("floatBitsToInt", self)
+            }
+
+            /// Reinterprets raw bits as a floating-point value.
+            pub fn from_bits(bits: impl crate::ToSl<Output = super::$tyu>) -> Self {
+                super::primitives::built_in_1("uintBitsToFloat", bits.to_sl())
+            }
+
+            /// Reinterprets raw bits given as signed integers as a
+            /// floating-point value.
+            pub fn from_bits_i32(bits: impl crate::ToSl<Output = super::$tyi>) -> Self {
+                super::primitives::built_in_1("intBitsToFloat", bits.to_sl())
+            }
+        }
+    };
+}
+
+// Implements the built-in functions that GLSL ES 3.00 provides for the
+// integer type `$ty`, which has `$tyb` as its boolean type. For vector types,
+// `$member` lists the components.
+macro_rules! impl_gen_type_integral {
+    ($ty:ident, $tyb:ident $(, $member:ident)*) => {
+        impl $ty {
+            pub fn min(self, y: impl crate::ToSl<Output = Self>) -> Self {
+                super::primitives::built_in_2("min", self, y.to_sl())
+            }
+
+            pub fn max(self, y: impl crate::ToSl<Output = Self>) -> Self {
+                super::primitives::built_in_2("max", self, y.to_sl())
+            }
+
+            pub fn clamp(
+                self,
+                min: impl crate::ToSl<Output = Self>,
+                max: impl crate::ToSl<Output = Self>,
+            ) -> Self {
+                super::primitives::built_in_3("clamp", self, min.to_sl(), max.to_sl())
+            }
+
+            /// Selects `if_true` where `mask` holds and `if_false` elsewhere.
+            ///
+            /// GLSL ES 3.00 lacks an integer overload of `mix`, so this is
+            /// evaluated with a conditional per component.
+            pub fn select(
+                mask: impl crate::ToSl<Output = $tyb>,
+                if_true: impl crate::ToSl<Output = Self>,
+                if_false: impl crate::ToSl<Output = Self>,
+            ) -> Self {
+                impl_gen_type_integral!(@select $ty, mask, if_true, if_false $(, $member)*)
+            }
+        }
+    };
+    (@select $ty:ident, $mask:ident, $if_true:ident, $if_false:ident) => {
+        super::branch($mask, $if_true, $if_false)
+    };
+    (@select $ty:ident, $mask:ident, $if_true:ident, $if_false:ident $(, $member:ident)+) => {{
+        let mask = $mask.to_sl();
+        let if_true = $if_true.to_sl();
+        let if_false = $if_false.to_sl();
+
+        $ty {
+            $(
+                $member: super::branch(mask.$member, if_true.$member, if_false.$member)
+            ),+
+        }
+    }};
+}
+
+// Implements the built-in functions that GLSL ES 3.00 provides only for the
+// signed integer type `$ty`.
+macro_rules! impl_gen_type_signed {
+    ($ty:ident) => {
+        impl $ty {
+            pub fn abs(self) -> Self {
+                super::primitives::built_in_1("abs", self)
+            }
+
+            pub fn signum(self) -> Self {
+                super::primitives::built_in_1("sign", self)
+            }
+        }
+    };
+}
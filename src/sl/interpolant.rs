@@ -1,10 +1,14 @@
 use std::rc::Rc;
 
-use crate::internal::join_ident_path;
+use crate::{internal::join_ident_path, ToSl};
 
 use super::{
-    dag::Expr, primitives::value_arg, program_def::InterpolationQualifier, IVec2, IVec3, IVec4,
-    Mat2, Mat3, Mat4, Object, UVec2, UVec3, UVec4, Value, Vec2, Vec3, Vec4, F32, I32, U32,
+    array,
+    dag::{Expr, Type},
+    primitives::value_arg,
+    program_def::InterpolationQualifier,
+    Array, Bool, IVec2, IVec3, IVec4, Mat2, Mat2x3, Mat2x4, Mat3, Mat3x2, Mat3x4, Mat4, Mat4x2,
+    Mat4x3, Object, UVec2, UVec3, UVec4, Value, ValueNonArray, Vec2, Vec3, Vec4, F32, I32, U32,
 };
 
 /// Data passed from a vertex shader to a fragment shader.
@@ -50,6 +54,12 @@ base_impl!(Vec4, Smooth);
 base_impl!(Mat2, Smooth);
 base_impl!(Mat3, Smooth);
 base_impl!(Mat4, Smooth);
+base_impl!(Mat2x3, Smooth);
+base_impl!(Mat2x4, Smooth);
+base_impl!(Mat3x2, Smooth);
+base_impl!(Mat3x4, Smooth);
+base_impl!(Mat4x2, Smooth);
+base_impl!(Mat4x3, Smooth);
 
 // GLSL ES 3.0: 4.3.6 Output Variables
 // > Vertex shader outputs that are, or contain, signed or unsigned integers or
@@ -63,6 +73,17 @@ base_impl!(UVec2, Flat);
 base_impl!(UVec3, Flat);
 base_impl!(UVec4, Flat);
 
+// Vertex shader outputs cannot be booleans, so they are passed as `u32`.
+unsafe impl Interpolant for Bool {
+    fn shader_outputs(&self, path: &str) -> Vec<(String, InterpolationQualifier, Rc<Expr>)> {
+        self.as_u32().shader_outputs(path)
+    }
+
+    fn shader_input(path: &str) -> Self {
+        U32::shader_input(path).ne(0u32)
+    }
+}
+
 macro_rules! tuple_impl {
     ($($name: ident),*) => {
         unsafe impl<$($name: Interpolant,)*> Interpolant for ($($name,)*) {
@@ -71,8 +92,6 @@ macro_rules! tuple_impl {
                 &self,
                 path: &str,
             ) -> Vec<(String, InterpolationQualifier, Rc<Expr>)> {
-                // TODO: `shader_outputs` should take an optional interpolation
-                // qualifier that we can distribute to tuple elements.
                 #[allow(non_snake_case)]
                 let ($($name,)*) = self;
 
@@ -97,3 +116,103 @@ macro_rules! tuple_impl {
 }
 
 smaller_tuples_too!(tuple_impl, T0, T1, T2, T3, T4, T5, T6, T7);
+
+// Arrays are passed element by element, so that each element can have its own
+// interpolation qualifier.
+unsafe impl<V: Interpolant + ValueNonArray, const N: usize> Interpolant for Array<V, N> {
+    fn shader_outputs(&self, path: &str) -> Vec<(String, InterpolationQualifier, Rc<Expr>)> {
+        (0..N)
+            .flat_map(|i| {
+                self.get(i as u32)
+                    .shader_outputs(&join_ident_path(path, &i.to_string()))
+            })
+            .collect()
+    }
+
+    fn shader_input(path: &str) -> Self {
+        array(std::array::from_fn(|i| {
+            V::shader_input(&join_ident_path(path, &i.to_string()))
+        }))
+    }
+}
+
+#[doc(hidden)]
+pub fn qualify_outputs(
+    outputs: Vec<(String, InterpolationQualifier, Rc<Expr>)>,
+    qualifier: InterpolationQualifier,
+) -> Vec<(String, InterpolationQualifier, Rc<Expr>)> {
+    outputs
+        .into_iter()
+        .map(|(name, default, expr)| (name, qualifier.apply(default), expr))
+        .collect()
+}
+
+macro_rules! wrapper_impl {
+    ($ty:ident, $qualifier:ident, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// The wrapped value can be accessed through the public field.
+        #[derive(Debug, Clone, Copy)]
+        pub struct $ty<V>(pub V);
+
+        impl<V: Value> Object for $ty<V> {
+            fn ty() -> Type {
+                V::ty()
+            }
+
+            fn expr(&self) -> Rc<Expr> {
+                self.0.expr()
+            }
+
+            fn from_arg(name: &str) -> Self {
+                Self(V::from_arg(name))
+            }
+        }
+
+        impl<V: Value> Value for $ty<V> {
+            fn from_expr(expr: Expr) -> Self {
+                Self(V::from_expr(expr))
+            }
+        }
+
+        impl<V: ValueNonArray> ValueNonArray for $ty<V> {}
+
+        impl<V: Value> ToSl for $ty<V> {
+            type Output = Self;
+
+            fn to_sl(self) -> Self::Output {
+                self
+            }
+        }
+
+        unsafe impl<V: Interpolant> Interpolant for $ty<V> {
+            fn shader_outputs(
+                &self,
+                path: &str,
+            ) -> Vec<(String, InterpolationQualifier, Rc<Expr>)> {
+                qualify_outputs(
+                    self.0.shader_outputs(path),
+                    InterpolationQualifier::$qualifier,
+                )
+            }
+
+            fn shader_input(path: &str) -> Self {
+                Self(V::shader_input(path))
+            }
+        }
+    };
+}
+
+wrapper_impl!(
+    Flat,
+    Flat,
+    "An interpolant that is not interpolated.\n\nThe fragment shader receives the value \
+     of the provoking vertex, which is the last vertex of each primitive."
+);
+wrapper_impl!(
+    Centroid,
+    Centroid,
+    "An interpolant that is sampled within the primitive.\n\nWhen multisampling, values \
+     are interpolated at a location that is covered by the primitive, which avoids \
+     extrapolation at primitive edges."
+);
@@ -0,0 +1,375 @@
+//! Mangling of Rust identifiers into GLSL and WGSL identifiers.
+//!
+//! Identifiers that cannot be used as they are get escaped:
+//!
+//! - The escaped form starts with `Q`, which identifiers that are used as they
+//!   are never do.
+//! - `Q` is written as `QQ`, `_` as `Qu`, and any non-ASCII character as `Qx`
+//!   followed by six hexadecimal digits.
+//!
+//! This applies to GLSL and WGSL keywords, identifiers that contain the
+//! reserved `__` or start with `gl_`, and identifiers that could collide with
+//! names generated by `posh`. The latter are identifiers that start with `posh`
+//! or contain `_posh` (in any case), such as `foo_posh_func0` or `Foo_Posh0`,
+//! and the names of local variables, which are `var_N`, `loop_acc_N` and
+//! `loop_index_N` for a number `N`.
+//!
+//! Paths of uniforms, vertex attributes, interpolants and fragment outputs are
+//! built by joining identifier components with `_`. In order to keep this
+//! injective, path components that contain `_` are escaped as well, so that
+//! escaped components never contain `_`.
+
+use std::{borrow::Cow, fmt::Write, hash::Hasher};
+
+/// The maximum length of identifiers in GLSL ES 3.00.
+const MAX_IDENT_LEN: usize = 1024;
+
+/// Keywords and reserved words of GLSL ES 3.00 and WGSL.
+const RESERVED: &[&str] = &[
+    "NULL",
+    "Self",
+    "abstract",
+    "active",
+    "alias",
+    "alignas",
+    "alignof",
+    "as",
+    "asm",
+    "asm_fragment",
+    "async",
+    "attribute",
+    "auto",
+    "await",
+    "become",
+    "binding_array",
+    "bool",
+    "break",
+    "buffer",
+    "bvec2",
+    "bvec3",
+    "bvec4",
+    "case",
+    "cast",
+    "catch",
+    "centroid",
+    "class",
+    "co_await",
+    "co_return",
+    "co_yield",
+    "coherent",
+    "column_major",
+    "common",
+    "compile",
+    "compile_fragment",
+    "concept",
+    "const",
+    "const_assert",
+    "const_cast",
+    "consteval",
+    "constexpr",
+    "constinit",
+    "continue",
+    "continuing",
+    "crate",
+    "debugger",
+    "decltype",
+    "default",
+    "delete",
+    "demote",
+    "demote_to_helper",
+    "diagnostic",
+    "discard",
+    "do",
+    "double",
+    "dvec2",
+    "dvec3",
+    "dvec4",
+    "dynamic_cast",
+    "else",
+    "enable",
+    "enum",
+    "explicit",
+    "export",
+    "extends",
+    "extern",
+    "external",
+    "fallthrough",
+    "false",
+    "filter",
+    "final",
+    "finally",
+    "fixed",
+    "flat",
+    "float",
+    "fn",
+    "for",
+    "friend",
+    "from",
+    "fvec2",
+    "fvec3",
+    "fvec4",
+    "fxgroup",
+    "get",
+    "goto",
+    "groupshared",
+    "half",
+    "highp",
+    "hvec2",
+    "hvec3",
+    "hvec4",
+    "if",
+    "impl",
+    "implements",
+    "import",
+    "in",
+    "inline",
+    "inout",
+    "input",
+    "instanceof",
+    "int",
+    "interface",
+    "invariant",
+    "isampler2D",
+    "isampler2DArray",
+    "isampler3D",
+    "isamplerCube",
+    "ivec2",
+    "ivec3",
+    "ivec4",
+    "layout",
+    "let",
+    "long",
+    "loop",
+    "lowp",
+    "macro",
+    "macro_rules",
+    "mat2",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "match",
+    "mediump",
+    "meta",
+    "mod",
+    "module",
+    "move",
+    "mut",
+    "mutable",
+    "namespace",
+    "new",
+    "nil",
+    "noexcept",
+    "noinline",
+    "nointerpolation",
+    "noperspective",
+    "null",
+    "nullptr",
+    "of",
+    "operator",
+    "out",
+    "output",
+    "override",
+    "package",
+    "packoffset",
+    "partition",
+    "pass",
+    "patch",
+    "pixelfragment",
+    "precise",
+    "precision",
+    "premerge",
+    "priv",
+    "protected",
+    "pub",
+    "public",
+    "readonly",
+    "ref",
+    "regardless",
+    "register",
+    "reinterpret_cast",
+    "require",
+    "requires",
+    "resource",
+    "restrict",
+    "return",
+    "sample",
+    "sampler2D",
+    "sampler2DArray",
+    "sampler2DArrayShadow",
+    "sampler2DShadow",
+    "sampler3D",
+    "samplerCube",
+    "samplerCubeShadow",
+    "self",
+    "set",
+    "shared",
+    "short",
+    "sizeof",
+    "smooth",
+    "snorm",
+    "static",
+    "static_assert",
+    "static_cast",
+    "std",
+    "struct",
+    "subroutine",
+    "super",
+    "superp",
+    "switch",
+    "target",
+    "template",
+    "this",
+    "thread_local",
+    "throw",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typedef",
+    "typeid",
+    "typename",
+    "typeof",
+    "uint",
+    "uniform",
+    "union",
+    "unless",
+    "unorm",
+    "unsafe",
+    "unsigned",
+    "unsized",
+    "usampler2D",
+    "usampler2DArray",
+    "usampler3D",
+    "usamplerCube",
+    "use",
+    "using",
+    "uvec2",
+    "uvec3",
+    "uvec4",
+    "var",
+    "varying",
+    "vec2",
+    "vec3",
+    "vec4",
+    "virtual",
+    "void",
+    "volatile",
+    "wgsl",
+    "where",
+    "while",
+    "with",
+    "writeonly",
+    "yield",
+];
+
+/// Joins a path with an identifier component.
+pub fn join_ident_path(lhs: &str, rhs: &str) -> String {
+    let rhs = if rhs.contains('_') {
+        Cow::Owned(escape(rhs))
+    } else {
+        mangle_ident(rhs)
+    };
+
+    limit_len(format!("{lhs}_{rhs}"))
+}
+
+/// Mangles an identifier that does not need to be combined with others, such
+/// as a struct field or a function parameter.
+pub fn mangle_ident(name: &str) -> Cow<'_, str> {
+    if is_plain(name) {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(escape(name))
+    }
+}
+
+fn is_plain(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with('Q')
+        && !name.contains("__")
+        && !name.starts_with("gl_")
+        && !is_generated(name)
+        && !RESERVED.contains(&name)
+}
+
+// Returns true if `name` could have been generated by `posh`.
+fn is_generated(name: &str) -> bool {
+    let lowercase = name.to_ascii_lowercase();
+
+    lowercase.starts_with("posh")
+        || lowercase.contains("_posh")
+        || ["var_", "loop_acc_", "loop_index_"].iter().any(|prefix| {
+            name.strip_prefix(prefix).is_some_and(|suffix| {
+                !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit())
+            })
+        })
+}
+
+fn escape(name: &str) -> String {
+    let mut result = String::from("Q");
+
+    for c in name.chars() {
+        match c {
+            'Q' => result.push_str("QQ"),
+            '_' => result.push_str("Qu"),
+            c if c.is_ascii_alphanumeric() => result.push(c),
+            c => write!(result, "Qx{:06x}", u32::from(c)).unwrap(),
+        }
+    }
+
+    limit_len(result)
+}
+
+/// Turns a Rust type or function name into a prefix for a GLSL identifier.
+///
+/// In contrast to [`mangle_ident`], this is not injective. It is used for names
+/// that are made unique by a suffix.
+pub fn mangle_prefix(name: &str) -> String {
+    let mut result = name
+        .split('_')
+        .map(|part| {
+            part.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if result.is_empty()
+        || result.starts_with(|c: char| c.is_ascii_digit())
+        || result == "gl"
+        || result.starts_with("gl_")
+    {
+        result.insert_str(0, "posh");
+    }
+
+    result
+}
+
+// Identifiers that are too long are truncated and disambiguated by a hash of
+// the full identifier.
+fn limit_len(name: String) -> String {
+    if name.len() <= MAX_IDENT_LEN {
+        return name;
+    }
+
+    let mut hasher = fxhash::FxHasher64::default();
+    hasher.write(name.as_bytes());
+
+    // Mangled identifiers only contain ASCII characters, so this does not split
+    // a character.
+    let suffix = format!("Qh{:016x}", hasher.finish());
+    let prefix = &name[..MAX_IDENT_LEN - suffix.len()];
+
+    format!("{prefix}{suffix}")
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use super::{
+    branch,
     dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp},
     primitives::{binary, built_in_1, built_in_2, common_field_base, field, unary, value_arg},
     Bool, Object, Value, ValueNonArray, Vec2, Vec3, Vec4, F32, U32,
@@ -35,9 +36,59 @@ pub struct Mat4 {
     pub w_axis: Vec4,
 }
 
+/// A floating-point matrix with two columns and three rows.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat2x3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+}
+
+/// A floating-point matrix with two columns and four rows.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat2x4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+}
+
+/// A floating-point matrix with three columns and two rows.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat3x2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub z_axis: Vec2,
+}
+
+/// A floating-point matrix with three columns and four rows.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat3x4 {
+    pub x_axis: Vec4,
+    pub y_axis: Vec4,
+    pub z_axis: Vec4,
+}
+
+/// A floating-point matrix with four columns and two rows.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat4x2 {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub z_axis: Vec2,
+    pub w_axis: Vec2,
+}
+
+/// A floating-point matrix with four columns and three rows.
+///
+/// This is a compact representation of affine transforms in 3D.
+#[derive(Debug, Copy, Clone)]
+pub struct Mat4x3 {
+    pub x_axis: Vec3,
+    pub y_axis: Vec3,
+    pub z_axis: Vec3,
+    pub w_axis: Vec3,
+}
+
 // Implements `Object` and `Value` for `$mat`.
 macro_rules! impl_value {
-    ($mat:ident, $($member:ident),+) => {
+    ($mat:ident, $col:ident, $($member:ident),+) => {
         impl Object for $mat {
             fn ty() -> Type {
                 Type::BuiltIn(BuiltInType::$mat)
@@ -99,15 +150,43 @@ macro_rules! impl_value {
                 <Self as Value>::ne(self, right)
             }
 
-            pub fn get(self, index: impl ToSl<Output = U32>) -> Vec4 {
-                // FIXME: Prevent out-of-bounds access.
+            // The number of columns.
+            const LEN: u32 = [$(std::stringify!($member)),+].len() as u32;
+
+            /// Returns the column at `index`.
+            ///
+            /// Accessing a column out of bounds leads to undefined behavior.
+            pub fn get(self, index: impl ToSl<Output = U32>) -> $col {
                 let base = self.expr();
                 let index = index.to_sl().expr();
-                let ty = Vec4::ty();
+                let ty = $col::ty();
 
                 let expr = Expr::Subscript { base, index, ty };
 
-                Vec4::from_expr(expr)
+                $col::from_expr(expr)
+            }
+
+            /// Returns the column at `index`, clamping `index` to the last
+            /// column.
+            pub fn get_clamped(self, index: impl ToSl<Output = U32>) -> $col {
+                self.get(index.to_sl().min(Self::LEN - 1))
+            }
+
+            /// Returns the column at `index` modulo the number of columns.
+            pub fn get_wrapped(self, index: impl ToSl<Output = U32>) -> $col {
+                self.get(index.to_sl() % Self::LEN)
+            }
+
+            /// Returns the column at `index`, or `default` if `index` is out of
+            /// bounds.
+            pub fn get_or(
+                self,
+                index: impl ToSl<Output = U32>,
+                default: impl ToSl<Output = $col>,
+            ) -> $col {
+                let index = index.to_sl();
+
+                branch(index.lt(Self::LEN), self.get(index), default)
             }
         }
     };
@@ -128,12 +207,12 @@ macro_rules! impl_binary_op_symmetric {
 
 // Implements `$mat <op> $vec`.
 macro_rules! impl_binary_op_vec_rhs {
-    ($mat:ident, $vec:ident, $fn:ident, $op:ident) => {
+    ($mat:ident, $vec:ident, $output:ident, $fn:ident, $op:ident) => {
         impl<Rhs> $op<Rhs> for $mat
         where
             Rhs: ToSl<Output = $vec>,
         {
-            type Output = $vec;
+            type Output = $output;
 
             fn $fn(self, right: Rhs) -> Self::Output {
                 binary(self, BinaryOp::$op, right)
@@ -163,14 +242,34 @@ macro_rules! impl_binary_op_scalar_rhs {
     };
 }
 
-// Implements all the things for `$mat`.
+// Implements `$lhs * $rhs` for matrices of different shapes.
+macro_rules! impl_mul_mat {
+    ($lhs:ident, $rhs:ident, $output:ident) => {
+        impl Mul<$rhs> for $lhs {
+            type Output = $output;
+
+            fn mul(self, right: $rhs) -> Self::Output {
+                binary(self, BinaryOp::Mul, right)
+            }
+        }
+    };
+}
+
+// Implements all the things for `$mat`, which has columns of type `$col` and
+// rows of type `$row`.
 macro_rules! impl_mat {
-    ($mat:ident, $vec_ty:ident, ($($member:ident),+), ($($axis:ident),+)) => {
-        impl_value!($mat, $($member),+);
+    (
+        $mat:ident,
+        $col:ident,
+        $row:ident,
+        $transpose:ident,
+        ($($member:ident),+),
+        ($($axis:ident),+)
+    ) => {
+        impl_value!($mat, $col, $($member),+);
 
         impl_binary_op_symmetric!($mat, add, Add);
         impl_binary_op_symmetric!($mat, div, Div);
-        impl_binary_op_symmetric!($mat, mul, Mul);
         impl_binary_op_symmetric!($mat, sub, Sub);
 
         impl_binary_op_scalar_rhs!($mat, add, Add);
@@ -178,7 +277,7 @@ macro_rules! impl_mat {
         impl_binary_op_scalar_rhs!($mat, mul, Mul);
         impl_binary_op_scalar_rhs!($mat, sub, Sub);
 
-        impl_binary_op_vec_rhs!($mat, $vec_ty, mul, Mul);
+        impl_binary_op_vec_rhs!($mat, $row, $col, mul, Mul);
 
         impl Neg for $mat {
             type Output = Self;
@@ -188,12 +287,6 @@ macro_rules! impl_mat {
             }
         }
 
-        impl Default for $mat {
-            fn default() -> Self {
-                Self::IDENTITY
-            }
-        }
-
         impl Sum for $mat {
             fn sum<I>(iter: I) -> Self
             where
@@ -203,25 +296,17 @@ macro_rules! impl_mat {
             }
         }
 
-        impl Product for $mat {
-            fn product<I>(iter: I) -> Self
-            where
-                I: Iterator<Item = Self>,
-            {
-                iter.fold(Self::IDENTITY, Self::mul)
-            }
-        }
-
         impl $mat {
             pub const ZERO: Self = Self {
                 $(
-                    $member: $vec_ty::ZERO
+                    $member: $col::ZERO
                 ),+
             };
 
+            /// The matrix with ones on the diagonal and zeros elsewhere.
             pub const IDENTITY: Self = Self {
                 $(
-                    $member: $vec_ty::$axis
+                    $member: $col::$axis
                 ),+
             };
 
@@ -229,10 +314,38 @@ macro_rules! impl_mat {
                 built_in_1(&format!("{}", Self::ty()), value.to_sl())
             }
 
-            pub fn transpose(self) -> Self {
+            pub fn transpose(self) -> $transpose {
                 built_in_1("transpose", self)
             }
 
+            pub fn cmpmul(self, y: Self) -> Self {
+                built_in_2("matrixCompMult", self, y)
+            }
+        }
+    };
+}
+
+// Implements the things that only make sense for square matrices.
+macro_rules! impl_mat_square {
+    ($mat:ident) => {
+        impl_binary_op_symmetric!($mat, mul, Mul);
+
+        impl Default for $mat {
+            fn default() -> Self {
+                Self::IDENTITY
+            }
+        }
+
+        impl Product for $mat {
+            fn product<I>(iter: I) -> Self
+            where
+                I: Iterator<Item = Self>,
+            {
+                iter.fold(Self::IDENTITY, Self::mul)
+            }
+        }
+
+        impl $mat {
             pub fn determinant(self) -> F32 {
                 built_in_1("determinant", self)
             }
@@ -240,17 +353,87 @@ macro_rules! impl_mat {
             pub fn inverse(self) -> Self {
                 built_in_1("inverse", self)
             }
-
-            pub fn cmpmul(self, y: Self) -> Self {
-                built_in_2("matrixCompMult", self, y)
-            }
         }
     };
 }
 
-impl_mat!(Mat2, Vec2, (x_axis, y_axis), (X, Y));
-impl_mat!(Mat3, Vec3, (x_axis, y_axis, z_axis), (X, Y, Z));
-impl_mat!(Mat4, Vec4, (x_axis, y_axis, z_axis, w_axis), (X, Y, Z, W));
+impl_mat!(Mat2, Vec2, Vec2, Mat2, (x_axis, y_axis), (X, Y));
+impl_mat!(Mat3, Vec3, Vec3, Mat3, (x_axis, y_axis, z_axis), (X, Y, Z));
+impl_mat!(
+    Mat4,
+    Vec4,
+    Vec4,
+    Mat4,
+    (x_axis, y_axis, z_axis, w_axis),
+    (X, Y, Z, W)
+);
+impl_mat!(Mat2x3, Vec3, Vec2, Mat3x2, (x_axis, y_axis), (X, Y));
+impl_mat!(Mat2x4, Vec4, Vec2, Mat4x2, (x_axis, y_axis), (X, Y));
+impl_mat!(
+    Mat3x2,
+    Vec2,
+    Vec3,
+    Mat2x3,
+    (x_axis, y_axis, z_axis),
+    (X, Y, ZERO)
+);
+impl_mat!(
+    Mat3x4,
+    Vec4,
+    Vec3,
+    Mat4x3,
+    (x_axis, y_axis, z_axis),
+    (X, Y, Z)
+);
+impl_mat!(
+    Mat4x2,
+    Vec2,
+    Vec4,
+    Mat2x4,
+    (x_axis, y_axis, z_axis, w_axis),
+    (X, Y, ZERO, ZERO)
+);
+impl_mat!(
+    Mat4x3,
+    Vec3,
+    Vec4,
+    Mat3x4,
+    (x_axis, y_axis, z_axis, w_axis),
+    (X, Y, Z, ZERO)
+);
+
+impl_mat_square!(Mat2);
+impl_mat_square!(Mat3);
+impl_mat_square!(Mat4);
+
+// GLSL ES 3.0: 5.10 Vector and Matrix Operations
+// > The number of columns of the left operand must equal the number of rows of
+// > the right operand. The result has the number of columns of the right
+// > operand and the number of rows of the left operand.
+impl_mul_mat!(Mat2, Mat3x2, Mat3x2);
+impl_mul_mat!(Mat2, Mat4x2, Mat4x2);
+impl_mul_mat!(Mat3, Mat2x3, Mat2x3);
+impl_mul_mat!(Mat3, Mat4x3, Mat4x3);
+impl_mul_mat!(Mat4, Mat2x4, Mat2x4);
+impl_mul_mat!(Mat4, Mat3x4, Mat3x4);
+impl_mul_mat!(Mat2x3, Mat2, Mat2x3);
+impl_mul_mat!(Mat2x3, Mat3x2, Mat3);
+impl_mul_mat!(Mat2x3, Mat4x2, Mat4x3);
+impl_mul_mat!(Mat2x4, Mat2, Mat2x4);
+impl_mul_mat!(Mat2x4, Mat3x2, Mat3x4);
+impl_mul_mat!(Mat2x4, Mat4x2, Mat4);
+impl_mul_mat!(Mat3x2, Mat3, Mat3x2);
+impl_mul_mat!(Mat3x2, Mat2x3, Mat2);
+impl_mul_mat!(Mat3x2, Mat4x3, Mat4x2);
+impl_mul_mat!(Mat3x4, Mat3, Mat3x4);
+impl_mul_mat!(Mat3x4, Mat2x3, Mat2x4);
+impl_mul_mat!(Mat3x4, Mat4x3, Mat4);
+impl_mul_mat!(Mat4x2, Mat4, Mat4x2);
+impl_mul_mat!(Mat4x2, Mat2x4, Mat2);
+impl_mul_mat!(Mat4x2, Mat3x4, Mat3x2);
+impl_mul_mat!(Mat4x3, Mat4, Mat4x3);
+impl_mul_mat!(Mat4x3, Mat2x4, Mat2x3);
+impl_mul_mat!(Mat4x3, Mat3x4, Mat3);
 
 #[cfg(feature = "glam")]
 impl ToSl for glam::Mat2 {
@@ -365,3 +548,81 @@ pub fn mat4(
         w_axis: w.to_sl(),
     }
 }
+
+/// Creates a floating-point matrix with two columns and three rows from column
+/// vectors.
+pub fn mat2x3(x: impl ToSl<Output = Vec3>, y: impl ToSl<Output = Vec3>) -> Mat2x3 {
+    Mat2x3 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+    }
+}
+
+/// Creates a floating-point matrix with two columns and four rows from column
+/// vectors.
+pub fn mat2x4(x: impl ToSl<Output = Vec4>, y: impl ToSl<Output = Vec4>) -> Mat2x4 {
+    Mat2x4 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+    }
+}
+
+/// Creates a floating-point matrix with three columns and two rows from column
+/// vectors.
+pub fn mat3x2(
+    x: impl ToSl<Output = Vec2>,
+    y: impl ToSl<Output = Vec2>,
+    z: impl ToSl<Output = Vec2>,
+) -> Mat3x2 {
+    Mat3x2 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+        z_axis: z.to_sl(),
+    }
+}
+
+/// Creates a floating-point matrix with three columns and four rows from
+/// column vectors.
+pub fn mat3x4(
+    x: impl ToSl<Output = Vec4>,
+    y: impl ToSl<Output = Vec4>,
+    z: impl ToSl<Output = Vec4>,
+) -> Mat3x4 {
+    Mat3x4 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+        z_axis: z.to_sl(),
+    }
+}
+
+/// Creates a floating-point matrix with four columns and two rows from column
+/// vectors.
+pub fn mat4x2(
+    x: impl ToSl<Output = Vec2>,
+    y: impl ToSl<Output = Vec2>,
+    z: impl ToSl<Output = Vec2>,
+    w: impl ToSl<Output = Vec2>,
+) -> Mat4x2 {
+    Mat4x2 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+        z_axis: z.to_sl(),
+        w_axis: w.to_sl(),
+    }
+}
+
+/// Creates a floating-point matrix with four columns and three rows from
+/// column vectors.
+pub fn mat4x3(
+    x: impl ToSl<Output = Vec3>,
+    y: impl ToSl<Output = Vec3>,
+    z: impl ToSl<Output = Vec3>,
+    w: impl ToSl<Output = Vec3>,
+) -> Mat4x3 {
+    Mat4x3 {
+        x_axis: x.to_sl(),
+        y_axis: y.to_sl(),
+        z_axis: z.to_sl(),
+        w_axis: w.to_sl(),
+    }
+}
@@ -1,10 +1,12 @@
-use std::rc::Rc;
+use std::{cell::Cell, collections::BTreeSet, ops::Range, rc::Rc};
+
+use sealed::sealed;
 
 use crate::ToSl;
 
 use super::{
     dag::{BinaryOp, Expr, FuncDef, StructType, Type, UnaryOp},
-    Bool, Object, Value,
+    Bool, Object, Value, I32, U32,
 };
 
 pub fn and(left: impl ToSl<Output = Bool>, right: impl ToSl<Output = Bool>) -> Bool {
@@ -61,6 +63,205 @@ where
     }
 }
 
+/// Scalar types that can be used as the selector of a [`switch`].
+#[sealed]
+pub trait SwitchValue: Value {
+    /// The type of constant case labels.
+    type Case: ToSl<Output = Self> + Into<i64>;
+}
+
+#[sealed]
+impl SwitchValue for I32 {
+    type Case = i32;
+}
+
+#[sealed]
+impl SwitchValue for U32 {
+    type Case = u32;
+}
+
+/// Selects one of several values depending on an integer.
+///
+/// This generates a `switch` statement, which can be more efficient than the
+/// chain of conditionals generated by [`branches`]. Each case is evaluated only
+/// if its label matches `value`. If no label matches, `default` is evaluated.
+///
+/// # Panics
+///
+/// Panics if `cases` contains duplicate labels.
+///
+/// # Example
+///
+/// ```
+/// use posh::sl;
+///
+/// fn albedo(material_id: sl::U32) -> sl::Vec3 {
+///     sl::switch(
+///         material_id,
+///         [
+///             (0, sl::vec3(1.0, 0.0, 0.0)),
+///             (1, sl::vec3(0.0, 1.0, 0.0)),
+///             (2, sl::vec3(0.0, 0.0, 1.0)),
+///         ],
+///         sl::Vec3::ONE,
+///     )
+/// }
+/// ```
+pub fn switch<S, V, V1, V2>(
+    value: impl ToSl<Output = S>,
+    cases: impl IntoIterator<Item = (S::Case, V1)>,
+    default: V2,
+) -> V
+where
+    S: SwitchValue,
+    V: Value,
+    V1: ToSl<Output = V>,
+    V2: ToSl<Output = V>,
+{
+    let mut labels = BTreeSet::new();
+
+    let cases = cases
+        .into_iter()
+        .map(|(label, case)| {
+            let label = label.into();
+
+            assert!(
+                labels.insert(label),
+                "duplicate switch case label `{label}`"
+            );
+
+            (label, case.to_sl().expr())
+        })
+        .collect();
+
+    let expr = Expr::Switch {
+        value: value.to_sl().expr(),
+        cases,
+        default: default.to_sl().expr(),
+        ty: V::ty(),
+    };
+
+    V::from_expr(expr)
+}
+
+/// Scalar types that can be used as the induction variable of a loop.
+#[sealed]
+pub trait LoopIndex: Value {}
+
+#[sealed]
+impl LoopIndex for I32 {}
+
+#[sealed]
+impl LoopIndex for U32 {}
+
+std::thread_local! {
+    static LOOP_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Folds `f` over a range of integers with a loop in the shading language.
+///
+/// In contrast to folding over a Rust iterator, which unrolls every iteration
+/// at trace time, `f` is traced only once and becomes the body of a GLSL `for`
+/// loop. The bounds of `range` need not be constant, so they can be taken from
+/// uniforms, for example.
+///
+/// # Example
+///
+/// ```
+/// use posh::sl;
+///
+/// fn blur(sampler: sl::ColorSampler2d, uv: sl::Vec2, taps: sl::I32) -> sl::Vec4 {
+///     let sum = sl::fold(sl::I32::ZERO..taps, sl::Vec4::ZERO, |sum, i| {
+///         let offset = sl::vec2(i.as_f32() / 512.0, 0.0);
+///
+///         sum + sampler.sample(uv + offset)
+///     });
+///
+///     sum / taps.as_f32()
+/// }
+/// ```
+pub fn fold<V, I, R>(range: Range<R>, init: impl ToSl<Output = V>, f: impl FnOnce(V, I) -> V) -> V
+where
+    V: Value,
+    I: LoopIndex,
+    R: ToSl<Output = I>,
+{
+    fold_impl(range, init, f, None::<fn(V) -> Bool>)
+}
+
+/// Folds `f` over a range of integers with a loop in the shading language,
+/// stopping early once `stop` holds for the accumulated value.
+///
+/// `stop` is evaluated after each iteration of the loop. See [`fold`] for
+/// details.
+pub fn fold_until<V, I, R>(
+    range: Range<R>,
+    init: impl ToSl<Output = V>,
+    f: impl FnOnce(V, I) -> V,
+    stop: impl FnOnce(V) -> Bool,
+) -> V
+where
+    V: Value,
+    I: LoopIndex,
+    R: ToSl<Output = I>,
+{
+    fold_impl(range, init, f, Some(stop))
+}
+
+// Increments the loop depth for as long as it is alive. The depth is restored
+// on drop, so that a panic while tracing the body of a loop does not affect
+// later traces on the same thread.
+struct LoopDepthGuard(usize);
+
+impl LoopDepthGuard {
+    fn enter() -> Self {
+        Self(LOOP_DEPTH.with(|depth| depth.replace(depth.get() + 1)))
+    }
+}
+
+impl Drop for LoopDepthGuard {
+    fn drop(&mut self) {
+        LOOP_DEPTH.with(|depth| depth.set(self.0));
+    }
+}
+
+fn fold_impl<V, I, R>(
+    range: Range<R>,
+    init: impl ToSl<Output = V>,
+    f: impl FnOnce(V, I) -> V,
+    stop: Option<impl FnOnce(V) -> Bool>,
+) -> V
+where
+    V: Value,
+    I: LoopIndex,
+    R: ToSl<Output = I>,
+{
+    // Loop variables are named by the nesting depth of the loop, so that nested
+    // loops do not shadow each other's variables.
+    let depth = LoopDepthGuard::enter();
+
+    let acc_name = format!("loop_acc_{}", depth.0);
+    let index_name = format!("loop_index_{}", depth.0);
+
+    let body = f(value_arg(&acc_name), value_arg(&index_name));
+    let stop = stop.map(|stop| stop(body).expr());
+
+    drop(depth);
+
+    let expr = Expr::Loop {
+        start: range.start.to_sl().expr(),
+        end: range.end.to_sl().expr(),
+        init: init.to_sl().expr(),
+        acc_name,
+        index_name,
+        body: body.expr(),
+        stop,
+        ty: V::ty(),
+    };
+
+    V::from_expr(expr)
+}
+
 pub(crate) fn cast<U, V>(u: impl ToSl<Output = U>) -> V
 where
     U: Value,
@@ -151,6 +352,41 @@ where
     R::from_expr(expr)
 }
 
+pub(crate) fn built_in_4<U, V, W, X, R>(name: &str, u: U, v: V, w: W, x: X) -> R
+where
+    U: Object,
+    V: Object,
+    W: Object,
+    X: Object,
+    R: Value,
+{
+    let ty = R::ty();
+    let name = name.to_string();
+    let args = vec![u.expr(), v.expr(), w.expr(), x.expr()];
+
+    let expr = Expr::CallBuiltIn { ty, name, args };
+
+    R::from_expr(expr)
+}
+
+pub(crate) fn built_in_5<U, V, W, X, Y, R>(name: &str, u: U, v: V, w: W, x: X, y: Y) -> R
+where
+    U: Object,
+    V: Object,
+    W: Object,
+    X: Object,
+    Y: Object,
+    R: Value,
+{
+    let ty = R::ty();
+    let name = name.to_string();
+    let args = vec![u.expr(), v.expr(), w.expr(), x.expr(), y.expr()];
+
+    let expr = Expr::CallBuiltIn { ty, name, args };
+
+    R::from_expr(expr)
+}
+
 #[doc(hidden)]
 pub fn field<R: Value>(base: Rc<Expr>, name: &'static str) -> R {
     let ty = R::ty();
@@ -160,10 +396,8 @@ pub fn field<R: Value>(base: Rc<Expr>, name: &'static str) -> R {
     R::from_expr(expr)
 }
 
-// TODO: Bring back function definitions in `sl`.
 #[doc(hidden)]
-#[allow(unused)]
-pub fn call_func_def<R: Value>(def: FuncDef, args: Vec<Rc<Expr>>) -> R {
+pub fn call_func_def<R: Value>(def: Rc<FuncDef>, args: Vec<Rc<Expr>>) -> R {
     assert!(def.params.len() == args.len());
 
     let expr = Expr::CallFuncDef { def, args };
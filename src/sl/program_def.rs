@@ -1,4 +1,4 @@
-//! Definitions for generated GLSL source code.
+//! Definitions for generated GLSL and WGSL source code.
 //!
 //! This is exposed only in order to make the internally generated source code
 //! more transparent. It is typically not necessary to use this module.
@@ -29,11 +29,35 @@ pub struct UniformSamplerDef {
     pub texture_unit: usize,
 }
 
+/// The format in which a vertex attribute is stored in a vertex buffer.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum VertexAttributeFormat {
+    /// The components have the same type as the attribute in the shader.
+    #[default]
+    Native,
+
+    /// Unsigned 8-bit integers, normalized to `[0, 1]`.
+    Unorm8,
+
+    /// Signed 8-bit integers, normalized to `[-1, 1]`.
+    Snorm8,
+
+    /// Unsigned 16-bit integers, normalized to `[0, 1]`.
+    Unorm16,
+
+    /// Signed 16-bit integers, normalized to `[-1, 1]`.
+    Snorm16,
+
+    /// 16-bit floating-point values.
+    F16,
+}
+
 /// Vertex attribute definition.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VertexAttributeDef {
     pub name: String,
     pub ty: BuiltInType,
+    pub format: VertexAttributeFormat,
     pub offset: usize,
 }
 
@@ -44,10 +68,18 @@ pub enum VertexInputRate {
     Instance,
 }
 
+/// How a value is interpolated between the vertex and the fragment shader.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum InterpolationQualifier {
+    /// Perspective-correct interpolation.
     Smooth,
+
+    /// No interpolation. The value is taken from the provoking vertex.
     Flat,
+
+    /// Perspective-correct interpolation, sampled at a location that lies
+    /// within the primitive even when multisampling.
+    Centroid,
 }
 
 impl InterpolationQualifier {
@@ -57,6 +89,31 @@ impl InterpolationQualifier {
         match self {
             Smooth => "smooth",
             Flat => "flat",
+            Centroid => "smooth centroid",
+        }
+    }
+
+    pub fn to_wgsl(self) -> &'static str {
+        use InterpolationQualifier::*;
+
+        match self {
+            Smooth => "@interpolate(perspective)",
+            Flat => "@interpolate(flat)",
+            Centroid => "@interpolate(perspective, centroid)",
+        }
+    }
+
+    /// Returns the qualifier that results from requesting `self` for a value
+    /// whose default qualifier is `default`.
+    ///
+    /// Values that must be flat, such as integers, stay flat.
+    pub fn apply(self, default: Self) -> Self {
+        use InterpolationQualifier::*;
+
+        match (self, default) {
+            (_, Flat) | (Flat, _) => Flat,
+            (Centroid, _) | (_, Centroid) => Centroid,
+            (Smooth, Smooth) => Smooth,
         }
     }
 }
@@ -89,3 +146,101 @@ pub struct ProgramDef {
     /// Fragment shader source code.
     pub fragment_shader_source: String,
 }
+
+/// The kind of resource that is bound in a WGSL module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WgslBindingKind {
+    /// A uniform buffer holding a value of the given type.
+    UniformBuffer(Type),
+
+    /// The texture of a sampler uniform.
+    Texture(SamplerType),
+
+    /// The sampler of a sampler uniform.
+    Sampler(SamplerType),
+}
+
+/// Resource binding definition of a WGSL module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WgslBindingDef {
+    /// The name of the global variable in the module.
+    pub name: String,
+    pub group: u32,
+    pub binding: u32,
+    pub kind: WgslBindingKind,
+}
+
+/// Type-erased definition of a WGSL module.
+///
+/// See [`crate::sl::transpile::transpile_to_wgsl`] for how to construct a
+/// [`WgslProgramDef`] from typed shader definitions.
+///
+/// Uniform blocks are placed in group 0, using their binding point as binding.
+/// Samplers are split into a texture and a sampler in group 1, which get the
+/// bindings `2 * texture_unit` and `2 * texture_unit + 1`, respectively.
+///
+/// Vertex attributes get consecutive locations in the order in which they
+/// appear in `vertex_block_defs`, with matrices taking up one location per
+/// column.
+#[derive(Debug, Clone, Default)]
+pub struct WgslProgramDef {
+    /// Resources that the module needs.
+    pub bindings: Vec<WgslBindingDef>,
+
+    /// Vertex blocks that the module needs.
+    pub vertex_block_defs: Vec<VertexBlockDef>,
+
+    /// Source code of the module.
+    pub source: String,
+}
+
+/// Plain uniform definition of a GLSL ES 1.00 program.
+///
+/// GLSL ES 1.00 has no uniform blocks, so the contents of each uniform block
+/// are declared as a plain uniform instead. Its leaves must then be uploaded
+/// one by one, for example with [`crate::gl::upload_plain_uniforms`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlainUniformDef {
+    /// The name under which the uniform can be located in the program.
+    pub name: String,
+
+    /// The type of the uniform. This is never a struct or an array.
+    pub ty: BuiltInType,
+
+    /// The index of the uniform block that contains the uniform.
+    pub block: usize,
+
+    /// The byte offset of the uniform within the `std140` data of its uniform
+    /// block.
+    ///
+    /// The columns of matrices are 16 bytes apart.
+    pub offset: usize,
+}
+
+/// Type-erased definition of a GLSL ES 1.00 program.
+///
+/// See [`crate::sl::transpile::transpile_to_es100_program_def`] for how to
+/// construct an [`Es100ProgramDef`] from typed shader definitions.
+#[derive(Debug, Clone, Default)]
+pub struct Es100ProgramDef {
+    /// Uniform blocks that the program needs.
+    ///
+    /// These are not declared as blocks in the source code. Their `location`
+    /// is meaningless, and their contents are given by `plain_uniform_defs`.
+    pub uniform_block_defs: Vec<UniformBlockDef>,
+
+    /// Plain uniforms that hold the contents of the uniform blocks.
+    pub plain_uniform_defs: Vec<PlainUniformDef>,
+
+    /// Samplers that the program needs.
+    pub uniform_sampler_defs: Vec<UniformSamplerDef>,
+
+    /// Vertex blocks that the program needs.
+    pub vertex_block_defs: Vec<VertexBlockDef>,
+
+    /// Vertex shader source code.
+    pub vertex_shader_source: String,
+
+    /// Fragment shader source code.
+    pub fragment_shader_source: String,
+}
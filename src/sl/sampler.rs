@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::{marker::PhantomData, rc::Rc};
 
 use sealed::sealed;
 
@@ -6,7 +6,7 @@ use crate::{Block, Sl, ToSl};
 
 use super::{
     dag::{BuiltInType, Expr, SamplerType, Trace, Type},
-    primitives::built_in_2,
+    primitives::{built_in_2, built_in_3, built_in_4, built_in_5},
     IVec2, IVec3, IVec4, Interpolant, Object, UVec2, UVec3, UVec4, Value, Vec2, Vec3, Vec4, F32,
     I32, U32,
 };
@@ -17,6 +17,12 @@ use super::{
 pub trait Sample {
     const SAMPLER_TYPE: SamplerType;
 
+    const SAMPLER_CUBE_TYPE: SamplerType;
+
+    const SAMPLER_3D_TYPE: SamplerType;
+
+    const SAMPLER_2D_ARRAY_TYPE: SamplerType;
+
     type Vec4: Value;
 
     type Gl;
@@ -28,11 +34,24 @@ pub trait Sample {
 pub trait ColorSample: Value + Interpolant + Sample {}
 
 macro_rules! impl_color_sample {
-    ($sample:ident, $ty:ident, |$vec4_name:ident: $vec4:ident| $from_vec4:expr) => {
+    (
+        $sample:ident,
+        $ty:ident,
+        $cube_ty:ident,
+        $ty_3d:ident,
+        $array_ty:ident,
+        |$vec4_name:ident: $vec4:ident| $from_vec4:expr
+    ) => {
         #[sealed]
         impl Sample for $sample {
             const SAMPLER_TYPE: SamplerType = SamplerType::$ty;
 
+            const SAMPLER_CUBE_TYPE: SamplerType = SamplerType::$cube_ty;
+
+            const SAMPLER_3D_TYPE: SamplerType = SamplerType::$ty_3d;
+
+            const SAMPLER_2D_ARRAY_TYPE: SamplerType = SamplerType::$array_ty;
+
             type Vec4 = $vec4;
 
             type Gl = <$sample as Block<Sl>>::Gl;
@@ -47,18 +66,102 @@ macro_rules! impl_color_sample {
     };
 }
 
-impl_color_sample!(F32, ColorSampler2d, |v: Vec4| v.x);
-impl_color_sample!(I32, IColorSampler2d, |v: IVec4| v.x);
-impl_color_sample!(U32, UColorSampler2d, |v: UVec4| v.x);
-impl_color_sample!(Vec2, ColorSampler2d, |v: Vec4| v.xy());
-impl_color_sample!(IVec2, IColorSampler2d, |v: IVec4| v.xy());
-impl_color_sample!(UVec2, UColorSampler2d, |v: UVec4| v.xy());
-impl_color_sample!(Vec3, ColorSampler2d, |v: Vec4| v.xyz());
-impl_color_sample!(IVec3, IColorSampler2d, |v: IVec4| v.xyz());
-impl_color_sample!(UVec3, UColorSampler2d, |v: UVec4| v.xyz());
-impl_color_sample!(Vec4, ColorSampler2d, |v: Vec4| v);
-impl_color_sample!(IVec4, IColorSampler2d, |v: IVec4| v);
-impl_color_sample!(UVec4, UColorSampler2d, |v: UVec4| v);
+impl_color_sample!(
+    F32,
+    ColorSampler2d,
+    ColorSamplerCube,
+    ColorSampler3d,
+    ColorSampler2dArray,
+    |v: Vec4| v.x
+);
+impl_color_sample!(
+    I32,
+    IColorSampler2d,
+    IColorSamplerCube,
+    IColorSampler3d,
+    IColorSampler2dArray,
+    |v: IVec4| v.x
+);
+impl_color_sample!(
+    U32,
+    UColorSampler2d,
+    UColorSamplerCube,
+    UColorSampler3d,
+    UColorSampler2dArray,
+    |v: UVec4| v.x
+);
+impl_color_sample!(
+    Vec2,
+    ColorSampler2d,
+    ColorSamplerCube,
+    ColorSampler3d,
+    ColorSampler2dArray,
+    |v: Vec4| v.xy()
+);
+impl_color_sample!(
+    IVec2,
+    IColorSampler2d,
+    IColorSamplerCube,
+    IColorSampler3d,
+    IColorSampler2dArray,
+    |v: IVec4| v.xy()
+);
+impl_color_sample!(
+    UVec2,
+    UColorSampler2d,
+    UColorSamplerCube,
+    UColorSampler3d,
+    UColorSampler2dArray,
+    |v: UVec4| v.xy()
+);
+impl_color_sample!(
+    Vec3,
+    ColorSampler2d,
+    ColorSamplerCube,
+    ColorSampler3d,
+    ColorSampler2dArray,
+    |v: Vec4| v.xyz()
+);
+impl_color_sample!(
+    IVec3,
+    IColorSampler2d,
+    IColorSamplerCube,
+    IColorSampler3d,
+    IColorSampler2dArray,
+    |v: IVec4| v.xyz()
+);
+impl_color_sample!(
+    UVec3,
+    UColorSampler2d,
+    UColorSamplerCube,
+    UColorSampler3d,
+    UColorSampler2dArray,
+    |v: UVec4| v.xyz()
+);
+impl_color_sample!(
+    Vec4,
+    ColorSampler2d,
+    ColorSamplerCube,
+    ColorSampler3d,
+    ColorSampler2dArray,
+    |v: Vec4| v
+);
+impl_color_sample!(
+    IVec4,
+    IColorSampler2d,
+    IColorSamplerCube,
+    IColorSampler3d,
+    IColorSampler2dArray,
+    |v: IVec4| v
+);
+impl_color_sample!(
+    UVec4,
+    UColorSampler2d,
+    UColorSamplerCube,
+    UColorSampler3d,
+    UColorSampler2dArray,
+    |v: UVec4| v
+);
 
 #[derive(Debug, Copy, Clone)]
 pub enum ColorSampler2dInternal<S> {
@@ -75,6 +178,37 @@ pub struct ComparisonSampler2d {
     trace: Trace,
 }
 
+/// A cube map which can be sampled with a direction vector.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSamplerCube<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ComparisonSamplerCube {
+    trace: Trace,
+}
+
+/// A three-dimensional texture which can be sampled.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSampler3d<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+/// An array of two-dimensional textures which can be sampled.
+#[derive(Debug, Copy, Clone)]
+pub struct ColorSampler2dArray<S = Vec4> {
+    trace: Trace,
+    _phantom: PhantomData<S>,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct ComparisonSampler2dArray {
+    trace: Trace,
+}
+
 impl<S: ColorSample> Object for ColorSampler2d<S> {
     fn ty() -> Type {
         Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_TYPE))
@@ -108,13 +242,127 @@ impl<S: ColorSample> ColorSampler2d<S> {
         S::from_vec4(sample)
     }
 
+    /// Samples the texture with a bias that is added to the computed level of
+    /// detail.
+    ///
+    /// This is only available in fragment shaders.
+    pub fn sample_bias(self, tex_coords: Vec2, bias: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("texture", self, tex_coords, bias.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture at an explicit level of detail.
+    ///
+    /// In contrast to [`sample`](Self::sample), this can also be used in
+    /// vertex shaders.
+    pub fn sample_lod(self, tex_coords: Vec2, lod: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("textureLod", self, tex_coords, lod.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with explicit partial derivatives of the texture
+    /// coordinates.
+    pub fn sample_grad(self, tex_coords: Vec2, d_dx: Vec2, d_dy: Vec2) -> S {
+        let sample = built_in_4("textureGrad", self, tex_coords, d_dx, d_dy);
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with a constant texel offset.
+    ///
+    /// The components of `offset` must be in the range given by
+    /// `MIN_PROGRAM_TEXEL_OFFSET` and `MAX_PROGRAM_TEXEL_OFFSET`, which is at
+    /// least `-8..=7`.
+    pub fn sample_offset(self, tex_coords: Vec2, offset: [i32; 2]) -> S {
+        let sample = built_in_3("textureOffset", self, tex_coords, const_offset(offset));
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture at an explicit level of detail with a constant
+    /// texel offset.
+    pub fn sample_lod_offset(
+        self,
+        tex_coords: Vec2,
+        lod: impl ToSl<Output = F32>,
+        offset: [i32; 2],
+    ) -> S {
+        let sample = built_in_4(
+            "textureLodOffset",
+            self,
+            tex_coords,
+            lod.to_sl(),
+            const_offset(offset),
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with explicit partial derivatives and a constant
+    /// texel offset.
+    pub fn sample_grad_offset(
+        self,
+        tex_coords: Vec2,
+        d_dx: Vec2,
+        d_dy: Vec2,
+        offset: [i32; 2],
+    ) -> S {
+        let sample = built_in_5(
+            "textureGradOffset",
+            self,
+            tex_coords,
+            d_dx,
+            d_dy,
+            const_offset(offset),
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with projective texture coordinates.
+    ///
+    /// The texture coordinates are divided by `tex_coords.z` before sampling.
+    pub fn sample_proj(self, tex_coords: Vec3) -> S {
+        let sample = built_in_2("textureProj", self, tex_coords);
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with projective texture coordinates at an explicit
+    /// level of detail.
+    pub fn sample_proj_lod(self, tex_coords: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("textureProjLod", self, tex_coords, lod.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Looks up a single texel of a mipmap level without any filtering.
+    pub fn fetch(self, texel: IVec2, level: impl ToSl<Output = U32>) -> S {
+        let sample = built_in_3("texelFetch", self, texel, level.to_sl().as_i32());
+
+        S::from_vec4(sample)
+    }
+
+    /// Looks up a single texel of a mipmap level with a constant texel offset.
+    pub fn fetch_offset(self, texel: IVec2, level: impl ToSl<Output = U32>, offset: [i32; 2]) -> S {
+        let sample = built_in_4(
+            "texelFetchOffset",
+            self,
+            texel,
+            level.to_sl().as_i32(),
+            const_offset(offset),
+        );
+
+        S::from_vec4(sample)
+    }
+
     pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
         let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
 
         size.as_uvec2()
     }
-
-    // TODO: Various sampling functions.
 }
 
 impl Object for ComparisonSampler2d {
@@ -141,9 +389,443 @@ impl ComparisonSampler2d {
         built_in_2("texture", self, tex_coords.extend(reference_depth))
     }
 
+    /// Compares against the texture at an explicit level of detail.
+    pub fn sample_compare_lod(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        lod: impl ToSl<Output = F32>,
+    ) -> F32 {
+        built_in_3(
+            "textureLod",
+            self,
+            tex_coords.extend(reference_depth),
+            lod.to_sl(),
+        )
+    }
+
+    /// Compares against the texture with explicit partial derivatives of the
+    /// texture coordinates.
+    pub fn sample_compare_grad(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        d_dx: Vec2,
+        d_dy: Vec2,
+    ) -> F32 {
+        built_in_4(
+            "textureGrad",
+            self,
+            tex_coords.extend(reference_depth),
+            d_dx,
+            d_dy,
+        )
+    }
+
+    /// Compares against the texture with a constant texel offset.
+    ///
+    /// See [`ColorSampler2d::sample_offset`] for the valid range of `offset`.
+    pub fn sample_compare_offset(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        offset: [i32; 2],
+    ) -> F32 {
+        built_in_3(
+            "textureOffset",
+            self,
+            tex_coords.extend(reference_depth),
+            const_offset(offset),
+        )
+    }
+
+    /// Compares against the texture with projective texture coordinates.
+    ///
+    /// The texture coordinates and `reference_depth` are divided by `q` before
+    /// the comparison.
+    pub fn sample_compare_proj(
+        self,
+        tex_coords: Vec2,
+        reference_depth: F32,
+        q: impl ToSl<Output = F32>,
+    ) -> F32 {
+        built_in_2(
+            "textureProj",
+            self,
+            tex_coords.extend(reference_depth).extend(q),
+        )
+    }
+
     pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
         let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
 
         size.as_uvec2()
     }
 }
+
+impl<S: ColorSample> Object for ColorSamplerCube<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_CUBE_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSamplerCube<S> {
+    /// Samples the cube map in the direction `dir`, which need not be
+    /// normalized.
+    pub fn sample(self, dir: Vec3) -> S {
+        let sample = built_in_2("texture", self, dir);
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the cube map with a bias that is added to the computed level of
+    /// detail.
+    ///
+    /// This is only available in fragment shaders.
+    pub fn sample_bias(self, dir: Vec3, bias: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("texture", self, dir, bias.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the cube map at an explicit level of detail.
+    pub fn sample_lod(self, dir: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("textureLod", self, dir, lod.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the cube map with explicit partial derivatives of the direction.
+    pub fn sample_grad(self, dir: Vec3, d_dx: Vec3, d_dy: Vec3) -> S {
+        let sample = built_in_4("textureGrad", self, dir, d_dx, d_dy);
+
+        S::from_vec4(sample)
+    }
+
+    /// Returns the size of a face of the cube map.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
+        let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec2()
+    }
+}
+
+impl Object for ComparisonSamplerCube {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(SamplerType::ComparisonSamplerCube))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+        }
+    }
+}
+
+impl ComparisonSamplerCube {
+    pub fn sample_compare(self, dir: Vec3, reference_depth: F32) -> F32 {
+        built_in_2("texture", self, dir.extend(reference_depth))
+    }
+
+    /// Compares against the cube map with explicit partial derivatives of the
+    /// direction.
+    pub fn sample_compare_grad(
+        self,
+        dir: Vec3,
+        reference_depth: F32,
+        d_dx: Vec3,
+        d_dy: Vec3,
+    ) -> F32 {
+        built_in_4("textureGrad", self, dir.extend(reference_depth), d_dx, d_dy)
+    }
+
+    /// Returns the size of a face of the cube map.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec2 {
+        let size: IVec2 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec2()
+    }
+}
+
+impl<S: ColorSample> Object for ColorSampler3d<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_3D_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSampler3d<S> {
+    pub fn sample(self, tex_coords: Vec3) -> S {
+        let sample = built_in_2("texture", self, tex_coords);
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with a bias that is added to the computed level of
+    /// detail.
+    ///
+    /// This is only available in fragment shaders.
+    pub fn sample_bias(self, tex_coords: Vec3, bias: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("texture", self, tex_coords, bias.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture at an explicit level of detail.
+    pub fn sample_lod(self, tex_coords: Vec3, lod: impl ToSl<Output = F32>) -> S {
+        let sample = built_in_3("textureLod", self, tex_coords, lod.to_sl());
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with explicit partial derivatives of the texture
+    /// coordinates.
+    pub fn sample_grad(self, tex_coords: Vec3, d_dx: Vec3, d_dy: Vec3) -> S {
+        let sample = built_in_4("textureGrad", self, tex_coords, d_dx, d_dy);
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples the texture with a constant texel offset.
+    ///
+    /// See [`ColorSampler2d::sample_offset`] for the valid range of `offset`.
+    pub fn sample_offset(self, tex_coords: Vec3, offset: [i32; 3]) -> S {
+        let sample = built_in_3("textureOffset", self, tex_coords, const_offset_3d(offset));
+
+        S::from_vec4(sample)
+    }
+
+    /// Looks up a single texel of a mipmap level without any filtering.
+    pub fn fetch(self, texel: IVec3, level: impl ToSl<Output = U32>) -> S {
+        let sample = built_in_3("texelFetch", self, texel, level.to_sl().as_i32());
+
+        S::from_vec4(sample)
+    }
+
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+impl<S: ColorSample> Object for ColorSampler2dArray<S> {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(S::SAMPLER_2D_ARRAY_TYPE))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S: ColorSample> ColorSampler2dArray<S> {
+    /// Samples layer `layer` of the texture array.
+    pub fn sample(self, tex_coords: Vec2, layer: impl ToSl<Output = U32>) -> S {
+        let sample = built_in_2("texture", self, array_coords(tex_coords, layer));
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples layer `layer` of the texture array at an explicit level of
+    /// detail.
+    pub fn sample_lod(
+        self,
+        tex_coords: Vec2,
+        layer: impl ToSl<Output = U32>,
+        lod: impl ToSl<Output = F32>,
+    ) -> S {
+        let sample = built_in_3(
+            "textureLod",
+            self,
+            array_coords(tex_coords, layer),
+            lod.to_sl(),
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples layer `layer` of the texture array with explicit partial
+    /// derivatives of the texture coordinates.
+    pub fn sample_grad(
+        self,
+        tex_coords: Vec2,
+        layer: impl ToSl<Output = U32>,
+        d_dx: Vec2,
+        d_dy: Vec2,
+    ) -> S {
+        let sample = built_in_4(
+            "textureGrad",
+            self,
+            array_coords(tex_coords, layer),
+            d_dx,
+            d_dy,
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Samples layer `layer` of the texture array with a constant texel
+    /// offset.
+    ///
+    /// See [`ColorSampler2d::sample_offset`] for the valid range of `offset`.
+    pub fn sample_offset(
+        self,
+        tex_coords: Vec2,
+        layer: impl ToSl<Output = U32>,
+        offset: [i32; 2],
+    ) -> S {
+        let sample = built_in_3(
+            "textureOffset",
+            self,
+            array_coords(tex_coords, layer),
+            const_offset(offset),
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Looks up a single texel of a mipmap level of layer `layer` without any
+    /// filtering.
+    pub fn fetch(
+        self,
+        texel: IVec2,
+        layer: impl ToSl<Output = U32>,
+        level: impl ToSl<Output = U32>,
+    ) -> S {
+        let sample = built_in_3(
+            "texelFetch",
+            self,
+            texel.extend(layer.to_sl().as_i32()),
+            level.to_sl().as_i32(),
+        );
+
+        S::from_vec4(sample)
+    }
+
+    /// Returns the width, the height, and the number of layers.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+impl Object for ComparisonSampler2dArray {
+    fn ty() -> Type {
+        Type::BuiltIn(BuiltInType::Sampler(SamplerType::ComparisonSampler2dArray))
+    }
+
+    fn expr(&self) -> Rc<Expr> {
+        self.trace.expr()
+    }
+
+    fn from_arg(name: &str) -> Self {
+        Self {
+            trace: Trace::new(Expr::Arg {
+                ty: Self::ty(),
+                name: name.into(),
+            }),
+        }
+    }
+}
+
+impl ComparisonSampler2dArray {
+    pub fn sample_compare(
+        self,
+        tex_coords: Vec2,
+        layer: impl ToSl<Output = U32>,
+        reference_depth: F32,
+    ) -> F32 {
+        built_in_2(
+            "texture",
+            self,
+            array_coords(tex_coords, layer).extend(reference_depth),
+        )
+    }
+
+    /// Compares against layer `layer` of the texture array with explicit
+    /// partial derivatives of the texture coordinates.
+    pub fn sample_compare_grad(
+        self,
+        tex_coords: Vec2,
+        layer: impl ToSl<Output = U32>,
+        reference_depth: F32,
+        d_dx: Vec2,
+        d_dy: Vec2,
+    ) -> F32 {
+        built_in_4(
+            "textureGrad",
+            self,
+            array_coords(tex_coords, layer).extend(reference_depth),
+            d_dx,
+            d_dy,
+        )
+    }
+
+    /// Returns the width, the height, and the number of layers.
+    pub fn size(self, level: impl ToSl<Output = U32>) -> UVec3 {
+        let size: IVec3 = built_in_2("textureSize", self, level.to_sl().as_i32());
+
+        size.as_uvec3()
+    }
+}
+
+// Array layers are passed to GLSL as the last texture coordinate.
+fn array_coords(tex_coords: Vec2, layer: impl ToSl<Output = U32>) -> Vec3 {
+    tex_coords.extend(layer.to_sl().as_f32())
+}
+
+// Texel offsets must be constant expressions in GLSL, so they are only accepted
+// as Rust values.
+fn const_offset(offset: [i32; 2]) -> IVec2 {
+    IVec2::new(offset[0], offset[1])
+}
+
+fn const_offset_3d(offset: [i32; 3]) -> IVec3 {
+    IVec3::new(offset[0], offset[1], offset[2])
+}
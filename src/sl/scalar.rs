@@ -9,8 +9,8 @@ use crate::ToSl;
 use super::{
     branches,
     dag::{BinaryOp, Expr, Trace, Type, UnaryOp},
-    primitives::{binary, cast, unary, value_arg},
-    FsInput, Object, Value, ValueNonArray,
+    primitives::{binary, built_in_1, cast, unary, value_arg},
+    FsInput, Object, Value, ValueNonArray, Vec2,
 };
 
 // Maps from logical scalar type to physical scalar type.
@@ -279,6 +279,13 @@ impl_integral_ops!(U32);
 
 impl_gen_type!(F32, Bool);
 
+impl_float_bits!(F32, I32, U32);
+
+impl_gen_type_integral!(I32, Bool);
+impl_gen_type_integral!(U32, Bool);
+
+impl_gen_type_signed!(I32);
+
 impl F32 {
     pub const ZERO: Self = F32(Trace::c(|| 0.0.to_sl().expr()));
     pub const ONE: Self = F32(Trace::c(|| 1.0.to_sl().expr()));
@@ -326,6 +333,24 @@ impl U32 {
     pub fn as_bool(self) -> Bool {
         cast(self)
     }
+
+    /// Converts two 16-bit floating-point values packed into `self` to a
+    /// vector. The first component is taken from the least significant bits.
+    pub fn unpack_half_2x16(self) -> Vec2 {
+        built_in_1("unpackHalf2x16", self)
+    }
+
+    /// Converts two 16-bit unsigned normalized values packed into `self` to a
+    /// vector with components in `[0, 1]`.
+    pub fn unpack_unorm_2x16(self) -> Vec2 {
+        built_in_1("unpackUnorm2x16", self)
+    }
+
+    /// Converts two 16-bit signed normalized values packed into `self` to a
+    /// vector with components in `[-1, 1]`.
+    pub fn unpack_snorm_2x16(self) -> Vec2 {
+        built_in_1("unpackSnorm2x16", self)
+    }
 }
 
 impl Bool {
@@ -3,23 +3,186 @@
 //! This is exposed only in order to make the internally generated source code
 //! more transparent. It is typically not necessary to use this module.
 
-use std::{iter::once, rc::Rc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+    iter::once,
+    rc::Rc,
+};
+
+use thiserror::Error;
 
 use crate::{
-    interface::{FragmentVisitor, UniformUnion, UniformVisitor, VertexVisitor},
+    interface::{FragmentVisitor, UniformBinding, UniformUnion, UniformVisitor, VertexVisitor},
     Block, FsInterface, Sl, VsInterface,
 };
 
 use super::{
     codegen,
-    dag::{Expr, SamplerType, Trace, Type},
+    const_fold::ConstFolder,
+    dag::{BinaryOp, BuiltInType, Expr, SamplerType, Trace, Type, UnaryOp},
     primitives::value_arg,
-    program_def::{ProgramDef, UniformBlockDef, UniformSamplerDef, VertexBlockDef},
+    program_def::{
+        Es100ProgramDef, InterpolationQualifier, ProgramDef, UniformBlockDef, UniformSamplerDef,
+        VertexBlockDef, WgslProgramDef,
+    },
     sig::{FromFsInput, FromVsInput, VsFunc, VsSig},
-    ColorSample, ColorSampler2d, ComparisonSampler2d, Derivatives, FsFunc, FsInput, FsSig,
-    Interpolant, IntoFullFsOutput, IntoFullVsOutput, Object, VsInput, I32,
+    ColorSample, ColorSampler2d, ColorSampler2dArray, ColorSampler3d, ColorSamplerCube,
+    ComparisonSampler2d, ComparisonSampler2dArray, ComparisonSamplerCube, Derivatives, FsFunc,
+    FsInput, FsSig, Interpolant, IntoFullFsOutput, IntoFullVsOutput, Object, VsInput, I32,
 };
 
+/// Options that control how programs are transpiled.
+#[derive(Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct TranspileOptions {
+    /// Whether to clamp the index of every subscript into the bounds of the
+    /// indexed array, vector or matrix.
+    ///
+    /// Out-of-bounds indices lead to undefined behavior on many drivers. This
+    /// is meant as a debugging aid, since it makes such accesses well-defined
+    /// at a small runtime cost.
+    pub clamp_subscripts: bool,
+
+    /// Whether to skip constant folding.
+    ///
+    /// By default, subexpressions that only depend on constants are evaluated
+    /// at transpile time. Disabling this keeps the generated code closer to
+    /// the shader as written, which can help with debugging.
+    pub disable_const_folding: bool,
+
+    /// The GLSL dialect to generate.
+    ///
+    /// [`Context`](crate::gl::Context) chooses this according to the version
+    /// of the underlying OpenGL context.
+    pub target: GlslTarget,
+}
+
+/// A GLSL dialect that shaders can be generated for.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GlslTarget {
+    /// GLSL ES 3.00, for OpenGL ES 3.0 and WebGL 2.
+    #[default]
+    Es300,
+
+    /// GLSL ES 3.10, for OpenGL ES 3.1 and above.
+    Es310,
+
+    /// GLSL 3.30 core, for OpenGL 3.3 core profile contexts.
+    Core330,
+
+    /// GLSL 4.10 core, for OpenGL 4.1 core profile contexts and above.
+    Core410,
+}
+
+impl GlslTarget {
+    /// Returns the version given in the `#version` directive.
+    pub fn version(self) -> &'static str {
+        use GlslTarget::*;
+
+        match self {
+            Es300 => "300 es",
+            Es310 => "310 es",
+            Core330 => "330 core",
+            Core410 => "410 core",
+        }
+    }
+
+    /// Returns whether this is a dialect of GLSL ES.
+    pub fn is_es(self) -> bool {
+        matches!(self, GlslTarget::Es300 | GlslTarget::Es310)
+    }
+
+    /// Returns whether uniform block bindings and texture units can be given
+    /// with `layout(binding = N)` in the shader source.
+    pub fn supports_layout_binding(self) -> bool {
+        matches!(self, GlslTarget::Es310)
+    }
+}
+
+/// A shader stage.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+}
+
+impl Display for ShaderStage {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => f.write_str("vertex"),
+            ShaderStage::Fragment => f.write_str("fragment"),
+        }
+    }
+}
+
+/// A feature that a program uses, but that has no equivalent in GLSL ES 1.00.
+#[derive(Debug, Clone, Error)]
+pub enum Es100Error {
+    #[error("vertex attribute `{0}` is not floating-point, which GLSL ES 1.00 requires")]
+    IntegerAttribute(String),
+
+    #[error("interpolant `{0}` is flat, but GLSL ES 1.00 only supports smooth interpolation")]
+    FlatInterpolant(String),
+
+    #[error("the program has {0} fragment outputs, but GLSL ES 1.00 only supports one")]
+    MultipleRenderTargets(usize),
+
+    #[error("fragment output `{0}` is not floating-point, which GLSL ES 1.00 requires")]
+    IntegerFragmentOutput(String),
+
+    #[error("GLSL ES 1.00 does not support writing the fragment depth")]
+    FragmentDepth,
+
+    #[error("GLSL ES 1.00 does not support the built-in variable `{0}`")]
+    BuiltInVariable(String),
+
+    #[error("GLSL ES 1.00 does not support `texelFetch`")]
+    TexelFetch,
+
+    #[error("GLSL ES 1.00 does not support the built-in function `{name}` in {stage} shaders")]
+    BuiltInFunction { name: String, stage: ShaderStage },
+
+    #[error("GLSL ES 1.00 only supports floating-point arguments for `{0}`")]
+    IntegerArgument(String),
+
+    #[error("GLSL ES 1.00 does not support the type `{0}`")]
+    UnsupportedType(BuiltInType),
+
+    #[error("GLSL ES 1.00 does not support the operator `{0}`")]
+    BinaryOperator(BinaryOp),
+
+    #[error("GLSL ES 1.00 does not support the operator `{0}`")]
+    UnaryOperator(UnaryOp),
+
+    #[error("GLSL ES 1.00 only supports arrays that are read from uniforms")]
+    ArrayValue,
+
+    #[error("GLSL ES 1.00 only supports subscripts with constant indices")]
+    DynamicIndex,
+
+    #[error("GLSL ES 1.00 only supports loops with constant bounds")]
+    DynamicLoopBounds,
+}
+
+/// A feature that a program uses, but that has no equivalent in WGSL.
+#[derive(Debug, Clone, Error)]
+pub enum WgslError {
+    #[error("WGSL does not support setting the point size")]
+    PointSize,
+
+    #[error("WGSL does not support reading the point coordinate")]
+    PointCoord,
+
+    #[error("WGSL does not support `{0}` on integer textures, since they cannot be filtered")]
+    IntegerSampling(String),
+
+    #[error("WGSL does not support comparisons at an explicit level of detail, as in `{0}`")]
+    ComparisonLod(String),
+
+    #[error("WGSL does not support comparisons with explicit derivatives, as in `{0}`")]
+    ComparisonGrad(String),
+}
+
 /// Transpiles a vertex shader and a fragment shader to GLSL source code.
 ///
 /// This is used internally by `posh` in order to create
@@ -59,37 +222,41 @@ where
     FSig: FsSig<C = VSig::C, W = VSig::W>,
     FFunc: FsFunc<FSig>,
 {
-    // TODO: Remove hardcoded path names.
-    let uniforms = U::shader_input("uniforms");
-
-    let (uniform_block_defs, uniform_sampler_defs) = {
-        // TODO: Remove hardcoded path names.
-        let mut visitor = CollectUniforms::default();
-        uniforms.visit("uniforms", &mut visitor);
-
-        (visitor.block_defs, visitor.sampler_defs)
-    };
-
-    let (vertex_block_defs, varying_outputs, vertex_shader_source) = {
-        let input = || VsInput {
-            vertex: <VSig as VsSig>::V::shader_input("vertex_input"),
-            vertex_id: value_arg::<I32>("gl_VertexID").as_u32(),
-            instance_id: value_arg::<I32>("gl_InstanceID").as_u32(),
-        };
-        let output = vertex_shader
-            .call(consts, uniforms.lhs(), FromVsInput::from_vs_input(input()))
-            .into_full_vs_output();
-
-        let varying_outputs = output.interp.shader_outputs("vertex_output");
-        let vertex_block_defs = {
-            // TODO: Remove hardcoded path names.
-            let mut visitor = CollectVertexBlocks::default();
-            input().vertex.visit("vertex_input", &mut visitor);
+    transpile_to_program_def_with_options::<U, VSig, VFunc, FSig, FFunc>(
+        &TranspileOptions::default(),
+        consts,
+        vertex_shader,
+        fragment_shader,
+    )
+}
 
-            visitor.block_defs
-        };
+/// Transpiles a vertex shader and a fragment shader with constant input to GLSL
+/// source code, using the given options.
+///
+/// See also [`transpile_to_program_def`].
+pub fn transpile_to_program_def_with_options<U, VSig, VFunc, FSig, FFunc>(
+    options: &TranspileOptions,
+    consts: &VSig::C,
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> ProgramDef
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = VSig::C, W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    let trace = trace_program::<U, VSig, VFunc, FSig, FFunc>(
+        options,
+        consts,
+        vertex_shader,
+        fragment_shader,
+    );
 
-        let attributes = vertex_block_defs
+    let vertex_shader_source = {
+        let attributes = trace
+            .vertex_block_defs
             .iter()
             .flat_map(|block_def| block_def.attributes.iter())
             .map(|attribute_def| {
@@ -99,146 +266,507 @@ where
                     Type::BuiltIn(attribute_def.ty),
                 )
             })
-            .chain(
-                // TODO: Interpolation type.
-                varying_outputs.iter().map(|(name, interp, expr)| {
-                    let kind = format!("{} out", interp.to_glsl());
+            .chain(trace.varying_outputs.iter().map(|(name, interp, expr)| {
+                let kind = format!("{} out", interp.to_glsl());
 
-                    (kind, name.clone(), expr.ty())
-                }),
-            );
-        let exprs = once(("gl_Position", output.clip_pos.expr()))
-            .chain(
-                varying_outputs
-                    .iter()
-                    .map(|(name, _, expr)| (name.as_str(), expr.clone())),
-            )
-            .chain(
-                output
-                    .point_size
-                    .map(|value| ("gl_PointSize", value.expr())),
-            );
+                (kind, name.clone(), expr.ty())
+            }));
 
         let mut source = String::new();
         codegen::write_shader_stage(
             &mut source,
-            &uniform_block_defs,
-            &uniform_sampler_defs,
+            options,
+            &trace.uniform_block_defs,
+            &trace.uniform_sampler_defs,
             attributes,
-            &exprs.collect::<Vec<_>>(),
+            &trace.vertex_outputs(),
         )
         .unwrap();
 
-        (vertex_block_defs, varying_outputs, source)
+        source
     };
 
-    // TODO: Remove hardcoded path names.
-    let uniforms = U::shader_input("uniforms");
-
     let fragment_shader_source = {
-        let input = FsInput {
-            interp: <VSig as VsSig>::W::shader_input("vertex_output"),
-            fragment_coord: value_arg("gl_FragCoord"),
-            front_facing: value_arg("gl_FrontFacing"),
-            point_coord: value_arg("gl_PointCoord"),
-            derivatives: Derivatives(()),
-        };
-        let output = fragment_shader
-            .call(consts, uniforms.rhs(), FromFsInput::from_fs_input(input))
-            .into_full_fs_output();
-
-        // TODO: Remove hardcoded path names.
-        let mut visitor = CollectOutputs::default();
-        output.fragment.visit("fragment_output", &mut visitor);
-
-        let attributes = varying_outputs
+        let attributes = trace
+            .varying_outputs
             .iter()
             .map(|(name, interp, expr)| {
                 let kind = format!("{} in", interp.to_glsl());
 
                 (kind, name.clone(), expr.ty())
             })
-            .chain(visitor.outputs.iter().enumerate().map(|(i, (name, expr))| {
-                (
-                    format!("layout(location = {i}) out"),
-                    name.clone(),
-                    expr.ty(),
-                )
-            }));
-
-        let exprs = visitor
-            .outputs
-            .iter()
-            .map(|(name, expr)| (name.as_str(), expr.clone()))
             .chain(
-                output
-                    .fragment_depth
-                    .map(|value| ("gl_FragDepth", value.expr())),
+                trace
+                    .fragment_outputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, expr))| {
+                        (
+                            format!("layout(location = {i}) out"),
+                            name.clone(),
+                            expr.ty(),
+                        )
+                    }),
             );
 
         let mut source = String::new();
         codegen::write_shader_stage(
             &mut source,
-            &uniform_block_defs,
-            &uniform_sampler_defs,
+            options,
+            &trace.uniform_block_defs,
+            &trace.uniform_sampler_defs,
             attributes,
-            &exprs.collect::<Vec<_>>(),
+            &trace.fragment_outputs(),
         )
         .unwrap();
 
         source
     };
 
-    Trace::clear_cache();
-
     ProgramDef {
-        uniform_block_defs,
-        uniform_sampler_defs,
-        vertex_block_defs,
+        uniform_block_defs: trace.uniform_block_defs,
+        uniform_sampler_defs: trace.uniform_sampler_defs,
+        vertex_block_defs: trace.vertex_block_defs,
         vertex_shader_source,
         fragment_shader_source,
     }
 }
 
+/// Transpiles a vertex shader and a fragment shader to a WGSL module.
+///
+/// The module contains the vertex entry point `vs_main` and the fragment entry
+/// point `fs_main`. See [`WgslProgramDef`] for how uniforms are bound.
+///
+/// Features without an equivalent in WGSL, such as setting the point size or
+/// sampling integer textures with filtering, result in an error.
+pub fn transpile_to_wgsl<U, VSig, VFunc, FSig, FFunc>(
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<WgslProgramDef, WgslError>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig<C = ()>,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = (), W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    transpile_to_wgsl_with_options::<U, VSig, VFunc, FSig, FFunc>(
+        &TranspileOptions::default(),
+        &(),
+        vertex_shader,
+        fragment_shader,
+    )
+}
+
+/// Transpiles a vertex shader and a fragment shader with constant input to a
+/// WGSL module, using the given options.
+///
+/// [`TranspileOptions::target`] is ignored. See also [`transpile_to_wgsl`].
+pub fn transpile_to_wgsl_with_options<U, VSig, VFunc, FSig, FFunc>(
+    options: &TranspileOptions,
+    consts: &VSig::C,
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<WgslProgramDef, WgslError>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = VSig::C, W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    let trace = trace_program::<U, VSig, VFunc, FSig, FFunc>(
+        options,
+        consts,
+        vertex_shader,
+        fragment_shader,
+    );
+
+    let vertex_attributes: Vec<_> = trace
+        .vertex_block_defs
+        .iter()
+        .flat_map(|block_def| block_def.attributes.iter())
+        .map(|attribute_def| (attribute_def.name.clone(), attribute_def.ty))
+        .collect();
+    let varyings: Vec<_> = trace
+        .varying_outputs
+        .iter()
+        .map(|(name, interp, _)| (name.clone(), *interp))
+        .collect();
+
+    let (source, bindings) = codegen::wgsl::write_program(
+        options,
+        &trace.uniform_block_defs,
+        &trace.uniform_sampler_defs,
+        &vertex_attributes,
+        &varyings,
+        &trace.vertex_outputs(),
+        &trace.fragment_outputs(),
+    )?;
+
+    Ok(WgslProgramDef {
+        bindings,
+        vertex_block_defs: trace.vertex_block_defs,
+        source,
+    })
+}
+
+/// Transpiles a vertex shader and a fragment shader to GLSL ES 1.00 source
+/// code, for WebGL 1.
+///
+/// GLSL ES 1.00 has no uniform blocks, so they are declared as plain uniforms
+/// and need to be uploaded by the host with
+/// [`upload_plain_uniforms`](crate::gl::upload_plain_uniforms). See
+/// [`Es100ProgramDef`] for details.
+/// The only fragment output is written to `gl_FragColor`, with missing
+/// components filled in from `(0, 0, 1)`.
+///
+/// Features without an equivalent in GLSL ES 1.00, such as unsigned integers,
+/// integer vertex attributes, `texelFetch` or multiple render targets, result
+/// in an error. The restrictions that WebGL 1 places on dynamic indexing are
+/// not checked.
+pub fn transpile_to_es100_program_def<U, VSig, VFunc, FSig, FFunc>(
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<Es100ProgramDef, Es100Error>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig<C = ()>,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = (), W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    transpile_to_es100_program_def_with_options::<U, VSig, VFunc, FSig, FFunc>(
+        &TranspileOptions::default(),
+        &(),
+        vertex_shader,
+        fragment_shader,
+    )
+}
+
+/// Transpiles a vertex shader and a fragment shader with constant input to GLSL
+/// ES 1.00 source code, using the given options.
+///
+/// [`TranspileOptions::target`] is ignored. See also
+/// [`transpile_to_es100_program_def`].
+pub fn transpile_to_es100_program_def_with_options<U, VSig, VFunc, FSig, FFunc>(
+    options: &TranspileOptions,
+    consts: &VSig::C,
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> Result<Es100ProgramDef, Es100Error>
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = VSig::C, W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    let trace = trace_program::<U, VSig, VFunc, FSig, FFunc>(
+        options,
+        consts,
+        vertex_shader,
+        fragment_shader,
+    );
+
+    let vertex_attributes: Vec<_> = trace
+        .vertex_block_defs
+        .iter()
+        .flat_map(|block_def| block_def.attributes.iter())
+        .map(|attribute_def| (attribute_def.name.clone(), attribute_def.ty))
+        .collect();
+    let varyings: Vec<_> = trace
+        .varying_outputs
+        .iter()
+        .map(|(name, interp, _)| (name.clone(), *interp))
+        .collect();
+
+    let (vertex_shader_source, fragment_shader_source) = codegen::es100::write_program(
+        options,
+        &trace.uniform_block_defs,
+        &trace.uniform_sampler_defs,
+        &vertex_attributes,
+        &varyings,
+        &trace.vertex_outputs(),
+        &trace.fragment_outputs(),
+    )?;
+
+    Ok(Es100ProgramDef {
+        plain_uniform_defs: codegen::es100::plain_uniform_defs(&trace.uniform_block_defs),
+        uniform_block_defs: trace.uniform_block_defs,
+        uniform_sampler_defs: trace.uniform_sampler_defs,
+        vertex_block_defs: trace.vertex_block_defs,
+        vertex_shader_source,
+        fragment_shader_source,
+    })
+}
+
+// The traced expressions of a program, simplified by constant folding, before
+// they are written in a target language.
+pub(crate) struct ProgramTrace {
+    pub(crate) uniform_block_defs: Vec<UniformBlockDef>,
+    pub(crate) uniform_sampler_defs: Vec<UniformSamplerDef>,
+    pub(crate) vertex_block_defs: Vec<VertexBlockDef>,
+    pub(crate) clip_pos: Rc<Expr>,
+    pub(crate) point_size: Option<Rc<Expr>>,
+    pub(crate) varying_outputs: Vec<(String, InterpolationQualifier, Rc<Expr>)>,
+    pub(crate) fragment_outputs: Vec<(String, Rc<Expr>)>,
+    pub(crate) fragment_depth: Option<Rc<Expr>>,
+}
+
+impl ProgramTrace {
+    fn vertex_outputs(&self) -> Vec<(&str, Rc<Expr>)> {
+        once(("gl_Position", self.clip_pos.clone()))
+            .chain(
+                self.varying_outputs
+                    .iter()
+                    .map(|(name, _, expr)| (name.as_str(), expr.clone())),
+            )
+            .chain(self.point_size.clone().map(|expr| ("gl_PointSize", expr)))
+            .collect()
+    }
+
+    fn fragment_outputs(&self) -> Vec<(&str, Rc<Expr>)> {
+        self.fragment_outputs
+            .iter()
+            .map(|(name, expr)| (name.as_str(), expr.clone()))
+            .chain(
+                self.fragment_depth
+                    .clone()
+                    .map(|expr| ("gl_FragDepth", expr)),
+            )
+            .collect()
+    }
+}
+
+pub(crate) fn trace_program<U, VSig, VFunc, FSig, FFunc>(
+    options: &TranspileOptions,
+    consts: &VSig::C,
+    vertex_shader: VFunc,
+    fragment_shader: FFunc,
+) -> ProgramTrace
+where
+    U: UniformUnion<VSig::U, FSig::U>,
+    VSig: VsSig,
+    VFunc: VsFunc<VSig>,
+    FSig: FsSig<C = VSig::C, W = VSig::W>,
+    FFunc: FsFunc<FSig>,
+{
+    // TODO: Remove hardcoded path names.
+    let uniforms = U::shader_input("uniforms");
+
+    let (uniform_block_defs, uniform_sampler_defs) = {
+        // TODO: Remove hardcoded path names.
+        let mut visitor = CollectUniforms::default();
+        uniforms.visit("uniforms", &mut visitor);
+
+        visitor.finish()
+    };
+
+    let input = || VsInput {
+        vertex: <VSig as VsSig>::V::shader_input("vertex_input"),
+        vertex_id: value_arg::<I32>("gl_VertexID").as_u32(),
+        instance_id: value_arg::<I32>("gl_InstanceID").as_u32(),
+    };
+    let vertex_output = vertex_shader
+        .call(consts, uniforms.lhs(), FromVsInput::from_vs_input(input()))
+        .into_full_vs_output();
+
+    let varying_outputs = vertex_output.interp.shader_outputs("vertex_output");
+    let vertex_block_defs = {
+        // TODO: Remove hardcoded path names.
+        let mut visitor = CollectVertexBlocks::default();
+        input().vertex.visit("vertex_input", &mut visitor);
+
+        visitor.block_defs
+    };
+
+    // TODO: Remove hardcoded path names.
+    let uniforms = U::shader_input("uniforms");
+
+    let input = FsInput {
+        interp: <VSig as VsSig>::W::shader_input("vertex_output"),
+        fragment_coord: value_arg("gl_FragCoord"),
+        front_facing: value_arg("gl_FrontFacing"),
+        point_coord: value_arg("gl_PointCoord"),
+        derivatives: Derivatives(()),
+    };
+    let fragment_output = fragment_shader
+        .call(consts, uniforms.rhs(), FromFsInput::from_fs_input(input))
+        .into_full_fs_output();
+
+    // TODO: Remove hardcoded path names.
+    let mut visitor = CollectOutputs::default();
+    fragment_output
+        .fragment
+        .visit("fragment_output", &mut visitor);
+
+    // All outputs are folded together, so that expressions which they share
+    // remain shared.
+    let mut folder = ConstFolder::default();
+    let mut fold = |expr: &Rc<Expr>| {
+        if options.disable_const_folding {
+            expr.clone()
+        } else {
+            folder.fold(expr)
+        }
+    };
+
+    let trace = ProgramTrace {
+        uniform_block_defs,
+        uniform_sampler_defs,
+        vertex_block_defs,
+        clip_pos: fold(&vertex_output.clip_pos.expr()),
+        point_size: vertex_output.point_size.map(|value| fold(&value.expr())),
+        varying_outputs: varying_outputs
+            .into_iter()
+            .map(|(name, interp, expr)| (name, interp, fold(&expr)))
+            .collect(),
+        fragment_outputs: visitor
+            .outputs
+            .into_iter()
+            .map(|(name, expr)| (name, fold(&expr)))
+            .collect(),
+        fragment_depth: fragment_output
+            .fragment_depth
+            .map(|value| fold(&value.expr())),
+    };
+
+    Trace::clear_cache();
+
+    trace
+}
+
 #[derive(Default)]
 struct CollectUniforms {
     sampler_defs: Vec<UniformSamplerDef>,
     block_defs: Vec<UniformBlockDef>,
+    bindings: BTreeMap<String, UniformBinding>,
+    implicit_samplers: Vec<usize>,
+    implicit_blocks: Vec<usize>,
+}
+
+impl CollectUniforms {
+    /// Assigns the lowest free binding points and texture units to all uniforms
+    /// without an explicit binding.
+    ///
+    /// Duplicate explicit bindings are kept, so that they are reported by
+    /// program validation.
+    fn finish(mut self) -> (Vec<UniformBlockDef>, Vec<UniformSamplerDef>) {
+        if let Some(path) = self.bindings.keys().next() {
+            panic!("uniform binding for `{path}` does not refer to a block or sampler");
+        }
+
+        let used: BTreeSet<_> = self
+            .block_defs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.implicit_blocks.contains(i))
+            .map(|(_, def)| def.location)
+            .collect();
+        let mut free = (0..).filter(|location| !used.contains(location));
+
+        for &i in &self.implicit_blocks {
+            self.block_defs[i].location = free.next().unwrap();
+        }
+
+        let used: BTreeSet<_> = self
+            .sampler_defs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.implicit_samplers.contains(i))
+            .map(|(_, def)| def.texture_unit)
+            .collect();
+        let mut free = (0..).filter(|unit| !used.contains(unit));
+
+        for &i in &self.implicit_samplers {
+            self.sampler_defs[i].texture_unit = free.next().unwrap();
+        }
+
+        (self.block_defs, self.sampler_defs)
+    }
+
+    fn push_sampler(&mut self, path: &str, ty: SamplerType) {
+        let texture_unit = match self.bindings.remove(path) {
+            Some(UniformBinding::TextureUnit(unit)) => unit,
+            Some(UniformBinding::Block(_)) => {
+                panic!("sampler `{path}` must be given a texture `unit`, not a `binding`")
+            }
+            None => {
+                self.implicit_samplers.push(self.sampler_defs.len());
+
+                0
+            }
+        };
+
+        let sampler_def = UniformSamplerDef {
+            name: path.to_string(),
+            ty,
+            texture_unit,
+        };
+
+        self.sampler_defs.push(sampler_def);
+    }
 }
 
 impl<'a> UniformVisitor<'a, Sl> for CollectUniforms {
+    fn accept_binding(&mut self, path: &str, binding: UniformBinding) {
+        self.bindings.insert(path.to_string(), binding);
+    }
+
     fn accept_block<U: Block<Sl>>(&mut self, path: &str, _: &U) {
-        // TODO: Allow user-specified uniform block locations.
+        let location = match self.bindings.remove(path) {
+            Some(UniformBinding::Block(location)) => location,
+            Some(UniformBinding::TextureUnit(_)) => {
+                panic!("uniform block `{path}` must be given a `binding`, not a texture `unit`")
+            }
+            None => {
+                self.implicit_blocks.push(self.block_defs.len());
+
+                0
+            }
+        };
+
         let block_def = UniformBlockDef {
             block_name: path.to_string() + "_posh_block",
             arg_name: path.to_string(),
             ty: <U::Sl as Object>::ty(),
-            location: self.block_defs.len(),
+            location,
         };
 
         self.block_defs.push(block_def)
     }
 
     fn accept_color_sampler_2d<S: ColorSample>(&mut self, path: &str, _: &ColorSampler2d<S>) {
-        // TODO: Allow user-specified sampler texture units.
-        let sampler_def = UniformSamplerDef {
-            name: path.to_string(),
-            ty: S::SAMPLER_TYPE,
-            texture_unit: self.sampler_defs.len(),
-        };
-
-        self.sampler_defs.push(sampler_def);
+        self.push_sampler(path, S::SAMPLER_TYPE);
     }
 
     fn accept_comparison_sampler_2d(&mut self, path: &str, _: &ComparisonSampler2d) {
-        // TODO: Allow user-specified sampler texture units.
-        let sampler_def = UniformSamplerDef {
-            name: path.to_string(),
-            ty: SamplerType::ComparisonSampler2d,
-            texture_unit: self.sampler_defs.len(),
-        };
+        self.push_sampler(path, SamplerType::ComparisonSampler2d);
+    }
 
-        self.sampler_defs.push(sampler_def);
+    fn accept_color_sampler_cube<S: ColorSample>(&mut self, path: &str, _: &ColorSamplerCube<S>) {
+        self.push_sampler(path, S::SAMPLER_CUBE_TYPE);
+    }
+
+    fn accept_comparison_sampler_cube(&mut self, path: &str, _: &ComparisonSamplerCube) {
+        self.push_sampler(path, SamplerType::ComparisonSamplerCube);
+    }
+
+    fn accept_color_sampler_3d<S: ColorSample>(&mut self, path: &str, _: &ColorSampler3d<S>) {
+        self.push_sampler(path, S::SAMPLER_3D_TYPE);
+    }
+
+    fn accept_color_sampler_2d_array<S: ColorSample>(
+        &mut self,
+        path: &str,
+        _: &ColorSampler2dArray<S>,
+    ) {
+        self.push_sampler(path, S::SAMPLER_2D_ARRAY_TYPE);
+    }
+
+    fn accept_comparison_sampler_2d_array(&mut self, path: &str, _: &ComparisonSampler2dArray) {
+        self.push_sampler(path, SamplerType::ComparisonSampler2dArray);
     }
 }
 
@@ -262,7 +790,7 @@ struct CollectOutputs {
     outputs: Vec<(String, Rc<Expr>)>,
 }
 
-impl<'a> FragmentVisitor<'a, Sl> for CollectOutputs {
+impl FragmentVisitor<Sl> for CollectOutputs {
     fn accept<S: ColorSample>(&mut self, path: &str, output: &S) {
         self.outputs.push((path.to_string(), output.expr()));
     }
@@ -7,6 +7,7 @@ use std::{
 use crate::ToSl;
 
 use super::{
+    branch,
     dag::{BinaryOp, BuiltInType, Expr, Type, UnaryOp},
     primitives::{
         binary, built_in_1, built_in_2, cast, common_field_base, field, unary, value_arg,
@@ -104,15 +105,62 @@ macro_rules! impl_value {
                 <Self as Value>::ne(self, right)
             }
 
-            pub fn get(self, index: impl ToSl<Output = U32>) -> F32 {
-                // FIXME: Prevent out-of-bounds access.
+            // Returns the swizzle `name` of `self`, where `components` must
+            // hold the components selected by the swizzle. If `self` is a
+            // view of a single expression, the swizzle is applied to that
+            // expression directly, so that codegen can produce `v.zyx` instead
+            // of `vec3(v.z, v.y, v.x)`.
+            fn swizzle<V: Value>(&self, name: &'static str, components: V) -> V {
+                let base = common_field_base(
+                    &Self::ty(),
+                    [$(std::stringify!($member)),+].into_iter(),
+                    &[$(self.$member.expr()),+],
+                );
+
+                if let Some(base) = base {
+                    field(base, name)
+                } else {
+                    components
+                }
+            }
+
+            // The number of components.
+            const LEN: u32 = [$(std::stringify!($member)),+].len() as u32;
+
+            /// Returns the component at `index`.
+            ///
+            /// Accessing a component out of bounds leads to undefined behavior.
+            pub fn get(self, index: impl ToSl<Output = U32>) -> $scalar {
                 let base = self.expr();
                 let index = index.to_sl().expr();
-                let ty = F32::ty();
+                let ty = $scalar::ty();
 
                 let expr = Expr::Subscript { base, index, ty };
 
-                F32::from_expr(expr)
+                $scalar::from_expr(expr)
+            }
+
+            /// Returns the component at `index`, clamping `index` to the last
+            /// component.
+            pub fn get_clamped(self, index: impl ToSl<Output = U32>) -> $scalar {
+                self.get(index.to_sl().min(Self::LEN - 1))
+            }
+
+            /// Returns the component at `index` modulo the number of components.
+            pub fn get_wrapped(self, index: impl ToSl<Output = U32>) -> $scalar {
+                self.get(index.to_sl() % Self::LEN)
+            }
+
+            /// Returns the component at `index`, or `default` if `index` is out of
+            /// bounds.
+            pub fn get_or(
+                self,
+                index: impl ToSl<Output = U32>,
+                default: impl ToSl<Output = $scalar>,
+            ) -> $scalar {
+                let index = index.to_sl();
+
+                branch(index.lt(Self::LEN), self.get(index), default)
             }
         }
     };
@@ -318,6 +366,569 @@ macro_rules! impl_ops {
     };
 }
 
+// Implements swizzles with result type `$target` for `$vec`.
+//
+// Each swizzle is given by its name in the `xyzw`, `rgba`, and `stpq` sets of
+// component names, followed by the components that it selects.
+macro_rules! impl_swizzles {
+    (
+        $vec:ident,
+        $target:ident,
+        $($xyzw:ident / $rgba:ident / $stpq:ident ($($member:ident),+)),+ $(,)?
+    ) => {
+        impl $vec {
+            $(
+                pub fn $xyzw(&self) -> $target {
+                    self.swizzle(stringify!($xyzw), $target::new($(self.$member),+))
+                }
+
+                pub fn $rgba(&self) -> $target {
+                    self.$xyzw()
+                }
+
+                pub fn $stpq(&self) -> $target {
+                    self.$xyzw()
+                }
+            )+
+        }
+    };
+}
+
+// Implements all swizzles for two-dimensional `$vec`.
+macro_rules! impl_swizzles_vec2 {
+    ($vec:ident, $vec2:ident, $vec3:ident, $vec4:ident) => {
+        impl_swizzles!(
+            $vec,
+            $vec2,
+            xx / rr / ss(x, x),
+            xy / rg / st(x, y),
+            yx / gr / ts(y, x),
+            yy / gg / tt(y, y),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec3,
+            xxx / rrr / sss(x, x, x),
+            xxy / rrg / sst(x, x, y),
+            xyx / rgr / sts(x, y, x),
+            xyy / rgg / stt(x, y, y),
+            yxx / grr / tss(y, x, x),
+            yxy / grg / tst(y, x, y),
+            yyx / ggr / tts(y, y, x),
+            yyy / ggg / ttt(y, y, y),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec4,
+            xxxx / rrrr / ssss(x, x, x, x),
+            xxxy / rrrg / ssst(x, x, x, y),
+            xxyx / rrgr / ssts(x, x, y, x),
+            xxyy / rrgg / sstt(x, x, y, y),
+            xyxx / rgrr / stss(x, y, x, x),
+            xyxy / rgrg / stst(x, y, x, y),
+            xyyx / rggr / stts(x, y, y, x),
+            xyyy / rggg / sttt(x, y, y, y),
+            yxxx / grrr / tsss(y, x, x, x),
+            yxxy / grrg / tsst(y, x, x, y),
+            yxyx / grgr / tsts(y, x, y, x),
+            yxyy / grgg / tstt(y, x, y, y),
+            yyxx / ggrr / ttss(y, y, x, x),
+            yyxy / ggrg / ttst(y, y, x, y),
+            yyyx / gggr / ttts(y, y, y, x),
+            yyyy / gggg / tttt(y, y, y, y),
+        );
+    };
+}
+
+// Implements all swizzles for three-dimensional `$vec`.
+macro_rules! impl_swizzles_vec3 {
+    ($vec:ident, $vec2:ident, $vec3:ident, $vec4:ident) => {
+        impl_swizzles!(
+            $vec,
+            $vec2,
+            xx / rr / ss(x, x),
+            xy / rg / st(x, y),
+            xz / rb / sp(x, z),
+            yx / gr / ts(y, x),
+            yy / gg / tt(y, y),
+            yz / gb / tp(y, z),
+            zx / br / ps(z, x),
+            zy / bg / pt(z, y),
+            zz / bb / pp(z, z),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec3,
+            xxx / rrr / sss(x, x, x),
+            xxy / rrg / sst(x, x, y),
+            xxz / rrb / ssp(x, x, z),
+            xyx / rgr / sts(x, y, x),
+            xyy / rgg / stt(x, y, y),
+            xyz / rgb / stp(x, y, z),
+            xzx / rbr / sps(x, z, x),
+            xzy / rbg / spt(x, z, y),
+            xzz / rbb / spp(x, z, z),
+            yxx / grr / tss(y, x, x),
+            yxy / grg / tst(y, x, y),
+            yxz / grb / tsp(y, x, z),
+            yyx / ggr / tts(y, y, x),
+            yyy / ggg / ttt(y, y, y),
+            yyz / ggb / ttp(y, y, z),
+            yzx / gbr / tps(y, z, x),
+            yzy / gbg / tpt(y, z, y),
+            yzz / gbb / tpp(y, z, z),
+            zxx / brr / pss(z, x, x),
+            zxy / brg / pst(z, x, y),
+            zxz / brb / psp(z, x, z),
+            zyx / bgr / pts(z, y, x),
+            zyy / bgg / ptt(z, y, y),
+            zyz / bgb / ptp(z, y, z),
+            zzx / bbr / pps(z, z, x),
+            zzy / bbg / ppt(z, z, y),
+            zzz / bbb / ppp(z, z, z),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec4,
+            xxxx / rrrr / ssss(x, x, x, x),
+            xxxy / rrrg / ssst(x, x, x, y),
+            xxxz / rrrb / sssp(x, x, x, z),
+            xxyx / rrgr / ssts(x, x, y, x),
+            xxyy / rrgg / sstt(x, x, y, y),
+            xxyz / rrgb / sstp(x, x, y, z),
+            xxzx / rrbr / ssps(x, x, z, x),
+            xxzy / rrbg / sspt(x, x, z, y),
+            xxzz / rrbb / sspp(x, x, z, z),
+            xyxx / rgrr / stss(x, y, x, x),
+            xyxy / rgrg / stst(x, y, x, y),
+            xyxz / rgrb / stsp(x, y, x, z),
+            xyyx / rggr / stts(x, y, y, x),
+            xyyy / rggg / sttt(x, y, y, y),
+            xyyz / rggb / sttp(x, y, y, z),
+            xyzx / rgbr / stps(x, y, z, x),
+            xyzy / rgbg / stpt(x, y, z, y),
+            xyzz / rgbb / stpp(x, y, z, z),
+            xzxx / rbrr / spss(x, z, x, x),
+            xzxy / rbrg / spst(x, z, x, y),
+            xzxz / rbrb / spsp(x, z, x, z),
+            xzyx / rbgr / spts(x, z, y, x),
+            xzyy / rbgg / sptt(x, z, y, y),
+            xzyz / rbgb / sptp(x, z, y, z),
+            xzzx / rbbr / spps(x, z, z, x),
+            xzzy / rbbg / sppt(x, z, z, y),
+            xzzz / rbbb / sppp(x, z, z, z),
+            yxxx / grrr / tsss(y, x, x, x),
+            yxxy / grrg / tsst(y, x, x, y),
+            yxxz / grrb / tssp(y, x, x, z),
+            yxyx / grgr / tsts(y, x, y, x),
+            yxyy / grgg / tstt(y, x, y, y),
+            yxyz / grgb / tstp(y, x, y, z),
+            yxzx / grbr / tsps(y, x, z, x),
+            yxzy / grbg / tspt(y, x, z, y),
+            yxzz / grbb / tspp(y, x, z, z),
+            yyxx / ggrr / ttss(y, y, x, x),
+            yyxy / ggrg / ttst(y, y, x, y),
+            yyxz / ggrb / ttsp(y, y, x, z),
+            yyyx / gggr / ttts(y, y, y, x),
+            yyyy / gggg / tttt(y, y, y, y),
+            yyyz / gggb / tttp(y, y, y, z),
+            yyzx / ggbr / ttps(y, y, z, x),
+            yyzy / ggbg / ttpt(y, y, z, y),
+            yyzz / ggbb / ttpp(y, y, z, z),
+            yzxx / gbrr / tpss(y, z, x, x),
+            yzxy / gbrg / tpst(y, z, x, y),
+            yzxz / gbrb / tpsp(y, z, x, z),
+            yzyx / gbgr / tpts(y, z, y, x),
+            yzyy / gbgg / tptt(y, z, y, y),
+            yzyz / gbgb / tptp(y, z, y, z),
+            yzzx / gbbr / tpps(y, z, z, x),
+            yzzy / gbbg / tppt(y, z, z, y),
+            yzzz / gbbb / tppp(y, z, z, z),
+            zxxx / brrr / psss(z, x, x, x),
+            zxxy / brrg / psst(z, x, x, y),
+            zxxz / brrb / pssp(z, x, x, z),
+            zxyx / brgr / psts(z, x, y, x),
+            zxyy / brgg / pstt(z, x, y, y),
+            zxyz / brgb / pstp(z, x, y, z),
+            zxzx / brbr / psps(z, x, z, x),
+            zxzy / brbg / pspt(z, x, z, y),
+            zxzz / brbb / pspp(z, x, z, z),
+            zyxx / bgrr / ptss(z, y, x, x),
+            zyxy / bgrg / ptst(z, y, x, y),
+            zyxz / bgrb / ptsp(z, y, x, z),
+            zyyx / bggr / ptts(z, y, y, x),
+            zyyy / bggg / pttt(z, y, y, y),
+            zyyz / bggb / pttp(z, y, y, z),
+            zyzx / bgbr / ptps(z, y, z, x),
+            zyzy / bgbg / ptpt(z, y, z, y),
+            zyzz / bgbb / ptpp(z, y, z, z),
+            zzxx / bbrr / ppss(z, z, x, x),
+            zzxy / bbrg / ppst(z, z, x, y),
+            zzxz / bbrb / ppsp(z, z, x, z),
+            zzyx / bbgr / ppts(z, z, y, x),
+            zzyy / bbgg / pptt(z, z, y, y),
+            zzyz / bbgb / pptp(z, z, y, z),
+            zzzx / bbbr / ppps(z, z, z, x),
+            zzzy / bbbg / pppt(z, z, z, y),
+            zzzz / bbbb / pppp(z, z, z, z),
+        );
+    };
+}
+
+// Implements all swizzles for four-dimensional `$vec`.
+macro_rules! impl_swizzles_vec4 {
+    ($vec:ident, $vec2:ident, $vec3:ident, $vec4:ident) => {
+        impl_swizzles!(
+            $vec,
+            $vec2,
+            xx / rr / ss(x, x),
+            xy / rg / st(x, y),
+            xz / rb / sp(x, z),
+            xw / ra / sq(x, w),
+            yx / gr / ts(y, x),
+            yy / gg / tt(y, y),
+            yz / gb / tp(y, z),
+            yw / ga / tq(y, w),
+            zx / br / ps(z, x),
+            zy / bg / pt(z, y),
+            zz / bb / pp(z, z),
+            zw / ba / pq(z, w),
+            wx / ar / qs(w, x),
+            wy / ag / qt(w, y),
+            wz / ab / qp(w, z),
+            ww / aa / qq(w, w),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec3,
+            xxx / rrr / sss(x, x, x),
+            xxy / rrg / sst(x, x, y),
+            xxz / rrb / ssp(x, x, z),
+            xxw / rra / ssq(x, x, w),
+            xyx / rgr / sts(x, y, x),
+            xyy / rgg / stt(x, y, y),
+            xyz / rgb / stp(x, y, z),
+            xyw / rga / stq(x, y, w),
+            xzx / rbr / sps(x, z, x),
+            xzy / rbg / spt(x, z, y),
+            xzz / rbb / spp(x, z, z),
+            xzw / rba / spq(x, z, w),
+            xwx / rar / sqs(x, w, x),
+            xwy / rag / sqt(x, w, y),
+            xwz / rab / sqp(x, w, z),
+            xww / raa / sqq(x, w, w),
+            yxx / grr / tss(y, x, x),
+            yxy / grg / tst(y, x, y),
+            yxz / grb / tsp(y, x, z),
+            yxw / gra / tsq(y, x, w),
+            yyx / ggr / tts(y, y, x),
+            yyy / ggg / ttt(y, y, y),
+            yyz / ggb / ttp(y, y, z),
+            yyw / gga / ttq(y, y, w),
+            yzx / gbr / tps(y, z, x),
+            yzy / gbg / tpt(y, z, y),
+            yzz / gbb / tpp(y, z, z),
+            yzw / gba / tpq(y, z, w),
+            ywx / gar / tqs(y, w, x),
+            ywy / gag / tqt(y, w, y),
+            ywz / gab / tqp(y, w, z),
+            yww / gaa / tqq(y, w, w),
+            zxx / brr / pss(z, x, x),
+            zxy / brg / pst(z, x, y),
+            zxz / brb / psp(z, x, z),
+            zxw / bra / psq(z, x, w),
+            zyx / bgr / pts(z, y, x),
+            zyy / bgg / ptt(z, y, y),
+            zyz / bgb / ptp(z, y, z),
+            zyw / bga / ptq(z, y, w),
+            zzx / bbr / pps(z, z, x),
+            zzy / bbg / ppt(z, z, y),
+            zzz / bbb / ppp(z, z, z),
+            zzw / bba / ppq(z, z, w),
+            zwx / bar / pqs(z, w, x),
+            zwy / bag / pqt(z, w, y),
+            zwz / bab / pqp(z, w, z),
+            zww / baa / pqq(z, w, w),
+            wxx / arr / qss(w, x, x),
+            wxy / arg / qst(w, x, y),
+            wxz / arb / qsp(w, x, z),
+            wxw / ara / qsq(w, x, w),
+            wyx / agr / qts(w, y, x),
+            wyy / agg / qtt(w, y, y),
+            wyz / agb / qtp(w, y, z),
+            wyw / aga / qtq(w, y, w),
+            wzx / abr / qps(w, z, x),
+            wzy / abg / qpt(w, z, y),
+            wzz / abb / qpp(w, z, z),
+            wzw / aba / qpq(w, z, w),
+            wwx / aar / qqs(w, w, x),
+            wwy / aag / qqt(w, w, y),
+            wwz / aab / qqp(w, w, z),
+            www / aaa / qqq(w, w, w),
+        );
+        impl_swizzles!(
+            $vec,
+            $vec4,
+            xxxx / rrrr / ssss(x, x, x, x),
+            xxxy / rrrg / ssst(x, x, x, y),
+            xxxz / rrrb / sssp(x, x, x, z),
+            xxxw / rrra / sssq(x, x, x, w),
+            xxyx / rrgr / ssts(x, x, y, x),
+            xxyy / rrgg / sstt(x, x, y, y),
+            xxyz / rrgb / sstp(x, x, y, z),
+            xxyw / rrga / sstq(x, x, y, w),
+            xxzx / rrbr / ssps(x, x, z, x),
+            xxzy / rrbg / sspt(x, x, z, y),
+            xxzz / rrbb / sspp(x, x, z, z),
+            xxzw / rrba / sspq(x, x, z, w),
+            xxwx / rrar / ssqs(x, x, w, x),
+            xxwy / rrag / ssqt(x, x, w, y),
+            xxwz / rrab / ssqp(x, x, w, z),
+            xxww / rraa / ssqq(x, x, w, w),
+            xyxx / rgrr / stss(x, y, x, x),
+            xyxy / rgrg / stst(x, y, x, y),
+            xyxz / rgrb / stsp(x, y, x, z),
+            xyxw / rgra / stsq(x, y, x, w),
+            xyyx / rggr / stts(x, y, y, x),
+            xyyy / rggg / sttt(x, y, y, y),
+            xyyz / rggb / sttp(x, y, y, z),
+            xyyw / rgga / sttq(x, y, y, w),
+            xyzx / rgbr / stps(x, y, z, x),
+            xyzy / rgbg / stpt(x, y, z, y),
+            xyzz / rgbb / stpp(x, y, z, z),
+            xyzw / rgba / stpq(x, y, z, w),
+            xywx / rgar / stqs(x, y, w, x),
+            xywy / rgag / stqt(x, y, w, y),
+            xywz / rgab / stqp(x, y, w, z),
+            xyww / rgaa / stqq(x, y, w, w),
+            xzxx / rbrr / spss(x, z, x, x),
+            xzxy / rbrg / spst(x, z, x, y),
+            xzxz / rbrb / spsp(x, z, x, z),
+            xzxw / rbra / spsq(x, z, x, w),
+            xzyx / rbgr / spts(x, z, y, x),
+            xzyy / rbgg / sptt(x, z, y, y),
+            xzyz / rbgb / sptp(x, z, y, z),
+            xzyw / rbga / sptq(x, z, y, w),
+            xzzx / rbbr / spps(x, z, z, x),
+            xzzy / rbbg / sppt(x, z, z, y),
+            xzzz / rbbb / sppp(x, z, z, z),
+            xzzw / rbba / sppq(x, z, z, w),
+            xzwx / rbar / spqs(x, z, w, x),
+            xzwy / rbag / spqt(x, z, w, y),
+            xzwz / rbab / spqp(x, z, w, z),
+            xzww / rbaa / spqq(x, z, w, w),
+            xwxx / rarr / sqss(x, w, x, x),
+            xwxy / rarg / sqst(x, w, x, y),
+            xwxz / rarb / sqsp(x, w, x, z),
+            xwxw / rara / sqsq(x, w, x, w),
+            xwyx / ragr / sqts(x, w, y, x),
+            xwyy / ragg / sqtt(x, w, y, y),
+            xwyz / ragb / sqtp(x, w, y, z),
+            xwyw / raga / sqtq(x, w, y, w),
+            xwzx / rabr / sqps(x, w, z, x),
+            xwzy / rabg / sqpt(x, w, z, y),
+            xwzz / rabb / sqpp(x, w, z, z),
+            xwzw / raba / sqpq(x, w, z, w),
+            xwwx / raar / sqqs(x, w, w, x),
+            xwwy / raag / sqqt(x, w, w, y),
+            xwwz / raab / sqqp(x, w, w, z),
+            xwww / raaa / sqqq(x, w, w, w),
+            yxxx / grrr / tsss(y, x, x, x),
+            yxxy / grrg / tsst(y, x, x, y),
+            yxxz / grrb / tssp(y, x, x, z),
+            yxxw / grra / tssq(y, x, x, w),
+            yxyx / grgr / tsts(y, x, y, x),
+            yxyy / grgg / tstt(y, x, y, y),
+            yxyz / grgb / tstp(y, x, y, z),
+            yxyw / grga / tstq(y, x, y, w),
+            yxzx / grbr / tsps(y, x, z, x),
+            yxzy / grbg / tspt(y, x, z, y),
+            yxzz / grbb / tspp(y, x, z, z),
+            yxzw / grba / tspq(y, x, z, w),
+            yxwx / grar / tsqs(y, x, w, x),
+            yxwy / grag / tsqt(y, x, w, y),
+            yxwz / grab / tsqp(y, x, w, z),
+            yxww / graa / tsqq(y, x, w, w),
+            yyxx / ggrr / ttss(y, y, x, x),
+            yyxy / ggrg / ttst(y, y, x, y),
+            yyxz / ggrb / ttsp(y, y, x, z),
+            yyxw / ggra / ttsq(y, y, x, w),
+            yyyx / gggr / ttts(y, y, y, x),
+            yyyy / gggg / tttt(y, y, y, y),
+            yyyz / gggb / tttp(y, y, y, z),
+            yyyw / ggga / tttq(y, y, y, w),
+            yyzx / ggbr / ttps(y, y, z, x),
+            yyzy / ggbg / ttpt(y, y, z, y),
+            yyzz / ggbb / ttpp(y, y, z, z),
+            yyzw / ggba / ttpq(y, y, z, w),
+            yywx / ggar / ttqs(y, y, w, x),
+            yywy / ggag / ttqt(y, y, w, y),
+            yywz / ggab / ttqp(y, y, w, z),
+            yyww / ggaa / ttqq(y, y, w, w),
+            yzxx / gbrr / tpss(y, z, x, x),
+            yzxy / gbrg / tpst(y, z, x, y),
+            yzxz / gbrb / tpsp(y, z, x, z),
+            yzxw / gbra / tpsq(y, z, x, w),
+            yzyx / gbgr / tpts(y, z, y, x),
+            yzyy / gbgg / tptt(y, z, y, y),
+            yzyz / gbgb / tptp(y, z, y, z),
+            yzyw / gbga / tptq(y, z, y, w),
+            yzzx / gbbr / tpps(y, z, z, x),
+            yzzy / gbbg / tppt(y, z, z, y),
+            yzzz / gbbb / tppp(y, z, z, z),
+            yzzw / gbba / tppq(y, z, z, w),
+            yzwx / gbar / tpqs(y, z, w, x),
+            yzwy / gbag / tpqt(y, z, w, y),
+            yzwz / gbab / tpqp(y, z, w, z),
+            yzww / gbaa / tpqq(y, z, w, w),
+            ywxx / garr / tqss(y, w, x, x),
+            ywxy / garg / tqst(y, w, x, y),
+            ywxz / garb / tqsp(y, w, x, z),
+            ywxw / gara / tqsq(y, w, x, w),
+            ywyx / gagr / tqts(y, w, y, x),
+            ywyy / gagg / tqtt(y, w, y, y),
+            ywyz / gagb / tqtp(y, w, y, z),
+            ywyw / gaga / tqtq(y, w, y, w),
+            ywzx / gabr / tqps(y, w, z, x),
+            ywzy / gabg / tqpt(y, w, z, y),
+            ywzz / gabb / tqpp(y, w, z, z),
+            ywzw / gaba / tqpq(y, w, z, w),
+            ywwx / gaar / tqqs(y, w, w, x),
+            ywwy / gaag / tqqt(y, w, w, y),
+            ywwz / gaab / tqqp(y, w, w, z),
+            ywww / gaaa / tqqq(y, w, w, w),
+            zxxx / brrr / psss(z, x, x, x),
+            zxxy / brrg / psst(z, x, x, y),
+            zxxz / brrb / pssp(z, x, x, z),
+            zxxw / brra / pssq(z, x, x, w),
+            zxyx / brgr / psts(z, x, y, x),
+            zxyy / brgg / pstt(z, x, y, y),
+            zxyz / brgb / pstp(z, x, y, z),
+            zxyw / brga / pstq(z, x, y, w),
+            zxzx / brbr / psps(z, x, z, x),
+            zxzy / brbg / pspt(z, x, z, y),
+            zxzz / brbb / pspp(z, x, z, z),
+            zxzw / brba / pspq(z, x, z, w),
+            zxwx / brar / psqs(z, x, w, x),
+            zxwy / brag / psqt(z, x, w, y),
+            zxwz / brab / psqp(z, x, w, z),
+            zxww / braa / psqq(z, x, w, w),
+            zyxx / bgrr / ptss(z, y, x, x),
+            zyxy / bgrg / ptst(z, y, x, y),
+            zyxz / bgrb / ptsp(z, y, x, z),
+            zyxw / bgra / ptsq(z, y, x, w),
+            zyyx / bggr / ptts(z, y, y, x),
+            zyyy / bggg / pttt(z, y, y, y),
+            zyyz / bggb / pttp(z, y, y, z),
+            zyyw / bgga / pttq(z, y, y, w),
+            zyzx / bgbr / ptps(z, y, z, x),
+            zyzy / bgbg / ptpt(z, y, z, y),
+            zyzz / bgbb / ptpp(z, y, z, z),
+            zyzw / bgba / ptpq(z, y, z, w),
+            zywx / bgar / ptqs(z, y, w, x),
+            zywy / bgag / ptqt(z, y, w, y),
+            zywz / bgab / ptqp(z, y, w, z),
+            zyww / bgaa / ptqq(z, y, w, w),
+            zzxx / bbrr / ppss(z, z, x, x),
+            zzxy / bbrg / ppst(z, z, x, y),
+            zzxz / bbrb / ppsp(z, z, x, z),
+            zzxw / bbra / ppsq(z, z, x, w),
+            zzyx / bbgr / ppts(z, z, y, x),
+            zzyy / bbgg / pptt(z, z, y, y),
+            zzyz / bbgb / pptp(z, z, y, z),
+            zzyw / bbga / pptq(z, z, y, w),
+            zzzx / bbbr / ppps(z, z, z, x),
+            zzzy / bbbg / pppt(z, z, z, y),
+            zzzz / bbbb / pppp(z, z, z, z),
+            zzzw / bbba / pppq(z, z, z, w),
+            zzwx / bbar / ppqs(z, z, w, x),
+            zzwy / bbag / ppqt(z, z, w, y),
+            zzwz / bbab / ppqp(z, z, w, z),
+            zzww / bbaa / ppqq(z, z, w, w),
+            zwxx / barr / pqss(z, w, x, x),
+            zwxy / barg / pqst(z, w, x, y),
+            zwxz / barb / pqsp(z, w, x, z),
+            zwxw / bara / pqsq(z, w, x, w),
+            zwyx / bagr / pqts(z, w, y, x),
+            zwyy / bagg / pqtt(z, w, y, y),
+            zwyz / bagb / pqtp(z, w, y, z),
+            zwyw / baga / pqtq(z, w, y, w),
+            zwzx / babr / pqps(z, w, z, x),
+            zwzy / babg / pqpt(z, w, z, y),
+            zwzz / babb / pqpp(z, w, z, z),
+            zwzw / baba / pqpq(z, w, z, w),
+            zwwx / baar / pqqs(z, w, w, x),
+            zwwy / baag / pqqt(z, w, w, y),
+            zwwz / baab / pqqp(z, w, w, z),
+            zwww / baaa / pqqq(z, w, w, w),
+            wxxx / arrr / qsss(w, x, x, x),
+            wxxy / arrg / qsst(w, x, x, y),
+            wxxz / arrb / qssp(w, x, x, z),
+            wxxw / arra / qssq(w, x, x, w),
+            wxyx / argr / qsts(w, x, y, x),
+            wxyy / argg / qstt(w, x, y, y),
+            wxyz / argb / qstp(w, x, y, z),
+            wxyw / arga / qstq(w, x, y, w),
+            wxzx / arbr / qsps(w, x, z, x),
+            wxzy / arbg / qspt(w, x, z, y),
+            wxzz / arbb / qspp(w, x, z, z),
+            wxzw / arba / qspq(w, x, z, w),
+            wxwx / arar / qsqs(w, x, w, x),
+            wxwy / arag / qsqt(w, x, w, y),
+            wxwz / arab / qsqp(w, x, w, z),
+            wxww / araa / qsqq(w, x, w, w),
+            wyxx / agrr / qtss(w, y, x, x),
+            wyxy / agrg / qtst(w, y, x, y),
+            wyxz / agrb / qtsp(w, y, x, z),
+            wyxw / agra / qtsq(w, y, x, w),
+            wyyx / aggr / qtts(w, y, y, x),
+            wyyy / aggg / qttt(w, y, y, y),
+            wyyz / aggb / qttp(w, y, y, z),
+            wyyw / agga / qttq(w, y, y, w),
+            wyzx / agbr / qtps(w, y, z, x),
+            wyzy / agbg / qtpt(w, y, z, y),
+            wyzz / agbb / qtpp(w, y, z, z),
+            wyzw / agba / qtpq(w, y, z, w),
+            wywx / agar / qtqs(w, y, w, x),
+            wywy / agag / qtqt(w, y, w, y),
+            wywz / agab / qtqp(w, y, w, z),
+            wyww / agaa / qtqq(w, y, w, w),
+            wzxx / abrr / qpss(w, z, x, x),
+            wzxy / abrg / qpst(w, z, x, y),
+            wzxz / abrb / qpsp(w, z, x, z),
+            wzxw / abra / qpsq(w, z, x, w),
+            wzyx / abgr / qpts(w, z, y, x),
+            wzyy / abgg / qptt(w, z, y, y),
+            wzyz / abgb / qptp(w, z, y, z),
+            wzyw / abga / qptq(w, z, y, w),
+            wzzx / abbr / qpps(w, z, z, x),
+            wzzy / abbg / qppt(w, z, z, y),
+            wzzz / abbb / qppp(w, z, z, z),
+            wzzw / abba / qppq(w, z, z, w),
+            wzwx / abar / qpqs(w, z, w, x),
+            wzwy / abag / qpqt(w, z, w, y),
+            wzwz / abab / qpqp(w, z, w, z),
+            wzww / abaa / qpqq(w, z, w, w),
+            wwxx / aarr / qqss(w, w, x, x),
+            wwxy / aarg / qqst(w, w, x, y),
+            wwxz / aarb / qqsp(w, w, x, z),
+            wwxw / aara / qqsq(w, w, x, w),
+            wwyx / aagr / qqts(w, w, y, x),
+            wwyy / aagg / qqtt(w, w, y, y),
+            wwyz / aagb / qqtp(w, w, y, z),
+            wwyw / aaga / qqtq(w, w, y, w),
+            wwzx / aabr / qqps(w, w, z, x),
+            wwzy / aabg / qqpt(w, w, z, y),
+            wwzz / aabb / qqpp(w, w, z, z),
+            wwzw / aaba / qqpq(w, w, z, w),
+            wwwx / aaar / qqqs(w, w, w, x),
+            wwwy / aaag / qqqt(w, w, w, y),
+            wwwz / aaab / qqqp(w, w, w, z),
+            wwww / aaaa / qqqq(w, w, w, w),
+        );
+    };
+}
+
 // Implements two-dimensional `$vec`.
 macro_rules! impl_vec2 {
     ($vec:ident, $mint:ty, $bvec:ident, $vec_lower:ident, $scalar:ident, $vec3:ident) => {
@@ -366,15 +977,7 @@ macro_rules! impl_vec2 {
 
 // Implements three-dimensional `$vec`.
 macro_rules! impl_vec3 {
-    (
-        $vec:ident,
-        $mint:ty,
-        $bvec:ident,
-        $vec_lower:ident,
-        $scalar:ident,
-        $vec2:ident,
-        $vec4:ident
-    ) => {
+    ($vec:ident, $mint:ty, $bvec:ident, $vec_lower:ident, $scalar:ident, $vec4:ident) => {
         #[doc = concat!("A three-dimensional ", scalar_name!($scalar), " vector.")]
         #[derive(Debug, Copy, Clone, Default)]
         pub struct $vec {
@@ -413,20 +1016,6 @@ macro_rules! impl_vec3 {
                     w: w.to_sl(),
                 }
             }
-
-            pub fn xy(&self) -> $vec2 {
-                $vec2 {
-                    x: self.x,
-                    y: self.y,
-                }
-            }
-
-            pub fn yz(&self) -> $vec2 {
-                $vec2 {
-                    x: self.y,
-                    y: self.z,
-                }
-            }
         }
 
         #[doc = concat!("Creates a three-dimensional ", scalar_name!($scalar), " vector.")]
@@ -445,15 +1034,7 @@ macro_rules! impl_vec3 {
 
 // Implements four-dimensional `$vec`.
 macro_rules! impl_vec4 {
-    (
-        $vec:ident,
-        $mint:ty,
-        $bvec:ident,
-        $vec_lower:ident,
-        $scalar:ident,
-        $vec2:ident,
-        $vec3:ident
-    ) => {
+    ($vec:ident, $mint:ty, $bvec:ident, $vec_lower:ident, $scalar:ident) => {
         #[doc = concat!("A four-dimensional ", scalar_name!($scalar), " vector.")]
         #[derive(Debug, Copy, Clone, Default)]
         pub struct $vec {
@@ -490,52 +1071,6 @@ macro_rules! impl_vec4 {
                     w: v,
                 }
             }
-
-            pub fn xyz(&self) -> $vec3 {
-                $vec3 {
-                    x: self.x,
-                    y: self.y,
-                    z: self.z,
-                }
-            }
-
-            pub fn yzw(&self) -> $vec3 {
-                $vec3 {
-                    x: self.y,
-                    y: self.z,
-                    z: self.w,
-                }
-            }
-
-            pub fn xy(&self) -> $vec2 {
-                $vec2 {
-                    x: self.x,
-                    y: self.y,
-                }
-            }
-
-            pub fn yz(&self) -> $vec2 {
-                $vec2 {
-                    x: self.y,
-                    y: self.z,
-                }
-            }
-
-            pub fn zw(&self) -> $vec2 {
-                $vec2 {
-                    x: self.z,
-                    y: self.w,
-                }
-            }
-
-            pub fn zwxy(&self) -> Self {
-                Self {
-                    x: self.z,
-                    y: self.w,
-                    z: self.x,
-                    w: self.y,
-                }
-            }
         }
 
         #[doc = concat!("Creates a four-dimensional ", scalar_name!($scalar), " vector.")]
@@ -567,20 +1102,36 @@ macro_rules! impl_casts {
 }
 
 impl_vec2!(Vec2, mint::Vector2<f32>, BVec2, vec2, F32, Vec3);
-impl_vec3!(Vec3, mint::Vector3<f32>, BVec3, vec3, F32, Vec2, Vec4);
-impl_vec4!(Vec4, mint::Vector4<f32>, BVec4, vec4, F32, Vec2, Vec3);
+impl_vec3!(Vec3, mint::Vector3<f32>, BVec3, vec3, F32, Vec4);
+impl_vec4!(Vec4, mint::Vector4<f32>, BVec4, vec4, F32);
 
 impl_vec2!(IVec2, mint::Vector2<i32>, BVec2, ivec2, I32, IVec3);
-impl_vec3!(IVec3, mint::Vector3<i32>, BVec3, ivec3, I32, IVec2, IVec4);
-impl_vec4!(IVec4, mint::Vector4<i32>, BVec4, ivec4, I32, IVec2, IVec3);
+impl_vec3!(IVec3, mint::Vector3<i32>, BVec3, ivec3, I32, IVec4);
+impl_vec4!(IVec4, mint::Vector4<i32>, BVec4, ivec4, I32);
 
 impl_vec2!(UVec2, mint::Vector2<u32>, BVec2, uvec2, U32, UVec3);
-impl_vec3!(UVec3, mint::Vector3<u32>, BVec3, uvec3, U32, UVec2, UVec4);
-impl_vec4!(UVec4, mint::Vector4<u32>, BVec4, uvec4, U32, UVec2, UVec3);
+impl_vec3!(UVec3, mint::Vector3<u32>, BVec3, uvec3, U32, UVec4);
+impl_vec4!(UVec4, mint::Vector4<u32>, BVec4, uvec4, U32);
 
 impl_vec2!(BVec2, mint::Vector2<bool>, BVec2, bvec2, Bool, BVec3);
-impl_vec3!(BVec3, mint::Vector3<bool>, BVec3, bvec3, Bool, BVec2, BVec4);
-impl_vec4!(BVec4, mint::Vector4<bool>, BVec4, bvec4, Bool, BVec2, BVec3);
+impl_vec3!(BVec3, mint::Vector3<bool>, BVec3, bvec3, Bool, BVec4);
+impl_vec4!(BVec4, mint::Vector4<bool>, BVec4, bvec4, Bool);
+
+impl_swizzles_vec2!(Vec2, Vec2, Vec3, Vec4);
+impl_swizzles_vec3!(Vec3, Vec2, Vec3, Vec4);
+impl_swizzles_vec4!(Vec4, Vec2, Vec3, Vec4);
+
+impl_swizzles_vec2!(IVec2, IVec2, IVec3, IVec4);
+impl_swizzles_vec3!(IVec3, IVec2, IVec3, IVec4);
+impl_swizzles_vec4!(IVec4, IVec2, IVec3, IVec4);
+
+impl_swizzles_vec2!(UVec2, UVec2, UVec3, UVec4);
+impl_swizzles_vec3!(UVec3, UVec2, UVec3, UVec4);
+impl_swizzles_vec4!(UVec4, UVec2, UVec3, UVec4);
+
+impl_swizzles_vec2!(BVec2, BVec2, BVec3, BVec4);
+impl_swizzles_vec3!(BVec3, BVec2, BVec3, BVec4);
+impl_swizzles_vec4!(BVec4, BVec2, BVec3, BVec4);
 
 impl_casts!(Vec2, as_ivec2, IVec2, as_uvec2, UVec2);
 impl_casts!(Vec3, as_ivec3, IVec3, as_uvec3, UVec3);
@@ -602,6 +1153,21 @@ impl_gen_type!(Vec2, BVec2);
 impl_gen_type!(Vec3, BVec3);
 impl_gen_type!(Vec4, BVec4);
 
+impl_float_bits!(Vec2, IVec2, UVec2);
+impl_float_bits!(Vec3, IVec3, UVec3);
+impl_float_bits!(Vec4, IVec4, UVec4);
+
+impl_gen_type_integral!(IVec2, BVec2, x, y);
+impl_gen_type_integral!(IVec3, BVec3, x, y, z);
+impl_gen_type_integral!(IVec4, BVec4, x, y, z, w);
+impl_gen_type_integral!(UVec2, BVec2, x, y);
+impl_gen_type_integral!(UVec3, BVec3, x, y, z);
+impl_gen_type_integral!(UVec4, BVec4, x, y, z, w);
+
+impl_gen_type_signed!(IVec2);
+impl_gen_type_signed!(IVec3);
+impl_gen_type_signed!(IVec4);
+
 impl Vec2 {
     // All zeroes.
     pub const ZERO: Self = Self {
@@ -668,6 +1234,25 @@ impl Vec2 {
             self.y * rhs.x + self.x * rhs.y,
         )
     }
+
+    /// Converts both components to 16-bit floating-point values and packs
+    /// them into a single integer. The first component is stored in the least
+    /// significant bits.
+    pub fn pack_half_2x16(self) -> U32 {
+        built_in_1("packHalf2x16", self)
+    }
+
+    /// Converts both components, clamped to `[0, 1]`, to 16-bit unsigned
+    /// normalized values and packs them into a single integer.
+    pub fn pack_unorm_2x16(self) -> U32 {
+        built_in_1("packUnorm2x16", self)
+    }
+
+    /// Converts both components, clamped to `[-1, 1]`, to 16-bit signed
+    /// normalized values and packs them into a single integer.
+    pub fn pack_snorm_2x16(self) -> U32 {
+        built_in_1("packSnorm2x16", self)
+    }
 }
 
 impl IVec2 {
@@ -0,0 +1,374 @@
+//! A software rasterizer that runs programs on the CPU.
+//!
+//! [`Program`] takes the same shader functions as
+//! [`gl::Program`](crate::gl::Program). Its vertex shader is evaluated for each
+//! vertex with [`sl::eval`], the resulting primitives are clipped and
+//! rasterized, and its fragment shader is evaluated for each covered pixel.
+//! The outputs are written to the CPU images of a [`Framebuffer`], taking
+//! [`DrawParams`] into account.
+//!
+//! Rendering does not require a GL context and is deterministic, which makes
+//! it possible to compare the output of programs against golden images on
+//! headless CI. The rasterizer is meant as a reference rather than for speed.
+//! It follows OpenGL ES 3.0, with the following exceptions:
+//!
+//! - Uniforms are given in their [`Sl`] view and must be constant. Blocks can
+//!   be converted from their [`Gl`](crate::Gl) view with
+//!   [`ToSl::to_sl`](crate::ToSl::to_sl), and samplers can be created with
+//!   [`sl::ColorSampler2d::constant`], for example.
+//! - Fragments are sampled once at their pixel center. Centroid interpolation
+//!   is the same as smooth interpolation.
+//! - Derivatives, stencil tests and multisampling are not supported.
+//!
+//! # Example
+//!
+//! ```
+//! use posh::{gl, sl, soft};
+//!
+//! fn vertex_shader((): (), vertex: sl::Vec2) -> sl::VsOutput<sl::Vec2> {
+//!     sl::VsOutput {
+//!         clip_pos: sl::vec4(vertex.x, vertex.y, 0.0, 1.0),
+//!         interp: vertex * 0.5 + 0.5,
+//!     }
+//! }
+//!
+//! fn fragment_shader((): (), interp: sl::Vec2) -> sl::Vec4 {
+//!     sl::vec4(interp.x, interp.y, 0.0, 1.0)
+//! }
+//!
+//! let program: soft::Program<(), sl::Vec2> = soft::Program::new(vertex_shader, fragment_shader);
+//!
+//! // A triangle that covers the whole framebuffer.
+//! let vertices = [
+//!     gl::Vec2 { x: -1.0, y: -1.0 },
+//!     gl::Vec2 { x: 3.0, y: -1.0 },
+//!     gl::Vec2 { x: -1.0, y: 3.0 },
+//! ];
+//!
+//! let mut framebuffer = soft::Framebuffer::new([4, 4]);
+//!
+//! program
+//!     .draw(
+//!         &mut framebuffer,
+//!         &soft::VertexSpec::new(gl::PrimitiveMode::Triangles).with_vertex_block(&vertices),
+//!     )
+//!     .unwrap();
+//!
+//! // The upper left pixel has its center at `(0.5, 3.5)` in window
+//! // coordinates.
+//! assert_eq!(
+//!     framebuffer.color_attachment(0).to_rgba8()[..4],
+//!     [32, 223, 0, 255]
+//! );
+//! ```
+
+mod image;
+mod raster;
+mod vertex_spec;
+
+use std::{marker::PhantomData, rc::Rc};
+
+use thiserror::Error;
+
+use crate::{
+    gl::DrawParams,
+    interface::UniformVisitor,
+    sl::{
+        self,
+        dag::Expr,
+        eval::{evaluate_exprs, Bindings, EvalError},
+        transpile::{trace_program, ProgramTrace, TranspileOptions},
+        ColorSample, FsFunc, FsSig, Object, VsFunc, VsSig,
+    },
+    Block, FsInterface, Sl, Uniform, UniformUnion, VsInterface,
+};
+
+pub use image::{ColorImage, DepthImage, Framebuffer};
+pub use vertex_spec::VertexSpec;
+
+use raster::Rasterizer;
+
+/// An error that occurred while drawing with the software rasterizer.
+#[derive(Debug, Clone, Error)]
+pub enum DrawError {
+    #[error("expected {expected} vertex blocks, but got {actual}")]
+    VertexBlockCount { expected: usize, actual: usize },
+
+    #[error("vertex block {index} does not match the vertex interface of the program")]
+    VertexBlockMismatch { index: usize },
+
+    #[error("vertex block {index} is too short for the vertices or instances that are drawn")]
+    VertexBlockTooShort { index: usize },
+
+    #[error("expected {expected} color attachments, but got {actual}")]
+    ColorAttachmentCount { expected: usize, actual: usize },
+
+    #[error("stencil tests are not supported by the software rasterizer")]
+    StencilTestUnsupported,
+
+    #[error("evaluation error: {0}")]
+    Eval(#[from] EvalError),
+}
+
+/// A program that is executed on the CPU.
+///
+/// See the [module-level documentation](self) for details.
+pub struct Program<U, V, F = sl::Vec4> {
+    trace: Rc<ProgramTrace>,
+    _phantom: PhantomData<(U, V, F)>,
+}
+
+impl<U, V, F> Clone for Program<U, V, F> {
+    fn clone(&self) -> Self {
+        Self {
+            trace: self.trace.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<U, V, F> Program<U, V, F>
+where
+    U: Uniform<Sl>,
+    V: VsInterface<Sl>,
+    F: FsInterface<Sl>,
+{
+    pub fn new<VSig, VFunc, FSig, FFunc>(vertex_shader: VFunc, fragment_shader: FFunc) -> Self
+    where
+        U: UniformUnion<VSig::U, FSig::U>,
+        VSig: VsSig<C = (), V = V>,
+        VFunc: VsFunc<VSig>,
+        FSig: FsSig<C = (), W = VSig::W, F = F>,
+        FFunc: FsFunc<FSig>,
+    {
+        Self::with_consts(&(), vertex_shader, fragment_shader)
+    }
+
+    pub fn with_consts<VSig, VFunc, FSig, FFunc>(
+        consts: &VSig::C,
+        vertex_shader: VFunc,
+        fragment_shader: FFunc,
+    ) -> Self
+    where
+        U: UniformUnion<VSig::U, FSig::U>,
+        VSig: VsSig<V = V>,
+        VFunc: VsFunc<VSig>,
+        FSig: FsSig<C = VSig::C, W = VSig::W, F = F>,
+        FFunc: FsFunc<FSig>,
+    {
+        let trace = trace_program::<U, VSig, VFunc, FSig, FFunc>(
+            &TranspileOptions::default(),
+            consts,
+            vertex_shader,
+            fragment_shader,
+        );
+
+        Self {
+            trace: Rc::new(trace),
+            _phantom: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn with_uniforms(&self, uniforms: U) -> DrawBuilder<'_, U, V, F> {
+        DrawBuilder {
+            program: self,
+            uniforms,
+            params: DrawParams::default(),
+        }
+    }
+}
+
+impl<V, F> Program<(), V, F>
+where
+    V: VsInterface<Sl>,
+    F: FsInterface<Sl>,
+{
+    #[must_use]
+    pub fn with_params(&self, params: DrawParams) -> DrawBuilder<'_, (), V, F> {
+        self.with_uniforms(()).with_params(params)
+    }
+
+    pub fn draw(
+        &self,
+        framebuffer: &mut Framebuffer,
+        vertex_spec: &VertexSpec<V>,
+    ) -> Result<(), DrawError> {
+        self.with_uniforms(()).draw(framebuffer, vertex_spec)
+    }
+}
+
+pub struct DrawBuilder<'a, U, V, F> {
+    program: &'a Program<U, V, F>,
+    uniforms: U,
+    params: DrawParams,
+}
+
+impl<'a, U, V, F> DrawBuilder<'a, U, V, F>
+where
+    U: Uniform<Sl>,
+    V: VsInterface<Sl>,
+    F: FsInterface<Sl>,
+{
+    #[must_use]
+    pub fn with_params(mut self, params: DrawParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    #[must_use]
+    pub fn with_uniforms(mut self, uniforms: U) -> Self {
+        self.uniforms = uniforms;
+        self
+    }
+
+    /// Draws the primitives of `vertex_spec` into `framebuffer`.
+    ///
+    /// Fragments that are discarded by the fragment shader are skipped. Any
+    /// other error during the evaluation of the shaders aborts drawing, with
+    /// the fragments up to that point already written.
+    pub fn draw(
+        &self,
+        framebuffer: &mut Framebuffer,
+        vertex_spec: &VertexSpec<V>,
+    ) -> Result<(), DrawError> {
+        let trace = &self.program.trace;
+
+        check_vertex_spec(trace, vertex_spec)?;
+
+        let expected = trace.fragment_outputs.len();
+        let actual = framebuffer.color_attachments().len();
+
+        if expected != actual {
+            return Err(DrawError::ColorAttachmentCount { expected, actual });
+        }
+
+        if self.params.stencil_test_front.is_some() || self.params.stencil_test_back.is_some() {
+            return Err(DrawError::StencilTestUnsupported);
+        }
+
+        // TODO: Remove hardcoded path names.
+        let mut visitor = BindUniforms::default();
+        self.uniforms.visit("uniforms", &mut visitor);
+        let uniforms = visitor.finish()?;
+
+        Rasterizer::new(trace, &uniforms, &self.params, framebuffer).draw(vertex_spec)
+    }
+}
+
+fn check_vertex_spec<V: VsInterface<Sl>>(
+    trace: &ProgramTrace,
+    vertex_spec: &VertexSpec<V>,
+) -> Result<(), DrawError> {
+    let blocks = vertex_spec.blocks();
+    let expected = trace.vertex_block_defs.len();
+
+    if blocks.len() != expected {
+        return Err(DrawError::VertexBlockCount {
+            expected,
+            actual: blocks.len(),
+        });
+    }
+
+    let num_vertices = vertex_spec.indices().into_iter().max().map_or(0, |i| i + 1);
+    let num_instances = vertex_spec.num_instances();
+
+    for (index, (block, block_def)) in blocks.iter().zip(&trace.vertex_block_defs).enumerate() {
+        if !block.matches(&block_def.attributes) {
+            return Err(DrawError::VertexBlockMismatch { index });
+        }
+
+        if !block.contains(num_vertices, num_instances) {
+            return Err(DrawError::VertexBlockTooShort { index });
+        }
+    }
+
+    Ok(())
+}
+
+// Binds the uniform arguments of the traced program to the constant values of
+// the given uniforms.
+#[derive(Default)]
+struct BindUniforms {
+    bindings: Bindings,
+    error: Option<EvalError>,
+}
+
+impl BindUniforms {
+    fn bind(&mut self, path: &str, expr: Rc<Expr>) {
+        if self.error.is_some() {
+            return;
+        }
+
+        match evaluate_exprs(&[expr], &Bindings::new()) {
+            Ok(mut values) => {
+                self.bindings.insert_value(path, values.remove(0));
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn finish(self) -> Result<Bindings, EvalError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.bindings),
+        }
+    }
+}
+
+impl<'a> UniformVisitor<'a, Sl> for BindUniforms {
+    fn accept_block<B: Block<Sl, Sl = B>>(&mut self, path: &str, block: &B) {
+        self.bind(path, block.to_sl().expr());
+    }
+
+    fn accept_color_sampler_2d<S: ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &sl::ColorSampler2d<S>,
+    ) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_comparison_sampler_2d(&mut self, path: &str, sampler: &sl::ComparisonSampler2d) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_color_sampler_cube<S: ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &sl::ColorSamplerCube<S>,
+    ) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_comparison_sampler_cube(&mut self, path: &str, sampler: &sl::ComparisonSamplerCube) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_color_sampler_3d<S: ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &sl::ColorSampler3d<S>,
+    ) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_color_sampler_2d_array<S: ColorSample>(
+        &mut self,
+        path: &str,
+        sampler: &sl::ColorSampler2dArray<S>,
+    ) {
+        self.bind(path, sampler.expr());
+    }
+
+    fn accept_comparison_sampler_2d_array(
+        &mut self,
+        path: &str,
+        sampler: &sl::ComparisonSampler2dArray,
+    ) {
+        self.bind(path, sampler.expr());
+    }
+}
+
+#[cfg(test)]
+mod tests;
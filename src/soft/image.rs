@@ -0,0 +1,219 @@
+use crate::gl::ClearParams;
+
+/// A color image in CPU memory.
+///
+/// Each pixel has four unclamped `f32` channels, like an `RGBA32F` texture.
+/// Pixels are addressed in window coordinates, so `[0, 0]` is the lower left
+/// corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorImage {
+    size: [u32; 2],
+    pixels: Vec<[f32; 4]>,
+}
+
+impl ColorImage {
+    /// Creates an image filled with transparent black.
+    pub fn new(size: [u32; 2]) -> Self {
+        Self {
+            size,
+            pixels: vec![[0.0; 4]; num_pixels(size)],
+        }
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// Returns the pixels in rows from bottom to top.
+    pub fn pixels(&self) -> &[[f32; 4]] {
+        &self.pixels
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn get(&self, pos: [u32; 2]) -> [f32; 4] {
+        self.pixels[pixel_index(self.size, pos)]
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn set(&mut self, pos: [u32; 2], color: [f32; 4]) {
+        self.pixels[pixel_index(self.size, pos)] = color;
+    }
+
+    pub fn fill(&mut self, color: [f32; 4]) {
+        self.pixels.fill(color);
+    }
+
+    /// Converts the image to 8-bit RGBA in rows from top to bottom.
+    ///
+    /// Channels are clamped to `[0, 1]` and rounded, as when writing to an
+    /// `RGBA8` texture. This is the layout of common image file formats, so
+    /// the result can be compared against stored golden images.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.pixels
+            .chunks_exact(self.size[0].max(1) as usize)
+            .rev()
+            .flatten()
+            .flatten()
+            .map(|&x| (x.clamp(0.0, 1.0) * 255.0).round() as u8)
+            .collect()
+    }
+}
+
+/// A depth image in CPU memory.
+///
+/// Pixels are addressed in window coordinates, so `[0, 0]` is the lower left
+/// corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthImage {
+    size: [u32; 2],
+    pixels: Vec<f32>,
+}
+
+impl DepthImage {
+    /// Creates an image filled with the far plane depth of `1.0`.
+    pub fn new(size: [u32; 2]) -> Self {
+        Self {
+            size,
+            pixels: vec![1.0; num_pixels(size)],
+        }
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// Returns the pixels in rows from bottom to top.
+    pub fn pixels(&self) -> &[f32] {
+        &self.pixels
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn get(&self, pos: [u32; 2]) -> f32 {
+        self.pixels[pixel_index(self.size, pos)]
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn set(&mut self, pos: [u32; 2], depth: f32) {
+        self.pixels[pixel_index(self.size, pos)] = depth;
+    }
+
+    pub fn fill(&mut self, depth: f32) {
+        self.pixels.fill(depth);
+    }
+}
+
+/// The images that a [`Program`](super::Program) draws into.
+///
+/// The fragment outputs of the program are written to the color attachments
+/// in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    size: [u32; 2],
+    color_attachments: Vec<ColorImage>,
+    depth_attachment: Option<DepthImage>,
+}
+
+impl Framebuffer {
+    /// Creates a framebuffer with a single color attachment.
+    pub fn new(size: [u32; 2]) -> Self {
+        Self {
+            size,
+            color_attachments: vec![ColorImage::new(size)],
+            depth_attachment: None,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there are no attachments or if the attachments differ in
+    /// size.
+    pub fn from_attachments(
+        color_attachments: Vec<ColorImage>,
+        depth_attachment: Option<DepthImage>,
+    ) -> Self {
+        let size = color_attachments
+            .first()
+            .map(ColorImage::size)
+            .or(depth_attachment.as_ref().map(DepthImage::size))
+            .expect("framebuffer must have at least one attachment");
+
+        assert!(
+            color_attachments
+                .iter()
+                .map(ColorImage::size)
+                .chain(depth_attachment.as_ref().map(DepthImage::size))
+                .all(|attachment_size| attachment_size == size),
+            "framebuffer attachments must have the same size"
+        );
+
+        Self {
+            size,
+            color_attachments,
+            depth_attachment,
+        }
+    }
+
+    /// Adds a depth attachment that is filled with the far plane depth.
+    pub fn with_depth(mut self) -> Self {
+        self.depth_attachment = Some(DepthImage::new(self.size));
+        self
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+
+    pub fn color_attachments(&self) -> &[ColorImage] {
+        &self.color_attachments
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there is no color attachment at `index`.
+    pub fn color_attachment(&self, index: usize) -> &ColorImage {
+        &self.color_attachments[index]
+    }
+
+    pub fn depth_attachment(&self) -> Option<&DepthImage> {
+        self.depth_attachment.as_ref()
+    }
+
+    /// Clears the attachments. The stencil value is ignored, since there is no
+    /// stencil attachment.
+    pub fn clear(&mut self, params: ClearParams) {
+        if let Some(color) = params.color {
+            for image in &mut self.color_attachments {
+                image.fill(color);
+            }
+        }
+
+        if let (Some(depth), Some(image)) = (params.depth, &mut self.depth_attachment) {
+            image.fill(depth);
+        }
+    }
+
+    pub(super) fn attachments_mut(&mut self) -> (&mut [ColorImage], Option<&mut DepthImage>) {
+        (&mut self.color_attachments, self.depth_attachment.as_mut())
+    }
+}
+
+fn num_pixels(size: [u32; 2]) -> usize {
+    size[0] as usize * size[1] as usize
+}
+
+fn pixel_index(size: [u32; 2], pos: [u32; 2]) -> usize {
+    assert!(
+        pos[0] < size[0] && pos[1] < size[1],
+        "pixel {pos:?} is out of bounds for image of size {size:?}"
+    );
+
+    pos[1] as usize * size[0] as usize + pos[0] as usize
+}
@@ -0,0 +1,785 @@
+use std::{array, collections::BTreeMap, iter::once, ops::Range};
+
+use crate::{
+    gl::{BlendEquation, BlendFunc, Blending, Comparison, CullFace, DrawParams, PrimitiveMode},
+    sl::{
+        eval::{evaluate_exprs, Bindings, EvalError, EvalValue},
+        program_def::InterpolationQualifier,
+        transpile::ProgramTrace,
+    },
+    Sl, VsInterface,
+};
+
+use super::{DrawError, Framebuffer, VertexSpec};
+
+// A vertex after the vertex shader has run.
+#[derive(Clone)]
+struct Vertex {
+    clip_pos: [f32; 4],
+    point_size: f32,
+    varyings: Vec<EvalValue>,
+}
+
+// A vertex in clip space, possibly created by clipping.
+#[derive(Clone)]
+struct ClipVertex {
+    pos: [f32; 4],
+    varyings: Vec<EvalValue>,
+}
+
+// A vertex after the perspective division and the viewport transformation.
+struct WindowVertex {
+    pos: [f32; 3],
+    inv_w: f32,
+    varyings: Vec<EvalValue>,
+}
+
+struct Fragment {
+    pos: [u32; 2],
+    coord: [f32; 4],
+    front_facing: bool,
+    point_coord: [f32; 2],
+    varyings: Vec<EvalValue>,
+}
+
+pub(super) struct Rasterizer<'a> {
+    trace: &'a ProgramTrace,
+    uniforms: &'a Bindings,
+    params: &'a DrawParams,
+    framebuffer: &'a mut Framebuffer,
+    viewport: [f32; 4],
+    bounds: [u32; 4],
+}
+
+impl<'a> Rasterizer<'a> {
+    pub(super) fn new(
+        trace: &'a ProgramTrace,
+        uniforms: &'a Bindings,
+        params: &'a DrawParams,
+        framebuffer: &'a mut Framebuffer,
+    ) -> Self {
+        let [width, height] = framebuffer.size();
+
+        let viewport = params
+            .viewport
+            .map_or([0.0, 0.0, width as f32, height as f32], |rect| {
+                [
+                    rect.lower_left_corner[0] as f32,
+                    rect.lower_left_corner[1] as f32,
+                    rect.size[0] as f32,
+                    rect.size[1] as f32,
+                ]
+            });
+
+        // Fragments are only generated inside of the framebuffer and the
+        // scissor rectangle. The bounds are given as `[min_x, min_y, max_x,
+        // max_y]`, where the maximum is exclusive.
+        let bounds = params.scissor.map_or([0, 0, width, height], |rect| {
+            let [x, y] = rect.lower_left_corner;
+            let [w, h] = rect.size;
+
+            [
+                x.min(width),
+                y.min(height),
+                x.saturating_add(w).min(width),
+                y.saturating_add(h).min(height),
+            ]
+        });
+
+        Self {
+            trace,
+            uniforms,
+            params,
+            framebuffer,
+            viewport,
+            bounds,
+        }
+    }
+
+    pub(super) fn draw<V: VsInterface<Sl>>(
+        &mut self,
+        vertex_spec: &VertexSpec<V>,
+    ) -> Result<(), DrawError> {
+        let indices = vertex_spec.indices();
+        let primitives = assemble(vertex_spec.mode(), indices.len());
+
+        for instance in 0..vertex_spec.num_instances() {
+            let mut cache = BTreeMap::new();
+            let mut vertices = Vec::with_capacity(indices.len());
+
+            for &index in &indices {
+                let vertex = match cache.get(&index) {
+                    Some(vertex) => Vertex::clone(vertex),
+                    None => {
+                        let vertex = self.shade_vertex(vertex_spec, index, instance)?;
+                        cache.insert(index, vertex.clone());
+
+                        vertex
+                    }
+                };
+
+                vertices.push(vertex);
+            }
+
+            for primitive in &primitives {
+                // Flat varyings are taken from the last vertex of a primitive,
+                // which is the provoking vertex in OpenGL.
+                let provoking = &vertices[*primitive.last().unwrap()];
+
+                match primitive[..] {
+                    [a] => self.draw_point(&vertices[a])?,
+                    [a, b] => self.draw_line([&vertices[a], &vertices[b]], provoking)?,
+                    [a, b, c] => {
+                        self.draw_triangle([&vertices[a], &vertices[b], &vertices[c]], provoking)?
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn shade_vertex<V: VsInterface<Sl>>(
+        &self,
+        vertex_spec: &VertexSpec<V>,
+        index: usize,
+        instance: usize,
+    ) -> Result<Vertex, DrawError> {
+        let mut bindings = self.uniforms.clone();
+
+        for (block, block_def) in vertex_spec
+            .blocks()
+            .iter()
+            .zip(&self.trace.vertex_block_defs)
+        {
+            for (value, attribute) in block.decode(index, instance).zip(&block_def.attributes) {
+                bindings.insert_value(attribute.name.clone(), value);
+            }
+        }
+
+        bindings
+            .insert_value("gl_VertexID", EvalValue::I32(index as i32))
+            .insert_value("gl_InstanceID", EvalValue::I32(instance as i32));
+
+        let exprs: Vec<_> = once(self.trace.clip_pos.clone())
+            .chain(
+                self.trace
+                    .varying_outputs
+                    .iter()
+                    .map(|(_, _, expr)| expr.clone()),
+            )
+            .chain(self.trace.point_size.clone())
+            .collect();
+
+        let mut values = evaluate_exprs(&exprs, &bindings)?;
+
+        let point_size = match self.trace.point_size {
+            Some(_) => scalar(&values.pop().unwrap()),
+            None => 1.0,
+        };
+        let clip_pos = vec4(&values.remove(0));
+
+        Ok(Vertex {
+            clip_pos,
+            point_size,
+            varyings: values,
+        })
+    }
+
+    fn draw_point(&mut self, vertex: &Vertex) -> Result<(), DrawError> {
+        // Points are discarded if their center lies outside of the clip
+        // volume.
+        let [x, y, z, w] = vertex.clip_pos;
+
+        if !(w > 0.0 && [x, y, z].iter().all(|c| c.abs() <= w)) {
+            return Ok(());
+        }
+
+        let window = self.to_window(ClipVertex {
+            pos: vertex.clip_pos,
+            varyings: vertex.varyings.clone(),
+        });
+        let [center_x, center_y, depth] = window.pos;
+        let size = vertex.point_size.max(1.0);
+
+        for y in self.pixel_range(1, center_y - size / 2.0, center_y + size / 2.0) {
+            for x in self.pixel_range(0, center_x - size / 2.0, center_x + size / 2.0) {
+                let [s, t] = [x as f32 + 0.5, y as f32 + 0.5];
+
+                self.shade_fragment(Fragment {
+                    pos: [x, y],
+                    coord: [s, t, depth, window.inv_w],
+                    front_facing: true,
+                    point_coord: [0.5 + (s - center_x) / size, 0.5 - (t - center_y) / size],
+                    varyings: window.varyings.clone(),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_line(&mut self, [a, b]: [&Vertex; 2], provoking: &Vertex) -> Result<(), DrawError> {
+        let Some([a, b]) = clip_line([a, b].map(ClipVertex::from)) else {
+            return Ok(());
+        };
+        let [a, b] = [a, b].map(|vertex| self.to_window(vertex));
+
+        // Lines are rasterized with one fragment per pixel column or row,
+        // whichever is the major axis, covering the pixel centers between the
+        // endpoints.
+        let delta = [b.pos[0] - a.pos[0], b.pos[1] - a.pos[1]];
+        let major = usize::from(delta[0].abs() < delta[1].abs());
+        let minor = 1 - major;
+
+        if delta[major] == 0.0 || delta[major].is_nan() {
+            return Ok(());
+        }
+
+        let range = self.pixel_range(
+            major,
+            a.pos[major].min(b.pos[major]),
+            a.pos[major].max(b.pos[major]),
+        );
+
+        for k in range {
+            let t = (k as f32 + 0.5 - a.pos[major]) / delta[major];
+            let m = (a.pos[minor] + t * delta[minor]).floor();
+
+            if m < self.bounds[minor] as f32 || m >= self.bounds[minor + 2] as f32 {
+                continue;
+            }
+
+            let mut pos = [0; 2];
+            pos[major] = k;
+            pos[minor] = m as u32;
+
+            let fragment = self.fragment(pos, &[&a, &b], &[1.0 - t, t], provoking, true);
+            self.shade_fragment(fragment)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_triangle(
+        &mut self,
+        vertices: [&Vertex; 3],
+        provoking: &Vertex,
+    ) -> Result<(), DrawError> {
+        let polygon: Vec<_> = clip_polygon(vertices.map(ClipVertex::from).to_vec())
+            .into_iter()
+            .map(|vertex| self.to_window(vertex))
+            .collect();
+
+        if polygon.len() < 3 {
+            return Ok(());
+        }
+
+        // Window coordinates have their origin in the lower left corner, so
+        // counter-clockwise polygons have a positive area.
+        let area: f32 = (0..polygon.len())
+            .map(|i| {
+                let [ax, ay, _] = polygon[i].pos;
+                let [bx, by, _] = polygon[(i + 1) % polygon.len()].pos;
+
+                ax * by - bx * ay
+            })
+            .sum();
+
+        if area == 0.0 || area.is_nan() {
+            return Ok(());
+        }
+
+        let front_facing = area > 0.0;
+
+        if let Some(cull_face) = self.params.cull_face {
+            if (cull_face == CullFace::Front) == front_facing {
+                return Ok(());
+            }
+        }
+
+        for i in 1..polygon.len() - 1 {
+            let triangle = [&polygon[0], &polygon[i], &polygon[i + 1]];
+
+            self.rasterize_triangle(triangle, provoking, front_facing)?;
+        }
+
+        Ok(())
+    }
+
+    fn rasterize_triangle(
+        &mut self,
+        triangle: [&WindowVertex; 3],
+        provoking: &Vertex,
+        front_facing: bool,
+    ) -> Result<(), DrawError> {
+        // Bring the triangle into counter-clockwise order, so that pixels
+        // inside of it have non-negative edge functions.
+        let [a, b, c] = if front_facing {
+            triangle
+        } else {
+            [triangle[0], triangle[2], triangle[1]]
+        };
+        let area = edge(&a.pos, &b.pos, [c.pos[0], c.pos[1]]);
+
+        if area <= 0.0 {
+            return Ok(());
+        }
+
+        let min = |i: usize| a.pos[i].min(b.pos[i]).min(c.pos[i]);
+        let max = |i: usize| a.pos[i].max(b.pos[i]).max(c.pos[i]);
+        let xs = self.pixel_range(0, min(0), max(0) + 1.0);
+        let ys = self.pixel_range(1, min(1), max(1) + 1.0);
+
+        for y in ys {
+            for x in xs.clone() {
+                let p = [x as f32 + 0.5, y as f32 + 0.5];
+                let edges = [(b, c), (c, a), (a, b)];
+                let weights = edges.map(|(from, to)| edge(&from.pos, &to.pos, p));
+
+                // Pixel centers that lie exactly on an edge belong to the
+                // triangle only if it is a left or a top edge, so that
+                // adjacent triangles do not cover the same pixel twice.
+                let inside = edges.iter().zip(weights).all(|(&(from, to), weight)| {
+                    weight > 0.0 || (weight == 0.0 && is_top_left(&from.pos, &to.pos))
+                });
+
+                if inside {
+                    let weights = weights.map(|weight| weight / area);
+                    let fragment =
+                        self.fragment([x, y], &[a, b, c], &weights, provoking, front_facing);
+
+                    self.shade_fragment(fragment)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fragment(
+        &self,
+        pos: [u32; 2],
+        vertices: &[&WindowVertex],
+        weights: &[f32],
+        provoking: &Vertex,
+        front_facing: bool,
+    ) -> Fragment {
+        let weighted_sum = |f: fn(&WindowVertex) -> f32| -> f32 {
+            vertices
+                .iter()
+                .zip(weights)
+                .map(|(vertex, weight)| f(vertex) * weight)
+                .sum()
+        };
+
+        let depth = weighted_sum(|vertex| vertex.pos[2]);
+        let inv_w = weighted_sum(|vertex| vertex.inv_w);
+
+        // Perspective-correct interpolation weights the attributes by the
+        // reciprocal of their clip space `w`.
+        let perspective_weights: Vec<_> = vertices
+            .iter()
+            .zip(weights)
+            .map(|(vertex, weight)| weight * vertex.inv_w / inv_w)
+            .collect();
+
+        let varyings = self
+            .trace
+            .varying_outputs
+            .iter()
+            .enumerate()
+            .map(|(i, (_, qualifier, _))| {
+                use InterpolationQualifier::*;
+
+                match qualifier {
+                    Flat => provoking.varyings[i].clone(),
+                    // Fragments are always sampled at pixel centers, which
+                    // lie within the primitive.
+                    Smooth | Centroid => {
+                        let values: Vec<_> =
+                            vertices.iter().map(|vertex| &vertex.varyings[i]).collect();
+
+                        interpolate(&values, &perspective_weights)
+                    }
+                }
+            })
+            .collect();
+
+        Fragment {
+            pos,
+            coord: [pos[0] as f32 + 0.5, pos[1] as f32 + 0.5, depth, inv_w],
+            front_facing,
+            point_coord: [0.0; 2],
+            varyings,
+        }
+    }
+
+    fn shade_fragment(&mut self, fragment: Fragment) -> Result<(), DrawError> {
+        let mut bindings = self.uniforms.clone();
+
+        for ((name, _, _), value) in self.trace.varying_outputs.iter().zip(fragment.varyings) {
+            bindings.insert_value(name.clone(), value);
+        }
+
+        bindings
+            .insert_value("gl_FragCoord", float_vec(&fragment.coord))
+            .insert_value("gl_FrontFacing", EvalValue::Bool(fragment.front_facing))
+            .insert_value("gl_PointCoord", float_vec(&fragment.point_coord));
+
+        let exprs: Vec<_> = self
+            .trace
+            .fragment_outputs
+            .iter()
+            .map(|(_, expr)| expr.clone())
+            .chain(self.trace.fragment_depth.clone())
+            .collect();
+
+        let mut values = match evaluate_exprs(&exprs, &bindings) {
+            Ok(values) => values,
+            Err(EvalError::Discarded) => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+
+        let depth = match self.trace.fragment_depth {
+            Some(_) => scalar(&values.pop().unwrap()),
+            None => fragment.coord[2],
+        }
+        .clamp(0.0, 1.0);
+
+        let pos = fragment.pos;
+        let params = self.params;
+        let (color_attachments, depth_attachment) = self.framebuffer.attachments_mut();
+
+        // Like in OpenGL, the depth test is skipped if there is no depth
+        // attachment.
+        if let (Some(comparison), Some(image)) = (params.depth_test, depth_attachment) {
+            if !compare(comparison, depth, image.get(pos)) {
+                return Ok(());
+            }
+
+            if params.depth_mask {
+                image.set(pos, depth);
+            }
+        }
+
+        for (image, value) in color_attachments.iter_mut().zip(&values) {
+            let src = rgba(value);
+            let dst = image.get(pos);
+            let color = params
+                .blending
+                .as_ref()
+                .map_or(src, |blending| blend(blending, src, dst));
+
+            image.set(
+                pos,
+                array::from_fn(|i| {
+                    if params.color_mask[i] {
+                        color[i]
+                    } else {
+                        dst[i]
+                    }
+                }),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn to_window(&self, vertex: ClipVertex) -> WindowVertex {
+        let [x, y, z, w] = vertex.pos;
+        let [viewport_x, viewport_y, width, height] = self.viewport;
+        let inv_w = 1.0 / w;
+
+        WindowVertex {
+            pos: [
+                viewport_x + (x * inv_w + 1.0) * width / 2.0,
+                viewport_y + (y * inv_w + 1.0) * height / 2.0,
+                (z * inv_w + 1.0) / 2.0,
+            ],
+            inv_w,
+            varyings: vertex.varyings,
+        }
+    }
+
+    // Returns the pixels along `axis` whose centers lie in `[min, max)`.
+    fn pixel_range(&self, axis: usize, min: f32, max: f32) -> Range<u32> {
+        let start = (min - 0.5).ceil().max(self.bounds[axis] as f32);
+        let end = (max - 0.5).ceil().min(self.bounds[axis + 2] as f32);
+
+        if start < end {
+            start as u32..end as u32
+        } else {
+            0..0
+        }
+    }
+}
+
+impl From<&Vertex> for ClipVertex {
+    fn from(vertex: &Vertex) -> Self {
+        Self {
+            pos: vertex.clip_pos,
+            varyings: vertex.varyings.clone(),
+        }
+    }
+}
+
+// Returns the vertices of each primitive, given as indices into the list of
+// vertices. The provoking vertex comes last.
+fn assemble(mode: PrimitiveMode, num_vertices: usize) -> Vec<Vec<usize>> {
+    use PrimitiveMode::*;
+
+    let n = num_vertices;
+
+    match mode {
+        Points => (0..n).map(|i| vec![i]).collect(),
+        Lines => (0..n / 2).map(|i| vec![2 * i, 2 * i + 1]).collect(),
+        LineStrip => (1..n).map(|i| vec![i - 1, i]).collect(),
+        LineLoop => (1..n)
+            .map(|i| vec![i - 1, i])
+            .chain((n >= 2).then(|| vec![n - 1, 0]))
+            .collect(),
+        Triangles => (0..n / 3)
+            .map(|i| vec![3 * i, 3 * i + 1, 3 * i + 2])
+            .collect(),
+        TriangleStrip => (2..n)
+            .map(|i| {
+                // Every other triangle is flipped, so that all of them have
+                // the same winding order.
+                if i % 2 == 0 {
+                    vec![i - 2, i - 1, i]
+                } else {
+                    vec![i - 1, i - 2, i]
+                }
+            })
+            .collect(),
+        TriangleFan => (2..n).map(|i| vec![0, i - 1, i]).collect(),
+    }
+}
+
+// Returns the signed distance of `pos` to the `plane`-th plane of the clip
+// volume, which is positive inside of the clip volume.
+fn plane_distance(pos: [f32; 4], plane: usize) -> f32 {
+    let [x, y, z, w] = pos;
+    let c = [x, y, z][plane / 2];
+
+    match plane % 2 {
+        0 => w + c,
+        _ => w - c,
+    }
+}
+
+fn lerp_vertex(a: &ClipVertex, b: &ClipVertex, t: f32) -> ClipVertex {
+    ClipVertex {
+        pos: array::from_fn(|i| a.pos[i] + t * (b.pos[i] - a.pos[i])),
+        varyings: a
+            .varyings
+            .iter()
+            .zip(&b.varyings)
+            .map(|(x, y)| interpolate(&[x, y], &[1.0 - t, t]))
+            .collect(),
+    }
+}
+
+// Clips a convex polygon against the clip volume with the Sutherland-Hodgman
+// algorithm.
+fn clip_polygon(mut polygon: Vec<ClipVertex>) -> Vec<ClipVertex> {
+    for plane in 0..6 {
+        let mut clipped = Vec::new();
+
+        for i in 0..polygon.len() {
+            let a = &polygon[i];
+            let b = &polygon[(i + 1) % polygon.len()];
+            let da = plane_distance(a.pos, plane);
+            let db = plane_distance(b.pos, plane);
+
+            if da >= 0.0 {
+                clipped.push(a.clone());
+            }
+
+            if (da >= 0.0) != (db >= 0.0) {
+                clipped.push(lerp_vertex(a, b, da / (da - db)));
+            }
+        }
+
+        polygon = clipped;
+    }
+
+    polygon
+}
+
+// Clips a line against the clip volume with the Liang-Barsky algorithm.
+fn clip_line([a, b]: [ClipVertex; 2]) -> Option<[ClipVertex; 2]> {
+    let mut t0 = 0.0f32;
+    let mut t1 = 1.0f32;
+
+    for plane in 0..6 {
+        let da = plane_distance(a.pos, plane);
+        let db = plane_distance(b.pos, plane);
+
+        if da < 0.0 && db < 0.0 {
+            return None;
+        } else if da < 0.0 {
+            t0 = t0.max(da / (da - db));
+        } else if db < 0.0 {
+            t1 = t1.min(da / (da - db));
+        }
+    }
+
+    (t0 <= t1).then(|| [lerp_vertex(&a, &b, t0), lerp_vertex(&a, &b, t1)])
+}
+
+// Returns twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: &[f32; 3], b: &[f32; 3], p: [f32; 2]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+// In a counter-clockwise triangle, left edges point downwards and top edges
+// point to the left.
+fn is_top_left(a: &[f32; 3], b: &[f32; 3]) -> bool {
+    let [dx, dy] = [b[0] - a[0], b[1] - a[1]];
+
+    dy < 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+// Interpolates the floating-point components of `values` with the given
+// weights. Other components are taken from the first value.
+fn interpolate(values: &[&EvalValue], weights: &[f32]) -> EvalValue {
+    let parts = |len: usize| -> Vec<EvalValue> {
+        (0..len)
+            .map(|i| {
+                let values: Vec<_> = values.iter().map(|value| &components(value)[i]).collect();
+
+                interpolate(&values, weights)
+            })
+            .collect()
+    };
+
+    match values[0] {
+        EvalValue::F32(_) => EvalValue::F32(
+            values
+                .iter()
+                .zip(weights)
+                .map(|(value, weight)| scalar(value) * weight)
+                .sum(),
+        ),
+        EvalValue::Vec(xs) => EvalValue::Vec(parts(xs.len())),
+        EvalValue::Mat(xs) => EvalValue::Mat(parts(xs.len())),
+        EvalValue::Struct(xs) => EvalValue::Struct(parts(xs.len())),
+        EvalValue::Array(xs) => EvalValue::Array(parts(xs.len())),
+        value => value.clone(),
+    }
+}
+
+fn components(value: &EvalValue) -> &[EvalValue] {
+    match value {
+        EvalValue::Vec(xs) | EvalValue::Mat(xs) | EvalValue::Struct(xs) | EvalValue::Array(xs) => {
+            xs
+        }
+        _ => &[],
+    }
+}
+
+fn scalar(value: &EvalValue) -> f32 {
+    match value {
+        EvalValue::F32(x) => *x,
+        EvalValue::I32(x) => *x as f32,
+        EvalValue::U32(x) => *x as f32,
+        EvalValue::Bool(x) => f32::from(u8::from(*x)),
+        _ => panic!("expected scalar, but got {value:?}"),
+    }
+}
+
+fn vec4(value: &EvalValue) -> [f32; 4] {
+    array::from_fn(|i| scalar(&components(value)[i]))
+}
+
+fn float_vec(xs: &[f32]) -> EvalValue {
+    EvalValue::Vec(xs.iter().copied().map(EvalValue::F32).collect())
+}
+
+// Converts a fragment output to a color. Missing components are filled in
+// from `(0, 0, 0, 1)`.
+fn rgba(value: &EvalValue) -> [f32; 4] {
+    let xs: Vec<_> = match value {
+        EvalValue::Vec(xs) => xs.iter().map(scalar).collect(),
+        value => vec![scalar(value)],
+    };
+
+    array::from_fn(|i| xs.get(i).copied().unwrap_or(if i == 3 { 1.0 } else { 0.0 }))
+}
+
+fn compare(comparison: Comparison, value: f32, stored: f32) -> bool {
+    use Comparison::*;
+
+    match comparison {
+        Always => true,
+        Equal => value == stored,
+        Greater => value > stored,
+        GreaterOrEqual => value >= stored,
+        Less => value < stored,
+        LessOrEqual => value <= stored,
+        Never => false,
+        NotEqual => value != stored,
+    }
+}
+
+fn blend(blending: &Blending, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    array::from_fn(|i| {
+        let (equation, src_func, dst_func) = if i < 3 {
+            (
+                blending.color_equation,
+                blending.src_func_color,
+                blending.dst_func_color,
+            )
+        } else {
+            (
+                blending.alpha_equation,
+                blending.src_func_alpha,
+                blending.dst_func_alpha,
+            )
+        };
+
+        let factor = |func| blend_factor(func, i, src, dst, blending.constant_color);
+        let s = src[i] * factor(src_func);
+        let d = dst[i] * factor(dst_func);
+
+        match equation {
+            BlendEquation::Add => s + d,
+            BlendEquation::Subtract => s - d,
+            BlendEquation::ReverseSubtract => d - s,
+            BlendEquation::Min => src[i].min(dst[i]),
+            BlendEquation::Max => src[i].max(dst[i]),
+        }
+    })
+}
+
+fn blend_factor(
+    func: BlendFunc,
+    i: usize,
+    src: [f32; 4],
+    dst: [f32; 4],
+    constant: [f32; 4],
+) -> f32 {
+    use BlendFunc::*;
+
+    match func {
+        Zero => 0.0,
+        One => 1.0,
+        SrcColor => src[i],
+        OneMinusSrcColor => 1.0 - src[i],
+        DstColor => dst[i],
+        OneMinusDstColor => 1.0 - dst[i],
+        SrcAlpha => src[3],
+        OneMinusSrcAlpha => 1.0 - src[3],
+        DstAlpha => dst[3],
+        OneMinusDstAlpha => 1.0 - dst[3],
+        ConstantColor => constant[i],
+        OneMinusConstantColor => 1.0 - constant[i],
+        ConstantAlpha => constant[3],
+        OneMinusConstantAlpha => 1.0 - constant[3],
+        SrcAlphaSaturate if i < 3 => src[3].min(1.0 - dst[3]),
+        SrcAlphaSaturate => 1.0,
+    }
+}
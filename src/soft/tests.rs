@@ -0,0 +1,449 @@
+use expect_test::expect;
+
+use crate::{
+    gl::{self, Blending, ClearParams, Comparison, CullFace, DrawParams, PrimitiveMode, Rect},
+    sl, Block, BlockDom, Gl, Sl, ToSl, Uniform, UniformDom,
+};
+
+use super::{DrawError, Framebuffer, Program, VertexSpec};
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct PackedVertex<D: BlockDom> {
+    pos: D::Vec3,
+    color: D::Unorm8x4,
+    uv: D::F16x2,
+    normal: D::Snorm16x4,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+struct Globals<D: BlockDom> {
+    ambient: D::Vec4,
+    world_to_clip: D::Mat4,
+}
+
+#[derive(Copy, Clone, Uniform)]
+struct TintUniform<D: UniformDom> {
+    sampler: D::ColorSampler2d<sl::Vec3>,
+    globals: D::Block<Globals<Sl>>,
+}
+
+fn position_shader((): (), vertex: sl::Vec4) -> sl::Vec4 {
+    vertex
+}
+
+fn color_shader((): ()) -> sl::Vec4 {
+    sl::vec4(0.25, 0.5, 1.0, 0.5)
+}
+
+fn vec4(x: f32, y: f32, z: f32, w: f32) -> gl::Vec4 {
+    gl::Vec4 { x, y, z, w }
+}
+
+fn quad(z: f32) -> Vec<gl::Vec4> {
+    vec![
+        vec4(-1.0, -1.0, z, 1.0),
+        vec4(1.0, -1.0, z, 1.0),
+        vec4(1.0, 1.0, z, 1.0),
+        vec4(-1.0, -1.0, z, 1.0),
+        vec4(1.0, 1.0, z, 1.0),
+        vec4(-1.0, 1.0, z, 1.0),
+    ]
+}
+
+fn count_covered(framebuffer: &Framebuffer) -> usize {
+    framebuffer
+        .color_attachment(0)
+        .pixels()
+        .iter()
+        .filter(|pixel| pixel[3] != 0.0)
+        .count()
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-5,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn test_fill_rule() {
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, color_shader);
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    // The shared diagonal of the two triangles passes exactly through pixel
+    // centers. With additive blending, any pixel that is covered twice would
+    // end up with a different color.
+    program
+        .with_params(DrawParams::new().with_blending(Blending::add().with_func(gl::BlendFunc::One)))
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&quad(0.0)),
+        )
+        .unwrap();
+
+    assert!(framebuffer
+        .color_attachment(0)
+        .pixels()
+        .iter()
+        .all(|&pixel| pixel == [0.25, 0.5, 1.0, 0.5]));
+}
+
+#[test]
+fn test_interpolation() {
+    fn vertex_shader((): (), vertex: sl::Vec4) -> sl::VsOutput<(sl::F32, sl::Flat<sl::F32>)> {
+        sl::VsOutput {
+            clip_pos: sl::vec4(vertex.x, vertex.y, 0.0, vertex.w),
+            interp: (vertex.z, sl::Flat(vertex.z)),
+        }
+    }
+
+    fn fragment_shader((): (), (smooth, flat): (sl::F32, sl::Flat<sl::F32>)) -> sl::Vec4 {
+        sl::vec4(smooth, flat.0, 0.0, 1.0)
+    }
+
+    let program: Program<(), sl::Vec4> = Program::new(vertex_shader, fragment_shader);
+
+    // A line whose second endpoint has `w = 3`, so that perspective-correct
+    // interpolation differs from linear interpolation in window space.
+    let vertices = [vec4(-1.0, 0.0, 0.0, 1.0), vec4(3.0, 0.0, 1.0, 3.0)];
+    let mut framebuffer = Framebuffer::new([4, 1]);
+
+    program
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Lines).with_vertex_block(&vertices),
+        )
+        .unwrap();
+
+    for x in 0..4 {
+        let t = (x as f32 + 0.5) / 4.0;
+        let expected = (t / 3.0) / (1.0 - t + t / 3.0);
+        let [smooth, flat, _, _] = framebuffer.color_attachment(0).get([x, 0]);
+
+        assert_close(smooth, expected);
+        assert_eq!(flat, 1.0);
+    }
+}
+
+#[test]
+fn test_depth_test_and_culling() {
+    fn fragment_shader((): (), input: sl::FsInput<()>) -> sl::Vec4 {
+        sl::vec4(input.fragment_coord.z, 0.0, 0.0, 1.0)
+    }
+
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, fragment_shader);
+    let mut framebuffer = Framebuffer::new([2, 2]).with_depth();
+    let params = DrawParams::new().with_depth_test(Comparison::Less);
+
+    for z in [-0.5, 0.5] {
+        program
+            .with_params(params)
+            .draw(
+                &mut framebuffer,
+                &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&quad(z)),
+            )
+            .unwrap();
+    }
+
+    assert_eq!(framebuffer.color_attachment(0).get([1, 1])[0], 0.25);
+    assert_eq!(framebuffer.depth_attachment().unwrap().get([1, 1]), 0.25);
+
+    // The second triangle of the quad is reversed, so it is back-facing.
+    let mut vertices = quad(0.0);
+    vertices.swap(4, 5);
+
+    framebuffer.clear(ClearParams {
+        color: Some([0.0; 4]),
+        depth: Some(1.0),
+        stencil: None,
+    });
+
+    program
+        .with_params(params.with_cull_face(CullFace::Back))
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&vertices),
+        )
+        .unwrap();
+
+    assert_eq!(framebuffer.color_attachment(0).get([1, 0])[3], 1.0);
+    assert_eq!(framebuffer.color_attachment(0).get([0, 1])[3], 0.0);
+}
+
+#[test]
+fn test_blending_scissor_and_color_mask() {
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, color_shader);
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    framebuffer.clear(ClearParams {
+        color: Some([0.0, 0.0, 0.0, 1.0]),
+        ..Default::default()
+    });
+
+    program
+        .with_params(
+            DrawParams::new()
+                .with_blending(Blending::alpha())
+                .with_scissor(Rect {
+                    lower_left_corner: [1, 1],
+                    size: [2, 2],
+                })
+                .with_color_mask([true, true, false, true]),
+        )
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&quad(0.0)),
+        )
+        .unwrap();
+
+    let image = framebuffer.color_attachment(0);
+
+    assert_eq!(image.get([1, 2]), [0.125, 0.25, 0.0, 0.75]);
+    assert_eq!(image.get([0, 2]), [0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(image.get([3, 3]), [0.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn test_points_and_lines() {
+    fn vertex_shader((): (), vertex: sl::Vec4) -> sl::FullVsOutput<()> {
+        sl::FullVsOutput {
+            clip_pos: vertex,
+            interp: (),
+            point_size: Some(2.0.into()),
+        }
+    }
+
+    fn fragment_shader((): (), input: sl::FsInput<()>) -> sl::Vec4 {
+        input.point_coord.extend(0.0).extend(1.0)
+    }
+
+    let program: Program<(), sl::Vec4> = Program::new(vertex_shader, fragment_shader);
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    program
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Points).with_vertex_block(&[vec4(0.0, 0.0, 0.0, 1.0)]),
+        )
+        .unwrap();
+
+    let image = framebuffer.color_attachment(0);
+
+    assert_eq!(count_covered(&framebuffer), 4);
+    assert_eq!(image.get([1, 2]), [0.25, 0.25, 0.0, 1.0]);
+    assert_eq!(image.get([2, 1]), [0.75, 0.75, 0.0, 1.0]);
+
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, color_shader);
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    // A closed loop around the center of the framebuffer. Each side covers
+    // two pixels, with the corners shared by some of the sides.
+    let vertices = [
+        vec4(-0.5, -0.5, 0.0, 1.0),
+        vec4(0.5, -0.5, 0.0, 1.0),
+        vec4(0.5, 0.5, 0.0, 1.0),
+        vec4(-0.5, 0.5, 0.0, 1.0),
+    ];
+
+    program
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::LineLoop).with_vertex_block(&vertices),
+        )
+        .unwrap();
+
+    assert_eq!(count_covered(&framebuffer), 7);
+}
+
+#[test]
+fn test_clipping() {
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, color_shader);
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    // Depth decreases from `z = 0` at the top to `z = -2` at the bottom, so
+    // that the lower half lies in front of the near plane.
+    let vertices = [
+        vec4(-1.0, -1.0, -2.0, 1.0),
+        vec4(1.0, -1.0, -2.0, 1.0),
+        vec4(-1.0, 1.0, 0.0, 1.0),
+        vec4(1.0, 1.0, 0.0, 1.0),
+    ];
+
+    program
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::TriangleStrip).with_vertex_block(&vertices),
+        )
+        .unwrap();
+
+    let image = framebuffer.color_attachment(0);
+
+    assert_eq!(count_covered(&framebuffer), 8);
+    assert_eq!(image.get([0, 1])[3], 0.0);
+    assert_eq!(image.get([0, 2])[3], 0.5);
+}
+
+#[test]
+fn test_instances_and_elements() {
+    fn vertex_shader((): (), input: sl::VsInput<sl::Vec2>) -> sl::VsOutput<sl::U32> {
+        let offset = input.instance_id.as_f32();
+
+        sl::VsOutput {
+            clip_pos: sl::vec4(input.vertex.x + offset, input.vertex.y, 0.0, 1.0),
+            interp: input.vertex_id,
+        }
+    }
+
+    fn fragment_shader((): (), vertex_id: sl::U32) -> sl::Vec4 {
+        sl::vec4(vertex_id.as_f32(), 0.0, 0.0, 1.0)
+    }
+
+    let program: Program<(), sl::Vec2> = Program::new(vertex_shader, fragment_shader);
+    let mut framebuffer = Framebuffer::new([4, 2]);
+
+    let vertices = [
+        gl::Vec2 { x: -1.0, y: -1.0 },
+        gl::Vec2 { x: 0.0, y: -1.0 },
+        gl::Vec2 { x: -1.0, y: 1.0 },
+        gl::Vec2 { x: 0.0, y: 1.0 },
+    ];
+
+    program
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles)
+                .with_vertex_block(&vertices)
+                .with_elements(&[0, 1, 2, 2, 1, 3])
+                .with_num_instances(2),
+        )
+        .unwrap();
+
+    let image = framebuffer.color_attachment(0);
+
+    assert_eq!(count_covered(&framebuffer), 8);
+    assert_eq!(image.get([0, 0])[0], 2.0);
+    assert_eq!(image.get([3, 1])[0], 3.0);
+}
+
+#[test]
+fn test_errors() {
+    fn fragment_shader((): (), input: sl::FsInput<()>) -> sl::Vec4 {
+        sl::branch(
+            input.fragment_coord.x.lt(2.0),
+            input.discard::<sl::Vec4>(),
+            sl::Vec4::ONE,
+        )
+    }
+
+    let program: Program<(), sl::Vec4> = Program::new(position_shader, fragment_shader);
+    let vertex_spec = VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&quad(0.0));
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    program.draw(&mut framebuffer, &vertex_spec).unwrap();
+
+    assert_eq!(count_covered(&framebuffer), 8);
+
+    assert!(matches!(
+        program.draw(
+            &mut Framebuffer::from_attachments(vec![], Some(super::DepthImage::new([4, 4]))),
+            &vertex_spec,
+        ),
+        Err(DrawError::ColorAttachmentCount {
+            expected: 1,
+            actual: 0
+        })
+    ));
+    assert!(matches!(
+        program.draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&[gl::Vec2::default()]),
+        ),
+        Err(DrawError::VertexBlockMismatch { index: 0 })
+    ));
+    assert!(matches!(
+        program.draw(
+            &mut framebuffer,
+            &vertex_spec.clone().with_elements(&[0, 1, 6]),
+        ),
+        Err(DrawError::VertexBlockTooShort { index: 0 })
+    ));
+    assert!(matches!(
+        program
+            .with_params(DrawParams::new().with_stencil_test(Default::default()))
+            .draw(&mut framebuffer, &vertex_spec),
+        Err(DrawError::StencilTestUnsupported)
+    ));
+}
+
+#[test]
+fn test_derived_interfaces() {
+    fn vertex_shader(uniform: TintUniform<Sl>, vertex: PackedVertex<Sl>) -> sl::VsOutput<sl::Vec4> {
+        sl::VsOutput {
+            clip_pos: uniform.globals.world_to_clip * vertex.pos.extend(1.0)
+                + vertex.normal * vertex.uv.x,
+            interp: vertex.color,
+        }
+    }
+
+    fn fragment_shader(uniform: TintUniform<Sl>, interp: sl::Vec4) -> sl::Vec4 {
+        let tint = uniform.sampler.sample(sl::Vec2::ZERO);
+
+        (interp.xyz() * tint).extend(interp.w) * uniform.globals.ambient
+    }
+
+    let program: Program<TintUniform<Sl>, PackedVertex<Sl>> =
+        Program::new(vertex_shader, fragment_shader);
+
+    let vertex = |pos: [f32; 3], color: [f32; 4]| PackedVertex::<Gl> {
+        pos: glam::Vec3::from(pos).into(),
+        color: color.into(),
+        uv: [1.0, 0.0].into(),
+        normal: [0.0, 0.0, 0.5, 0.0].into(),
+    };
+    let vertices = [
+        vertex([-1.0, -1.0, 0.0], [1.0, 0.0, 0.0, 1.0]),
+        vertex([1.0, -1.0, 0.0], [0.0, 1.0, 0.0, 1.0]),
+        vertex([-1.0, 1.0, 0.0], [0.0, 0.0, 1.0, 1.0]),
+    ];
+
+    let globals = Globals::<Gl> {
+        ambient: glam::Vec4::ONE.into(),
+        world_to_clip: glam::Mat4::IDENTITY.into(),
+    };
+    let uniforms = TintUniform::<Sl> {
+        sampler: sl::ColorSampler2d::constant(sl::vec3(1.0, 0.5, 1.0)),
+        globals: globals.to_sl(),
+    };
+
+    let mut framebuffer = Framebuffer::new([4, 4]);
+
+    program
+        .with_uniforms(uniforms)
+        .draw(
+            &mut framebuffer,
+            &VertexSpec::new(PrimitiveMode::Triangles).with_vertex_block(&vertices),
+        )
+        .unwrap();
+
+    let rows: Vec<String> = framebuffer
+        .color_attachment(0)
+        .to_rgba8()
+        .chunks_exact(4 * 4)
+        .map(|row| {
+            row.chunks_exact(4)
+                .map(|pixel| format!("{:02x}{:02x}{:02x}", pixel[0], pixel[1], pixel[2]))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+
+    expect![[r#"
+        000000 000000 000000 000000
+        40109f 000000 000000 000000
+        801060 403060 000000 000000
+        bf1020 803020 405020 000000"#]]
+    .assert_eq(&rows.join("\n"));
+}
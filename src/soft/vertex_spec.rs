@@ -0,0 +1,222 @@
+use std::{marker::PhantomData, mem::size_of, ops::Range};
+
+use bytemuck::Pod;
+
+use crate::{
+    gl::{self, PrimitiveMode},
+    sl::{
+        dag::BuiltInType,
+        eval::{construct, matrix_shape, vector_shape, EvalValue},
+        program_def::{VertexAttributeDef, VertexAttributeFormat, VertexInputRate},
+    },
+    Block, Gl, Sl, VsInterface,
+};
+
+/// Vertex data in CPU memory, together with the primitives that it forms.
+///
+/// This is the counterpart of [`gl::VertexSpec`] for
+/// [`Program`](super::Program). The [`Gl`] view of `V` refers to GPU buffers,
+/// so the data of its blocks is given as slices instead, one for each block of
+/// `V` in order of declaration.
+#[derive(Clone)]
+pub struct VertexSpec<V> {
+    mode: PrimitiveMode,
+    blocks: Vec<BlockData>,
+    vertex_range: Option<Range<usize>>,
+    elements: Option<Vec<u32>>,
+    num_instances: Option<usize>,
+    _phantom: PhantomData<V>,
+}
+
+#[derive(Clone)]
+pub(super) struct BlockData {
+    bytes: Vec<u8>,
+    stride: usize,
+    input_rate: VertexInputRate,
+    attributes: Vec<VertexAttributeDef>,
+}
+
+impl<V: VsInterface<Sl>> VertexSpec<V> {
+    pub fn new(mode: PrimitiveMode) -> Self {
+        Self {
+            mode,
+            blocks: Vec::new(),
+            vertex_range: None,
+            elements: None,
+            num_instances: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Adds the data of the next block of `V`, which advances once per
+    /// vertex.
+    pub fn with_vertex_block<B: Block<Gl> + Pod>(self, data: &[B]) -> Self {
+        self.with_block(data, VertexInputRate::Vertex)
+    }
+
+    /// Adds the data of the next block of `V`, which advances once per
+    /// instance.
+    pub fn with_instance_block<B: Block<Gl> + Pod>(self, data: &[B]) -> Self {
+        self.with_block(data, VertexInputRate::Instance)
+    }
+
+    pub fn with_vertex_range(mut self, vertex_range: Range<usize>) -> Self {
+        // NOTE: The stored `vertex_range` is ignored if elements are passed as
+        // well.
+        self.vertex_range = Some(vertex_range);
+        self
+    }
+
+    pub fn with_elements(mut self, elements: &[u32]) -> Self {
+        self.elements = Some(elements.to_vec());
+        self
+    }
+
+    pub fn with_num_instances(mut self, num_instances: usize) -> Self {
+        self.num_instances = Some(num_instances);
+        self
+    }
+
+    pub(super) fn mode(&self) -> PrimitiveMode {
+        self.mode
+    }
+
+    pub(super) fn blocks(&self) -> &[BlockData] {
+        &self.blocks
+    }
+
+    /// Returns the indices of the vertices in the order in which they are
+    /// assembled into primitives.
+    pub(super) fn indices(&self) -> Vec<usize> {
+        match &self.elements {
+            Some(elements) => elements.iter().map(|&i| i as usize).collect(),
+            None => self
+                .vertex_range
+                .clone()
+                .or_else(|| self.count(VertexInputRate::Vertex).map(|n| 0..n))
+                .unwrap_or(0..0)
+                .collect(),
+        }
+    }
+
+    pub(super) fn num_instances(&self) -> usize {
+        self.num_instances
+            .or_else(|| self.count(VertexInputRate::Instance))
+            .unwrap_or(1)
+    }
+
+    fn with_block<B: Block<Gl> + Pod>(mut self, data: &[B], input_rate: VertexInputRate) -> Self {
+        if let Some(len) = self.count(input_rate) {
+            assert_eq!(
+                len,
+                data.len(),
+                "blocks with the same input rate must have the same length"
+            );
+        }
+
+        // TODO: Remove hardcoded path names.
+        self.blocks.push(BlockData {
+            bytes: bytemuck::cast_slice(data).to_vec(),
+            stride: size_of::<B>(),
+            input_rate,
+            attributes: B::vertex_attribute_defs("vertex_input"),
+        });
+
+        self
+    }
+
+    fn count(&self, input_rate: VertexInputRate) -> Option<usize> {
+        self.blocks
+            .iter()
+            .find(|block| block.input_rate == input_rate)
+            .map(BlockData::len)
+    }
+}
+
+impl BlockData {
+    /// Returns whether the attributes of this block have the same layout as
+    /// `attributes`. Their names are not compared.
+    pub(super) fn matches(&self, attributes: &[VertexAttributeDef]) -> bool {
+        self.attributes.len() == attributes.len()
+            && self
+                .attributes
+                .iter()
+                .zip(attributes)
+                .all(|(a, b)| (a.ty, a.format, a.offset) == (b.ty, b.format, b.offset))
+    }
+
+    /// Decodes the attributes of the element that is used for the given vertex
+    /// and instance.
+    pub(super) fn decode(
+        &self,
+        vertex: usize,
+        instance: usize,
+    ) -> impl Iterator<Item = EvalValue> + '_ {
+        let index = match self.input_rate {
+            VertexInputRate::Vertex => vertex,
+            VertexInputRate::Instance => instance,
+        };
+        let element = &self.bytes[index * self.stride..(index + 1) * self.stride];
+
+        self.attributes.iter().map(move |attribute| {
+            decode_attribute(&element[attribute.offset..], attribute.ty, attribute.format)
+        })
+    }
+
+    /// Returns whether this block has elements for the given number of
+    /// vertices and instances.
+    pub(super) fn contains(&self, num_vertices: usize, num_instances: usize) -> bool {
+        match self.input_rate {
+            VertexInputRate::Vertex => self.len() >= num_vertices,
+            VertexInputRate::Instance => self.len() >= num_instances,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len() / self.stride.max(1)
+    }
+}
+
+fn decode_attribute(bytes: &[u8], ty: BuiltInType, format: VertexAttributeFormat) -> EvalValue {
+    use VertexAttributeFormat::*;
+
+    let (scalar, len) = matrix_shape(ty)
+        .map(|(num_columns, num_rows)| (BuiltInType::F32, num_columns * num_rows))
+        .or_else(|| vector_shape(ty))
+        .unwrap_or((ty, 1));
+
+    let size = match format {
+        Native => 4,
+        Unorm8 | Snorm8 => 1,
+        Unorm16 | Snorm16 | F16 => 2,
+    };
+
+    let components: Vec<_> = bytes
+        .chunks_exact(size)
+        .take(len)
+        .map(|bytes| match format {
+            Native => {
+                let bits = u32::from_ne_bytes(bytes.try_into().unwrap());
+
+                match scalar {
+                    BuiltInType::F32 => EvalValue::F32(f32::from_bits(bits)),
+                    BuiltInType::I32 => EvalValue::I32(bits as i32),
+                    _ => EvalValue::U32(bits),
+                }
+            }
+            Unorm8 => EvalValue::F32(f32::from(bytes[0]) / 255.0),
+            Snorm8 => EvalValue::F32((f32::from(bytes[0] as i8) / 127.0).max(-1.0)),
+            Unorm16 => EvalValue::F32(gl::decode_unorm16(u16::from_ne_bytes(
+                bytes.try_into().unwrap(),
+            ))),
+            Snorm16 => EvalValue::F32(gl::decode_snorm16(i16::from_ne_bytes(
+                bytes.try_into().unwrap(),
+            ))),
+            F16 => EvalValue::F32(gl::decode_f16(u16::from_ne_bytes(
+                bytes.try_into().unwrap(),
+            ))),
+        })
+        .collect();
+
+    construct(&components, ty)
+}
@@ -2,7 +2,7 @@ use expect_test::{expect, Expect};
 
 use posh::{
     sl::{self, program_def::ProgramDef, transpile::transpile_to_program_def},
-    Block, BlockDom, FsDom, FsInterface, Sl, Uniform, UniformDom, VsDom, VsInterface,
+    Block, BlockDom, FsDom, FsInterface, Sl, ToSl, Uniform, UniformDom, VsDom, VsInterface,
 };
 
 fn check_program_def(actual: ProgramDef, expect_vs: Expect, expect_fs: Expect) {
@@ -175,11 +175,6 @@ fn test_more_types() {
     let actual =
         transpile_to_program_def::<MyUniform<Sl>, _, _, _, _>(vertex_shader, fragment_shader);
 
-    // FIXME: Why is `common_field_base` not collapsing the vector expressions
-    // below?
-    // ```
-    // var_2 = (vec3(vertex_output_T6.x, vertex_output_T6.y, vertex_output_T6.z) * vec3(var_0.x, var_0.y, var_0.z));
-    // ```
     check_program_def(
         actual,
         expect![[r#"
@@ -204,21 +199,21 @@ fn test_more_types() {
                 MyGlobals_Posh1 uniforms_globals;
             };
 
-            in vec2 vertex_input_world_pos;
+            in vec2 vertex_input_QworldQupos;
             in vec4 vertex_input_color;
             in float vertex_input_block_scale;
             smooth out vec4 vertex_output_T6;
             smooth out vec2 vertex_output_T7_uv;
             smooth out float vertex_output_T7_scale;
-            flat out uint vertex_output_T7_instance_id;
+            flat out uint vertex_output_T7_QinstanceQuid;
 
             void main() {
                 float var_0 = ((uint(gl_VertexID) == uint(gl_InstanceID))) ? (1.0) : (0.5);
-                gl_Position = (uniforms_globals.world_to_clip * vec4(vertex_input_world_pos.x, vertex_input_world_pos.y, var_0, 1.0));
+                gl_Position = (uniforms_globals.world_to_clip * vec4(vertex_input_QworldQupos.x, vertex_input_QworldQupos.y, var_0, 1.0));
                 vertex_output_T6 = vertex_input_color;
-                vertex_output_T7_uv = (vertex_input_world_pos * 0.01);
+                vertex_output_T7_uv = (vertex_input_QworldQupos * 0.01);
                 vertex_output_T7_scale = vertex_input_block_scale;
-                vertex_output_T7_instance_id = uint(gl_InstanceID);
+                vertex_output_T7_QinstanceQuid = uint(gl_InstanceID);
             }
         "#]],
         expect![[r#"
@@ -246,7 +241,7 @@ fn test_more_types() {
             smooth in vec4 vertex_output_T6;
             smooth in vec2 vertex_output_T7_uv;
             smooth in float vertex_output_T7_scale;
-            flat in uint vertex_output_T7_instance_id;
+            flat in uint vertex_output_T7_QinstanceQuid;
             layout(location = 0) out vec4 fragment_output_albedo;
             layout(location = 1) out vec3 fragment_output_normal;
             layout(location = 2) out float fragment_output_glow;
@@ -257,7 +252,7 @@ fn test_more_types() {
                 if ((var_0.z == 0.0)) {
                     discard;
                 } else {
-                    var_2 = (vec3(vertex_output_T6.x, vertex_output_T6.y, vertex_output_T6.z) * vec3(var_0.x, var_0.y, var_0.z));
+                    var_2 = (vertex_output_T6.xyz * var_0.xyz);
                 }
                 vec3 var_3 = (gl_FrontFacing) ? (vec3(0.0, 1.0, 0.0)) : (vec3(0.0, 0.0, 1.0));
                 fragment_output_albedo = (vec4(var_2.x, var_2.y, var_2.z, vertex_output_T6.w) + gl_FragCoord);
@@ -268,3 +263,868 @@ fn test_more_types() {
         "#]],
     );
 }
+
+#[sl::function]
+fn lambert(normal: sl::Vec3, light_dir: sl::Vec3) -> sl::F32 {
+    normal.dot(light_dir).max(0.0)
+}
+
+#[sl::function]
+fn shade(interp: MyInterpolant, normal: sl::Vec3) -> sl::Vec4 {
+    let diffuse = lambert(normal, sl::Vec3::Z) + lambert(normal, sl::Vec3::Y);
+
+    (interp.uv * diffuse * interp.scale).extend(0.0).extend(1.0)
+}
+
+#[test]
+fn test_function() {
+    fn vertex_shader((): (), vertex: sl::Vec3) -> sl::VsOutput<MyInterpolant> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(1.0),
+            interp: MyInterpolant {
+                uv: vertex.xy(),
+                scale: lambert(vertex, sl::Vec3::X),
+                instance_id: 0u32.to_sl(),
+            },
+        }
+    }
+
+    fn fragment_shader((): (), interp: sl::FsInput<MyInterpolant>) -> sl::Vec4 {
+        let interp = interp.interp;
+
+        shade(interp, sl::Vec3::X) + shade(interp, sl::Vec3::Y)
+    }
+
+    let actual = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+            #version 300 es
+
+            precision highp float;
+            precision highp int;
+            precision highp sampler2DShadow;
+            precision highp sampler2D;
+
+
+
+            in vec3 vertex_input;
+            smooth out vec2 vertex_output_uv;
+            smooth out float vertex_output_scale;
+            flat out uint vertex_output_QinstanceQuid;
+
+            float lambert_posh_func0(vec3 normal, vec3 light_dir) {
+                return max(dot(normal, light_dir), 0.0);
+            }
+
+            void main() {
+                gl_Position = vec4(vertex_input.x, vertex_input.y, vertex_input.z, 1.0);
+                vertex_output_uv = vertex_input.xy;
+                vertex_output_scale = lambert_posh_func0(vertex_input, vec3(1.0, 0.0, 0.0));
+                vertex_output_QinstanceQuid = 0u;
+            }
+        "#]],
+        expect![[r#"
+            #version 300 es
+
+            precision highp float;
+            precision highp int;
+            precision highp sampler2DShadow;
+            precision highp sampler2D;
+
+            struct MyInterpolant_Posh0 {
+                vec2 uv;
+                float scale;
+                uint instance_id;
+            };
+
+
+            smooth in vec2 vertex_output_uv;
+            smooth in float vertex_output_scale;
+            flat in uint vertex_output_QinstanceQuid;
+            layout(location = 0) out vec4 fragment_output;
+
+            float lambert_posh_func0(vec3 normal, vec3 light_dir) {
+                return max(dot(normal, light_dir), 0.0);
+            }
+
+            vec4 shade_posh_func1(MyInterpolant_Posh0 interp, vec3 normal) {
+                vec2 var_0 = ((interp.uv * (lambert_posh_func0(normal, vec3(0.0, 0.0, 1.0)) + lambert_posh_func0(normal, vec3(0.0, 1.0, 0.0)))) * interp.scale);
+                return vec4(var_0.x, var_0.y, 0.0, 1.0);
+            }
+
+            void main() {
+                fragment_output = (shade_posh_func1(MyInterpolant_Posh0(vertex_output_uv, vertex_output_scale, vertex_output_QinstanceQuid), vec3(1.0, 0.0, 0.0)) + shade_posh_func1(MyInterpolant_Posh0(vertex_output_uv, vertex_output_scale, vertex_output_QinstanceQuid), vec3(0.0, 1.0, 0.0)));
+            }
+        "#]],
+    );
+}
+
+#[derive(Copy, Clone, Uniform)]
+pub struct MyCubeUniform<D: UniformDom> {
+    environment: D::ColorSamplerCube<sl::Vec3>,
+    shadow: D::ComparisonSamplerCube,
+}
+
+#[test]
+fn test_sampler_cube() {
+    fn vertex_shader(_: MyCubeUniform<Sl>, vertex: sl::Vec3) -> sl::VsOutput<sl::Vec3> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(1.0),
+            interp: vertex,
+        }
+    }
+
+    fn fragment_shader(uniform: MyCubeUniform<Sl>, input: sl::FsInput<sl::Vec3>) -> sl::Vec4 {
+        let dir = input.interp;
+        let visibility = uniform.shadow.sample_compare(dir, dir.length());
+
+        (uniform.environment.sample(dir) * visibility).extend(1.0)
+    }
+
+    let actual =
+        transpile_to_program_def::<MyCubeUniform<Sl>, _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+        precision highp samplerCube;
+        precision highp samplerCubeShadow;
+
+
+        uniform samplerCube uniforms_environment;
+        uniform samplerCubeShadow uniforms_shadow;
+
+        in vec3 vertex_input;
+        smooth out vec3 vertex_output;
+
+        void main() {
+            gl_Position = vec4(vertex_input.x, vertex_input.y, vertex_input.z, 1.0);
+            vertex_output = vertex_input;
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+        precision highp samplerCube;
+        precision highp samplerCubeShadow;
+
+
+        uniform samplerCube uniforms_environment;
+        uniform samplerCubeShadow uniforms_shadow;
+
+        smooth in vec3 vertex_output;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            vec3 var_0 = (texture(uniforms_environment, vertex_output).xyz * texture(uniforms_shadow, vec4(vertex_output.x, vertex_output.y, vertex_output.z, length(vertex_output))));
+            fragment_output = vec4(var_0.x, var_0.y, var_0.z, 1.0);
+        }
+    "#]],
+    );
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MySkin<D: BlockDom> {
+    bones: D::Array<D::Mat4, 2>,
+    weights: D::Array<D::F32, 2>,
+}
+
+#[test]
+fn test_block_array() {
+    fn vertex_shader(skin: MySkin<Sl>, pos: sl::Vec4) -> sl::Vec4 {
+        skin.bones.get(0u32) * pos * skin.weights.get(0u32)
+            + skin.bones.get(1u32) * pos * skin.weights.get(1u32)
+    }
+
+    fn fragment_shader((): ()) -> sl::Vec4 {
+        sl::Vec4::ZERO
+    }
+
+    let actual = transpile_to_program_def::<MySkin<Sl>, _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MySkin_Posh0 {
+            mat4[2] bones;
+            float[2] weights;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MySkin_Posh0 uniforms;
+        };
+
+        in vec4 vertex_input;
+
+        void main() {
+            gl_Position = (((uniforms.bones[0u] * vertex_input) * uniforms.weights[0u]) + ((uniforms.bones[1u] * vertex_input) * uniforms.weights[1u]));
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MySkin_Posh0 {
+            mat4[2] bones;
+            float[2] weights;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MySkin_Posh0 uniforms;
+        };
+
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = vec4(0.0, 0.0, 0.0, 0.0);
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn test_block_array_std140_stride() {
+    use posh::{crevice::std140::AsStd140, gl, Gl};
+
+    assert_eq!(
+        std::mem::size_of::<<gl::Array<f32, 4> as AsStd140>::Output>(),
+        64
+    );
+    assert_eq!(
+        std::mem::size_of::<<gl::Array<gl::Vec3, 2> as AsStd140>::Output>(),
+        32
+    );
+    assert_eq!(
+        std::mem::size_of::<<gl::Array<MyBlock<Gl>, 2> as AsStd140>::Output>(),
+        32
+    );
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyArrayVertex<D: BlockDom> {
+    pos: D::Vec2,
+    offsets: D::Array<D::Vec2, 2>,
+}
+
+#[test]
+fn test_vertex_block_array() {
+    fn vertex_shader((): (), vertex: MyArrayVertex<Sl>) -> sl::Vec4 {
+        (vertex.pos + vertex.offsets.get(0u32) + vertex.offsets.get(1u32))
+            .extend(0.0)
+            .extend(1.0)
+    }
+
+    fn fragment_shader((): ()) -> sl::Vec4 {
+        sl::Vec4::ZERO
+    }
+
+    let actual = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        in vec2 vertex_input_pos;
+        in vec2 vertex_input_offsets_0;
+        in vec2 vertex_input_offsets_1;
+
+        void main() {
+            vec2[2] var_0 = vec2[2](vertex_input_offsets_0, vertex_input_offsets_1);
+            vec2 var_1 = ((vertex_input_pos + var_0[0u]) + var_0[1u]);
+            gl_Position = vec4(var_1.x, var_1.y, 0.0, 1.0);
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = vec4(0.0, 0.0, 0.0, 0.0);
+        }
+    "#]],
+    );
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyToggles<D: BlockDom> {
+    enabled: D::Bool,
+    scale: D::F32,
+}
+
+#[test]
+fn test_block_bool() {
+    fn vertex_shader(toggles: MyToggles<Sl>, vertex: MyToggles<Sl>) -> sl::VsOutput<sl::Bool> {
+        sl::VsOutput {
+            clip_pos: toggles
+                .enabled
+                .branch(sl::Vec4::ONE * vertex.scale, sl::Vec4::ZERO),
+            interp: vertex.enabled,
+        }
+    }
+
+    fn fragment_shader((): (), input: sl::FsInput<sl::Bool>) -> sl::Vec4 {
+        input.interp.branch(sl::Vec4::ONE, sl::Vec4::ZERO)
+    }
+
+    let actual =
+        transpile_to_program_def::<MyToggles<Sl>, _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyToggles_Posh0 {
+            bool enabled;
+            float scale;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MyToggles_Posh0 uniforms;
+        };
+
+        in uint vertex_input_enabled;
+        in float vertex_input_scale;
+        flat out uint vertex_output;
+
+        void main() {
+            vec4 var_0 = (uniforms.enabled) ? ((vec4(1.0, 1.0, 1.0, 1.0) * vertex_input_scale)) : (vec4(0.0, 0.0, 0.0, 0.0));
+            gl_Position = var_0;
+            vertex_output = uint((vertex_input_enabled != 0u));
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyToggles_Posh0 {
+            bool enabled;
+            float scale;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MyToggles_Posh0 uniforms;
+        };
+
+        flat in uint vertex_output;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            vec4 var_0 = ((vertex_output != 0u)) ? (vec4(1.0, 1.0, 1.0, 1.0)) : (vec4(0.0, 0.0, 0.0, 0.0));
+            fragment_output = var_0;
+        }
+    "#]],
+    );
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyPackedVertex<D: BlockDom> {
+    pos: D::Vec3,
+    color: D::Unorm8x4,
+    uv: D::F16x2,
+    normal: D::Snorm16x4,
+}
+
+#[test]
+fn test_packed_vertex_attributes() {
+    use posh::{
+        gl,
+        sl::program_def::{VertexAttributeDef, VertexAttributeFormat},
+    };
+
+    fn vertex_shader((): (), vertex: MyPackedVertex<Sl>) -> sl::VsOutput<sl::Vec4> {
+        sl::VsOutput {
+            clip_pos: vertex.pos.extend(1.0) + vertex.normal * vertex.uv.x,
+            interp: vertex.color,
+        }
+    }
+
+    fn fragment_shader((): (), input: sl::FsInput<sl::Vec4>) -> sl::Vec4 {
+        input.interp
+    }
+
+    let actual = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    let attributes: Vec<_> = actual.vertex_block_defs[0]
+        .attributes
+        .iter()
+        .map(|VertexAttributeDef { format, offset, .. }| (*format, *offset))
+        .collect();
+
+    assert_eq!(
+        attributes,
+        [
+            (VertexAttributeFormat::Native, 0),
+            (VertexAttributeFormat::Unorm8, 12),
+            (VertexAttributeFormat::F16, 16),
+            (VertexAttributeFormat::Snorm16, 20),
+        ]
+    );
+    assert_eq!(std::mem::size_of::<MyPackedVertex<posh::Gl>>(), 28);
+
+    assert_eq!(
+        gl::Unorm8x4::from([0.0, 0.5, 1.0, 2.0]),
+        gl::Unorm8x4([0, 128, 255, 255])
+    );
+    assert_eq!(
+        <[f32; 2]>::from(gl::F16x2::from([1.5, -0.25])),
+        [1.5, -0.25]
+    );
+    assert_eq!(gl::F16x2::from([65504.0, 1e6]), gl::F16x2([0x7bff, 0x7c00]));
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        in vec3 vertex_input_pos;
+        in vec4 vertex_input_color;
+        in vec2 vertex_input_uv;
+        in vec4 vertex_input_normal;
+        smooth out vec4 vertex_output;
+
+        void main() {
+            gl_Position = (vec4(vertex_input_pos.x, vertex_input_pos.y, vertex_input_pos.z, 1.0) + (vertex_input_normal * vertex_input_uv.x));
+            vertex_output = vertex_input_color;
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        smooth in vec4 vertex_output;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = vertex_output;
+        }
+    "#]],
+    );
+}
+
+#[derive(Copy, Clone, sl::Value, sl::Interpolant)]
+pub struct MyShading {
+    #[interp(flat)]
+    normal: sl::Vec3,
+    #[interp(centroid)]
+    uv: sl::Vec2,
+    #[interp(centroid)]
+    material: sl::U32,
+    color: sl::Flat<sl::Vec4>,
+}
+
+#[test]
+fn test_interpolation_qualifiers() {
+    fn vertex_shader((): (), vertex: sl::Vec3) -> sl::VsOutput<(MyShading, sl::Centroid<sl::F32>)> {
+        sl::VsOutput {
+            clip_pos: vertex.extend(1.0),
+            interp: (
+                MyShading {
+                    normal: vertex,
+                    uv: vertex.xy(),
+                    material: 0u32.to_sl(),
+                    color: sl::Flat(vertex.extend(1.0)),
+                },
+                sl::Centroid(vertex.z),
+            ),
+        }
+    }
+
+    fn fragment_shader((): (), input: sl::FsInput<(MyShading, sl::Centroid<sl::F32>)>) -> sl::Vec4 {
+        let (shading, depth) = input.interp;
+
+        shading.color.0 * shading.normal.extend(depth.0)
+    }
+
+    let actual = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, fragment_shader);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        in vec3 vertex_input;
+        flat out vec3 vertex_output_T6_normal;
+        smooth centroid out vec2 vertex_output_T6_uv;
+        flat out uint vertex_output_T6_material;
+        flat out vec4 vertex_output_T6_color;
+        smooth centroid out float vertex_output_T7;
+
+        void main() {
+            gl_Position = vec4(vertex_input.x, vertex_input.y, vertex_input.z, 1.0);
+            vertex_output_T6_normal = vertex_input;
+            vertex_output_T6_uv = vertex_input.xy;
+            vertex_output_T6_material = 0u;
+            vertex_output_T6_color = vec4(vertex_input.x, vertex_input.y, vertex_input.z, 1.0);
+            vertex_output_T7 = vertex_input.z;
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        flat in vec3 vertex_output_T6_normal;
+        smooth centroid in vec2 vertex_output_T6_uv;
+        flat in uint vertex_output_T6_material;
+        flat in vec4 vertex_output_T6_color;
+        smooth centroid in float vertex_output_T7;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = (vertex_output_T6_color * vec4(vertex_output_T6_normal.x, vertex_output_T6_normal.y, vertex_output_T6_normal.z, vertex_output_T7));
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn test_tuple_and_array_fs_interface() {
+    fn vertex_shader((): (), vertex: sl::Vec4) -> sl::Vec4 {
+        vertex
+    }
+
+    fn tuple_shader((): ()) -> (sl::Vec4, sl::Vec3) {
+        (sl::Vec4::ONE, sl::Vec3::ZERO)
+    }
+
+    fn array_shader((): ()) -> [sl::Vec4; 2] {
+        [sl::Vec4::ONE, sl::Vec4::ZERO]
+    }
+
+    fn sl_array_shader((): ()) -> sl::Array<sl::Vec4, 2> {
+        sl::array([sl::Vec4::ONE, sl::Vec4::ZERO])
+    }
+
+    let tuple = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, tuple_shader);
+    let array = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, array_shader);
+    let sl_array = transpile_to_program_def::<(), _, _, _, _>(vertex_shader, sl_array_shader);
+
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        layout(location = 0) out vec4 fragment_output_T6;
+        layout(location = 1) out vec3 fragment_output_T7;
+
+        void main() {
+            fragment_output_T6 = vec4(1.0, 1.0, 1.0, 1.0);
+            fragment_output_T7 = vec3(0.0, 0.0, 0.0);
+        }
+    "#]]
+    .assert_eq(&tuple.fragment_shader_source);
+    expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+
+
+        layout(location = 0) out vec4 fragment_output_0;
+        layout(location = 1) out vec4 fragment_output_1;
+
+        void main() {
+            fragment_output_0 = vec4(1.0, 1.0, 1.0, 1.0);
+            fragment_output_1 = vec4(0.0, 0.0, 0.0, 0.0);
+        }
+    "#]]
+    .assert_eq(&array.fragment_shader_source);
+
+    // Arrays are viewed as `sl::Array` in shaders, which can also be returned
+    // directly.
+    assert_eq!(
+        sl_array.fragment_shader_source,
+        array.fragment_shader_source
+    );
+}
+
+#[derive(Copy, Clone, Uniform)]
+pub struct MyBoundUniform<D: UniformDom> {
+    scene: D::Block<MyBlock<Sl>>,
+    #[uniform(binding = 0)]
+    camera: D::Block<sl::Mat4>,
+    #[uniform(unit = 1)]
+    albedo: D::ColorSampler2d<sl::Vec4>,
+    shadow: D::ComparisonSampler2d,
+}
+
+#[test]
+fn test_uniform_bindings() {
+    fn vertex_shader(uniform: MyBoundUniform<Sl>, pos: sl::Vec4) -> sl::Vec4 {
+        uniform.camera * pos * uniform.scene.scale
+    }
+
+    fn fragment_shader(uniform: MyBoundUniform<Sl>, _: sl::FsInput<()>) -> sl::Vec4 {
+        uniform.albedo.sample(sl::Vec2::ZERO)
+            * uniform.shadow.sample_compare(sl::Vec2::ZERO, 0.5.to_sl())
+    }
+
+    let actual =
+        transpile_to_program_def::<MyBoundUniform<Sl>, _, _, _, _>(vertex_shader, fragment_shader);
+
+    let blocks: Vec<_> = actual
+        .uniform_block_defs
+        .iter()
+        .map(|def| (def.arg_name.as_str(), def.location))
+        .collect();
+    let samplers: Vec<_> = actual
+        .uniform_sampler_defs
+        .iter()
+        .map(|def| (def.name.as_str(), def.texture_unit))
+        .collect();
+
+    assert_eq!(blocks, [("uniforms_scene", 1), ("uniforms_camera", 0)]);
+    assert_eq!(samplers, [("uniforms_albedo", 1), ("uniforms_shadow", 0)]);
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyCollidingA<D: BlockDom> {
+    c: D::F32,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyCollidingB<D: BlockDom> {
+    b_c: D::F32,
+}
+
+#[derive(Copy, Clone, Block)]
+#[repr(C)]
+pub struct MyCollidingVertex<D: BlockDom> {
+    a_b: MyCollidingA<D>,
+    a: MyCollidingB<D>,
+    sample: D::F32,
+    posh_block: D::F32,
+}
+
+#[derive(Copy, Clone, sl::Value, sl::Interpolant)]
+pub struct MyCollidingInterp {
+    input: sl::F32,
+    gl_depth: sl::F32,
+}
+
+#[sl::function]
+fn blend(output: sl::F32, sample: sl::F32) -> sl::F32 {
+    output * sample
+}
+
+#[sl::function]
+fn accumulate(var_0: sl::F32, loop_acc_0: sl::F32) -> sl::F32 {
+    sl::fold(0i32..2, var_0, |acc, _| acc + loop_acc_0)
+}
+
+#[test]
+fn test_ident_mangling() {
+    use std::collections::BTreeSet;
+
+    fn vertex_shader(
+        uniforms: MyCollidingVertex<Sl>,
+        vertex: MyCollidingVertex<Sl>,
+    ) -> sl::VsOutput<MyCollidingInterp> {
+        sl::VsOutput {
+            clip_pos: sl::Vec4::ONE * uniforms.sample,
+            interp: MyCollidingInterp {
+                input: vertex.a_b.c + vertex.posh_block,
+                gl_depth: accumulate(blend(vertex.a.b_c, vertex.sample), uniforms.sample),
+            },
+        }
+    }
+
+    fn fragment_shader((): (), input: sl::FsInput<MyCollidingInterp>) -> sl::Vec4 {
+        sl::Vec4::ONE * (input.interp.input + input.interp.gl_depth)
+    }
+
+    let actual = transpile_to_program_def::<MyCollidingVertex<Sl>, _, _, _, _>(
+        vertex_shader,
+        fragment_shader,
+    );
+
+    let attribute_names: BTreeSet<_> = actual.vertex_block_defs[0]
+        .attributes
+        .iter()
+        .map(|attribute| attribute.name.clone())
+        .collect();
+
+    assert_eq!(attribute_names.len(), 4);
+
+    let long_path = posh::internal::join_ident_path(&"a".repeat(2000), "b");
+    let other_long_path = posh::internal::join_ident_path(&"a".repeat(2000), "c");
+
+    assert_eq!(long_path.len(), 1024);
+    assert_ne!(long_path, other_long_path);
+
+    check_program_def(
+        actual,
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyCollidingA_Posh0 {
+            float c;
+        };
+        struct MyCollidingB_Posh1 {
+            float b_c;
+        };
+        struct MyCollidingVertex_Posh2 {
+            MyCollidingA_Posh0 a_b;
+            MyCollidingB_Posh1 a;
+            float Qsample;
+            float QposhQublock;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MyCollidingVertex_Posh2 uniforms;
+        };
+
+        in float vertex_input_QaQub_c;
+        in float vertex_input_a_QbQuc;
+        in float vertex_input_Qsample;
+        in float vertex_input_QposhQublock;
+        smooth out float vertex_output_Qinput;
+        smooth out float vertex_output_QglQudepth;
+
+        float blend_posh_func0(float Qoutput, float Qsample) {
+            return (Qoutput * Qsample);
+        }
+
+        float accumulate_posh_func1(float QvarQu0, float QloopQuaccQu0) {
+            float var_0 = QvarQu0;
+            for (int loop_index_0 = 0; loop_index_0 < 2; loop_index_0++) {
+                float loop_acc_0 = var_0;
+                var_0 = (loop_acc_0 + QloopQuaccQu0);
+            }
+            return var_0;
+        }
+
+        void main() {
+            gl_Position = (vec4(1.0, 1.0, 1.0, 1.0) * uniforms.Qsample);
+            vertex_output_Qinput = (vertex_input_QaQub_c + vertex_input_QposhQublock);
+            vertex_output_QglQudepth = accumulate_posh_func1(blend_posh_func0(vertex_input_a_QbQuc, vertex_input_Qsample), uniforms.Qsample);
+        }
+    "#]],
+        expect![[r#"
+        #version 300 es
+
+        precision highp float;
+        precision highp int;
+        precision highp sampler2DShadow;
+        precision highp sampler2D;
+
+        struct MyCollidingA_Posh0 {
+            float c;
+        };
+        struct MyCollidingB_Posh1 {
+            float b_c;
+        };
+        struct MyCollidingVertex_Posh2 {
+            MyCollidingA_Posh0 a_b;
+            MyCollidingB_Posh1 a;
+            float Qsample;
+            float QposhQublock;
+        };
+
+        layout(std140) uniform uniforms_posh_block {
+            MyCollidingVertex_Posh2 uniforms;
+        };
+
+        smooth in float vertex_output_Qinput;
+        smooth in float vertex_output_QglQudepth;
+        layout(location = 0) out vec4 fragment_output;
+
+        void main() {
+            fragment_output = (vec4(1.0, 1.0, 1.0, 1.0) * (vertex_output_Qinput + vertex_output_QglQudepth));
+        }
+    "#]],
+    );
+}